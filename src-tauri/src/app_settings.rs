@@ -26,6 +26,7 @@ pub fn set_app_setting_cmd(
     key: String,
     value: String,
 ) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
     let conn_guard = state
         .conn
         .lock()
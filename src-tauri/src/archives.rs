@@ -0,0 +1,387 @@
+// src-tauri/src/archives.rs
+// Archives (.zip, .tar, .tar.gz/.tgz) scanned as virtual folders. With the
+// `scan_archives` app setting on, each archive the enumeration finds becomes a
+// folder whose children are its entries, at `<archive path>/<entry path>`; that
+// virtual path is also the entry's cache key, so rows are namespaced by the
+// archive. Entries take the archive's modification time, so they're recounted
+// when the archive changes. Reads of a virtual path (read_for_context) are
+// served from inside the archive. Ignore rules apply to entries like to files
+// on disk; the size/age filters don't. Archives nested in an archive are plain
+// (binary) entries.
+
+use crate::app_settings;
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::scan_cache::CacheEntry;
+use crate::scan_tree::file_modified_timestamp;
+use crate::scanner::{cache_entry_from_bytes, MAX_FILE_SIZE_BYTES};
+use crate::types::{ScanError, SKIP_TOO_LARGE, SKIP_UNREADABLE};
+use flate2::read::GzDecoder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+pub const SCAN_ARCHIVES_SETTING: &str = "scan_archives";
+
+// Larger archives aren't expanded (a .tar.gz has to be decompressed to be listed)
+const MAX_ARCHIVE_BYTES: u64 = 256 * 1024 * 1024;
+// An entry read for a context is capped too, whatever size its header claims
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+const TAR_BLOCK: usize = 512;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+/// A file inside an archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    /// Path inside the archive, `/`-separated
+    pub name: String,
+    /// Virtual path: the archive's path joined with `name`
+    pub path: PathBuf,
+    /// Uncompressed size
+    pub size: u64,
+}
+
+/// An archive expanded into the scan, with the files it contributes.
+#[derive(Debug, Clone)]
+pub struct ExpandedArchive {
+    pub archive: PathBuf,
+    pub files: Vec<ArchiveEntry>,
+}
+
+/// Whether archive scanning is switched on in app_settings (off by default).
+pub fn archives_enabled(conn: &rusqlite::Connection) -> bool {
+    matches!(
+        app_settings::get_setting_internal(conn, SCAN_ARCHIVES_SETTING),
+        Ok(Some(v)) if v.trim().eq_ignore_ascii_case("true")
+    )
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Some(Format::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(Format::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(Format::Tar)
+    } else {
+        None
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    format_of(path).is_some()
+}
+
+/// Splits a virtual path into the archive on disk and the entry name inside it.
+/// None for a path that isn't below an archive file.
+pub fn split_virtual_path(path: &Path) -> Option<(PathBuf, String)> {
+    let archive = path.ancestors().skip(1).find(|a| is_archive(a) && a.is_file())?;
+    let name: Vec<String> = path
+        .strip_prefix(archive)
+        .ok()?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some((archive.to_path_buf(), name.join("/")))
+}
+
+pub fn is_virtual_path(path: &Path) -> bool {
+    split_virtual_path(path).is_some()
+}
+
+/// Contents of the archive entry at virtual path `path`.
+pub fn read_virtual(path: &Path) -> io::Result<Vec<u8>> {
+    let (archive, name) = split_virtual_path(path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Not inside an archive: {}", path.display())))?;
+    let mut found = None;
+    visit_entries(&archive, &mut |entry, is_dir, _, data| {
+        if is_dir || entry != name {
+            return Ok(true);
+        }
+        found = Some(read_limited(data, MAX_ENTRY_BYTES));
+        Ok(false)
+    })?;
+    found.unwrap_or_else(|| {
+        Err(io::Error::new(io::ErrorKind::NotFound, format!("No entry '{}' in archive '{}'", name, archive.display())))
+    })
+}
+
+/// Turns every archive file among `paths` into a folder holding its entries:
+/// the archive and its inner folders join `dir_paths`, its entries join
+/// `paths`. Entries the ignore rules exclude are left out like files on disk.
+/// An archive that can't be listed stays a plain file and gets an error.
+pub fn expand_archives(
+    paths: &mut Vec<PathBuf>,
+    dir_paths: &mut HashSet<PathBuf>,
+    ignores: &CompiledIgnorePatterns,
+    errors: &mut Vec<ScanError>,
+) -> Vec<ExpandedArchive> {
+    let archives: Vec<PathBuf> = paths
+        .iter()
+        .filter(|p| !dir_paths.contains(*p) && is_archive(p))
+        .filter(|p| fs::metadata(p).is_ok_and(|m| m.len() <= MAX_ARCHIVE_BYTES))
+        .cloned()
+        .collect();
+    let mut expanded = Vec::new();
+    for archive in archives {
+        let mut dirs: BTreeSet<String> = BTreeSet::new(); // Inner folders, listed or implied by an entry
+        let mut files: BTreeMap<String, u64> = BTreeMap::new(); // A name listed twice (tar appends) keeps the last
+        let listed = visit_entries(&archive, &mut |name, is_dir, size, _| {
+            let mut parent = name;
+            while let Some((up, _)) = parent.rsplit_once('/') {
+                dirs.insert(up.to_string());
+                parent = up;
+            }
+            if is_dir {
+                dirs.insert(name.to_string());
+            } else {
+                files.insert(name.to_string(), size);
+            }
+            Ok(true)
+        });
+        if let Err(e) = listed {
+            errors.push(ScanError { path: archive.to_string_lossy().to_string(), message: format!("Couldn't open archive: {}", e) });
+            continue;
+        }
+
+        // Sorted, so a folder is decided before what's inside it
+        let mut ignored_dirs: Vec<PathBuf> = Vec::new();
+        let mut inner_dirs = Vec::new();
+        for dir in &dirs {
+            let path = archive.join(dir);
+            if ignored_dirs.iter().any(|d| path.starts_with(d)) || ignores.is_ignored(&path, true) {
+                ignored_dirs.push(path);
+            } else {
+                inner_dirs.push(path);
+            }
+        }
+        let entries: Vec<ArchiveEntry> = files
+            .into_iter()
+            .filter(|(name, _)| !dirs.contains(name))
+            .map(|(name, size)| ArchiveEntry { path: archive.join(&name), name, size })
+            .filter(|e| !ignored_dirs.iter().any(|d| e.path.starts_with(d)) && !ignores.is_ignored(&e.path, false))
+            .collect();
+
+        dir_paths.insert(archive.clone());
+        dir_paths.extend(inner_dirs.iter().cloned());
+        paths.extend(inner_dirs);
+        paths.extend(entries.iter().map(|e| e.path.clone()));
+        expanded.push(ExpandedArchive { archive, files: entries });
+    }
+    if !expanded.is_empty() {
+        let entry_count: usize = expanded.iter().map(|a| a.files.len()).sum();
+        println!("[ARCHIVES] Expanded {} archive(s) into {} entries.", expanded.len(), entry_count);
+    }
+    expanded
+}
+
+/// Counts the files of `expanded` that have no up-to-date row in `cached`, in
+/// one pass over the archive (a .tar.gz can't be read entry by entry). `done`
+/// gets every file with its fresh entry, or None when the cached row still
+/// holds. Files the archive no longer yields get an unreadable entry.
+pub fn count_entries<'c>(
+    expanded: &ExpandedArchive,
+    cached: impl Fn(&str) -> Option<&'c CacheEntry>,
+    mut done: impl FnMut(&ArchiveEntry, Option<CacheEntry>),
+) -> io::Result<()> {
+    let last_modified = fs::metadata(&expanded.archive).map(|m| file_modified_timestamp(&m))?;
+    let mut pending: HashMap<&str, &ArchiveEntry> = HashMap::new();
+    for entry in &expanded.files {
+        match cached(&entry.path.to_string_lossy()) {
+            Some(row) if row.last_modified == last_modified && row.size == entry.size => done(entry, None),
+            _ if entry.size > MAX_FILE_SIZE_BYTES => {
+                done(entry, Some(CacheEntry::skipped(last_modified.clone(), entry.size, SKIP_TOO_LARGE)))
+            }
+            _ => {
+                pending.insert(entry.name.as_str(), entry);
+            }
+        }
+    }
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let wanted: HashSet<&str> = pending.keys().copied().collect();
+    let result = visit_entries(&expanded.archive, &mut |name, is_dir, _, data| {
+        if is_dir || !wanted.contains(name) {
+            return Ok(true);
+        }
+        if let Some(entry) = pending.remove(name) {
+            let fresh = match read_limited(data, MAX_ENTRY_BYTES) {
+                Ok(bytes) => cache_entry_from_bytes(bytes, entry.size, last_modified.clone()),
+                Err(_e) => CacheEntry::skipped(last_modified.clone(), entry.size, SKIP_UNREADABLE),
+            };
+            done(entry, Some(fresh));
+        }
+        Ok(!pending.is_empty())
+    });
+    for entry in pending.into_values() {
+        done(entry, Some(CacheEntry::skipped(last_modified.clone(), entry.size, SKIP_UNREADABLE)));
+    }
+    result
+}
+
+// Entry visitor: (name, is_dir, uncompressed size, contents); false stops the walk
+type Visitor<'v> = dyn FnMut(&str, bool, u64, &mut dyn Read) -> io::Result<bool> + 'v;
+
+// Walks the entries of `archive` in order. Names are cleaned up (`/`-separated,
+// no `.`/empty components); entries that would point outside the archive are skipped.
+fn visit_entries(archive: &Path, visit: &mut Visitor) -> io::Result<()> {
+    let format = format_of(archive)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Not an archive: {}", archive.display())))?;
+    let mut clean_visit = |raw: &str, is_dir: bool, size: u64, data: &mut dyn Read| match clean_name(raw) {
+        Some(name) => visit(&name, is_dir || raw.ends_with('/'), size, data),
+        None => Ok(true),
+    };
+    let file = BufReader::new(File::open(archive)?);
+    match format {
+        Format::Zip => visit_zip(file, &mut clean_visit),
+        Format::Tar => visit_tar(file, &mut clean_visit),
+        Format::TarGz => visit_tar(GzDecoder::new(file), &mut clean_visit),
+    }
+}
+
+fn clean_name(raw: &str) -> Option<String> {
+    let normalized = raw.replace('\\', "/");
+    if normalized.starts_with('/') {
+        return None;
+    }
+    let mut parts = Vec::new();
+    for part in normalized.split('/').filter(|p| !p.is_empty() && *p != ".") {
+        if part == ".." || part.contains(':') {
+            return None;
+        }
+        parts.push(part);
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+fn read_limited(data: &mut dyn Read, limit: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    data.take(limit + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Archive entry is larger than {} bytes", limit)));
+    }
+    Ok(bytes)
+}
+
+fn visit_zip(file: BufReader<File>, visit: &mut Visitor) -> io::Result<()> {
+    let mut zip = ZipArchive::new(file).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    for index in 0..zip.len() {
+        // Encrypted entries and unsupported compression methods are skipped
+        let Ok(mut entry) = zip.by_index(index) else { continue };
+        let name = entry.name().to_string();
+        let (is_dir, size) = (entry.is_dir(), entry.size());
+        if !visit(&name, is_dir, size, &mut entry)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Minimal tar reader: ustar and GNU headers, GNU long names ('L') and pax
+// `path` records ('x'). Links and special entries are skipped.
+fn visit_tar<R: Read>(mut reader: R, visit: &mut Visitor) -> io::Result<()> {
+    let mut header = [0u8; TAR_BLOCK];
+    let mut long_name: Option<String> = None;
+    while read_block(&mut reader, &mut header)? {
+        if header.iter().all(|b| *b == 0) {
+            break; // End-of-archive marker
+        }
+        let size = tar_number(&header[124..136])?;
+        let padding = (TAR_BLOCK as u64 - size % TAR_BLOCK as u64) % TAR_BLOCK as u64;
+        let mut data = (&mut reader).take(size);
+        match header[156] {
+            b'L' => long_name = Some(c_string(&read_limited(&mut data, MAX_ENTRY_BYTES)?)),
+            b'x' => {
+                if let Some(path) = pax_path(&read_limited(&mut data, MAX_ENTRY_BYTES)?) {
+                    long_name = Some(path);
+                }
+            }
+            kind @ (0 | b'0' | b'5' | b'7') => {
+                let name = long_name.take().unwrap_or_else(|| header_name(&header));
+                if !visit(&name, kind == b'5', size, &mut data)? {
+                    return Ok(());
+                }
+            }
+            _ => long_name = None,
+        }
+        io::copy(&mut data, &mut io::sink())?;
+        io::copy(&mut (&mut reader).take(padding), &mut io::sink())?;
+    }
+    Ok(())
+}
+
+// False at a clean end of input
+fn read_block<R: Read>(reader: &mut R, block: &mut [u8; TAR_BLOCK]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < TAR_BLOCK {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated tar header")),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+// Octal, or GNU base-256 when the high bit of the first byte is set
+fn tar_number(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..].iter().fold(u64::from(field[0] & 0x7f), |n, b| (n << 8) | u64::from(*b)));
+    }
+    let text = String::from_utf8_lossy(field);
+    let digits = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(digits, 8).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("Bad tar number: {:?}", digits)))
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+fn header_name(header: &[u8; TAR_BLOCK]) -> String {
+    let name = c_string(&header[0..100]);
+    // POSIX ustar splits long names into a prefix; GNU headers use that space for other fields
+    if &header[257..263] == b"ustar\0" {
+        let prefix = c_string(&header[345..500]);
+        if !prefix.is_empty() {
+            return format!("{}/{}", prefix, name);
+        }
+    }
+    name
+}
+
+// The `path` record of a pax extended header ("<len> path=<value>\n" records)
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = data;
+    while !rest.is_empty() {
+        let space = rest.iter().position(|b| *b == b' ')?;
+        let len: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        if len <= space || len > rest.len() {
+            return None;
+        }
+        let record = &rest[space + 1..len];
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some(value) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(value).to_string());
+        }
+        rest = &rest[len..];
+    }
+    None
+}
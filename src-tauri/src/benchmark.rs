@@ -0,0 +1,172 @@
+// src-tauri/src/benchmark.rs
+// `benchmark_scan`: times enumeration and counting of a directory without the
+// file cache or any database writes, so users can report comparable numbers
+// for slow scans on their own machines.
+
+use crate::app_settings;
+use crate::db::AppState;
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::scan_tree::{file_modified_timestamp, gather_valid_items};
+use crate::scanner::{compute_cache_entry, MAX_FILE_SIZE_BYTES};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
+use tauri::{command, State};
+
+const MAX_ITERATIONS: usize = 20;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BenchmarkIteration {
+    pub enumeration_ms: u64,
+    /// Stat + read + line/token count of every file
+    pub counting_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BenchmarkTimings {
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub mean_ms: f64,
+    pub max_ms: u64,
+}
+
+impl BenchmarkTimings {
+    fn from_samples(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        BenchmarkTimings {
+            min_ms: samples[0],
+            median_ms: samples[samples.len() / 2],
+            mean_ms: samples.iter().sum::<u64>() as f64 / samples.len() as f64,
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BenchmarkReport {
+    pub path: String,
+    pub iterations: Vec<BenchmarkIteration>,
+    pub enumeration: BenchmarkTimings,
+    pub counting: BenchmarkTimings,
+    pub total: BenchmarkTimings,
+    pub file_count: usize,
+    pub dir_count: usize,
+    /// Files over the scan size limit (stat only, not read)
+    pub oversized_files: usize,
+    pub total_bytes: u64,
+    pub total_tokens: usize,
+    /// Based on the median counting time
+    pub files_per_sec: f64,
+    pub mb_per_sec: f64,
+    pub threads: usize,
+    pub ignore_pattern_count: usize,
+}
+
+#[derive(Default)]
+struct CountTotals {
+    bytes: u64,
+    tokens: usize,
+    oversized: usize,
+}
+
+// Same counting a scan does on a cache miss, for every file
+fn count_files(files: &[PathBuf]) -> CountTotals {
+    let bytes = AtomicU64::new(0);
+    let tokens = AtomicUsize::new(0);
+    let oversized = AtomicUsize::new(0);
+    files.par_iter().for_each(|p| {
+        let Ok(meta) = fs::metadata(p) else { return };
+        bytes.fetch_add(meta.len(), Ordering::Relaxed);
+        if meta.len() > MAX_FILE_SIZE_BYTES {
+            oversized.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let entry = compute_cache_entry(p, meta.len(), file_modified_timestamp(&meta), false);
+        tokens.fetch_add(entry.tokens, Ordering::Relaxed);
+    });
+    CountTotals { bytes: bytes.into_inner(), tokens: tokens.into_inner(), oversized: oversized.into_inner() }
+}
+
+fn run_benchmark(root: PathBuf, iterations: usize, ignore_patterns: Vec<String>) -> BenchmarkReport {
+    let compiled_ignores = CompiledIgnorePatterns::new(&root, &ignore_patterns);
+    let mut report = BenchmarkReport {
+        path: root.to_string_lossy().to_string(),
+        threads: rayon::current_num_threads(),
+        ignore_pattern_count: ignore_patterns.len(),
+        ..Default::default()
+    };
+
+    for i in 0..iterations {
+        let started = Instant::now();
+        let mut paths = Vec::new();
+        let mut dir_paths: HashSet<PathBuf> = HashSet::new();
+        gather_valid_items(&root, &compiled_ignores, &mut paths, &mut dir_paths, &mut Vec::new(), &mut Vec::new(), 0);
+        let enumeration_ms = started.elapsed().as_millis() as u64;
+
+        let files: Vec<PathBuf> = paths.into_iter().filter(|p| !dir_paths.contains(p)).collect();
+        let counting_started = Instant::now();
+        let totals = count_files(&files);
+        let counting_ms = counting_started.elapsed().as_millis() as u64;
+
+        report.iterations.push(BenchmarkIteration { enumeration_ms, counting_ms, total_ms: started.elapsed().as_millis() as u64 });
+        if i == 0 {
+            report.file_count = files.len();
+            report.dir_count = dir_paths.len();
+            report.oversized_files = totals.oversized;
+            report.total_bytes = totals.bytes;
+            report.total_tokens = totals.tokens;
+        }
+    }
+
+    let samples = |f: fn(&BenchmarkIteration) -> u64| report.iterations.iter().map(f).collect::<Vec<_>>();
+    report.enumeration = BenchmarkTimings::from_samples(samples(|it| it.enumeration_ms));
+    report.counting = BenchmarkTimings::from_samples(samples(|it| it.counting_ms));
+    report.total = BenchmarkTimings::from_samples(samples(|it| it.total_ms));
+    let counting_secs = report.counting.median_ms.max(1) as f64 / 1000.0;
+    report.files_per_sec = report.file_count as f64 / counting_secs;
+    report.mb_per_sec = report.total_bytes as f64 / (1024.0 * 1024.0) / counting_secs;
+    report
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Enumerates and counts `path` `iterations` times (1-20, default 3) with the
+/// global default ignore patterns, bypassing the file cache. Later iterations
+/// mostly measure a warm OS file cache; the first one is closest to a cold scan.
+#[command(async)]
+pub async fn benchmark_scan(
+    state: State<'_, AppState>,
+    path: String,
+    iterations: Option<usize>,
+) -> Result<BenchmarkReport, String> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(format!("Benchmark path is not a directory: {}", path));
+    }
+    let iterations = iterations.unwrap_or(3).clamp(1, MAX_ITERATIONS);
+    let ignore_patterns: Vec<String> = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        app_settings::get_setting_internal(&conn, "default_ignore_patterns")
+            .map_err(|e| format!("Failed to query default_ignore_patterns from app_settings: {}", e))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    };
+
+    println!("[BENCHMARK] {} iteration(s) over '{}'.", iterations, path);
+    let report = tauri::async_runtime::spawn_blocking(move || run_benchmark(root, iterations, ignore_patterns))
+        .await
+        .map_err(|e| format!("Benchmark task failed: {}", e))?;
+    println!(
+        "[BENCHMARK] {} files, median enumeration {} ms, median counting {} ms.",
+        report.file_count, report.enumeration.median_ms, report.counting.median_ms
+    );
+    Ok(report)
+}
@@ -0,0 +1,237 @@
+// src-tauri/src/cache_maintenance.rs
+// Maintenance commands for the shared file cache (`code_context_builder_file_cache`).
+// Rows aren't tagged with a project; a project's rows are the ones under its root.
+
+use crate::db::AppState;
+use crate::errors::{self, app_error};
+use crate::path_case::CaseMode;
+use crate::projects;
+use crate::scan_cache::{self, root_prefix};
+use rayon::prelude::*;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{command, State};
+
+// Approximate stored size of a cache row: its text columns plus five 8-byte integers
+const ROW_SIZE_SQL: &str =
+    "length(file_path) + length(last_modified) + coalesce(length(skipped_reason), 0) + 40";
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CacheCleanupReport {
+    pub rows_removed: usize,
+    /// Rows whose path isn't under any project's root
+    pub outside_projects: usize,
+    /// Rows under a project root whose file no longer exists
+    pub missing_on_disk: usize,
+    /// Estimated payload freed (the DB file itself only shrinks after a VACUUM)
+    pub bytes_reclaimed: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProjectCacheStats {
+    pub project_id: i32,
+    pub title: String,
+    pub row_count: usize,
+    pub total_tokens: usize,
+    pub last_scanned_at: Option<String>,
+    /// Files the last full scan reused from the cache
+    pub last_scan_hits: Option<usize>,
+    /// Files the last full scan had to recount
+    pub last_scan_misses: Option<usize>,
+    pub last_scan_hit_ratio: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CacheStats {
+    pub row_count: usize,
+    pub total_tokens: usize,
+    /// Sum of the cached files' sizes on disk
+    pub total_file_bytes: u64,
+    /// When the least/most recently (re)counted rows were written; rows from
+    /// before this was tracked have no timestamp and are left out
+    pub oldest_entry_at: Option<String>,
+    pub newest_entry_at: Option<String>,
+    pub projects: Vec<ProjectCacheStats>,
+    /// Rows under no project's root (see `cleanup_orphaned_cache`)
+    pub unassigned_rows: usize,
+}
+
+// (root prefix, case mode) of every project with a root folder
+fn project_root_prefixes(conn: &rusqlite::Connection) -> Result<Vec<(String, CaseMode)>, String> {
+    Ok(projects::load_all_projects(conn)?
+        .iter()
+        .filter_map(|p| p.root_folder.as_deref().map(|root| (root_prefix(root), CaseMode::for_project(p))))
+        .collect())
+}
+
+fn hit_ratio(hits: Option<usize>, misses: Option<usize>) -> Option<f64> {
+    match (hits, misses) {
+        (Some(h), Some(m)) if h + m > 0 => Some(h as f64 / (h + m) as f64),
+        _ => None,
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Size and effectiveness of the file cache, overall and per project.
+#[command]
+pub fn get_cache_stats(state: State<AppState>) -> Result<CacheStats, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let (row_count, total_tokens, total_file_bytes, oldest_entry_at, newest_entry_at) = conn
+        .query_row(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(tokens), 0), COALESCE(SUM(size), 0), MIN(cached_at), MAX(cached_at)
+            FROM code_context_builder_file_cache
+            "#,
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as usize,
+                    row.get::<_, i64>(2)? as u64,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("Failed to query cache totals: {}", e))?;
+
+    let mut projects = Vec::new();
+    for project in projects::load_all_projects(&conn)? {
+        let Some(root) = project.root_folder.as_deref() else { continue };
+        let (rows, tokens) = scan_cache::count_entries_under(&conn, root, CaseMode::for_project(&project))
+            .map_err(|e| format!("Failed to count cache rows for project {}: {}", project.id, e))?;
+        let last_scan: Option<(String, Option<i64>, Option<i64>)> = conn
+            .query_row(
+                "SELECT scanned_at, cache_hits, cache_misses FROM code_context_builder_scan_results WHERE project_id = ?1",
+                params![project.id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query last scan of project {}: {}", project.id, e))?;
+        let (scanned_at, hits, misses) = match last_scan {
+            Some((at, h, m)) => (Some(at), h.map(|v| v as usize), m.map(|v| v as usize)),
+            None => (None, None, None),
+        };
+        projects.push(ProjectCacheStats {
+            project_id: project.id,
+            title: project.title.clone(),
+            row_count: rows,
+            total_tokens: tokens,
+            last_scanned_at: scanned_at,
+            last_scan_hits: hits,
+            last_scan_misses: misses,
+            last_scan_hit_ratio: hit_ratio(hits, misses),
+        });
+    }
+    // Nested project roots share rows, so the per-project counts can't just be subtracted
+    let roots = project_root_prefixes(&conn)?;
+    let mut stmt = conn
+        .prepare("SELECT file_path FROM code_context_builder_file_cache")
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let paths = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Query file cache failed: {}", e))?;
+    let mut unassigned_rows = 0;
+    for path in paths {
+        let path = path.map_err(|e| format!("Failed to map cache row: {}", e))?;
+        if !roots.iter().any(|(prefix, mode)| mode.starts_with(&path, prefix)) {
+            unassigned_rows += 1;
+        }
+    }
+
+    Ok(CacheStats {
+        row_count,
+        total_tokens,
+        total_file_bytes,
+        oldest_entry_at,
+        newest_entry_at,
+        projects,
+        unassigned_rows,
+    })
+}
+
+
+/// Deletes the project's cache rows so the next scan recounts every file
+/// (e.g. when counts look wrong). Returns the number of rows removed.
+#[command]
+pub fn clear_project_cache(state: State<AppState>, project_id: i32) -> Result<usize, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let project = projects::load_project_by_id(&conn, project_id)?;
+    let root = project
+        .root_folder
+        .as_deref()
+        .ok_or_else(|| app_error(errors::PROJECT_NO_ROOT, &[("project_id", project_id.to_string())]))?;
+    let removed = scan_cache::delete_entries_under(&conn, root, CaseMode::for_project(&project))
+        .map_err(|e| format!("Failed to clear cache for project {}: {}", project_id, e))?;
+    println!("[CACHE] Cleared {} cache rows for project {}.", removed, project_id);
+    Ok(removed)
+}
+
+/// Removes cache rows that no project can use anymore: paths outside every
+/// project's root (e.g. left behind by a deleted project) and files that no
+/// longer exist on disk.
+#[command(async)]
+pub async fn cleanup_orphaned_cache(state: State<'_, AppState>) -> Result<CacheCleanupReport, String> {
+    let (roots, rows) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let roots = project_root_prefixes(&conn)?;
+        let mut stmt = conn
+            .prepare(&format!("SELECT file_path, {} FROM code_context_builder_file_cache", ROW_SIZE_SQL))
+            .map_err(|e| format!("Prepare statement failed: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+            .map_err(|e| format!("Query file cache failed: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to map cache row: {}", e))?;
+        (roots, rows)
+    };
+
+    // (path, bytes, outside any project?) for every orphan; stat'ing runs without the DB lock
+    let orphans: Vec<(String, i64, bool)> = tauri::async_runtime::spawn_blocking(move || {
+        rows.into_par_iter()
+            .filter_map(|(path, bytes)| {
+                let in_project = roots.iter().any(|(prefix, mode)| mode.starts_with(&path, prefix));
+                if !in_project {
+                    Some((path, bytes, true))
+                } else if !Path::new(&path).exists() {
+                    Some((path, bytes, false))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Cache cleanup task failed: {}", e))?;
+
+    let mut report = CacheCleanupReport::default();
+    if orphans.is_empty() {
+        return Ok(report);
+    }
+    let mut conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    {
+        let mut delete_stmt = tx
+            .prepare("DELETE FROM code_context_builder_file_cache WHERE file_path = ?1")
+            .map_err(|e| format!("Prepare statement failed: {}", e))?;
+        for (path, bytes, outside) in &orphans {
+            report.rows_removed += delete_stmt
+                .execute(params![path])
+                .map_err(|e| format!("Failed to delete cache row '{}': {}", path, e))?;
+            report.bytes_reclaimed += *bytes as u64;
+            if *outside {
+                report.outside_projects += 1;
+            } else {
+                report.missing_on_disk += 1;
+            }
+        }
+    }
+    tx.commit().map_err(|e| format!("Failed to commit cache cleanup: {}", e))?;
+    println!(
+        "[CACHE] Removed {} orphaned cache rows (~{} bytes).",
+        report.rows_removed, report.bytes_reclaimed
+    );
+    Ok(report)
+}
@@ -0,0 +1,67 @@
+// src-tauri/src/change_journal.rs
+// Persistent log of file changes observed by the monitor, so the UI can ask
+// "what changed since X" without rescanning.
+
+use crate::db::AppState;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::{command, State};
+
+pub const CHANGE_MODIFIED: &str = "modified";
+pub const CHANGE_DELETED: &str = "deleted";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChangeRecord {
+    pub path: String,
+    pub kind: String,
+    pub observed_at: String,
+}
+
+/// Appends one observed change (timestamped now, RFC 3339 UTC).
+pub fn record_change(conn: &Connection, project_id: i32, path: &str, kind: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO change_journal (project_id, path, kind, observed_at) VALUES (?1, ?2, ?3, ?4)",
+        params![project_id, path, kind, Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to record change for '{}': {}", path, e))?;
+    Ok(())
+}
+
+pub fn load_changes_since(conn: &Connection, project_id: i32, since: &str) -> Result<Vec<ChangeRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT path, kind, observed_at
+            FROM change_journal
+            WHERE project_id = ?1 AND observed_at > ?2
+            ORDER BY observed_at ASC, id ASC
+            "#,
+        )
+        .map_err(|e| format!("Prepare change journal query failed: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![project_id, since], |row| {
+            Ok(ChangeRecord { path: row.get(0)?, kind: row.get(1)?, observed_at: row.get(2)? })
+        })
+        .map_err(|e| format!("Query change journal failed: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map change journal row: {}", e))
+}
+
+/// Changes observed for a project after `since` (RFC 3339 timestamp), oldest first.
+#[command]
+pub fn get_changes_since(
+    state: State<AppState>,
+    project_id: i32,
+    since: String,
+) -> Result<Vec<ChangeRecord>, String> {
+    // Normalize to the stored format so string comparison in SQL is chronological
+    let since_utc = chrono::DateTime::parse_from_rfc3339(&since)
+        .map_err(|e| format!("Invalid timestamp '{}': {}", since, e))?
+        .with_timezone(&Utc)
+        .to_rfc3339();
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_changes_since(&conn, project_id, &since_utc)
+}
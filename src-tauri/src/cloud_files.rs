@@ -0,0 +1,53 @@
+// src-tauri/src/cloud_files.rs
+// Detection of cloud-sync placeholder files (OneDrive Files On-Demand, Dropbox
+// online-only, iCloud "dataless" files). Reading one triggers a download, so
+// scans can skip them or record size only, per the `cloud_placeholder_mode` setting.
+
+use crate::app_settings;
+use std::fs;
+
+pub const CLOUD_PLACEHOLDER_SETTING: &str = "cloud_placeholder_mode";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaceholderPolicy {
+    /// Read placeholders like any other file (hydrates them). Default, matches old behavior.
+    Read,
+    /// Leave placeholders out of the scan entirely.
+    Skip,
+    /// Keep them in the tree with their size, but never read content (0 lines/tokens).
+    StatOnly,
+}
+
+/// Reads the policy from app_settings ("read" | "skip" | "stat_only").
+pub fn load_policy(conn: &rusqlite::Connection) -> PlaceholderPolicy {
+    match app_settings::get_setting_internal(conn, CLOUD_PLACEHOLDER_SETTING) {
+        Ok(Some(v)) => match v.trim() {
+            "skip" => PlaceholderPolicy::Skip,
+            "stat_only" => PlaceholderPolicy::StatOnly,
+            _ => PlaceholderPolicy::Read,
+        },
+        _ => PlaceholderPolicy::Read,
+    }
+}
+
+/// True when the file's content isn't stored locally and a read would hydrate it.
+#[cfg(windows)]
+pub fn is_cloud_placeholder(meta: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+    meta.file_attributes() & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) != 0
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_cloud_placeholder(meta: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const SF_DATALESS: u32 = 0x4000_0000; // File Provider (iCloud/Dropbox/OneDrive) dataless file
+    meta.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn is_cloud_placeholder(_meta: &fs::Metadata) -> bool {
+    false
+}
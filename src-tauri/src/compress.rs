@@ -1,19 +1,40 @@
 // src-tauri/src/compress.rs
-use rayon::prelude::*; // Import Rayon for parallel processing
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use tree_sitter::{Node, Parser, Query, QueryCursor};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::compression_defaults::CompressionDefaults;
+use crate::db::AppState;
+use crate::document_extract;
+use crate::file_notes;
+use crate::file_types::{read_for_context, ReadOutcome};
+use crate::path_canon::canonical_paths;
+use crate::redaction::{RedactionHit, Redactor};
+use crate::read_limits::{apply_limits, ReadLimits, Truncation};
+use crate::scanner::read_batch;
+use tauri::State;
 
 // --- Types for Tauri Command ---
 
 // THE FAULTY FileResult ENUM HAS BEEN REMOVED.
 
-#[derive(Deserialize, Default, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct SmartCompressOptions {
+    #[serde(default)]
     pub remove_comments: bool,
+    /// When set, `.csv`/`.tsv`/`.jsonl` files keep only their header plus this many rows.
+    #[serde(default)]
+    pub data_head_rows: Option<usize>,
+    /// Replace lockfiles with a compact `name version` dependency list.
+    #[serde(default)]
+    pub summarize_lockfiles: bool,
+    /// Drop the `<style>` blocks of Vue/Svelte components.
+    #[serde(default)]
+    pub drop_styles: bool,
 }
 
 // --- Internal Struct for Edits ---
@@ -41,8 +62,49 @@ fn clean_blank_lines(text: String) -> String {
 
 // --- Compressor Trait (Strategy Pattern) ---
 
+/// Which strategy produced a file's compressed output.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionMethod {
+    /// Tree-sitter AST compressor.
+    Ast,
+    /// Format-aware rewriting without a grammar (data heads, lockfiles, schemas).
+    Structural,
+    /// Regex/indentation fallback used when no grammar applies or parsing failed.
+    Heuristic,
+    /// Content returned unchanged (binary placeholder, extracted document, unknown type).
+    None,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompressedFile {
+    pub content: String,
+    pub method: CompressionMethod,
+    /// Secrets replaced before compression (see `redaction`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<RedactionHit>,
+    /// Why the structured compressor wasn't used (e.g. a grammar that failed to load)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_reason: Option<String>,
+    /// Set when a batch read size cap cut the content (see read_limits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+}
+
 trait Compressor {
-    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> String;
+    /// Returns `Err` when the structured (AST) path couldn't handle the source,
+    /// so the caller can fall back to the heuristic compressor.
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String>;
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Ast
+    }
+
+    /// False when `opts` turn this compressor off; the file is then read as
+    /// if it had none (and reported as not compressed).
+    fn enabled(&self, _opts: &SmartCompressOptions) -> bool {
+        true
+    }
 }
 
 // --- Python Compressor Implementation ---
@@ -50,14 +112,18 @@ trait Compressor {
 struct PythonCompressor;
 
 impl Compressor for PythonCompressor {
-    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> String {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
         let language = tree_sitter_python::language();
         let mut parser = Parser::new();
-        parser.set_language(language).expect("Error loading Python grammar");
+        parser.set_language(language).map_err(|e| format!("Failed to load Python grammar: {}", e))?;
         let tree = match parser.parse(source, None) {
             Some(t) => t,
-            None => return source.to_string(),
+            None => return Err("tree-sitter parse failed".to_string()),
         };
+        // Edits on a tree with error nodes mangle the source; let the heuristic path take it
+        if tree.root_node().has_error() {
+            return Err("Python source has syntax errors".to_string());
+        }
 
         let mut edits = Vec::new();
         let src_bytes = source.as_bytes();
@@ -112,7 +178,7 @@ impl Compressor for PythonCompressor {
                 out.replace_range(edit.start..edit.end, &edit.replacement);
             }
         }
-        clean_blank_lines(out)
+        Ok(clean_blank_lines(out))
     }
 }
 
@@ -145,14 +211,18 @@ fn extend_to_eol(bytes: &[u8], end: usize) -> usize {
 struct TsxCompressor;
 
 impl Compressor for TsxCompressor {
-    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> String {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
         let language = tree_sitter_typescript::language_tsx();
         let mut parser = Parser::new();
-        parser.set_language(language).expect("Error loading TSX grammar");
+        parser.set_language(language).map_err(|e| format!("Failed to load TSX grammar: {}", e))?;
         let tree = match parser.parse(source, None) {
             Some(t) => t,
-            None => return source.to_string(),
+            None => return Err("tree-sitter parse failed".to_string()),
         };
+        // Edits on a tree with error nodes mangle the source; let the heuristic path take it
+        if tree.root_node().has_error() {
+            return Err("TSX source has syntax errors".to_string());
+        }
 
         let src_bytes = source.as_bytes();
         let mut edits = Vec::new();
@@ -168,10 +238,25 @@ impl Compressor for TsxCompressor {
             (lexical_declaration
               (variable_declarator
                 name: (identifier) @func_name (#not-match? @func_name "^[A-Z]")
-                value: (arrow_function body: (statement_block) @body)))
+                value: [(arrow_function body: (statement_block) @body) (function_expression body: (statement_block) @body)]))
+            (variable_declaration
+              (variable_declarator
+                name: (identifier) @func_name (#not-match? @func_name "^[A-Z]")
+                value: [(arrow_function body: (statement_block) @body) (function_expression body: (statement_block) @body)]))
+            (assignment_expression
+              left: (member_expression) @export_target (#match? @export_target "^(module\\.)?exports\\b")
+              right: [(arrow_function body: (statement_block) @body) (function_expression body: (statement_block) @body)])
+            (assignment_expression
+              left: (member_expression) @export_target (#match? @export_target "^(module\\.)?exports\\b")
+              right: (object [
+                (method_definition body: (statement_block) @body)
+                (pair value: [(arrow_function body: (statement_block) @body) (function_expression body: (statement_block) @body)])
+              ]))
         "#;
         
-        let query = Query::new(language, query_text).unwrap();
+        // Also covers plain JavaScript, CommonJS included: `var f = function`,
+        // `module.exports = function`, `exports.f = () =>` and `module.exports = { f() {} }`
+        let query = Query::new(language, query_text).map_err(|e| format!("Invalid TSX query: {}", e))?;
         let mut cursor = QueryCursor::new();
         let matches = cursor.matches(&query, tree.root_node(), src_bytes);
 
@@ -195,57 +280,884 @@ impl Compressor for TsxCompressor {
             }
         }
         
+        // Edits inside a collapsed body (nested functions, comments) are covered by it
+        edits.sort_by_key(|e| (e.start, std::cmp::Reverse(e.end)));
+        let mut kept: Vec<Edit> = Vec::with_capacity(edits.len());
+        for edit in edits {
+            if kept.last().is_some_and(|outer| edit.start < outer.end) {
+                continue;
+            }
+            kept.push(edit);
+        }
+        kept.reverse();
+        let mut out = source.to_string();
+        for edit in kept {
+            if edit.start < edit.end && edit.end <= out.len() {
+                out.replace_range(edit.start..edit.end, &edit.replacement);
+            }
+        }
+        Ok(clean_blank_lines(out))
+    }
+}
+
+// --- C/C++ Compressor Implementation ---
+// Function definitions become prototypes; declarations (struct/union/enum,
+// class, typedef), preprocessor lines and includes are kept as written.
+// Headers are the interface, so their short inline helpers keep their bodies;
+// implementation files also collapse large initializer tables (lookup tables,
+// register maps). A `.h` is parsed as C, or as C++ when only that parses cleanly.
+
+// Inline function bodies of at most this many lines are kept in headers
+const HEADER_INLINE_MAX_LINES: usize = 3;
+// Initializer lists with more entries are collapsed in implementation files
+const TABLE_MIN_ENTRIES: usize = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CDialect {
+    C,
+    Cpp,
+    // `.h`: C first, C++ when the C parse has errors
+    Either,
+}
+
+// A grammar's `language()` function
+type GrammarFn = fn() -> tree_sitter::Language;
+
+struct CCompressor {
+    dialect: CDialect,
+    header: bool,
+}
+
+impl CCompressor {
+    fn parse(&self, source: &str) -> Result<tree_sitter::Tree, String> {
+        let languages: &[(GrammarFn, &str)] = match self.dialect {
+            CDialect::C => &[(tree_sitter_c::language, "C")],
+            CDialect::Cpp => &[(tree_sitter_cpp::language, "C++")],
+            CDialect::Either => &[(tree_sitter_c::language, "C"), (tree_sitter_cpp::language, "C++")],
+        };
+        for (language, name) in languages {
+            let mut parser = Parser::new();
+            parser.set_language(language()).map_err(|e| format!("Failed to load {} grammar: {}", name, e))?;
+            let tree = parser.parse(source, None).ok_or_else(|| "tree-sitter parse failed".to_string())?;
+            if !tree.root_node().has_error() {
+                return Ok(tree);
+            }
+        }
+        Err("C/C++ source has syntax errors".to_string())
+    }
+}
+
+impl Compressor for CCompressor {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
+        let tree = self.parse(source)?;
+        let src_bytes = source.as_bytes();
+        let mut edits = Vec::new();
+        let mut cursor = tree.root_node().walk();
+
+        'outer: loop {
+            let node = cursor.node();
+            // A replaced node's children aren't visited, so edits never overlap
+            let mut replaced = false;
+
+            if opts.remove_comments && node.kind() == "comment" {
+                let line_start = sol_of(src_bytes, node.start_byte());
+                let edit = if source[line_start..node.start_byte()].trim().is_empty() {
+                    Edit { start: line_start, end: extend_to_eol(src_bytes, node.end_byte()), replacement: String::new() }
+                } else {
+                    // Trailing comment: the code before it stays
+                    let start = source[..node.start_byte()].trim_end_matches([' ', '\t']).len();
+                    Edit { start, end: node.end_byte(), replacement: String::new() }
+                };
+                edits.push(edit);
+                replaced = true;
+            }
+
+            if node.kind() == "function_definition" {
+                let body = node.child_by_field_name("body").filter(|b| b.kind() == "compound_statement");
+                if let (Some(declarator), Some(body)) = (node.child_by_field_name("declarator"), body) {
+                    let body_lines = body.end_position().row - body.start_position().row + 1;
+                    if !(self.header && body_lines <= HEADER_INLINE_MAX_LINES) {
+                        // From the end of the signature: drops constructor initializer lists too
+                        edits.push(Edit { start: declarator.end_byte(), end: body.end_byte(), replacement: ";".to_string() });
+                        replaced = true;
+                    }
+                }
+            }
+
+            if !self.header && node.kind() == "initializer_list" && node.named_child_count() > TABLE_MIN_ENTRIES
+                && node.start_position().row != node.end_position().row
+                && node.parent().is_some_and(|p| p.kind() == "init_declarator")
+            {
+                edits.push(Edit { start: node.start_byte(), end: node.end_byte(), replacement: "{ ... }".to_string() });
+                replaced = true;
+            }
+
+            if !replaced && cursor.goto_first_child() { continue; }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() { break 'outer; }
+            }
+        }
+
         edits.sort_by_key(|e| e.start);
         edits.reverse();
         let mut out = source.to_string();
         for edit in edits {
-            if edit.start < edit.end && edit.end <= out.len() {
+            if edit.start <= edit.end && edit.end <= out.len() {
                 out.replace_range(edit.start..edit.end, &edit.replacement);
             }
         }
-        clean_blank_lines(out)
+        Ok(clean_blank_lines(out))
+    }
+}
+
+// --- Vue / Svelte Single-File Component Compressor ---
+// A component is split at its top-level `<script>` and `<style>` blocks. Scripts
+// in JS/TS go through the TSX compressor (kept as written when it fails or the
+// language is another one), styles are kept or dropped (`drop_styles`), and the
+// markup between them keeps its first MARKUP_KEEP_LEVELS levels of nesting,
+// deeper markup collapsing to `...`.
+
+// Nesting levels of markup kept (a Vue `<template>` counts as one)
+const MARKUP_KEEP_LEVELS: usize = 4;
+
+static SFC_BLOCK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<script\b([^>]*)>(.*?)</script\s*>|<style\b([^>]*)>(.*?)</style\s*>").expect("valid SFC block regex")
+});
+static SFC_LANG_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\blang\s*=\s*["']?([\w-]+)"#).expect("valid SFC lang regex")
+});
+static HTML_COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<!--.*?-->").expect("valid HTML comment regex"));
+
+struct SfcCompressor;
+
+impl Compressor for SfcCompressor {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
+        let mut out = String::with_capacity(source.len());
+        let mut markup_start = 0;
+        for caps in SFC_BLOCK_RE.captures_iter(source) {
+            let block = caps.get(0).expect("whole match");
+            out.push_str(&compress_markup(&source[markup_start..block.start()], opts));
+            markup_start = block.end();
+            match (caps.get(1), caps.get(2)) {
+                (Some(attrs), Some(body)) => {
+                    let is_js = SFC_LANG_RE.captures(attrs.as_str()).is_none_or(|lang| {
+                        matches!(lang[1].to_ascii_lowercase().as_str(), "js" | "jsx" | "ts" | "tsx" | "javascript" | "typescript")
+                    });
+                    let script = match is_js {
+                        true => TsxCompressor.compress(body.as_str(), opts).unwrap_or_else(|_| body.as_str().to_string()),
+                        false => body.as_str().to_string(),
+                    };
+                    out.push_str(&format!("<script{}>\n{}\n</script>", attrs.as_str(), script.trim_matches('\n')));
+                }
+                _ if opts.drop_styles => (),
+                _ => out.push_str(block.as_str()),
+            }
+        }
+        out.push_str(&compress_markup(&source[markup_start..], opts));
+        Ok(clean_blank_lines(out))
+    }
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Structural
+    }
+}
+
+fn compress_markup(markup: &str, opts: &SmartCompressOptions) -> String {
+    let mut collapsed = if opts.remove_comments {
+        collapse_below_level(&HTML_COMMENT_RE.replace_all(markup, ""), MARKUP_KEEP_LEVELS)
+    } else {
+        collapse_below_level(markup, MARKUP_KEEP_LEVELS)
+    };
+    // Keeps the next block on its own line
+    if markup.ends_with('\n') && !collapsed.ends_with('\n') {
+        collapsed.push('\n');
+    }
+    collapsed
+}
+
+// --- Data File (CSV/TSV/JSONL) Head Truncation ---
+
+struct DataHeadCompressor {
+    has_header: bool,
+}
+
+impl Compressor for DataHeadCompressor {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
+        let max_rows = match opts.data_head_rows {
+            Some(n) => n,
+            None => return Ok(source.to_string()),
+        };
+        // Line-based on purpose: quoted multi-line CSV fields are rare enough
+        // that a cheap split beats pulling in a full CSV parser here.
+        let mut lines = source.lines().filter(|l| !l.trim().is_empty());
+        let mut kept: Vec<&str> = Vec::new();
+        if self.has_header {
+            if let Some(header) = lines.next() {
+                kept.push(header);
+            }
+        }
+        kept.extend(lines.by_ref().take(max_rows));
+        let remaining = lines.count();
+        let mut out = kept.join("\n");
+        if remaining > 0 {
+            out.push_str(&format!("\n... ({} more rows)", format_thousands(remaining)));
+        }
+        Ok(out)
+    }
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Structural
+    }
+
+    fn enabled(&self, opts: &SmartCompressOptions) -> bool {
+        opts.data_head_rows.is_some()
+    }
+}
+
+fn format_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    digits
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// --- Lockfile / Manifest Summarizer ---
+
+#[derive(Clone, Copy)]
+enum LockfileKind {
+    NpmLock,
+    PackageJson,
+    TomlPackages, // Cargo.lock, poetry.lock, uv.lock
+    GoSum,
+}
+
+struct LockfileSummarizer {
+    kind: LockfileKind,
+}
+
+impl Compressor for LockfileSummarizer {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
+        if !opts.summarize_lockfiles {
+            return Ok(source.to_string());
+        }
+        let deps = match self.kind {
+            LockfileKind::NpmLock => npm_lock_deps(source),
+            LockfileKind::PackageJson => package_json_deps(source),
+            LockfileKind::TomlPackages => toml_package_deps(source),
+            LockfileKind::GoSum => go_sum_deps(source),
+        };
+        let deps = match deps {
+            Some(d) if !d.is_empty() => d,
+            _ => return Err("Unrecognized lockfile format".to_string()),
+        };
+        let mut out = format!("# Dependency summary ({} entries)\n", deps.len());
+        for (name, version) in deps {
+            out.push_str(&format!("{} {}\n", name, version));
+        }
+        Ok(out)
+    }
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Structural
+    }
+
+    fn enabled(&self, opts: &SmartCompressOptions) -> bool {
+        opts.summarize_lockfiles
+    }
+}
+
+fn npm_lock_deps(source: &str) -> Option<Vec<(String, String)>> {
+    let json: serde_json::Value = serde_json::from_str(source).ok()?;
+    let mut deps: Vec<(String, String)> = Vec::new();
+    // lockfileVersion 2/3: "packages" keyed by "node_modules/<name>"
+    if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
+        for (key, info) in packages {
+            if key.is_empty() { continue; } // root package
+            let name = key.rsplit("node_modules/").next().unwrap_or(key);
+            let version = info.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+            deps.push((name.to_string(), version.to_string()));
+        }
+    } else if let Some(dependencies) = json.get("dependencies").and_then(|d| d.as_object()) {
+        // lockfileVersion 1
+        for (name, info) in dependencies {
+            let version = info.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+            deps.push((name.clone(), version.to_string()));
+        }
+    }
+    deps.sort();
+    deps.dedup();
+    Some(deps)
+}
+
+fn package_json_deps(source: &str) -> Option<Vec<(String, String)>> {
+    let json: serde_json::Value = serde_json::from_str(source).ok()?;
+    let mut deps = Vec::new();
+    for section in ["dependencies", "devDependencies", "peerDependencies", "optionalDependencies"] {
+        if let Some(map) = json.get(section).and_then(|d| d.as_object()) {
+            for (name, version) in map {
+                deps.push((name.clone(), version.as_str().unwrap_or("?").to_string()));
+            }
+        }
+    }
+    deps.sort();
+    Some(deps)
+}
+
+fn toml_package_deps(source: &str) -> Option<Vec<(String, String)>> {
+    let mut deps = Vec::new();
+    let mut name: Option<String> = None;
+    for line in source.lines() {
+        let line = line.trim();
+        if line == "[[package]]" {
+            name = None;
+        } else if let Some(v) = toml_string_value(line, "name") {
+            name = Some(v);
+        } else if let Some(v) = toml_string_value(line, "version") {
+            if let Some(n) = name.take() {
+                deps.push((n, v));
+            }
+        }
+    }
+    Some(deps)
+}
+
+fn toml_string_value(line: &str, key: &str) -> Option<String> {
+    let rest = line.strip_prefix(key)?.trim_start().strip_prefix('=')?.trim();
+    Some(rest.trim_matches('"').to_string())
+}
+
+fn go_sum_deps(source: &str) -> Option<Vec<(String, String)>> {
+    let mut deps: Vec<(String, String)> = source
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let module = parts.next()?;
+            let version = parts.next()?.trim_end_matches("/go.mod");
+            Some((module.to_string(), version.to_string()))
+        })
+        .collect();
+    deps.sort();
+    deps.dedup();
+    Some(deps)
+}
+
+// --- GraphQL / Protobuf Schema Compressor ---
+// Schema definitions are already compact, so nothing is elided: comments are
+// stripped and top-level definitions reordered so services/root operation
+// types come first (after proto header statements like `syntax`/`import`).
+
+#[derive(Clone, Copy, PartialEq)]
+enum SchemaDialect {
+    GraphQl,
+    Proto,
+}
+
+struct SchemaCompressor {
+    dialect: SchemaDialect,
+}
+
+impl Compressor for SchemaCompressor {
+    fn compress(&self, source: &str, _opts: &SmartCompressOptions) -> Result<String, String> {
+        let stripped = strip_schema_comments(source, self.dialect);
+        let trimmed: String = stripped.lines().map(|l| l.trim_end()).collect::<Vec<_>>().join("\n");
+
+        let mut header = Vec::new();
+        let mut services = Vec::new();
+        let mut rest = Vec::new();
+        for block in split_top_level_blocks(&trimmed) {
+            let keywords: Vec<&str> = schema_block_keywords(&block);
+            let first = keywords.first().copied().unwrap_or("");
+            let second = keywords.get(1).copied().unwrap_or("");
+            let is_header = matches!(first, "syntax" | "edition" | "package" | "import" | "option");
+            let is_service = match self.dialect {
+                SchemaDialect::Proto => first == "service",
+                SchemaDialect::GraphQl => {
+                    first == "schema"
+                        || (first == "type" && matches!(second, "Query" | "Mutation" | "Subscription"))
+                        || (first == "extend" && keywords.get(2).is_some_and(|n| matches!(*n, "Query" | "Mutation" | "Subscription")))
+                }
+            };
+            if is_header && self.dialect == SchemaDialect::Proto {
+                header.push(block);
+            } else if is_service {
+                services.push(block);
+            } else {
+                rest.push(block);
+            }
+        }
+
+        let header_text = header.iter().map(|b| b.trim_end()).collect::<Vec<_>>().join("\n");
+        let mut sections: Vec<String> = Vec::new();
+        if !header_text.is_empty() {
+            sections.push(header_text);
+        }
+        sections.extend(services.iter().chain(rest.iter()).map(|b| b.trim_end().to_string()));
+        Ok(clean_blank_lines(sections.join("\n\n")))
+    }
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Structural
+    }
+}
+
+fn strip_schema_comments(source: &str, dialect: SchemaDialect) -> String {
+    let bytes = source.as_bytes();
+    let line_end = |from: usize| bytes[from..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |p| from + p);
+    let mut out = String::with_capacity(source.len());
+    let mut seg_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(b"\"\"\"") {
+            // GraphQL block string (description): keep verbatim
+            i = find_bytes(bytes, i + 3, b"\"\"\"").map_or(bytes.len(), |p| p + 3);
+            continue;
+        }
+        if bytes[i] == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                if bytes[i] == b'\\' { i += 1; }
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        let comment_end = match dialect {
+            SchemaDialect::GraphQl if bytes[i] == b'#' => Some(line_end(i)),
+            SchemaDialect::Proto if rest.starts_with(b"//") => Some(line_end(i)),
+            SchemaDialect::Proto if rest.starts_with(b"/*") => {
+                Some(find_bytes(bytes, i + 2, b"*/").map_or(bytes.len(), |p| p + 2))
+            }
+            _ => None,
+        };
+        if let Some(end) = comment_end {
+            out.push_str(&source[seg_start..i]);
+            seg_start = end;
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    out.push_str(&source[seg_start.min(source.len())..]);
+    out
+}
+
+fn find_bytes(haystack: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if from >= haystack.len() { return None; }
+    haystack[from..].windows(needle.len()).position(|w| w == needle).map(|p| from + p)
+}
+
+/// Splits schema text into top-level definitions. A definition starts on a
+/// non-indented line at brace depth 0; descriptions stay attached to the
+/// definition that follows them.
+fn split_top_level_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    let mut depth: i32 = 0;
+    let mut in_block_string = false;
+    for line in text.lines() {
+        let starts_definition = !in_block_string
+            && depth <= 0
+            && line.starts_with(|c: char| !c.is_whitespace() && !matches!(c, '|' | '&' | '@' | '=' | '}' | ')'));
+        let current_is_description = current.trim_start().starts_with('"') && !current.contains('{');
+        if starts_definition && !current.trim().is_empty() && !current_is_description {
+            blocks.push(std::mem::take(&mut current));
+        }
+        if line.trim().is_empty() && depth <= 0 && !in_block_string {
+            continue;
+        }
+        current.push_str(line);
+        current.push('\n');
+        if line.matches("\"\"\"").count() % 2 == 1 {
+            in_block_string = !in_block_string;
+        }
+        if !in_block_string {
+            depth += bracket_delta(line);
+        }
+    }
+    if !current.trim().is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+fn bracket_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut in_string = false;
+    for c in line.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '{' | '(' if !in_string => delta += 1,
+            '}' | ')' if !in_string => delta -= 1,
+            _ => (),
+        }
+    }
+    delta
+}
+
+/// Leading identifiers of a definition, skipping any description string.
+fn schema_block_keywords(block: &str) -> Vec<&str> {
+    let mut body = block.trim_start();
+    if let Some(after) = body.strip_prefix("\"\"\"") {
+        body = after.find("\"\"\"").map_or("", |p| &after[p + 3..]);
+    } else if body.starts_with('"') {
+        body = body.find('\n').map_or("", |p| &body[p + 1..]);
+    }
+    body.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .take(3)
+        .collect()
+}
+
+// --- Heuristic (Grammar-less) Compressor ---
+// Fallback for languages without a bundled grammar, or when a parse fails:
+// strips comments with a string-aware regex and collapses deeply indented
+// runs (function bodies, nested blocks) into a single `...` line.
+
+#[derive(Clone, Copy)]
+enum CommentStyle {
+    CLike, // `//` and `/* */`
+    Hash,  // `#`
+    DashDash, // `--` (Lua, SQL, Haskell)
+}
+
+static C_LIKE_COMMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""(?:\\.|[^"\\])*"|'(?:\\.|[^'\\\n])*'|/\*[\s\S]*?\*/|//[^\n]*"#).expect("valid C-like comment regex")
+});
+static HASH_COMMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""(?:\\.|[^"\\])*"|'(?:\\.|[^'\\\n])*'|#[^\n]*"#).expect("valid hash comment regex")
+});
+static DASH_COMMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""(?:\\.|[^"\\])*"|'(?:\\.|[^'\\\n])*'|--[^\n]*"#).expect("valid dash comment regex")
+});
+
+struct HeuristicCompressor {
+    comments: CommentStyle,
+}
+
+impl Compressor for HeuristicCompressor {
+    fn compress(&self, source: &str, opts: &SmartCompressOptions) -> Result<String, String> {
+        let text = if opts.remove_comments { strip_comments_heuristic(source, self.comments) } else { source.to_string() };
+        Ok(clean_blank_lines(collapse_indented_blocks(&text)))
+    }
+
+    fn method(&self) -> CompressionMethod {
+        CompressionMethod::Heuristic
+    }
+}
+
+fn strip_comments_heuristic(source: &str, style: CommentStyle) -> String {
+    let re: &Regex = match style {
+        CommentStyle::CLike => &C_LIKE_COMMENT_RE,
+        CommentStyle::Hash => &HASH_COMMENT_RE,
+        CommentStyle::DashDash => &DASH_COMMENT_RE,
+    };
+    let stripped = re.replace_all(source, |caps: &regex::Captures| {
+        let m = caps.get(0).unwrap();
+        let text = m.as_str();
+        let is_string = text.starts_with('"') || text.starts_with('\'');
+        let is_shebang = m.start() == 0 && text.starts_with("#!");
+        if is_string || is_shebang {
+            text.to_string()
+        } else {
+            // Keep the newlines of block comments so lines still align with the source.
+            "\n".repeat(text.matches('\n').count())
+        }
+    });
+    // Drop lines that only held a comment; keep intentional blank lines.
+    stripped
+        .lines()
+        .map(str::trim_end)
+        .zip(source.lines().map(|l| l.trim().is_empty()))
+        .filter(|(line, was_blank)| !line.is_empty() || *was_blank)
+        .map(|(line, _)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Keeps top-level lines and one nesting level (signatures, class members),
+/// replacing every deeper-indented run with a single `...` line.
+fn collapse_indented_blocks(text: &str) -> String {
+    collapse_below_level(text, 1)
+}
+
+// `collapse_indented_blocks` keeping `levels` nesting levels
+fn collapse_below_level(text: &str, levels: usize) -> String {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let unit = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(indent_of)
+        .filter(|&i| i > 0)
+        .min()
+        .unwrap_or(4);
+    let max_kept_indent = unit * levels;
+
+    let mut out: Vec<String> = Vec::new();
+    let mut collapsing = false;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !collapsing { out.push(String::new()); }
+            continue;
+        }
+        let indent = indent_of(line);
+        if indent > max_kept_indent {
+            if !collapsing {
+                out.push(format!("{}...", &line[..indent]));
+                collapsing = true;
+            }
+        } else {
+            collapsing = false;
+            out.push(line.to_string());
+        }
     }
+    out.join("\n")
+}
+
+fn get_heuristic_compressor_for_path(path: &str) -> Option<HeuristicCompressor> {
+    let extension = Path::new(path).extension().and_then(|s| s.to_str())?.to_ascii_lowercase();
+    let comments = match extension.as_str() {
+        "py" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "ps1" | "nim" | "cr" | "ex" | "exs" => CommentStyle::Hash,
+        "lua" | "sql" | "hs" | "elm" => CommentStyle::DashDash,
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "rs" | "go" | "java" | "kt" | "kts" | "scala"
+        | "swift" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "cs" | "dart" | "php"
+        | "groovy" | "zig" | "sol" | "v" => CommentStyle::CLike,
+        _ => return None,
+    };
+    Some(HeuristicCompressor { comments })
 }
 
 // --- Compressor Factory ---
 
 fn get_compressor_for_path(path: &str) -> Option<Box<dyn Compressor + Send + Sync>> {
+    let file_name = Path::new(path).file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let lockfile_kind = match file_name {
+        "package-lock.json" | "npm-shrinkwrap.json" => Some(LockfileKind::NpmLock),
+        "package.json" => Some(LockfileKind::PackageJson),
+        "Cargo.lock" | "poetry.lock" | "uv.lock" => Some(LockfileKind::TomlPackages),
+        "go.sum" => Some(LockfileKind::GoSum),
+        _ => None,
+    };
+    if let Some(kind) = lockfile_kind {
+        return Some(Box::new(LockfileSummarizer { kind }));
+    }
+
     let extension = Path::new(path).extension().and_then(|s| s.to_str());
     match extension {
         Some("py") => Some(Box::new(PythonCompressor)),
-        Some("ts" | "tsx") => Some(Box::new(TsxCompressor)),
+        Some("ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs") => Some(Box::new(TsxCompressor)),
+        Some("vue" | "svelte") => Some(Box::new(SfcCompressor)),
+        Some("c") => Some(Box::new(CCompressor { dialect: CDialect::C, header: false })),
+        Some("h") => Some(Box::new(CCompressor { dialect: CDialect::Either, header: true })),
+        Some("cc" | "cpp" | "cxx" | "c++") => Some(Box::new(CCompressor { dialect: CDialect::Cpp, header: false })),
+        Some("hh" | "hpp" | "hxx" | "h++") => Some(Box::new(CCompressor { dialect: CDialect::Cpp, header: true })),
+        Some("graphql" | "graphqls" | "gql") => Some(Box::new(SchemaCompressor { dialect: SchemaDialect::GraphQl })),
+        Some("proto") => Some(Box::new(SchemaCompressor { dialect: SchemaDialect::Proto })),
+        Some("csv" | "tsv") => Some(Box::new(DataHeadCompressor { has_header: true })),
+        Some("jsonl" | "ndjson") => Some(Box::new(DataHeadCompressor { has_header: false })),
         _ => None,
     }
 }
 
+/// Compresses one file's source: the structured compressor first, the
+/// heuristic one when that fails or no grammar is bundled for the type.
+fn compress_source(path: &str, source: String, opts: &SmartCompressOptions) -> CompressedFile {
+    let mut fallback_reason = None;
+    if let Some(compressor) = get_compressor_for_path(path).filter(|c| c.enabled(opts)) {
+        match compressor.compress(&source, opts) {
+            Ok(content) => return CompressedFile { content, method: compressor.method(), redactions: Vec::new(), fallback_reason: None, truncation: None },
+            Err(e) => {
+                eprintln!("[COMPRESS] AST compression failed for '{}': {}. Using heuristic fallback.", path, e);
+                fallback_reason = Some(e);
+            }
+        }
+    }
+    let mut file = match get_heuristic_compressor_for_path(path) {
+        Some(heuristic) => match heuristic.compress(&source, opts) {
+            Ok(content) => CompressedFile { content, method: CompressionMethod::Heuristic, redactions: Vec::new(), fallback_reason: None, truncation: None },
+            Err(_) => CompressedFile { content: source, method: CompressionMethod::None, redactions: Vec::new(), fallback_reason: None, truncation: None },
+        },
+        None => CompressedFile { content: source, method: CompressionMethod::None, redactions: Vec::new(), fallback_reason: None, truncation: None },
+    };
+    file.fallback_reason = fallback_reason;
+    file
+}
+
+// `opts` None reads the file without compressing it
+fn read_and_compress(
+    path_str: &str,
+    opts: Option<&SmartCompressOptions>,
+    extract_documents: bool,
+    redactor: &Redactor,
+) -> Result<CompressedFile, String> {
+    let path = Path::new(path_str);
+    if extract_documents && document_extract::is_extractable(path) {
+        let redacted = redactor.redact(&document_extract::extract_text(path)?);
+        return Ok(CompressedFile { content: redacted.content, method: CompressionMethod::None, redactions: redacted.redactions, fallback_reason: None, truncation: None });
+    }
+    match read_for_context(path) {
+        Ok(ReadOutcome::Binary(placeholder)) => Ok(CompressedFile { content: placeholder, method: CompressionMethod::None, redactions: Vec::new(), fallback_reason: None, truncation: None }),
+        Ok(ReadOutcome::Text(raw_content)) => {
+            // Redact first so a secret can't survive inside a kept signature or literal
+            let redacted = redactor.redact(&raw_content);
+            let mut file = match opts {
+                Some(opts) => compress_source(path_str, redacted.content, opts),
+                None => CompressedFile { content: redacted.content, method: CompressionMethod::None, redactions: Vec::new(), fallback_reason: None, truncation: None },
+            };
+            file.redactions = redacted.redactions;
+            Ok(file)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// `read_and_compress` with a panic in a grammar or compressor turned into this
+// file's error, so one pathological file can't fail a whole batch.
+pub fn read_and_compress_guarded(
+    path_str: &str,
+    opts: Option<&SmartCompressOptions>,
+    extract_documents: bool,
+    redactor: &Redactor,
+) -> Result<CompressedFile, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| read_and_compress(path_str, opts, extract_documents, redactor)))
+        .unwrap_or_else(|payload| {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            eprintln!("[COMPRESS] Compression panicked for '{}': {}", path_str, reason);
+            Err(format!("Compression failed for this file: {}", reason))
+        })
+}
+
+// Explicit options apply to every file; without them each file gets its
+// language's defaults from the `compression_defaults` setting
+pub enum OptionsSource {
+    Explicit(SmartCompressOptions),
+    PerLanguage(CompressionDefaults),
+}
+
+impl OptionsSource {
+    pub fn new(state: &AppState, options: Option<SmartCompressOptions>) -> Self {
+        match options {
+            Some(options) => OptionsSource::Explicit(options),
+            None => OptionsSource::PerLanguage(CompressionDefaults::for_state(state)),
+        }
+    }
+
+    pub fn for_path(&self, path: &str) -> Option<SmartCompressOptions> {
+        match self {
+            OptionsSource::Explicit(options) => Some(*options),
+            OptionsSource::PerLanguage(defaults) => defaults.options_for(path),
+        }
+    }
+}
+
 // --- Tauri Command ---
 
 // --- THIS IS THE CORRECTED COMMAND ---
 // It now returns the same data shape as the non-compressed version and uses Rayon for performance.
-#[tauri::command]
-pub fn read_multiple_file_contents_compressed(
+// Async with the work on a blocking thread, so `cancel_batch_read` gets through.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)] // Tauri command arguments are the IPC parameters
+pub async fn read_multiple_file_contents_compressed(
+    state: State<'_, AppState>,
     paths: Vec<String>,
-    options: Option<SmartCompressOptions>,
+    options: Option<SmartCompressOptions>, // None = each file's per-language defaults
+    project_id: Option<i32>, // With a project, its file notes are injected above the contents
+    request_id: Option<String>, // With an ID, cancel_batch_read can stop the read
+    max_bytes_per_file: Option<usize>, // Size caps, applied after compression
+    max_total_bytes: Option<usize>,
+    concurrency: Option<usize>, // Files read at once; default by drive type, 0 = unbounded
 ) -> Result<HashMap<String, Result<String, String>>, String> {
-    let opts = options.unwrap_or_default();
-
-    let results: HashMap<String, Result<String, String>> = paths
-        .par_iter() // Use parallel iterator for performance
-        .map(|p_str| {
-            let result = match fs::read_to_string(p_str) {
-                Ok(raw_content) => {
-                    let final_content = if let Some(compressor) = get_compressor_for_path(p_str) {
-                        compressor.compress(&raw_content, &opts)
-                    } else {
-                        raw_content
-                    };
-                    Ok(final_content)
-                }
-                Err(e) => Err(e.to_string()),
-            };
-            (p_str.clone(), result)
-        })
-        .collect();
+    let paths = canonical_paths(&paths);
+    let opts = OptionsSource::new(&state, options);
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+    let notes = file_notes::notes_for(&state, project_id);
+
+    // Parallel (Rayon) and cancellable
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut results = read_batch(&paths, request_id, concurrency, |p_str| {
+            read_and_compress_guarded(p_str, opts.for_path(p_str).as_ref(), extract_documents, &redactor)
+                .map(|file| file_notes::with_note(p_str, file.content, &notes))
+        })?;
+        apply_limits(&paths, &mut results, ReadLimits::new(max_bytes_per_file, max_total_bytes), |c| c, |_, _| ());
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Batch read task failed: {}", e))?
+}
 
-    Ok(results)
-}
\ No newline at end of file
+/// Same as `read_multiple_file_contents_compressed`, but each entry also
+/// reports which compression path (ast / structural / heuristic / none) was used.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+pub async fn read_multiple_file_contents_compressed_report(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    options: Option<SmartCompressOptions>,
+    project_id: Option<i32>,
+    request_id: Option<String>,
+    max_bytes_per_file: Option<usize>,
+    max_total_bytes: Option<usize>,
+    concurrency: Option<usize>,
+) -> Result<HashMap<String, Result<CompressedFile, String>>, String> {
+    let paths = canonical_paths(&paths);
+    let opts = OptionsSource::new(&state, options);
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+    let notes = file_notes::notes_for(&state, project_id);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut results = read_batch(&paths, request_id, concurrency, |p_str| {
+            read_and_compress_guarded(p_str, opts.for_path(p_str).as_ref(), extract_documents, &redactor).map(|mut file| {
+                file.content = file_notes::with_note(p_str, file.content, &notes);
+                file
+            })
+        })?;
+        let limits = ReadLimits::new(max_bytes_per_file, max_total_bytes);
+        apply_limits(&paths, &mut results, limits, |f| &mut f.content, |f, t| f.truncation = Some(t));
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Batch read task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compress_js(source: &str) -> String {
+        TsxCompressor.compress(source, &SmartCompressOptions::default()).expect("valid JavaScript")
+    }
+
+    #[test]
+    fn collapses_module_exports_function() {
+        let source = "module.exports = function (a, b) {\n  const c = a + b;\n  return c;\n};\n";
+        assert_eq!(compress_js(source), "module.exports = function (a, b) { ... };");
+    }
+
+    #[test]
+    fn collapses_exports_arrow_function() {
+        let source = "exports.f = () => {\n  const c = 1;\n  return c;\n};\n";
+        assert_eq!(compress_js(source), "exports.f = () => { ... };");
+    }
+
+    #[test]
+    fn collapses_module_exports_object_methods() {
+        let source = "module.exports = {\n  f() {\n    const c = 1;\n    return c;\n  },\n};\n";
+        assert_eq!(compress_js(source), "module.exports = {\n  f() { ... },\n};");
+    }
+
+    #[test]
+    fn collapses_var_function_expression() {
+        let source = "var f = function (a) {\n  const c = a;\n  return c;\n};\n";
+        assert_eq!(compress_js(source), "var f = function (a) { ... };");
+    }
+}
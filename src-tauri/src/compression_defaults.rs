@@ -0,0 +1,101 @@
+// src-tauri/src/compression_defaults.rs
+// Per-language default compression options, used by the compressed batch reads
+// when the caller passes no explicit options: e.g. strip comments from
+// TypeScript but send Rust untouched. Keys are the language ids of
+// `file_types::language_for_path` ("rust", "typescript", ...) plus "*" for
+// every other file.
+
+use crate::app_settings;
+use crate::compress::SmartCompressOptions;
+use crate::db::AppState;
+use crate::file_types::language_for_path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{command, State};
+
+pub const COMPRESSION_DEFAULTS_SETTING: &str = "compression_defaults";
+pub const ANY_LANGUAGE: &str = "*";
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageCompression {
+    /// false sends files of the language as-is (still redacted)
+    #[serde(default = "default_true")]
+    pub compress: bool,
+    #[serde(flatten)]
+    pub options: SmartCompressOptions,
+}
+
+/// Language id -> defaults. Empty means the built-in defaults for every file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompressionDefaults(pub BTreeMap<String, LanguageCompression>);
+
+impl CompressionDefaults {
+    pub fn from_conn(conn: &rusqlite::Connection) -> Self {
+        match app_settings::get_setting_internal(conn, COMPRESSION_DEFAULTS_SETTING) {
+            Ok(Some(json)) if !json.trim().is_empty() => serde_json::from_str(&json).unwrap_or_else(|e| {
+                eprintln!("[COMPRESS] Ignoring malformed '{}' setting: {}", COMPRESSION_DEFAULTS_SETTING, e);
+                Self::default()
+            }),
+            _ => Self::default(),
+        }
+    }
+
+    pub fn for_state(state: &AppState) -> Self {
+        match state.conn.lock() {
+            Ok(conn) => Self::from_conn(&conn),
+            Err(e) => {
+                eprintln!("[COMPRESS] DB lock failed, using built-in compression defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Options for `path`, or None when its language shouldn't be compressed.
+    pub fn options_for(&self, path: &str) -> Option<SmartCompressOptions> {
+        let entry = language_for_path(Path::new(path))
+            .and_then(|language| self.0.get(language))
+            .or_else(|| self.0.get(ANY_LANGUAGE));
+        match entry {
+            Some(entry) if !entry.compress => None,
+            Some(entry) => Some(entry.options),
+            None => Some(SmartCompressOptions::default()),
+        }
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn get_compression_defaults(state: State<AppState>) -> Result<CompressionDefaults, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    Ok(CompressionDefaults::from_conn(&conn))
+}
+
+/// Replaces the per-language defaults; language ids are lowercased.
+#[command]
+pub fn save_compression_defaults(state: State<AppState>, defaults: CompressionDefaults) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let mut normalized = BTreeMap::new();
+    for (language, entry) in defaults.0 {
+        let language = language.trim().to_lowercase();
+        if language.is_empty() {
+            return Err("Language id cannot be empty.".to_string());
+        }
+        normalized.insert(language, entry);
+    }
+    let json = serde_json::to_string(&CompressionDefaults(normalized))
+        .map_err(|e| format!("Failed to serialize compression defaults: {}", e))?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params![COMPRESSION_DEFAULTS_SETTING, json],
+    )
+    .map_err(|e| format!("Failed to save compression defaults: {}", e))?;
+    Ok(())
+}
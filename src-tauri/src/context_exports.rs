@@ -0,0 +1,277 @@
+// src-tauri/src/context_exports.rs
+// Records which file versions (mtime + content hash) went into each context
+// export, so a follow-up message in the same LLM conversation can carry only
+// the files that changed since then (`build_delta_context`).
+
+use crate::db::AppState;
+use crate::document_extract;
+use crate::file_notes;
+use crate::path_canon::canonical_paths;
+use crate::redaction::{RedactionHit, Redactor};
+use crate::scan_tree::{file_modified_timestamp, fnv1a};
+use crate::scanner::read_context_text;
+use crate::utils::approximate_token_count;
+use chrono::Utc;
+use rayon::prelude::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportedFileVersion {
+    pub last_modified: String,
+    pub size: u64,
+    /// FNV-1a of the raw bytes, hex
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ContextExportInfo {
+    pub id: i64,
+    pub project_id: i32,
+    pub created_at: String,
+    pub file_count: usize,
+    pub total_tokens: usize,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DeltaContext {
+    pub base_export_id: i64,
+    /// Export recorded for the delta (current versions of every file still present);
+    /// pass it as the base of the next follow-up. None when nothing changed.
+    pub export_id: Option<i64>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged_count: usize,
+    /// Per-file read errors; those files are left out of `content`
+    pub errors: BTreeMap<String, String>,
+    /// Secrets replaced in `content`, per file
+    pub redactions: BTreeMap<String, Vec<RedactionHit>>,
+    pub content: String,
+    pub tokens: usize,
+}
+
+pub fn file_version(path: &str) -> Result<ExportedFileVersion, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Failed to stat '{}': {}", path, e))?;
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+    Ok(ExportedFileVersion {
+        last_modified: file_modified_timestamp(&meta),
+        size: meta.len(),
+        hash: format!("{:016x}", fnv1a(&bytes)),
+    })
+}
+
+fn insert_export(
+    conn: &Connection,
+    project_id: i32,
+    files: &BTreeMap<String, ExportedFileVersion>,
+    total_tokens: usize,
+) -> Result<i64, String> {
+    let files_json = serde_json::to_string(files).map_err(|e| format!("Failed to serialize export: {}", e))?;
+    conn.execute(
+        r#"
+        INSERT INTO context_exports (project_id, created_at, file_count, total_tokens, files_json)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        params![project_id, Utc::now().to_rfc3339(), files.len() as i64, total_tokens as i64, files_json],
+    )
+    .map_err(|e| format!("Failed to record export for project {}: {}", project_id, e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn load_export_files(
+    conn: &Connection,
+    project_id: i32,
+    export_id: i64,
+) -> Result<BTreeMap<String, ExportedFileVersion>, String> {
+    let files_json: String = conn
+        .query_row(
+            "SELECT files_json FROM context_exports WHERE id = ?1 AND project_id = ?2",
+            params![export_id, project_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query export {}: {}", export_id, e))?
+        .ok_or_else(|| format!("Export {} not found for project {}.", export_id, project_id))?;
+    serde_json::from_str(&files_json).map_err(|e| format!("Export {} data is corrupt: {}", export_id, e))
+}
+
+// Same layouts as the aggregator's output formats ("markdown", "xml", "sentinel", "raw")
+pub fn format_file_block(path: &str, content: &str, format: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    let lang = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let format_attr = if lang.is_empty() { "text" } else { lang.as_str() };
+    match format {
+        "markdown" => format!("---\npath: {}\nformat: {}\n---\n~~~~{}\n{}\n~~~~\n\n", normalized, format_attr, lang, content),
+        "xml" => {
+            let name = Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            format!(
+                "<file name=\"{}\" path=\"{}\" format=\"{}\">\n  <content><![CDATA[\n{}\n]]></content>\n</file>\n\n",
+                escape_xml(&name),
+                escape_xml(&normalized),
+                format_attr,
+                content
+            )
+        }
+        "raw" => format!("--- {} ---\n```{}\n{}\n```\n\n", normalized, lang, content),
+        _ => format!(
+            "-----BEGIN FILE path=\"{}\" format=\"{}\"-----\n{}\n-----END FILE-----\n\n",
+            normalized, format_attr, content
+        ),
+    }
+}
+
+// A folder's README or summary, placed before its files when directory headers are on
+pub fn format_directory_block(path: &str, content: &str, format: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    match format {
+        "markdown" => format!("---\ndirectory: {}\n---\n{}\n\n", normalized, content),
+        "xml" => format!(
+            "<directory path=\"{}\">\n  <summary><![CDATA[\n{}\n]]></summary>\n</directory>\n\n",
+            escape_xml(&normalized),
+            content
+        ),
+        "raw" => format!("=== {}/ ===\n{}\n\n", normalized, content),
+        _ => format!("-----BEGIN DIRECTORY path=\"{}\"-----\n{}\n-----END DIRECTORY-----\n\n", normalized, content),
+    }
+}
+
+// (header, footer) around a group of files when an export is grouped by language or package
+pub fn format_group_section(label: &str, file_count: usize, tokens: usize, format: &str) -> (String, String) {
+    let files = if file_count == 1 { "1 file".to_string() } else { format!("{} files", file_count) };
+    match format {
+        "markdown" => (format!("# {} ({}, ~{} tokens)\n\n", label, files, tokens), String::new()),
+        "xml" => (
+            format!("<group name=\"{}\" files=\"{}\" tokens=\"{}\">\n\n", escape_xml(label), file_count, tokens),
+            "</group>\n\n".to_string(),
+        ),
+        "raw" => (format!("===== {} ({}, ~{} tokens) =====\n\n", label, files, tokens), String::new()),
+        _ => (
+            format!("-----BEGIN GROUP name=\"{}\" files=\"{}\" tokens=\"{}\"-----\n\n", label, file_count, tokens),
+            format!("-----END GROUP name=\"{}\"-----\n\n", label),
+        ),
+    }
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Records the current versions of the exported files. Returns the export ID.
+#[command]
+pub fn record_context_export(
+    state: State<AppState>,
+    project_id: i32,
+    paths: Vec<String>,
+    total_tokens: Option<usize>,
+) -> Result<i64, String> {
+    crate::db::ensure_writable(&state)?;
+    // Files that can't be read weren't in the export either
+    let files: BTreeMap<String, ExportedFileVersion> = canonical_paths(&paths)
+        .par_iter()
+        .filter_map(|p| file_version(p).ok().map(|v| (p.clone(), v)))
+        .collect();
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    insert_export(&conn, project_id, &files, total_tokens.unwrap_or(0))
+}
+
+#[command]
+pub fn list_context_exports(state: State<AppState>, project_id: i32) -> Result<Vec<ContextExportInfo>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, project_id, created_at, file_count, total_tokens
+            FROM context_exports
+            WHERE project_id = ?1
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| {
+            Ok(ContextExportInfo {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                created_at: row.get(2)?,
+                file_count: row.get::<_, i64>(3)? as usize,
+                total_tokens: row.get::<_, i64>(4)? as usize,
+            })
+        })
+        .map_err(|e| format!("Query exports failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to map export row: {}", e))
+}
+
+/// Context containing only the files of export `export_id` whose content changed
+/// since, in the given output format (default "sentinel"). Deleted files are listed.
+#[command(async)]
+pub async fn build_delta_context(
+    state: State<'_, AppState>,
+    project_id: i32,
+    export_id: i64,
+    format: Option<String>,
+) -> Result<DeltaContext, String> {
+    let base = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        load_export_files(&conn, project_id, export_id)?
+    };
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+    let notes = file_notes::notes_for(&state, Some(project_id));
+    let format = format.unwrap_or_else(|| "sentinel".to_string());
+
+    let (mut delta, current) = tauri::async_runtime::spawn_blocking(move || {
+        let versions: Vec<(String, Option<ExportedFileVersion>)> =
+            base.par_iter().map(|(path, _)| (path.clone(), file_version(path).ok())).collect();
+
+        let mut delta = DeltaContext { base_export_id: export_id, ..Default::default() };
+        let mut current = BTreeMap::new();
+        for (path, version) in versions {
+            match version {
+                None => delta.removed.push(path),
+                Some(v) => {
+                    // Hash decides; a touched-but-identical file isn't resent
+                    if base.get(&path).is_some_and(|old| old.hash == v.hash) {
+                        delta.unchanged_count += 1;
+                    } else {
+                        delta.changed.push(path.clone());
+                    }
+                    current.insert(path, v);
+                }
+            }
+        }
+        for path in &delta.changed {
+            match read_context_text(path, extract_documents, &redactor) {
+                Ok(file) => {
+                    let content = file_notes::with_note(path, file.content, &notes);
+                    delta.content.push_str(&format_file_block(path, &content, &format));
+                    if !file.redactions.is_empty() {
+                        delta.redactions.insert(path.clone(), file.redactions);
+                    }
+                }
+                Err(e) => {
+                    delta.errors.insert(path.clone(), e);
+                }
+            }
+        }
+        delta.tokens = approximate_token_count(&delta.content);
+        (delta, current)
+    })
+    .await
+    .map_err(|e| format!("Delta context task failed: {}", e))?;
+
+    if !delta.changed.is_empty() || !delta.removed.is_empty() {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        delta.export_id = Some(insert_export(&conn, project_id, &current, delta.tokens)?);
+    }
+    Ok(delta)
+}
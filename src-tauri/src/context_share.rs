@@ -0,0 +1,162 @@
+// src-tauri/src/context_share.rs
+// `share_context`: hands an assembled context to someone else in one step,
+// either as a secret GitHub Gist (returns its URL) or as a timestamped file in
+// a configured share folder, e.g. a synced drive (returns its path). The text
+// is redacted again before it leaves the app, whatever the caller did.
+
+use crate::app_settings;
+use crate::db::AppState;
+use crate::projects;
+use crate::redaction::Redactor;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{command, State};
+
+pub const GIST_TOKEN_SETTING: &str = "github_gist_token";
+pub const SHARE_DIR_SETTING: &str = "share_directory";
+// Used when the setting is empty
+const TOKEN_ENV_VARS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+const GIST_API_URL: &str = "https://api.github.com/gists";
+const UPLOAD_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ShareResult {
+    /// "gist" or "file"
+    pub target: String,
+    /// Gist URL or file path
+    pub location: String,
+    pub file_name: String,
+    pub bytes: usize,
+    pub redactions: usize,
+    pub shared_at: String,
+}
+
+// "context-my-app-20250101-120000.md"
+fn default_file_name(project_name: Option<&str>, now: &chrono::DateTime<Utc>) -> String {
+    let slug: String = project_name
+        .unwrap_or("")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let stamp = now.format("%Y%m%d-%H%M%S");
+    if slug.is_empty() {
+        format!("context-{}.md", stamp)
+    } else {
+        format!("context-{}-{}.md", slug, stamp)
+    }
+}
+
+fn upload_gist(token: &str, file_name: &str, description: &str, content: &str) -> Result<String, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(UPLOAD_TIMEOUT_SECS)))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let body = json!({
+        "description": description,
+        "public": false,
+        "files": { file_name: { "content": content } },
+    });
+    let mut response = agent
+        .post(GIST_API_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("User-Agent", "code-context-builder")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .send_json(&body)
+        .map_err(|e| format!("Gist upload failed: {}", e))?;
+    let status = response.status();
+    let reply: Value = response.body_mut().read_json().map_err(|e| format!("Unreadable reply from GitHub ({}): {}", status, e))?;
+    if !status.is_success() {
+        let message = reply.get("message").and_then(Value::as_str).unwrap_or("no details");
+        return Err(format!("GitHub refused the Gist ({}): {}", status, message));
+    }
+    reply
+        .get("html_url")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "GitHub's reply has no Gist URL.".to_string())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Shares `content` as a secret Gist (`target: "gist"`, token from the
+/// `github_gist_token` setting or GITHUB_TOKEN / GH_TOKEN) or as a file in the
+/// `share_directory` folder (`target: "file"`). The file name defaults to
+/// `context-<project>-<timestamp>.md`.
+#[command(async)]
+pub async fn share_context(
+    state: State<'_, AppState>,
+    content: String,
+    target: String,
+    project_id: Option<i32>,
+    file_name: Option<String>,
+    description: Option<String>,
+) -> Result<ShareResult, String> {
+    if content.trim().is_empty() {
+        return Err("There is no context to share.".to_string());
+    }
+    let (project_name, token, share_dir) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let project_name = match project_id {
+            Some(id) => Some(projects::load_project_by_id(&conn, id)?.title),
+            None => None,
+        };
+        let setting = |key: &str| -> Result<Option<String>, String> {
+            Ok(app_settings::get_setting_internal(&conn, key)
+                .map_err(|e| format!("Failed to read setting '{}': {}", key, e))?
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()))
+        };
+        (project_name, setting(GIST_TOKEN_SETTING)?, setting(SHARE_DIR_SETTING)?)
+    };
+    let now = Utc::now();
+    let file_name = file_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| default_file_name(project_name.as_deref(), &now));
+    if file_name.contains(['/', '\\']) || file_name == "." || file_name == ".." {
+        return Err(format!("'{}' isn't a plain file name.", file_name));
+    }
+    let redacted = Redactor::for_state(&state).redact(&content);
+    let redactions = redacted.redactions.len();
+    let content = redacted.content;
+
+    let location = match target.as_str() {
+        "gist" => {
+            let token = token
+                .or_else(|| TOKEN_ENV_VARS.iter().find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty())))
+                .ok_or_else(|| format!("No GitHub token: set '{}' or the GITHUB_TOKEN environment variable.", GIST_TOKEN_SETTING))?;
+            let description = description.unwrap_or_else(|| match &project_name {
+                Some(name) => format!("Code context for {}", name),
+                None => "Code context".to_string(),
+            });
+            let name = file_name.clone();
+            let body = content.clone();
+            tauri::async_runtime::spawn_blocking(move || upload_gist(&token, &name, &description, &body))
+                .await
+                .map_err(|e| format!("Gist upload task failed: {}", e))??
+        }
+        "file" => {
+            let dir = PathBuf::from(share_dir.ok_or_else(|| format!("No share folder: set '{}' first.", SHARE_DIR_SETTING))?);
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create share folder '{}': {}", dir.display(), e))?;
+            let path = dir.join(&file_name);
+            if path.exists() {
+                return Err(format!("'{}' already exists.", path.display()));
+            }
+            fs::write(&path, &content).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+            path.to_string_lossy().to_string()
+        }
+        other => return Err(format!("Unknown share target '{}'; use \"gist\" or \"file\".", other)),
+    };
+    println!("[SHARE] Shared {} bytes as {} ({} redaction(s)): {}", content.len(), target, redactions, location);
+    Ok(ShareResult { target, location, file_name, bytes: content.len(), redactions, shared_at: now.to_rfc3339() })
+}
@@ -1,19 +1,43 @@
 
 // src-tauri/src/db.rs
 use rusqlite::Connection;
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::env; // Import std::env to get executable path
 use std::sync::{Arc, Mutex};
-use tauri::AppHandle; // AppHandle is passed to init_connection, so keep it in signature
+use crate::errors::{self, app_error};
+use tauri::{command, AppHandle, State}; // AppHandle is passed to init_connection, so keep it in signature
 
 pub struct AppState {
     pub conn: Arc<Mutex<Connection>>,
+    /// Why the database file can't be written (read-only volume, locked by another
+    /// instance). `conn` is then an in-memory copy: scans and caching keep working,
+    /// but nothing persists and configuration writes are refused.
+    pub read_only_reason: Arc<Mutex<Option<String>>>,
 }
 
-// Function to get the full path to the database file
-// _app_handle is marked as unused for this specific logic, but kept for signature consistency
-fn get_db_path(_app_handle: &AppHandle) -> Result<PathBuf, String> {
+/// Fails with a `read_only_database` error (see errors.rs) when the database is
+/// read-only. Call at the top of commands that change persisted configuration.
+pub fn ensure_writable(state: &AppState) -> Result<(), String> {
+    let reason = state.read_only_reason.lock().map_err(|e| format!("DB mode lock failed: {}", e))?;
+    match reason.as_deref() {
+        None => Ok(()),
+        Some(reason) => Err(app_error(errors::READ_ONLY_DATABASE, &[("reason", reason.to_string())])),
+    }
+}
+
+pub const DEFAULT_PROFILE: &str = "default";
+// Remembers the active profile between launches (next to the databases)
+const ACTIVE_PROFILE_FILE: &str = "code_context_builder.profile";
+
+// Directory holding the database files: the executable's directory, or the
+// data folder next to it in portable mode
+pub fn db_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = crate::portable::data_dir() {
+        return Ok(dir.to_path_buf());
+    }
     // Get the path to the currently running executable
     let exe_path = env::current_exe()
         .map_err(|e| format!("Failed to get current executable path: {}", e))?;
@@ -22,15 +46,7 @@ fn get_db_path(_app_handle: &AppHandle) -> Result<PathBuf, String> {
     let exe_dir = exe_path.parent()
         .ok_or_else(|| format!("Failed to get parent directory of executable: {}", exe_path.display()))?;
 
-    // Define the database file name and join it with the executable's directory
-    let db_file_name = "code_context_builder.db";
-    let db_file_path = exe_dir.join(db_file_name);
-
-    // Ensure the directory for the database file exists.
-    // For "next to exe", this is the executable's directory.
     // fs::create_dir_all is idempotent (it will not error if the directory already exists).
-    // This step is generally good practice, although for the executable's directory,
-    // it should already exist.
     if !exe_dir.exists() {
         // This scenario (executable's directory not existing) is highly unlikely.
         // If it does, attempting to create it might lead to permission issues
@@ -38,16 +54,150 @@ fn get_db_path(_app_handle: &AppHandle) -> Result<PathBuf, String> {
         fs::create_dir_all(exe_dir)
             .map_err(|e| format!("Failed to create directory for database '{}': {}", exe_dir.display(), e))?;
     }
-    
-    Ok(db_file_path)
+    Ok(exe_dir.to_path_buf())
+}
+
+/// Database file of a profile: the default profile keeps the original
+/// `code_context_builder.db`, others get `code_context_builder.<name>.db`.
+pub fn db_path_for_profile(profile: &str) -> Result<PathBuf, String> {
+    let db_file_name = if profile == DEFAULT_PROFILE {
+        "code_context_builder.db".to_string()
+    } else {
+        format!("code_context_builder.{}.db", profile)
+    };
+    Ok(db_dir()?.join(db_file_name))
 }
 
-// Initializes the database connection
-pub fn init_connection(app_handle: &AppHandle) -> Result<Connection, String> {
-    let db_path = get_db_path(app_handle)?;
+/// The profile selected last time, or the default one.
+pub fn active_profile() -> String {
+    db_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(ACTIVE_PROFILE_FILE)).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub fn set_active_profile(profile: &str) -> Result<(), String> {
+    let path = db_dir()?.join(ACTIVE_PROFILE_FILE);
+    fs::write(&path, profile).map_err(|e| format!("Failed to save active profile to '{}': {}", path.display(), e))
+}
+
+// Initializes the database connection of the active profile
+// _app_handle is unused for this specific logic, but kept for signature consistency.
+// Returns the connection (tables initialized) and the read-only reason, if any.
+pub fn init_connection(_app_handle: &AppHandle) -> Result<(Connection, Option<String>), String> {
+    let profile = active_profile();
+    let db_path = db_path_for_profile(&profile)?;
     // Update log message to reflect new location strategy
-    println!("Database path (next to executable): {}", db_path.display()); 
-    Connection::open(&db_path).map_err(|e| format!("Failed to open database at '{}': {}", db_path.display(), e))
+    println!("Database path (next to executable, profile '{}'): {}", profile, db_path.display());
+    open_database(&db_path)
+}
+
+// Opens and initializes the database at `db_path`. When the file can't be
+// written, falls back to an in-memory copy of it and returns the reason.
+pub fn open_database(db_path: &Path) -> Result<(Connection, Option<String>), String> {
+    let writable = Connection::open(db_path)
+        .map_err(|e| format!("Failed to open database at '{}': {}", db_path.display(), e))
+        .and_then(|conn| {
+            // Don't wait long on another instance's lock; fall back instead
+            conn.busy_timeout(Duration::from_secs(2))
+                .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+            probe_writable(&conn)?;
+            init_db_tables(&conn)?;
+            Ok(conn)
+        });
+    match writable {
+        Ok(conn) => Ok((conn, None)),
+        Err(reason) => {
+            eprintln!("[DB] Database is not writable, continuing read-only in memory: {}", reason);
+            let conn = Connection::open_in_memory()
+                .map_err(|e| format!("Failed to open in-memory database: {}", e))?;
+            init_db_tables(&conn)?;
+            if let Err(e) = copy_from_file(&conn, db_path) {
+                eprintln!("[DB] Could not copy data from '{}': {}", db_path.display(), e);
+            }
+            Ok((conn, Some(reason)))
+        }
+    }
+}
+
+// A rolled-back write: fails on read-only files/volumes and on databases
+// locked by another process.
+fn probe_writable(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch("BEGIN IMMEDIATE; CREATE TABLE __ccb_write_probe (x INTEGER); ROLLBACK;")
+        .map_err(|e| {
+            let _ = conn.execute_batch("ROLLBACK;");
+            format!("write check failed: {}", e)
+        })
+}
+
+// Copies every table of the file at `db_path` into the in-memory `conn`, limited
+// to the columns both schemas share (the file may predate newer columns).
+fn copy_from_file(conn: &Connection, db_path: &Path) -> Result<(), String> {
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let uri = format!("file:{}?mode=ro", db_path.to_string_lossy().replace('?', "%3f").replace('#', "%23"));
+    conn.execute("ATTACH DATABASE ?1 AS disk", [&uri])
+        .map_err(|e| format!("Failed to attach database read-only: {}", e))?;
+    let result = copy_attached_tables(conn);
+    if let Err(e) = conn.execute_batch("DETACH DATABASE disk;") {
+        eprintln!("[DB] Failed to detach database: {}", e);
+    }
+    result
+}
+
+fn copy_attached_tables(conn: &Connection) -> Result<(), String> {
+    let column_names = |schema: &str, table: &str| -> Result<Vec<String>, String> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA {}.table_info(\"{}\")", schema, table))
+            .map_err(|e| format!("Failed to inspect table '{}': {}", table, e))?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| format!("Failed to read columns of '{}': {}", table, e))?
+            .filter_map(Result::ok)
+            .collect();
+        Ok(names)
+    };
+    let tables: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT name FROM disk.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+            .map_err(|e| format!("Failed to list tables: {}", e))?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to list tables: {}", e))?
+            .filter_map(Result::ok)
+            .collect();
+        names
+    };
+
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")
+        .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+    let mut copied = 0;
+    for table in &tables {
+        let main_columns = column_names("main", table)?;
+        let shared: Vec<String> = column_names("disk", table)?
+            .into_iter()
+            .filter(|c| main_columns.contains(c))
+            .map(|c| format!("\"{}\"", c))
+            .collect();
+        if shared.is_empty() {
+            continue; // Not one of our tables
+        }
+        let columns = shared.join(", ");
+        conn.execute_batch(&format!(
+            "DELETE FROM main.\"{t}\"; INSERT INTO main.\"{t}\" ({c}) SELECT {c} FROM disk.\"{t}\";",
+            t = table,
+            c = columns
+        ))
+        .map_err(|e| format!("Failed to copy table '{}': {}", table, e))?;
+        copied += 1;
+    }
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Failed to re-enable foreign keys: {}", e))?;
+    println!("[DB] Copied {} tables into the in-memory database.", copied);
+    Ok(())
 }
 
 // Creates the necessary tables if they don't exist
@@ -70,6 +220,137 @@ pub fn init_db_tables(conn: &Connection) -> Result<(), String> {
             lines INTEGER NOT NULL,
             tokens INTEGER NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS code_context_builder_scan_results (
+            project_id INTEGER PRIMARY KEY NOT NULL,
+            tree_json TEXT NOT NULL,
+            scanned_at TEXT NOT NULL,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS change_journal (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            observed_at TEXT NOT NULL,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_change_journal_project_time
+            ON change_journal(project_id, observed_at);
+        CREATE TABLE IF NOT EXISTS tree_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            file_count INTEGER NOT NULL,
+            total_tokens INTEGER NOT NULL,
+            files_json TEXT NOT NULL,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS context_exports (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            file_count INTEGER NOT NULL,
+            total_tokens INTEGER NOT NULL,
+            files_json TEXT NOT NULL,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS file_notes (
+            project_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            note TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY(project_id, path),
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS inclusion_rules (
+            project_id INTEGER NOT NULL,
+            pattern TEXT NOT NULL,
+            mode TEXT NOT NULL,
+            PRIMARY KEY(project_id, pattern),
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS project_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            changed_at TEXT NOT NULL,
+            field TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            summary TEXT NOT NULL,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_project_history_project_time
+            ON project_history(project_id, changed_at);
+        CREATE TABLE IF NOT EXISTS pinned_files (
+            project_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            pinned_at TEXT NOT NULL,
+            PRIMARY KEY(project_id, path),
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS scan_metrics (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            scanned_at TEXT NOT NULL,
+            total_ms INTEGER NOT NULL,
+            enumeration_ms INTEGER NOT NULL,
+            processing_ms INTEGER NOT NULL,
+            db_commit_ms INTEGER NOT NULL,
+            tree_build_ms INTEGER NOT NULL,
+            file_count INTEGER NOT NULL,
+            dir_count INTEGER NOT NULL,
+            files_per_sec REAL NOT NULL,
+            cache_hits INTEGER NOT NULL,
+            cache_misses INTEGER NOT NULL,
+            cache_hit_ratio REAL NOT NULL,
+            network_mode INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_scan_metrics_project
+            ON scan_metrics(project_id, id);
+        CREATE TABLE IF NOT EXISTS scan_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            finished_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            scope TEXT NOT NULL DEFAULT 'full',
+            file_count INTEGER NOT NULL DEFAULT 0,
+            dir_count INTEGER NOT NULL DEFAULT 0,
+            total_tokens INTEGER NOT NULL DEFAULT 0,
+            total_lines INTEGER NOT NULL DEFAULT 0,
+            total_bytes INTEGER NOT NULL DEFAULT 0,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_scan_history_project
+            ON scan_history(project_id, id);
+        CREATE TABLE IF NOT EXISTS dir_ignore_rules (
+            project_id INTEGER NOT NULL,
+            dir_path TEXT NOT NULL,
+            pattern TEXT NOT NULL,
+            mode TEXT NOT NULL,
+            PRIMARY KEY(project_id, dir_path, pattern),
+            FOREIGN KEY(project_id) REFERENCES code_context_builder_projects(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS prompt_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            body TEXT NOT NULL,
+            position TEXT NOT NULL DEFAULT 'prepend',
+            updated_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS model_presets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            model TEXT NOT NULL DEFAULT '',
+            context_window INTEGER NOT NULL,
+            reserved_output_tokens INTEGER NOT NULL DEFAULT 0,
+            input_price_per_mtok REAL NOT NULL DEFAULT 0,
+            output_price_per_mtok REAL NOT NULL DEFAULT 0
+        );
         CREATE TABLE IF NOT EXISTS app_settings (
             key TEXT PRIMARY KEY NOT NULL,
             value TEXT NOT NULL
@@ -77,6 +358,73 @@ pub fn init_db_tables(conn: &Connection) -> Result<(), String> {
         "#,
     )
     .map_err(|e| format!("Failed to initialize database tables: {}", e))?;
+
+    // Columns added after the original schema; existing databases get them here
+    ensure_column(conn, "code_context_builder_projects", "fs_mode", "TEXT NOT NULL DEFAULT 'auto'")?;
+    ensure_column(conn, "code_context_builder_projects", "case_sensitivity", "TEXT NOT NULL DEFAULT 'auto'")?;
+    ensure_column(conn, "code_context_builder_projects", "respect_gitignore", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "code_context_builder_projects", "deleted_at", "TEXT")?;
+    ensure_column(conn, "code_context_builder_projects", "follow_symlinks", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "code_context_builder_projects", "max_scan_depth", "INTEGER NOT NULL DEFAULT 30")?;
+    ensure_column(conn, "code_context_builder_projects", "include_hidden", "INTEGER NOT NULL DEFAULT 1")?;
+    ensure_column(conn, "code_context_builder_projects", "min_file_size", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "code_context_builder_projects", "max_file_size", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "code_context_builder_projects", "max_file_age_days", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "code_context_builder_projects", "include_patterns", "TEXT NOT NULL DEFAULT '[]'")?;
+    if ensure_column(conn, "code_context_builder_file_cache", "is_binary", "INTEGER NOT NULL DEFAULT 0")? {
+        // Rows cached before binary detection have no flag; recount them on the next scan
+        conn.execute("DELETE FROM code_context_builder_file_cache", [])
+            .map_err(|e| format!("Failed to reset file cache: {}", e))?;
+    }
+    ensure_column(conn, "code_context_builder_file_cache", "skipped_reason", "TEXT")?;
+    ensure_column(conn, "code_context_builder_file_cache", "cached_at", "TEXT")?;
+    ensure_column(conn, "code_context_builder_scan_results", "cache_hits", "INTEGER")?;
+    ensure_column(conn, "code_context_builder_scan_results", "cache_misses", "INTEGER")?;
+    crate::path_canon::migrate_db_paths(conn)?;
+
     println!("Database tables initialized successfully.");
     Ok(())
-}
\ No newline at end of file
+}
+
+// Adds `column` to `table` when missing (CREATE TABLE IF NOT EXISTS won't alter old tables).
+// Returns true when the column was just added.
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| format!("Failed to inspect table '{}': {}", table, e))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to read columns of '{}': {}", table, e))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition))
+            .map_err(|e| format!("Failed to add column '{}.{}': {}", table, column, e))?;
+        println!("[DB] Added column {}.{}", table, column);
+    }
+    Ok(!exists)
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DatabaseMode {
+    pub profile: String,
+    pub db_path: String,
+    pub read_only: bool,
+    pub reason: Option<String>,
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Whether the active database is writable, so the UI can warn and disable editing.
+#[command]
+pub fn get_database_mode(state: State<AppState>) -> Result<DatabaseMode, String> {
+    let profile = active_profile();
+    let db_path = db_path_for_profile(&profile)?;
+    let reason = state.read_only_reason.lock().map_err(|e| format!("DB mode lock failed: {}", e))?.clone();
+    Ok(DatabaseMode {
+        profile,
+        db_path: db_path.to_string_lossy().to_string(),
+        read_only: reason.is_some(),
+        reason,
+    })
+}
@@ -0,0 +1,392 @@
+// src-tauri/src/dependency_closure.rs
+// Suggests a selection from a target file's real dependencies: relative imports
+// are followed as in trace_select, and bare imports of sibling workspace
+// packages (npm workspaces, Cargo workspaces, Python projects, Go modules) are
+// resolved through the manifests found in the last scan. The result is the
+// transitive closure, which in a monorepo is far smaller than the whole tree.
+
+use crate::db::AppState;
+use crate::inclusion_rules::load_rules;
+use crate::path_canon::canonical_path;
+use crate::scan_results;
+use crate::trace_select::{direct_imports, normalize_lexically, JS_EXTENSIONS};
+use crate::tree_queries::load_tree;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+use tauri::{command, State};
+
+const DEFAULT_MAX_FILES: usize = 500;
+
+// Bare specifiers: import x from 'pkg' / require('@scope/pkg/sub')
+static JS_BARE_IMPORT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:import\s[^'"]*?from\s*|import\s*\(\s*|require\s*\(\s*|export\s[^'"]*?from\s*|import\s+)['"]([^'"./][^'"]*)['"]"#)
+        .expect("js bare import regex")
+});
+static RS_CRATE_USE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:use|extern\s+crate)\s+::?(\w+)").expect("rust use regex"));
+static PY_TOP_IMPORT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*(?:from\s+(\w+)[\w.]*\s+import|import\s+(\w+))").expect("python import regex"));
+// A line that is only an import path, inside `import ( ... )` or after `import`
+static GO_IMPORT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*(?:import\s+)?(?:[\w.]+\s+)?"([\w.~/-]+)"\s*$"#).expect("go import regex"));
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkspacePackage {
+    pub name: String,
+    /// "npm", "cargo", "python" or "go"
+    pub ecosystem: String,
+    pub dir: String,
+    pub manifest: String,
+    /// Files a bare import of the package resolves to
+    pub entry_files: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DependencyFile {
+    pub path: String,
+    /// "target", "import" (relative import), "package" (entry file of an imported
+    /// workspace package), "manifest" or "always" (project's always-include list)
+    pub reason: String,
+    /// File whose import pulled this one in
+    pub via: Option<String>,
+    /// Workspace package the import went through
+    pub package: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DependencyClosure {
+    pub files: Vec<DependencyFile>,
+    /// Workspace packages the targets depend on
+    pub packages: Vec<String>,
+    /// Bare imports that aren't workspace packages (third-party or stdlib)
+    pub external: Vec<String>,
+    /// Targets that aren't in the last scan
+    pub missing_targets: Vec<String>,
+    /// True when `max_files` stopped the walk
+    pub truncated: bool,
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn parent_dir(path: &str) -> &str {
+    Path::new(path).parent().and_then(|p| p.to_str()).unwrap_or("")
+}
+
+fn join(dir: &str, relative: &str) -> String {
+    normalize_lexically(&Path::new(dir).join(relative))
+}
+
+// `key = "value"` in the TOML `section` ("[package]"), without a TOML parser
+pub fn toml_field(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == section;
+        } else if in_section {
+            if let Some(value) = line.strip_prefix(key).map(str::trim_start).and_then(|l| l.strip_prefix('=')) {
+                return Some(value.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    None
+}
+
+fn existing(candidates: impl IntoIterator<Item = String>, files: &HashSet<&str>) -> Vec<String> {
+    let mut found: Vec<String> = candidates.into_iter().filter(|c| files.contains(c.as_str())).collect();
+    found.dedup();
+    found
+}
+
+fn npm_package(manifest: &str, dir: &str, files: &HashSet<&str>) -> Option<WorkspacePackage> {
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    let name = json.get("name")?.as_str()?.to_string();
+    let mut candidates: Vec<String> = ["source", "types", "typings", "module", "main"]
+        .iter()
+        .filter_map(|key| json.get(*key).and_then(|v| v.as_str()))
+        .map(|entry| join(dir, entry))
+        .collect();
+    for ext in JS_EXTENSIONS {
+        candidates.push(join(dir, &format!("src/index.{}", ext)));
+        candidates.push(join(dir, &format!("index.{}", ext)));
+    }
+    Some(WorkspacePackage {
+        name,
+        ecosystem: "npm".to_string(),
+        dir: dir.to_string(),
+        manifest: manifest.to_string(),
+        entry_files: existing(candidates, files).into_iter().take(1).collect(),
+    })
+}
+
+fn cargo_package(manifest: &str, dir: &str, files: &HashSet<&str>) -> Option<WorkspacePackage> {
+    let name = toml_field(&fs::read_to_string(manifest).ok()?, "[package]", "name")?;
+    Some(WorkspacePackage {
+        // Crates are imported with underscores
+        name: name.replace('-', "_"),
+        ecosystem: "cargo".to_string(),
+        dir: dir.to_string(),
+        manifest: manifest.to_string(),
+        entry_files: existing([join(dir, "src/lib.rs")], files),
+    })
+}
+
+fn python_package(manifest: &str, dir: &str, files: &HashSet<&str>) -> Option<WorkspacePackage> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let name = toml_field(&content, "[project]", "name").or_else(|| toml_field(&content, "[tool.poetry]", "name"))?;
+    let module = name.replace('-', "_").to_lowercase();
+    let candidates = [
+        join(dir, &format!("src/{}/__init__.py", module)),
+        join(dir, &format!("{}/__init__.py", module)),
+        join(dir, &format!("{}.py", module)),
+    ];
+    Some(WorkspacePackage {
+        name: module,
+        ecosystem: "python".to_string(),
+        dir: dir.to_string(),
+        manifest: manifest.to_string(),
+        entry_files: existing(candidates, files).into_iter().take(1).collect(),
+    })
+}
+
+fn go_package(manifest: &str, dir: &str) -> Option<WorkspacePackage> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let module = content.lines().find_map(|l| l.trim().strip_prefix("module "))?.trim().trim_matches('"');
+    Some(WorkspacePackage {
+        name: module.to_string(),
+        ecosystem: "go".to_string(),
+        dir: dir.to_string(),
+        manifest: manifest.to_string(),
+        entry_files: Vec::new(), // resolved per imported directory
+    })
+}
+
+/// Workspace packages declared by the manifests among `files`.
+pub fn discover_packages(files: &HashSet<&str>) -> Vec<WorkspacePackage> {
+    let mut packages: Vec<WorkspacePackage> = files
+        .iter()
+        .filter_map(|&path| {
+            let dir = parent_dir(path);
+            match file_name(path) {
+                "package.json" => npm_package(path, dir, files),
+                "Cargo.toml" => cargo_package(path, dir, files),
+                "pyproject.toml" => python_package(path, dir, files),
+                "go.mod" => go_package(path, dir),
+                _ => None,
+            }
+        })
+        .collect();
+    packages.sort_by(|a, b| a.dir.cmp(&b.dir).then(a.name.cmp(&b.name)));
+    packages
+}
+
+struct ResolvedImport {
+    package: usize,
+    files: Vec<String>,
+}
+
+// Bare imports of `path` split into workspace packages and external names
+fn package_imports(
+    path: &str,
+    packages: &[WorkspacePackage],
+    files: &HashSet<&str>,
+    go_files_by_dir: &BTreeMap<&str, Vec<&str>>,
+    external: &mut BTreeSet<String>,
+) -> Vec<ResolvedImport> {
+    let Ok(content) = fs::read_to_string(path) else { return Vec::new() };
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let find = |ecosystem: &str, name: &str| packages.iter().position(|p| p.ecosystem == ecosystem && p.name == name);
+    let mut resolved = Vec::new();
+
+    match extension {
+        ext if JS_EXTENSIONS.contains(&ext) => {
+            for cap in JS_BARE_IMPORT.captures_iter(&content) {
+                let specifier = &cap[1];
+                let segments = if specifier.starts_with('@') { 2 } else { 1 };
+                let name: String = specifier.splitn(segments + 1, '/').take(segments).collect::<Vec<_>>().join("/");
+                let subpath = specifier[name.len()..].trim_start_matches('/');
+                let Some(index) = find("npm", &name) else {
+                    external.insert(name);
+                    continue;
+                };
+                let package = &packages[index];
+                let files_for_import = if subpath.is_empty() {
+                    package.entry_files.clone()
+                } else {
+                    let mut candidates = Vec::new();
+                    for base in [join(&package.dir, subpath), join(&package.dir, &format!("src/{}", subpath))] {
+                        candidates.push(base.clone());
+                        for ext in JS_EXTENSIONS {
+                            candidates.push(format!("{}.{}", base, ext));
+                            candidates.push(join(&base, &format!("index.{}", ext)));
+                        }
+                    }
+                    existing(candidates, files).into_iter().take(1).collect()
+                };
+                resolved.push(ResolvedImport { package: index, files: files_for_import });
+            }
+        }
+        "rs" => {
+            for cap in RS_CRATE_USE.captures_iter(&content) {
+                let name = &cap[1];
+                if matches!(name, "crate" | "self" | "super" | "std" | "core" | "alloc") {
+                    continue;
+                }
+                match find("cargo", name) {
+                    Some(index) => resolved.push(ResolvedImport { package: index, files: packages[index].entry_files.clone() }),
+                    None => {
+                        external.insert(name.to_string());
+                    }
+                }
+            }
+        }
+        "py" => {
+            for cap in PY_TOP_IMPORT.captures_iter(&content) {
+                let name = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+                if let Some(index) = find("python", &name.to_lowercase()) {
+                    resolved.push(ResolvedImport { package: index, files: packages[index].entry_files.clone() });
+                }
+                // Absolute imports of the file's own project are handled by direct_imports
+            }
+        }
+        "go" => {
+            for cap in GO_IMPORT.captures_iter(&content) {
+                let import = &cap[1];
+                let Some((index, package)) = packages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.ecosystem == "go" && (import == p.name || import.starts_with(&format!("{}/", p.name))))
+                    .max_by_key(|(_, p)| p.name.len())
+                else {
+                    if import.contains('.') {
+                        external.insert(import.to_string());
+                    }
+                    continue;
+                };
+                let dir = join(&package.dir, import[package.name.len()..].trim_start_matches('/'));
+                let dir_files = go_files_by_dir.get(dir.as_str()).cloned().unwrap_or_default();
+                resolved.push(ResolvedImport { package: index, files: dir_files.iter().map(|f| f.to_string()).collect() });
+            }
+        }
+        _ => (),
+    }
+    resolved
+}
+
+/// The dependency closure of `targets` within `tree`, at most `max_files` files.
+pub fn dependency_closure(tree: &crate::types::FileNode, targets: &[String], max_files: usize) -> DependencyClosure {
+    let all: Vec<&str> = scan_results::collect_files(tree).into_iter().map(|f| f.path.as_str()).collect();
+    let files: HashSet<&str> = all.iter().copied().collect();
+    let packages = discover_packages(&files);
+    let mut go_files_by_dir: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for &file in all.iter().filter(|f| f.ends_with(".go") && !f.ends_with("_test.go")) {
+        go_files_by_dir.entry(parent_dir(file)).or_default().push(file);
+    }
+
+    let mut closure = DependencyClosure::default();
+    let mut selected: BTreeMap<String, DependencyFile> = BTreeMap::new();
+    let mut used_packages: BTreeSet<usize> = BTreeSet::new();
+    let mut external = BTreeSet::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+
+    for target in targets {
+        if files.contains(target.as_str()) {
+            selected.insert(
+                target.clone(),
+                DependencyFile { path: target.clone(), reason: "target".to_string(), via: None, package: None },
+            );
+            queue.push_back(target.clone());
+        } else {
+            closure.missing_targets.push(target.clone());
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let mut found: Vec<(String, &str, Option<usize>)> =
+            direct_imports(&current, &files).into_iter().map(|path| (path, "import", None)).collect();
+        for import in package_imports(&current, &packages, &files, &go_files_by_dir, &mut external) {
+            used_packages.insert(import.package);
+            found.extend(import.files.into_iter().map(|path| (path, "package", Some(import.package))));
+        }
+        for (path, reason, package) in found {
+            if selected.contains_key(&path) {
+                continue;
+            }
+            if selected.len() >= max_files {
+                closure.truncated = true;
+                break;
+            }
+            selected.insert(
+                path.clone(),
+                DependencyFile {
+                    path: path.clone(),
+                    reason: reason.to_string(),
+                    via: Some(current.clone()),
+                    package: package.map(|i| packages[i].name.clone()),
+                },
+            );
+            queue.push_back(path);
+        }
+        if closure.truncated {
+            break;
+        }
+    }
+
+    // Manifests of the packages the closure goes through, and of the targets' own packages
+    let mut manifest_packages = used_packages.clone();
+    for target in targets {
+        if let Some((index, _)) =
+            packages.iter().enumerate().filter(|(_, p)| Path::new(target).starts_with(&p.dir)).max_by_key(|(_, p)| p.dir.len())
+        {
+            manifest_packages.insert(index);
+        }
+    }
+    for index in manifest_packages {
+        let package = &packages[index];
+        selected.entry(package.manifest.clone()).or_insert_with(|| DependencyFile {
+            path: package.manifest.clone(),
+            reason: "manifest".to_string(),
+            via: None,
+            package: Some(package.name.clone()),
+        });
+    }
+
+    closure.packages = used_packages.into_iter().map(|i| packages[i].name.clone()).collect();
+    closure.external = external.into_iter().collect();
+    closure.files = selected.into_values().collect();
+    closure
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Suggests the files `targets` (paths from the last scan) actually depend on,
+/// following relative imports and imports of sibling workspace packages.
+#[command(async)]
+pub async fn suggest_dependency_selection(
+    state: State<'_, AppState>,
+    project_id: i32,
+    targets: Vec<String>,
+    max_files: Option<usize>,
+) -> Result<DependencyClosure, String> {
+    let tree = load_tree(&state, project_id)?;
+    let rules = load_rules(&state, project_id, Path::new(&tree.path))?;
+    let max_files = max_files.unwrap_or(DEFAULT_MAX_FILES).max(1);
+    tauri::async_runtime::spawn_blocking(move || {
+        let targets: Vec<String> = targets.iter().map(|t| canonical_path(t)).collect();
+        let mut closure = dependency_closure(&tree, &targets, max_files);
+        closure.files.retain(|f| f.reason == "target" || !rules.is_never(&f.path));
+        for path in rules.always_files(&tree) {
+            if !closure.files.iter().any(|f| f.path == path) {
+                closure.files.push(DependencyFile { path, reason: "always".to_string(), via: None, package: None });
+            }
+        }
+        closure
+    })
+    .await
+    .map_err(|e| format!("Dependency selection task failed: {}", e))
+}
@@ -0,0 +1,118 @@
+// src-tauri/src/dir_ignore_rules.rs
+// Extra ignore/include patterns attached to one subdirectory of a project, so
+// `examples/` can be excluded in a single vendored package without a project
+// pattern that matches every `examples/` in the tree. Patterns use gitignore
+// syntax relative to their directory and only apply below it; scans merge them
+// into `CompiledIgnorePatterns`, where the deepest directory's rules win.
+
+use crate::db::AppState;
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::path_canon::canonical_path;
+use ignore::gitignore::GitignoreBuilder;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use tauri::{command, State};
+
+pub const MODE_IGNORE: &str = "ignore";
+pub const MODE_INCLUDE: &str = "include";
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DirIgnoreRules {
+    pub dir: String,
+    pub ignore: Vec<String>,
+    /// Re-includes paths a project or global pattern ignores, below `dir` only.
+    /// A directory that is itself ignored isn't entered, so its contents can't
+    /// be re-included from a deeper rule.
+    pub include: Vec<String>,
+}
+
+impl DirIgnoreRules {
+    /// gitignore lines for the subtree; includes become `!` whitelist lines.
+    pub fn lines(&self) -> Vec<String> {
+        self.ignore.iter().cloned().chain(self.include.iter().map(|p| format!("!{}", p))).collect()
+    }
+}
+
+/// All rule sets of a project, one per directory, sorted by directory.
+pub fn load_dir_rules(conn: &Connection, project_id: i32) -> Result<Vec<DirIgnoreRules>, String> {
+    let mut stmt = conn
+        .prepare("SELECT dir_path, pattern, mode FROM dir_ignore_rules WHERE project_id = ?1 ORDER BY dir_path, pattern")
+        .map_err(|e| format!("Prepare directory ignore rules query failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+        .map_err(|e| format!("Query directory ignore rules failed: {}", e))?;
+    let mut by_dir: BTreeMap<String, DirIgnoreRules> = BTreeMap::new();
+    for row in rows {
+        let (dir, pattern, mode) = row.map_err(|e| format!("Failed to map directory ignore rule row: {}", e))?;
+        let rules = by_dir.entry(dir.clone()).or_insert_with(|| DirIgnoreRules { dir, ..Default::default() });
+        match mode.as_str() {
+            MODE_IGNORE => rules.ignore.push(pattern),
+            MODE_INCLUDE => rules.include.push(pattern),
+            other => eprintln!("[RULES] Ignoring directory rule '{}' with unknown mode '{}'.", pattern, other),
+        }
+    }
+    Ok(by_dir.into_values().collect())
+}
+
+/// Adds the project's directory rules to compiled scan patterns.
+pub fn apply_dir_rules(compiled: &mut CompiledIgnorePatterns, rules: &[DirIgnoreRules]) {
+    for set in rules {
+        compiled.add_subtree_rules(Path::new(&set.dir), &set.lines());
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn get_dir_ignore_rules(state: State<AppState>, project_id: i32) -> Result<Vec<DirIgnoreRules>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_dir_rules(&conn, project_id)
+}
+
+/// Replaces the rules of one directory; two empty lists remove them. Applies
+/// from the next scan.
+#[command]
+pub fn set_dir_ignore_rules(
+    state: State<AppState>,
+    project_id: i32,
+    dir: String,
+    ignore: Vec<String>,
+    include: Vec<String>,
+) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let dir = canonical_path(&dir);
+    if dir.is_empty() {
+        return Err("Directory path is empty.".to_string());
+    }
+    let clean = |patterns: Vec<String>| -> Vec<String> {
+        let set: BTreeSet<String> = patterns.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+        set.into_iter().collect()
+    };
+    let rules = DirIgnoreRules { dir: dir.clone(), ignore: clean(ignore), include: clean(include) };
+    if let Some(pattern) = rules.ignore.iter().find(|p| rules.include.contains(p)) {
+        return Err(format!("Pattern '{}' can't be both ignored and included.", pattern));
+    }
+    // Reject invalid globs before anything is stored
+    let mut builder = GitignoreBuilder::new(&dir);
+    for line in rules.lines() {
+        builder.add_line(None, &line).map_err(|e| format!("Invalid pattern '{}': {}", line, e))?;
+    }
+    builder.build().map_err(|e| format!("Invalid patterns for '{}': {}", dir, e))?;
+
+    let mut conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    tx.execute("DELETE FROM dir_ignore_rules WHERE project_id = ?1 AND dir_path = ?2", params![project_id, dir])
+        .map_err(|e| format!("Failed to clear rules of '{}': {}", dir, e))?;
+    for (patterns, mode) in [(&rules.ignore, MODE_IGNORE), (&rules.include, MODE_INCLUDE)] {
+        for pattern in patterns {
+            tx.execute(
+                "INSERT INTO dir_ignore_rules (project_id, dir_path, pattern, mode) VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, dir, pattern, mode],
+            )
+            .map_err(|e| format!("Failed to save rule '{}' for '{}': {}", pattern, dir, e))?;
+        }
+    }
+    tx.commit().map_err(|e| format!("Failed to commit directory ignore rules: {}", e))
+}
@@ -0,0 +1,197 @@
+// src-tauri/src/directory_headers.rs
+// Directory headers: for every folder an export includes files from, a short
+// orientation block placed before that folder's files. It is the folder's
+// README (cut to a token budget), else the description from its package
+// manifest, else a one-line summary of what the scan found there. In a context
+// spanning many packages, this tells the LLM what each one is for.
+
+use crate::context_exports::format_directory_block;
+use crate::db::AppState;
+use crate::dependency_closure::toml_field;
+use crate::export_forecast::selected_files;
+use crate::path_canon::canonical_paths;
+use crate::redaction::Redactor;
+use crate::tree_queries::load_tree;
+use crate::types::FileNode;
+use crate::utils::approximate_token_count;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::{command, State};
+
+pub const DEFAULT_README_TOKENS: usize = 400;
+const README_EXTENSIONS: &[&str] = &["", "md", "markdown", "txt", "rst", "adoc"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DirectoryHeader {
+    pub path: String,
+    /// "readme", "manifest" or "summary"
+    pub source: String,
+    /// The README or manifest the content came from
+    pub source_path: Option<String>,
+    pub content: String,
+    /// `content` wrapped for the export format
+    pub block: String,
+    pub tokens: usize,
+}
+
+fn is_readme(node: &FileNode) -> bool {
+    let name = node.name.to_lowercase();
+    let (stem, extension) = name.split_once('.').unwrap_or((name.as_str(), ""));
+    !node.is_dir && stem == "readme" && README_EXTENSIONS.contains(&extension)
+}
+
+// The README's leading lines within `max_tokens`
+fn readme_excerpt(text: &str, max_tokens: usize) -> String {
+    let text = text.trim();
+    if approximate_token_count(text) <= max_tokens {
+        return text.to_string();
+    }
+    let mut excerpt = String::new();
+    let mut tokens = 0;
+    for line in text.lines() {
+        tokens += approximate_token_count(line) + 1;
+        if tokens > max_tokens {
+            break;
+        }
+        excerpt.push_str(line);
+        excerpt.push('\n');
+    }
+    format!("{}\n[README truncated]", excerpt.trim_end())
+}
+
+fn manifest_description(dir: &FileNode) -> Option<(String, String)> {
+    for child in dir.children.iter().filter(|c| !c.is_dir) {
+        let description = match child.name.as_str() {
+            "package.json" => fs::read_to_string(&child.path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+                .and_then(|json| json.get("description")?.as_str().map(str::to_string)),
+            "Cargo.toml" => fs::read_to_string(&child.path).ok().and_then(|text| toml_field(&text, "[package]", "description")),
+            "pyproject.toml" => fs::read_to_string(&child.path).ok().and_then(|text| {
+                toml_field(&text, "[project]", "description").or_else(|| toml_field(&text, "[tool.poetry]", "description"))
+            }),
+            _ => None,
+        };
+        if let Some(description) = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()) {
+            return Some((description, child.path.clone()));
+        }
+    }
+    None
+}
+
+// "12 files, ~3400 tokens, mostly typescript (9), json (2); folders: hooks, utils"
+fn summary_line(dir: &FileNode) -> String {
+    fn count_languages(node: &FileNode, counts: &mut BTreeMap<String, usize>) {
+        for child in &node.children {
+            if child.is_dir {
+                count_languages(child, counts);
+            } else if let Some(language) = &child.language {
+                *counts.entry(language.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut counts = BTreeMap::new();
+    count_languages(dir, &mut counts);
+    let mut languages: Vec<(String, usize)> = counts.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let plural = if dir.file_count == 1 { "" } else { "s" };
+    let mut line = format!("{} file{}, ~{} tokens", dir.file_count, plural, dir.tokens);
+    if !languages.is_empty() {
+        let top: Vec<String> = languages.iter().take(3).map(|(l, n)| format!("{} ({})", l, n)).collect();
+        line.push_str(&format!(", mostly {}", top.join(", ")));
+    }
+    let folders: Vec<&str> = dir.children.iter().filter(|c| c.is_dir).map(|c| c.name.as_str()).collect();
+    if !folders.is_empty() {
+        let shown = folders.iter().take(8).copied().collect::<Vec<_>>().join(", ");
+        let more = if folders.len() > 8 { format!(" and {} more", folders.len() - 8) } else { String::new() };
+        line.push_str(&format!("; folders: {}{}", shown, more));
+    }
+    line
+}
+
+/// Headers for the folders holding `files` (the root included), in path order.
+/// A folder whose README is itself among the files gets none.
+pub fn directory_headers(
+    tree: &FileNode,
+    files: &[&FileNode],
+    format: &str,
+    max_readme_tokens: usize,
+    redactor: &Redactor,
+) -> Vec<DirectoryHeader> {
+    fn index<'a>(node: &'a FileNode, dirs: &mut HashMap<&'a str, &'a FileNode>) {
+        if node.is_dir {
+            dirs.insert(node.path.as_str(), node);
+            node.children.iter().for_each(|c| index(c, dirs));
+        }
+    }
+    let mut dirs = HashMap::new();
+    index(tree, &mut dirs);
+    let selected: HashSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    let mut included: BTreeSet<&str> = BTreeSet::new();
+    for file in files {
+        for ancestor in Path::new(&file.path).ancestors().skip(1) {
+            match ancestor.to_str().and_then(|a| dirs.get_key_value(a)) {
+                Some((path, _)) if included.insert(path) => {}
+                _ => break,
+            }
+        }
+    }
+
+    let mut headers = Vec::new();
+    for path in included {
+        let dir = dirs[path];
+        let readme = dir.children.iter().find(|c| is_readme(c));
+        if readme.is_some_and(|r| selected.contains(r.path.as_str())) {
+            continue;
+        }
+        let from_readme = readme.and_then(|r| {
+            let text = fs::read_to_string(&r.path).ok()?;
+            let excerpt = readme_excerpt(&text, max_readme_tokens);
+            (!excerpt.is_empty()).then(|| ("readme", Some(r.path.clone()), excerpt))
+        });
+        let (source, source_path, content) = from_readme
+            .or_else(|| manifest_description(dir).map(|(text, manifest)| ("manifest", Some(manifest), text)))
+            .unwrap_or_else(|| ("summary", None, summary_line(dir)));
+        let content = redactor.redact(&content).content;
+        let block = format_directory_block(path, &content, format);
+        headers.push(DirectoryHeader {
+            path: path.to_string(),
+            source: source.to_string(),
+            source_path,
+            tokens: approximate_token_count(&block),
+            content,
+            block,
+        });
+    }
+    headers
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Directory headers for an export of `selection` (files or folders) from the
+/// last scan, in the export `format` (default "sentinel"); READMEs are cut to
+/// `max_readme_tokens` (default 400). Insert each `block` before its folder's files.
+#[command(async)]
+pub async fn get_directory_headers(
+    state: State<'_, AppState>,
+    project_id: i32,
+    selection: Vec<String>,
+    format: Option<String>,
+    max_readme_tokens: Option<usize>,
+) -> Result<Vec<DirectoryHeader>, String> {
+    let selection = canonical_paths(&selection);
+    let tree = load_tree(&state, project_id)?;
+    let redactor = Redactor::for_state(&state);
+    let format = format.unwrap_or_else(|| "sentinel".to_string());
+    let max_readme_tokens = max_readme_tokens.unwrap_or(DEFAULT_README_TOKENS);
+    tauri::async_runtime::spawn_blocking(move || {
+        let (files, _) = selected_files(&tree, &selection);
+        let files: Vec<&FileNode> = files.into_values().collect();
+        directory_headers(&tree, &files, &format, max_readme_tokens, &redactor)
+    })
+    .await
+    .map_err(|e| format!("Directory header task failed: {}", e))
+}
@@ -0,0 +1,110 @@
+// src-tauri/src/document_extract.rs
+// Optional plain-text extraction for office/spec documents (.pdf, .docx) so they
+// can be token-counted during scans and included in built contexts.
+// Enabled via the `extract_documents` app setting ("true"/"false").
+
+use crate::app_settings;
+use crate::db::AppState;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+pub const EXTRACT_DOCUMENTS_SETTING: &str = "extract_documents";
+
+/// Whether document extraction is switched on in app_settings (off by default).
+pub fn extraction_enabled(conn: &rusqlite::Connection) -> bool {
+    matches!(
+        app_settings::get_setting_internal(conn, EXTRACT_DOCUMENTS_SETTING),
+        Ok(Some(v)) if v.trim().eq_ignore_ascii_case("true")
+    )
+}
+
+/// Same as `extraction_enabled`, for commands holding the managed state.
+/// A poisoned lock just means "disabled".
+pub fn is_enabled(state: &AppState) -> bool {
+    state.conn.lock().map(|conn| extraction_enabled(&conn)).unwrap_or(false)
+}
+
+pub fn is_extractable(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref(),
+        Some("pdf" | "docx")
+    )
+}
+
+/// Extracts the text layer of a supported document.
+pub fn extract_text(path: &Path) -> Result<String, String> {
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("pdf") => pdf_extract::extract_text(path)
+            .map(|text| normalize_extracted(&text))
+            .map_err(|e| format!("Failed to extract PDF text from '{}': {}", path.display(), e)),
+        Some("docx") => extract_docx_text(path),
+        _ => Err(format!("Unsupported document type: {}", path.display())),
+    }
+}
+
+fn extract_docx_text(path: &Path) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("'{}' is not a valid DOCX archive: {}", path.display(), e))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| format!("DOCX '{}' has no word/document.xml: {}", path.display(), e))?
+        .read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read DOCX body of '{}': {}", path.display(), e))?;
+    Ok(normalize_extracted(&docx_xml_to_text(&xml)))
+}
+
+// Walks WordprocessingML tags: text comes from <w:t>, paragraphs/breaks become newlines.
+fn docx_xml_to_text(xml: &str) -> String {
+    let mut out = String::with_capacity(xml.len() / 4);
+    let mut rest = xml;
+    let mut in_text = false;
+    while let Some(lt) = rest.find('<') {
+        if in_text {
+            out.push_str(&unescape_xml(&rest[..lt]));
+        }
+        let gt = match rest[lt..].find('>') {
+            Some(p) => lt + p,
+            None => break,
+        };
+        let tag = &rest[lt + 1..gt];
+        let name = tag.trim_start_matches('/').split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("");
+        match name {
+            "w:t" => in_text = !tag.starts_with('/') && !tag.ends_with('/'),
+            "w:p" if tag.starts_with('/') => out.push('\n'),
+            "w:br" | "w:cr" => out.push('\n'),
+            "w:tab" => out.push('\t'),
+            _ => (),
+        }
+        rest = &rest[gt + 1..];
+    }
+    out
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Trims trailing whitespace and collapses runs of blank lines left by layout extraction.
+fn normalize_extracted(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 { continue; }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
@@ -0,0 +1,184 @@
+// src-tauri/src/editor_launch.rs
+// Opens a file in the user's editor at a given line, so search and symbol
+// results can jump to the code. The editor is a command template from the
+// `editor_command` setting: either a preset name ("vscode", "jetbrains"...) or
+// a custom template with `{path}`, `{line}` and `{column}` placeholders, e.g.
+// `code --goto {path}:{line}:{column}`.
+
+use crate::app_settings;
+use crate::db::AppState;
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{command, State};
+
+pub const EDITOR_SETTING: &str = "editor_command";
+pub const DEFAULT_EDITOR: &str = "vscode";
+
+// (name, template)
+const PRESETS: &[(&str, &str)] = &[
+    ("vscode", "code --goto {path}:{line}:{column}"),
+    ("cursor", "cursor --goto {path}:{line}:{column}"),
+    ("jetbrains", "idea --line {line} --column {column} {path}"),
+    ("sublime", "subl {path}:{line}:{column}"),
+    ("vim", "gvim +{line} {path}"),
+    ("neovim", "nvim-qt -- +{line} {path}"),
+    ("zed", "zed {path}:{line}:{column}"),
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EditorPreset {
+    pub name: String,
+    pub template: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EditorLaunch {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+fn resolve_template(editor: &str) -> String {
+    PRESETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(editor.trim()))
+        .map(|(_, template)| template.to_string())
+        .unwrap_or_else(|| editor.trim().to_string())
+}
+
+// Whitespace-separated words; double or single quotes group words with spaces
+fn split_template(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in editor command '{}'.", template));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// The program and arguments `template` expands to. Placeholders are replaced
+/// per argument, so paths with spaces stay one argument; a template without
+/// `{path}` gets the path appended.
+pub fn build_launch(template: &str, path: &str, line: u32, column: u32) -> Result<EditorLaunch, String> {
+    let words = split_template(template)?;
+    let has_path = words.iter().any(|w| w.contains("{path}"));
+    let mut args: Vec<String> = words
+        .iter()
+        .map(|w| w.replace("{path}", path).replace("{line}", &line.to_string()).replace("{column}", &column.to_string()))
+        .collect();
+    if !has_path {
+        args.push(path.to_string());
+    }
+    if args.is_empty() {
+        return Err("Editor command is empty.".to_string());
+    }
+    let program = args.remove(0);
+    Ok(EditorLaunch { program, args })
+}
+
+// The file `program` runs as on Windows: a bare name is looked up on PATH with
+// each PATHEXT extension, so `code` finds the `code.cmd` shim
+fn resolve_windows_program(program: &str) -> PathBuf {
+    let given = Path::new(program);
+    if given.extension().is_some() && given.is_file() {
+        return given.to_path_buf();
+    }
+    let extensions = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let dirs: Vec<PathBuf> = match given.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(parent) => vec![parent.to_path_buf()],
+        None => env::var_os("PATH").map(|p| env::split_paths(&p).collect()).unwrap_or_default(),
+    };
+    let name = given.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let name = name.as_str();
+    dirs.iter()
+        .flat_map(|dir| extensions.split(';').filter(|e| !e.is_empty()).map(move |ext| dir.join(format!("{}{}", name, ext))))
+        .find(|candidate| candidate.is_file())
+        .unwrap_or_else(|| given.to_path_buf())
+}
+
+fn spawn(launch: &EditorLaunch) -> std::io::Result<()> {
+    // Editor launchers on Windows are usually .cmd shims. They're spawned by
+    // their full path rather than through `cmd /C`, which would re-parse `&`
+    // or `|` in file names; std escapes batch-file arguments itself (and
+    // refuses arguments it can't pass safely).
+    let program = if cfg!(target_os = "windows") {
+        resolve_windows_program(&launch.program)
+    } else {
+        PathBuf::from(&launch.program)
+    };
+    let mut cmd = Command::new(program);
+    cmd.args(&launch.args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    // The editor outlives the request (and usually returns at once); it's
+    // reaped on a detached thread so it doesn't linger as a zombie
+    let mut child = cmd.spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Editor presets usable as the `editor_command` setting.
+#[command]
+pub fn list_editor_presets() -> Vec<EditorPreset> {
+    PRESETS
+        .iter()
+        .map(|(name, template)| EditorPreset { name: name.to_string(), template: template.to_string() })
+        .collect()
+}
+
+/// Opens `path` at `line`/`column` (1-based, default 1) with `editor` (preset
+/// name or template), or the `editor_command` setting when omitted.
+#[command]
+pub fn open_in_editor(
+    state: State<AppState>,
+    path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    editor: Option<String>,
+) -> Result<EditorLaunch, String> {
+    if !Path::new(&path).is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+    let editor = match editor.filter(|e| !e.trim().is_empty()) {
+        Some(editor) => editor,
+        None => {
+            let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+            app_settings::get_setting_internal(&conn, EDITOR_SETTING)
+                .map_err(|e| format!("Failed to read setting '{}': {}", EDITOR_SETTING, e))?
+                .filter(|e| !e.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+        }
+    };
+    let launch = build_launch(&resolve_template(&editor), &path, line.unwrap_or(1).max(1), column.unwrap_or(1).max(1))?;
+    println!("[EDITOR] Opening '{}' with {} {:?}", path, launch.program, launch.args);
+    spawn(&launch).map_err(|e| format!("Failed to start editor '{}': {}", launch.program, e))?;
+    Ok(launch)
+}
@@ -0,0 +1,176 @@
+// src-tauri/src/errors.rs
+// User-presentable errors. Commands keep returning `Result<_, String>`; errors
+// meant for users carry a JSON `{code, params, fallback_message}` string
+// instead of raw English, and the frontend renders `code` from the catalog in
+// the language chosen by the `error_language` setting (falling back to
+// `fallback_message`). Errors not migrated yet are plain strings; use
+// `localize_error` to get either kind in structured form.
+
+use crate::app_settings;
+use crate::db::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{command, State};
+
+pub const LANGUAGE_SETTING: &str = "error_language";
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+pub const READ_ONLY_DATABASE: &str = "read_only_database";
+pub const PROJECT_NOT_FOUND: &str = "project_not_found";
+pub const PROJECT_NO_ROOT: &str = "project_no_root";
+pub const ROOT_NOT_DIRECTORY: &str = "root_not_directory";
+pub const NO_SCAN_RESULT: &str = "no_scan_result";
+pub const PROFILE_NOT_FOUND: &str = "profile_not_found";
+/// Any error without its own code; `params.detail` holds the raw message.
+pub const INTERNAL: &str = "internal";
+
+// (code, template) per language; `{name}` is replaced by the param `name`
+const EN: &[(&str, &str)] = &[
+    (READ_ONLY_DATABASE, "The database is read-only ({reason}); changes can't be saved."),
+    (PROJECT_NOT_FOUND, "Project {project_id} doesn't exist anymore."),
+    (PROJECT_NO_ROOT, "Project {project_id} has no root folder. Choose one in the project settings."),
+    (ROOT_NOT_DIRECTORY, "The root folder '{path}' can't be found or isn't a folder."),
+    (NO_SCAN_RESULT, "Project {project_id} hasn't been scanned yet. Scan it first."),
+    (PROFILE_NOT_FOUND, "There is no profile named '{name}'."),
+    (INTERNAL, "Something went wrong: {detail}"),
+];
+const DE: &[(&str, &str)] = &[
+    (READ_ONLY_DATABASE, "Die Datenbank ist schreibgeschützt ({reason}); Änderungen können nicht gespeichert werden."),
+    (PROJECT_NOT_FOUND, "Projekt {project_id} existiert nicht mehr."),
+    (PROJECT_NO_ROOT, "Projekt {project_id} hat keinen Stammordner. Wähle einen in den Projekteinstellungen."),
+    (ROOT_NOT_DIRECTORY, "Der Stammordner '{path}' wurde nicht gefunden oder ist kein Ordner."),
+    (NO_SCAN_RESULT, "Projekt {project_id} wurde noch nicht gescannt. Bitte zuerst scannen."),
+    (PROFILE_NOT_FOUND, "Es gibt kein Profil mit dem Namen '{name}'."),
+    (INTERNAL, "Etwas ist schiefgelaufen: {detail}"),
+];
+const ES: &[(&str, &str)] = &[
+    (READ_ONLY_DATABASE, "La base de datos es de solo lectura ({reason}); no se pueden guardar los cambios."),
+    (PROJECT_NOT_FOUND, "El proyecto {project_id} ya no existe."),
+    (PROJECT_NO_ROOT, "El proyecto {project_id} no tiene carpeta raíz. Elige una en la configuración del proyecto."),
+    (ROOT_NOT_DIRECTORY, "La carpeta raíz '{path}' no existe o no es una carpeta."),
+    (NO_SCAN_RESULT, "El proyecto {project_id} aún no se ha escaneado. Escanéalo primero."),
+    (PROFILE_NOT_FOUND, "No existe ningún perfil llamado '{name}'."),
+    (INTERNAL, "Algo salió mal: {detail}"),
+];
+const FR: &[(&str, &str)] = &[
+    (READ_ONLY_DATABASE, "La base de données est en lecture seule ({reason}) ; les modifications ne peuvent pas être enregistrées."),
+    (PROJECT_NOT_FOUND, "Le projet {project_id} n'existe plus."),
+    (PROJECT_NO_ROOT, "Le projet {project_id} n'a pas de dossier racine. Choisissez-en un dans les paramètres du projet."),
+    (ROOT_NOT_DIRECTORY, "Le dossier racine '{path}' est introuvable ou n'est pas un dossier."),
+    (NO_SCAN_RESULT, "Le projet {project_id} n'a pas encore été analysé. Lancez d'abord une analyse."),
+    (PROFILE_NOT_FOUND, "Aucun profil nommé '{name}'."),
+    (INTERNAL, "Une erreur s'est produite : {detail}"),
+];
+
+const LANGUAGES: &[(&str, &[(&str, &str)])] = &[("en", EN), ("de", DE), ("es", ES), ("fr", FR)];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppError {
+    pub code: String,
+    pub params: BTreeMap<String, String>,
+    /// English message, for logs and frontends without the catalog
+    pub fallback_message: String,
+}
+
+impl AppError {
+    pub fn new(code: &str, params: &[(&str, String)]) -> Self {
+        let params: BTreeMap<String, String> = params.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        AppError { fallback_message: render(EN, code, &params), code: code.to_string(), params }
+    }
+
+    /// The error as a command error string (JSON).
+    pub fn into_message(self) -> String {
+        serde_json::to_string(&self).unwrap_or(self.fallback_message)
+    }
+
+    /// A command error string in structured form: catalog errors as they are,
+    /// plain strings as `internal` with the text as `detail`.
+    pub fn parse(message: &str) -> Self {
+        serde_json::from_str::<AppError>(message)
+            .unwrap_or_else(|_| AppError::new(INTERNAL, &[("detail", message.to_string())]))
+    }
+}
+
+/// Readable text of a command error string: a catalog error's fallback
+/// message, a plain string as it is. For logs and errors wrapped in prose.
+pub fn plain_message(message: &str) -> String {
+    serde_json::from_str::<AppError>(message).map(|e| e.fallback_message).unwrap_or_else(|_| message.to_string())
+}
+
+/// Shorthand for `AppError::new(code, params).into_message()`.
+pub fn app_error(code: &str, params: &[(&str, String)]) -> String {
+    AppError::new(code, params).into_message()
+}
+
+fn catalog_for(language: &str) -> (&'static str, &'static [(&'static str, &'static str)]) {
+    let base = language.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(lang, _)| *lang == base)
+        .copied()
+        .unwrap_or((DEFAULT_LANGUAGE, EN))
+}
+
+fn render(catalog: &[(&str, &str)], code: &str, params: &BTreeMap<String, String>) -> String {
+    let template = catalog
+        .iter()
+        .chain(EN.iter())
+        .find(|(c, _)| *c == code)
+        .map(|(_, t)| *t)
+        .unwrap_or("{detail}");
+    let mut message = template.to_string();
+    for (name, value) in params {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+fn resolve_language(state: &AppState, language: Option<String>) -> Result<String, String> {
+    if let Some(language) = language.filter(|l| !l.trim().is_empty()) {
+        return Ok(language);
+    }
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    Ok(app_settings::get_setting_internal(&conn, LANGUAGE_SETTING)
+        .map_err(|e| format!("Failed to read setting '{}': {}", LANGUAGE_SETTING, e))?
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string()))
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ErrorCatalog {
+    /// Language actually used (unknown languages fall back to English)
+    pub language: String,
+    pub available_languages: Vec<String>,
+    /// Code -> template with `{param}` placeholders
+    pub messages: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LocalizedError {
+    pub code: String,
+    pub params: BTreeMap<String, String>,
+    pub message: String,
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Message templates for `language`, or the `error_language` setting when omitted.
+#[command]
+pub fn get_error_catalog(state: State<AppState>, language: Option<String>) -> Result<ErrorCatalog, String> {
+    let (language, catalog) = catalog_for(&resolve_language(&state, language)?);
+    let mut messages: BTreeMap<String, String> = EN.iter().map(|(c, t)| (c.to_string(), t.to_string())).collect();
+    messages.extend(catalog.iter().map(|(c, t)| (c.to_string(), t.to_string())));
+    Ok(ErrorCatalog {
+        language: language.to_string(),
+        available_languages: LANGUAGES.iter().map(|(l, _)| l.to_string()).collect(),
+        messages,
+    })
+}
+
+/// Renders any command error (catalog JSON or plain string) in `language`, or
+/// the `error_language` setting when omitted.
+#[command]
+pub fn localize_error(state: State<AppState>, error: String, language: Option<String>) -> Result<LocalizedError, String> {
+    let (_, catalog) = catalog_for(&resolve_language(&state, language)?);
+    let parsed = AppError::parse(&error);
+    Ok(LocalizedError { message: render(catalog, &parsed.code, &parsed.params), code: parsed.code, params: parsed.params })
+}
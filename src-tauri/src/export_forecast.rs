@@ -0,0 +1,255 @@
+// src-tauri/src/export_forecast.rs
+// `forecast_export`: the size of an export before it's built, so a selection
+// can be tuned without generating (and copying) megabytes of output each time.
+// Raw sizes come from the last scan's per-file counts plus the size of each
+// file's wrapper; compressed sizes need each file compressed, but only its
+// counts are kept, never the assembled output.
+
+use crate::compress::{read_and_compress_guarded, OptionsSource, SmartCompressOptions};
+use crate::context_exports::{escape_xml, format_file_block};
+use crate::db::AppState;
+use crate::directory_headers::{directory_headers, DEFAULT_README_TOKENS};
+use crate::document_extract;
+use crate::path_canon::canonical_paths;
+use crate::projects;
+use crate::redaction::Redactor;
+use crate::scan_results::collect_files;
+use crate::tree_queries::load_tree;
+use crate::types::FileNode;
+use crate::utils::approximate_token_count;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Add;
+use tauri::{command, State};
+
+const FORMATS: &[&str] = &["markdown", "xml", "sentinel", "raw"];
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastOptions {
+    /// "markdown", "xml", "sentinel" (default) or "raw"
+    #[serde(default)]
+    pub format: Option<String>,
+    /// None = each file's per-language compression defaults
+    #[serde(default)]
+    pub compression: Option<SmartCompressOptions>,
+    /// Skip the compressed forecast, the only part that reads the files
+    #[serde(default)]
+    pub skip_compressed: bool,
+    /// Count a README or summary header per included folder (see directory_headers)
+    #[serde(default)]
+    pub directory_headers: bool,
+    /// README budget per header; default 400
+    #[serde(default)]
+    pub max_readme_tokens: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExportSize {
+    pub bytes: u64,
+    pub tokens: usize,
+}
+
+impl Add for ExportSize {
+    type Output = ExportSize;
+    fn add(self, other: ExportSize) -> ExportSize {
+        ExportSize { bytes: self.bytes + other.bytes, tokens: self.tokens + other.tokens }
+    }
+}
+
+impl ExportSize {
+    fn of(text: &str) -> Self {
+        ExportSize { bytes: text.len() as u64, tokens: approximate_token_count(text) }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ExportForecast {
+    pub format: String,
+    pub file_count: usize,
+    /// The selected files' blocks as read, from the last scan's counts
+    pub raw: ExportSize,
+    /// The same blocks compressed; None when skipped
+    pub compressed: Option<ExportSize>,
+    /// `raw` with the project's file tree prepended
+    pub with_tree: ExportSize,
+    /// `raw` with the project prefix prepended
+    pub with_prefix: ExportSize,
+    /// The file tree block alone
+    pub tree: ExportSize,
+    /// The project prefix alone (zero when the project has none)
+    pub prefix: ExportSize,
+    /// The directory headers alone; already part of `raw` and `compressed` (zero when off)
+    pub directory_headers: ExportSize,
+    /// Selected paths the last scan doesn't have
+    pub not_found: Vec<String>,
+    /// Files the scan didn't count (binary, too large, unreadable); only their wrappers are included
+    pub uncounted: Vec<String>,
+    /// Files that failed to compress; left out of `compressed`
+    pub compression_errors: BTreeMap<String, String>,
+}
+
+fn index_nodes<'a>(node: &'a FileNode, index: &mut HashMap<&'a str, &'a FileNode>) {
+    index.insert(node.path.as_str(), node);
+    for child in &node.children {
+        index_nodes(child, index);
+    }
+}
+
+/// The files of `tree` named by `selection` (files, or folders for everything
+/// below them) by path, and the selected paths the tree doesn't have.
+pub fn selected_files<'a>(tree: &'a FileNode, selection: &[String]) -> (BTreeMap<&'a str, &'a FileNode>, Vec<String>) {
+    let mut index = HashMap::new();
+    index_nodes(tree, &mut index);
+    let mut files = BTreeMap::new();
+    let mut not_found = Vec::new();
+    for path in BTreeSet::from_iter(selection.iter()) {
+        match index.get(path.as_str()) {
+            Some(node) if node.is_dir => files.extend(collect_files(node).into_iter().map(|f| (f.path.as_str(), f))),
+            Some(node) => {
+                files.insert(node.path.as_str(), *node);
+            }
+            None => not_found.push(path.clone()),
+        }
+    }
+    (files, not_found)
+}
+
+// The aggregator's "prepend file tree" block for `format`
+fn tree_block(root: &FileNode, format: &str) -> String {
+    fn walk(node: &FileNode, last_stack: &mut Vec<bool>, out: &mut String) {
+        let depth = last_stack.len();
+        for is_last in &last_stack[..depth - 1] {
+            out.push_str(if *is_last { "    " } else { "│   " });
+        }
+        out.push_str(if last_stack[depth - 1] { "└── " } else { "├── " });
+        let icon = if node.is_dir { "📁" } else { "📄" };
+        let slash = if node.is_dir { "/" } else { "" };
+        out.push_str(&format!("{} {}{}\n", icon, escape_xml(&node.name.replace('\\', "/")), slash));
+        if node.is_dir {
+            for (i, child) in node.children.iter().enumerate() {
+                last_stack.push(i == node.children.len() - 1);
+                walk(child, last_stack, out);
+                last_stack.pop();
+            }
+        }
+    }
+
+    let icon = if root.is_dir { "📁" } else { "📄" };
+    let mut text = format!("{} {}/\n", icon, escape_xml(&root.name.replace('\\', "/")));
+    let mut last_stack = Vec::new();
+    for (i, child) in root.children.iter().enumerate() {
+        last_stack.push(i == root.children.len() - 1);
+        walk(child, &mut last_stack, &mut text);
+        last_stack.pop();
+    }
+    let text = text.trim();
+    match format {
+        "xml" => format!("<File_Tree><![CDATA[\n{}\n]]></File_Tree>\n\n\n", text),
+        "sentinel" => format!("-----BEGIN FILE TREE-----\n{}\n-----END FILE TREE-----\n\n\n", text),
+        _ => format!("# File Tree\n\n~~~~text\n{}\n~~~~\n\n\n", text),
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Token and byte totals of exporting `selection` (files or folders) from the
+/// last scan of `project_id`: raw, compressed, with the file tree and with the
+/// project prefix. Counts are estimates: redaction and file notes aren't applied.
+#[command(async)]
+pub async fn forecast_export(
+    state: State<'_, AppState>,
+    project_id: i32,
+    selection: Vec<String>,
+    options: Option<ForecastOptions>,
+) -> Result<ExportForecast, String> {
+    let selection = canonical_paths(&selection);
+    let options = options.unwrap_or_default();
+    let format = options.format.unwrap_or_else(|| "sentinel".to_string());
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(format!("Unknown export format '{}'; use one of: {}.", format, FORMATS.join(", ")));
+    }
+    let prefix = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        projects::load_project_by_id(&conn, project_id)?.prefix
+    };
+    let tree = load_tree(&state, project_id)?;
+    let compression = if options.skip_compressed { None } else { Some(OptionsSource::new(&state, options.compression)) };
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (files, not_found) = selected_files(&tree, &selection);
+        let mut forecast = ExportForecast { format: format.clone(), not_found, ..Default::default() };
+        forecast.file_count = files.len();
+
+        // (path, wrapper size, wrapper-free raw size)
+        let sized: Vec<(&str, ExportSize, ExportSize)> = files
+            .par_iter()
+            .map(|(path, node)| {
+                let wrapper = ExportSize::of(&format_file_block(path, "", &format));
+                let content = if node.skipped_reason.is_some() {
+                    ExportSize::default()
+                } else {
+                    ExportSize { bytes: node.size, tokens: node.tokens }
+                };
+                (*path, wrapper, content)
+            })
+            .collect();
+        for (path, wrapper, content) in &sized {
+            forecast.raw = forecast.raw + *wrapper + *content;
+            if files[path].skipped_reason.is_some() {
+                forecast.uncounted.push(path.to_string());
+            }
+        }
+
+        if options.directory_headers {
+            let listed: Vec<&FileNode> = files.values().copied().collect();
+            let max_readme_tokens = options.max_readme_tokens.unwrap_or(DEFAULT_README_TOKENS);
+            for header in directory_headers(&tree, &listed, &format, max_readme_tokens, &redactor) {
+                forecast.directory_headers = forecast.directory_headers + ExportSize { bytes: header.block.len() as u64, tokens: header.tokens };
+            }
+            forecast.raw = forecast.raw + forecast.directory_headers;
+        }
+
+        if let Some(compression) = &compression {
+            let compressed: Vec<(&str, Result<ExportSize, String>)> = sized
+                .par_iter()
+                .map(|(path, wrapper, _)| {
+                    let size = read_and_compress_guarded(path, compression.for_path(path).as_ref(), extract_documents, &redactor)
+                        .map(|file| *wrapper + ExportSize::of(&file.content));
+                    (*path, size)
+                })
+                .collect();
+            let mut total = ExportSize::default();
+            for (path, size) in compressed {
+                match size {
+                    Ok(size) => total = total + size,
+                    Err(e) => {
+                        forecast.compression_errors.insert(path.to_string(), e);
+                    }
+                }
+            }
+            forecast.compressed = Some(total + forecast.directory_headers);
+        }
+
+        forecast.tree = ExportSize::of(&tree_block(&tree, &format));
+        if !prefix.trim().is_empty() {
+            forecast.prefix = ExportSize::of(&format!("{}\n\n", prefix.trim()));
+        }
+        forecast.with_tree = forecast.raw + forecast.tree;
+        forecast.with_prefix = forecast.raw + forecast.prefix;
+        println!(
+            "[FORECAST] Project {}: {} file(s) as {}, ~{} raw tokens, {} not found",
+            project_id,
+            forecast.file_count,
+            format,
+            forecast.raw.tokens,
+            forecast.not_found.len()
+        );
+        forecast
+    })
+    .await
+    .map_err(|e| format!("Forecast task failed: {}", e))
+}
@@ -0,0 +1,126 @@
+// src-tauri/src/export_layout.rs
+// Grouped export layouts: instead of strict tree order, an export can list its
+// files by language or by package, each group under a section header carrying
+// its file count and token subtotal. A package is the nearest workspace
+// package (see dependency_closure) or, outside any, the top-level folder.
+
+use crate::context_exports::format_group_section;
+use crate::db::AppState;
+use crate::dependency_closure::discover_packages;
+use crate::export_forecast::selected_files;
+use crate::path_canon::canonical_paths;
+use crate::scan_results::collect_files;
+use crate::tree_queries::load_tree;
+use crate::types::FileNode;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tauri::{command, State};
+
+// Files without a detected language / outside every folder
+const OTHER_GROUP: &str = "other";
+const ROOT_GROUP: &str = "(root)";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportGroup {
+    pub label: String,
+    /// In tree (path) order
+    pub files: Vec<String>,
+    pub tokens: usize,
+    /// Section header and footer for the export format; insert around the group's files
+    pub header: String,
+    pub footer: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportLayout {
+    /// "language" or "package"
+    pub group_by: String,
+    /// Largest group first
+    pub groups: Vec<ExportGroup>,
+    pub total_tokens: usize,
+    /// Selected paths the last scan doesn't have
+    pub not_found: Vec<String>,
+}
+
+// Package label per file: the deepest workspace package holding it, else its top-level folder
+fn package_labels(tree: &FileNode, files: &[&FileNode]) -> Vec<String> {
+    let all: HashSet<&str> = collect_files(tree).into_iter().map(|f| f.path.as_str()).collect();
+    let mut packages = discover_packages(&all);
+    packages.sort_by_key(|p| std::cmp::Reverse(p.dir.len()));
+    let root = Path::new(&tree.path);
+    files
+        .iter()
+        .map(|file| {
+            let path = Path::new(&file.path);
+            if let Some(package) = packages.iter().find(|p| path.starts_with(&p.dir)) {
+                return package.name.clone();
+            }
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let mut components = relative.components();
+            match (components.next(), components.next()) {
+                (Some(top), Some(_)) => top.as_os_str().to_string_lossy().to_string(),
+                _ => ROOT_GROUP.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// `files` grouped by "language" or "package", largest group first.
+pub fn group_files(tree: &FileNode, files: &[&FileNode], group_by: &str, format: &str) -> Result<Vec<ExportGroup>, String> {
+    let labels: Vec<String> = match group_by {
+        "language" => files.iter().map(|f| f.language.clone().unwrap_or_else(|| OTHER_GROUP.to_string())).collect(),
+        "package" => package_labels(tree, files),
+        other => return Err(format!("Unknown grouping '{}'; use \"language\" or \"package\".", other)),
+    };
+    let mut grouped: BTreeMap<String, Vec<&FileNode>> = BTreeMap::new();
+    for (file, label) in files.iter().zip(labels) {
+        grouped.entry(label).or_default().push(file);
+    }
+    let mut groups: Vec<ExportGroup> = grouped
+        .into_iter()
+        .map(|(label, mut members)| {
+            members.sort_by(|a, b| a.path.cmp(&b.path));
+            let tokens = members.iter().map(|f| f.tokens).sum();
+            let (header, footer) = format_group_section(&label, members.len(), tokens, format);
+            ExportGroup { files: members.iter().map(|f| f.path.clone()).collect(), label, tokens, header, footer }
+        })
+        .collect();
+    // The catch-all group goes last whatever its size
+    groups.sort_by(|a, b| {
+        let catch_all = |g: &ExportGroup| g.label == OTHER_GROUP || g.label == ROOT_GROUP;
+        catch_all(a).cmp(&catch_all(b)).then(b.tokens.cmp(&a.tokens)).then(a.label.cmp(&b.label))
+    });
+    Ok(groups)
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Groups the files of `selection` (files or folders) from the last scan by
+/// `group_by` ("language" or "package"), with section headers in the export
+/// `format` (default "sentinel"). Token subtotals come from the scan's counts.
+#[command(async)]
+pub async fn get_export_layout(
+    state: State<'_, AppState>,
+    project_id: i32,
+    selection: Vec<String>,
+    group_by: String,
+    format: Option<String>,
+) -> Result<ExportLayout, String> {
+    let selection = canonical_paths(&selection);
+    let tree = load_tree(&state, project_id)?;
+    // Package grouping reads the project's manifests
+    tauri::async_runtime::spawn_blocking(move || {
+        let (files, not_found) = selected_files(&tree, &selection);
+        let files: Vec<&FileNode> = files.into_values().collect();
+        let groups = group_files(&tree, &files, &group_by, format.as_deref().unwrap_or("sentinel"))?;
+        Ok(ExportLayout {
+            total_tokens: groups.iter().map(|g| g.tokens).sum(),
+            group_by,
+            groups,
+            not_found,
+        })
+    })
+    .await
+    .map_err(|e| format!("Export layout task failed: {}", e))?
+}
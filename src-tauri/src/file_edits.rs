@@ -0,0 +1,337 @@
+// src-tauri/src/file_edits.rs
+// `apply_file_edits`: writes edits suggested by an LLM back to disk, closing the
+// loop from context export to applied change. Each edit is either the file's
+// full new content or a unified diff. Before writing, the file is checked
+// against the version recorded by the export (or an explicit hash), so edits
+// made against an outdated copy are refused instead of clobbering newer work.
+// Originals are backed up under `edit_backups/` next to the database.
+
+use crate::context_exports::{file_version, load_export_files};
+use crate::db::{db_dir, AppState};
+use crate::path_canon::canonical_path;
+use crate::path_case::CaseMode;
+use crate::projects;
+use crate::scan_cache::root_prefix;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+const BACKUP_DIR: &str = "edit_backups";
+
+static HUNK_HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").expect("hunk header regex"));
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileEdit {
+    pub path: String,
+    /// Full new content; takes precedence over `diff`
+    pub content: Option<String>,
+    /// Unified diff against the current content
+    pub diff: Option<String>,
+    /// FNV-1a hex hash the file must still have (overrides the export's record)
+    pub expected_hash: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileEditResult {
+    pub path: String,
+    /// "applied", "would_apply" (dry run), "unchanged", "conflict" or "error"
+    pub status: String,
+    pub message: Option<String>,
+    pub backup_path: Option<String>,
+    pub created: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ApplyEditsReport {
+    pub results: Vec<FileEditResult>,
+    pub applied: usize,
+    pub failed: usize,
+    /// Folder holding this batch's backups, when anything was backed up
+    pub backup_dir: Option<String>,
+}
+
+struct Hunk {
+    old_start: usize,
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+}
+
+fn parse_diff(diff: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in diff.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if let Some(cap) = HUNK_HEADER.captures(line) {
+            hunks.push(Hunk { old_start: cap[1].parse().unwrap_or(1), old_lines: Vec::new(), new_lines: Vec::new() });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else { continue }; // ---/+++ headers before the first hunk
+        match line.chars().next() {
+            Some('-') => hunk.old_lines.push(line[1..].to_string()),
+            Some('+') => hunk.new_lines.push(line[1..].to_string()),
+            Some(' ') => {
+                hunk.old_lines.push(line[1..].to_string());
+                hunk.new_lines.push(line[1..].to_string());
+            }
+            // Blank context lines often lose their leading space when pasted
+            None => {
+                hunk.old_lines.push(String::new());
+                hunk.new_lines.push(String::new());
+            }
+            _ => (), // "\ No newline at end of file", "diff --git", "index ..."
+        }
+    }
+    if hunks.is_empty() {
+        return Err("The diff has no hunks.".to_string());
+    }
+    Ok(hunks)
+}
+
+// Position of `needle` in `lines` at or after `from`, nearest to `expected` first
+fn find_hunk(lines: &[String], needle: &[String], from: usize, expected: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(expected.clamp(from, lines.len()));
+    }
+    let last_start = lines.len().checked_sub(needle.len())?;
+    if from > last_start {
+        return None;
+    }
+    let matches_at = |start: usize| lines[start..start + needle.len()] == *needle;
+    let expected = expected.clamp(from, last_start);
+    (0..=last_start.saturating_sub(from)).find_map(|distance| {
+        let after = expected + distance;
+        if after <= last_start && matches_at(after) {
+            return Some(after);
+        }
+        let before = expected.checked_sub(distance).filter(|b| *b >= from)?;
+        matches_at(before).then_some(before)
+    })
+}
+
+/// Applies a unified diff to `original`, keeping its line endings. Hunks may
+/// sit at other line numbers than their headers say, but must match exactly.
+pub fn apply_unified_diff(original: &str, diff: &str) -> Result<String, String> {
+    let line_ending = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    let trailing_newline = original.is_empty() || original.ends_with('\n');
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut cursor = 0;
+    let mut shift: isize = 0;
+    for (index, hunk) in parse_diff(diff)?.iter().enumerate() {
+        let expected = (hunk.old_start.saturating_sub(1) as isize + shift).max(0) as usize;
+        let start = find_hunk(&lines, &hunk.old_lines, cursor, expected)
+            .ok_or_else(|| format!("Hunk {} (line {}) doesn't match the file.", index + 1, hunk.old_start))?;
+        lines.splice(start..start + hunk.old_lines.len(), hunk.new_lines.iter().cloned());
+        cursor = start + hunk.new_lines.len();
+        shift += hunk.new_lines.len() as isize - hunk.old_lines.len() as isize;
+    }
+    let mut result = lines.join(line_ending);
+    if trailing_newline && !result.is_empty() {
+        result.push_str(line_ending);
+    }
+    Ok(result)
+}
+
+// Backup location mirroring the file's path below the batch folder
+fn backup_path_for(batch_dir: &Path, path: &str) -> PathBuf {
+    let relative: PathBuf = Path::new(path)
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => Some(part.to_os_string()),
+            std::path::Component::Prefix(prefix) => {
+                Some(prefix.as_os_str().to_string_lossy().replace([':', '\\', '/', '?'], "").into())
+            }
+            _ => None,
+        })
+        .collect();
+    batch_dir.join(relative)
+}
+
+// Temp file + rename, so a crash never leaves a half-written file
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.ccb-edit.tmp", file_name));
+    fs::write(&temp, content).map_err(|e| format!("Failed to write '{}': {}", temp.display(), e))?;
+    if let Ok(meta) = fs::metadata(path) {
+        let _ = fs::set_permissions(&temp, meta.permissions());
+    }
+    fs::rename(&temp, path).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to replace '{}': {}", path.display(), e)
+    })
+}
+
+// `path` with symlinks resolved; for a file that doesn't exist yet, its nearest
+// existing ancestor is resolved and the rest appended
+fn resolve_on_disk(path: &Path) -> Option<String> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(real) = existing.canonicalize() {
+            let full = missing.iter().rev().fold(real, |acc: PathBuf, part| acc.join(part));
+            return Some(canonical_path(&full.to_string_lossy()));
+        }
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+fn apply_one(
+    edit: &FileEdit,
+    root: &str,
+    case_mode: CaseMode,
+    exported: &BTreeMap<String, String>,
+    batch_dir: &Path,
+    dry_run: bool,
+) -> FileEditResult {
+    let path = canonical_path(&edit.path);
+    let mut result =
+        FileEditResult { path: path.clone(), status: "error".to_string(), message: None, backup_path: None, created: false };
+    let fail = |mut result: FileEditResult, status: &str, message: String| {
+        result.status = status.to_string();
+        result.message = Some(message);
+        result
+    };
+
+    if !case_mode.starts_with(&path, &root_prefix(root)) {
+        return fail(result, "error", format!("'{}' is outside the project root.", path));
+    }
+    // Checked again once resolved, so a symlink in the project can't lead the write outside it
+    let real_root = resolve_on_disk(Path::new(root)).unwrap_or_else(|| root.to_string());
+    let real_path = match resolve_on_disk(Path::new(&path)) {
+        Some(real) if case_mode.starts_with(&real, &root_prefix(&real_root)) => real,
+        _ => return fail(result, "error", format!("'{}' resolves to a location outside the project root.", path)),
+    };
+    let exists = Path::new(&real_path).is_file();
+    let original = if exists {
+        match fs::read_to_string(&real_path) {
+            Ok(content) => content,
+            Err(e) => return fail(result, "error", format!("Failed to read '{}': {}", path, e)),
+        }
+    } else {
+        String::new()
+    };
+
+    // The file must still be the version the LLM saw; an existing file without
+    // a known version is refused rather than overwritten blindly
+    let expected = edit.expected_hash.as_deref().or_else(|| exported.get(&path).map(String::as_str));
+    match expected {
+        Some(_) if !exists => {
+            return fail(result, "conflict", "The file was deleted since it was exported.".to_string());
+        }
+        Some(expected) => match file_version(&real_path) {
+            Ok(current) if !current.hash.eq_ignore_ascii_case(expected) => {
+                return fail(result, "conflict", "The file changed since it was exported.".to_string());
+            }
+            Err(e) => return fail(result, "error", e),
+            Ok(_) => (),
+        },
+        None if exists => {
+            let message = if exported.is_empty() {
+                "The file already exists; pass expected_hash or an export_id to edit it."
+            } else {
+                "The file wasn't part of the export; pass expected_hash to edit it."
+            };
+            return fail(result, "conflict", message.to_string());
+        }
+        None => (),
+    }
+
+    let new_content = match (&edit.content, &edit.diff) {
+        (Some(content), _) => content.clone(),
+        (None, Some(diff)) => match apply_unified_diff(&original, diff) {
+            Ok(content) => content,
+            Err(e) => return fail(result, "conflict", e),
+        },
+        (None, None) => return fail(result, "error", "The edit has neither content nor a diff.".to_string()),
+    };
+    result.created = !exists;
+    if exists && new_content == original {
+        result.status = "unchanged".to_string();
+        return result;
+    }
+    if dry_run {
+        result.status = "would_apply".to_string();
+        return result;
+    }
+
+    if exists {
+        let backup = backup_path_for(batch_dir, &real_path);
+        if let Some(parent) = backup.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                return fail(result, "error", format!("Failed to create backup folder '{}': {}", parent.display(), e));
+            }
+        }
+        if let Err(e) = fs::copy(&real_path, &backup) {
+            return fail(result, "error", format!("Failed to back up '{}': {}", path, e));
+        }
+        result.backup_path = Some(backup.to_string_lossy().to_string());
+    }
+    match write_atomically(Path::new(&real_path), &new_content) {
+        Ok(()) => {
+            result.status = "applied".to_string();
+            result
+        }
+        Err(e) => fail(result, "error", e),
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Writes `edits` (full content or unified diff per file) into the project.
+/// Every existing file must still match the version recorded by `export_id` or
+/// the edit's `expected_hash`; an existing file with neither is refused. Files
+/// that don't exist yet are created. `dry_run` validates without writing.
+#[command(async)]
+pub async fn apply_file_edits(
+    state: State<'_, AppState>,
+    project_id: i32,
+    edits: Vec<FileEdit>,
+    export_id: Option<i64>,
+    dry_run: Option<bool>,
+) -> Result<ApplyEditsReport, String> {
+    let (root, case_mode, exported) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let project = projects::load_project_by_id(&conn, project_id)?;
+        let root = project.root_folder.clone().ok_or_else(|| format!("Project {} has no root folder.", project_id))?;
+        let exported: BTreeMap<String, String> = match export_id {
+            Some(id) => load_export_files(&conn, project_id, id)?
+                .into_iter()
+                .map(|(path, version)| (canonical_path(&path), version.hash))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        (root, CaseMode::for_project(&project), exported)
+    };
+    let dry_run = dry_run.unwrap_or(false);
+    let batch_dir = db_dir()?.join(BACKUP_DIR).join(Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = ApplyEditsReport::default();
+        for edit in &edits {
+            let result = apply_one(edit, &root, case_mode, &exported, &batch_dir, dry_run);
+            match result.status.as_str() {
+                "applied" | "would_apply" | "unchanged" => report.applied += usize::from(result.status != "unchanged"),
+                _ => report.failed += 1,
+            }
+            if let Some(message) = &result.message {
+                eprintln!("[EDITS] {}: {}", result.path, message);
+            }
+            report.results.push(result);
+        }
+        if report.results.iter().any(|r| r.backup_path.is_some()) {
+            report.backup_dir = Some(batch_dir.to_string_lossy().to_string());
+        }
+        println!("[EDITS] Project {}: {} applied, {} failed{}.", project_id, report.applied, report.failed, if dry_run { " (dry run)" } else { "" });
+        report
+    })
+    .await
+    .map_err(|e| format!("Apply edits task failed: {}", e))
+}
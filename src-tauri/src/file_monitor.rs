@@ -6,7 +6,15 @@ use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
-use tauri::{AppHandle, Emitter, State}; // Removed unused Manager
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+use crate::archives;
+use crate::db::AppState;
+use crate::path_canon::canonical_path;
+use crate::path_case::CaseMode;
+use crate::window_registry::{emit_to_project, WindowRegistry};
+use crate::scan_cache::CacheEntry;
+use crate::scanner::{compute_cache_entry, MAX_FILE_SIZE_BYTES};
+use crate::{change_journal, document_extract, projects, scan_cache, scan_results};
 
 // NEW STRUCT for deserialization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +29,11 @@ pub struct MonitorState {
     pub current_project_id: Option<i32>,
     // Use the new struct here
     pub monitored_files: HashMap<String, MonitoredFileDetails>, 
+    // Last state written to the change journal per path, so a file that stays
+    // modified is journaled once per distinct change rather than every poll.
+    pub journaled_files: HashMap<String, MonitoredFileDetails>,
+    // Path case handling of the monitored project, for cache and tree lookups
+    pub case_mode: CaseMode,
 }
 
 fn file_modified_timestamp_secs(metadata: &fs::Metadata) -> String {
@@ -37,10 +50,18 @@ fn check_file_freshness_and_emit(
     monitor_state_arc: Arc<Mutex<MonitorState>>,
 ) {
     let mut out_of_date_paths: Vec<String> = Vec::new();
-    let (project_id_opt, files_to_check) = {
+    let mut deleted_paths: Vec<String> = Vec::new();
+    let mut journal_entries: Vec<(String, MonitoredFileDetails)> = Vec::new();
+    let mut modified_files: Vec<(String, MonitoredFileDetails)> = Vec::new();
+    let (project_id_opt, files_to_check, journaled_files, case_mode) = {
         let state_guard = monitor_state_arc.lock().unwrap();
         // Clone data needed for checks to release lock quickly
-        (state_guard.current_project_id, state_guard.monitored_files.clone())
+        (
+            state_guard.current_project_id,
+            state_guard.monitored_files.clone(),
+            state_guard.journaled_files.clone(),
+            state_guard.case_mode,
+        )
     };
 
     if project_id_opt.is_none() || files_to_check.is_empty() {
@@ -51,8 +72,12 @@ fn check_file_freshness_and_emit(
     for (path_str, stored_details) in files_to_check.iter() {
         let path = Path::new(path_str);
         if !path.exists() {
-            // File was part of treeData but now deleted
-            out_of_date_paths.push(path_str.clone());
+            // Archive entries change with their archive; a rescan picks that up
+            if archives::is_virtual_path(path) {
+                continue;
+            }
+            // File was part of treeData but now deleted; removed from cache/tree below
+            deleted_paths.push(path_str.clone());
             continue;
         }
 
@@ -67,6 +92,14 @@ fn check_file_freshness_and_emit(
 
                 if current_last_modified != stored_details.last_modified || current_size != stored_details.size {
                     out_of_date_paths.push(path_str.clone());
+                    let current = MonitoredFileDetails { last_modified: current_last_modified, size: current_size };
+                    let already_journaled = journaled_files.get(path_str).is_some_and(|j| {
+                        j.last_modified == current.last_modified && j.size == current.size
+                    });
+                    if !already_journaled {
+                        journal_entries.push((path_str.clone(), current.clone()));
+                    }
+                    modified_files.push((path_str.clone(), current));
                 }
             }
             Err(_e) => {
@@ -76,9 +109,188 @@ fn check_file_freshness_and_emit(
         }
     }
 
-    if !out_of_date_paths.is_empty() {
-        if let Err(e) = app_handle.emit("file-freshness-update", &out_of_date_paths) {
-            eprintln!("[Monitor] Failed to emit file-freshness-update: {}", e);
+    if let Some(project_id) = project_id_opt {
+        if !journal_entries.is_empty() {
+            journal_modifications(app_handle, &monitor_state_arc, project_id, journal_entries);
+        }
+        if !modified_files.is_empty() {
+            let refreshed = recount_modified_files(app_handle, &monitor_state_arc, project_id, &modified_files, case_mode);
+            out_of_date_paths.retain(|p| !refreshed.contains(p));
+        }
+    }
+
+    if let (Some(project_id), false) = (project_id_opt, out_of_date_paths.is_empty()) {
+        emit_to_project(app_handle, project_id, "file-freshness-update", &out_of_date_paths);
+    }
+
+    if let Some(project_id) = project_id_opt {
+        if !deleted_paths.is_empty() {
+            propagate_deletions(app_handle, &monitor_state_arc, project_id, &deleted_paths, case_mode);
+        }
+    }
+}
+
+fn journal_modifications(
+    app_handle: &AppHandle,
+    monitor_state_arc: &Arc<Mutex<MonitorState>>,
+    project_id: i32,
+    entries: Vec<(String, MonitoredFileDetails)>,
+) {
+    {
+        let app_state = app_handle.state::<AppState>();
+        let conn = match app_state.conn.lock() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("[Monitor] DB lock failed while journaling changes: {}", e);
+                return;
+            }
+        };
+        for (path, _) in &entries {
+            if let Err(e) = change_journal::record_change(&conn, project_id, path, change_journal::CHANGE_MODIFIED) {
+                eprintln!("[Monitor] {}", e);
+            }
+        }
+    }
+    if let Ok(mut state_guard) = monitor_state_arc.lock() {
+        state_guard.journaled_files.extend(entries);
+    }
+}
+
+// Recounts lines/tokens of modified files, refreshes their cache rows and the
+// persisted tree, and emits `file-stats-updated` with the new file and project
+// totals. Returns the paths that were refreshed (and so are no longer stale).
+fn recount_modified_files(
+    app_handle: &AppHandle,
+    monitor_state_arc: &Arc<Mutex<MonitorState>>,
+    project_id: i32,
+    modified: &[(String, MonitoredFileDetails)],
+    case_mode: CaseMode,
+) -> Vec<String> {
+    let app_state = app_handle.state::<AppState>();
+    let extract_documents = document_extract::is_enabled(&app_state);
+
+    // Count outside the DB lock; reading and tokenizing can be slow
+    let entries: Vec<(String, CacheEntry)> = modified
+        .iter()
+        .filter(|(_, details)| details.size <= MAX_FILE_SIZE_BYTES)
+        .map(|(path, details)| {
+            let entry = compute_cache_entry(Path::new(path), details.size, details.last_modified.clone(), extract_documents);
+            (path.clone(), entry)
+        })
+        .collect();
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    {
+        let conn = match app_state.conn.lock() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("[Monitor] DB lock failed while recounting: {}", e);
+                return Vec::new();
+            }
+        };
+        let mut tree = scan_results::load_scan_result(&conn, project_id).unwrap_or_else(|e| {
+            eprintln!("[Monitor] {}", e);
+            None
+        });
+
+        for (path, entry) in &entries {
+            if let Err(e) = scan_cache::save_cache_entry(&conn, path, entry) {
+                eprintln!("[Monitor] Failed to update cache entry for '{}': {}", path, e);
+            }
+            if let Some(t) = tree.as_mut() {
+                scan_results::update_file_node(t, path, entry, case_mode);
+            }
+            let payload = serde_json::json!({
+                "project_id": project_id,
+                "path": path,
+                "lines": entry.lines,
+                "tokens": entry.tokens,
+                "size": entry.size,
+                "last_modified": entry.last_modified,
+                "total_tokens": tree.as_ref().map(|t| t.tokens),
+                "total_lines": tree.as_ref().map(|t| t.lines),
+                "total_size": tree.as_ref().map(|t| t.size),
+            });
+            emit_to_project(app_handle, project_id, "file-stats-updated", payload);
+        }
+
+        if let Some(t) = &tree {
+            if let Err(e) = scan_results::save_scan_result(&conn, project_id, t) {
+                eprintln!("[Monitor] {}", e);
+            }
+        }
+    }
+
+    // The recounted stats are now the baseline the monitor compares against
+    if let Ok(mut state_guard) = monitor_state_arc.lock() {
+        for (path, entry) in &entries {
+            state_guard.monitored_files.insert(
+                path.clone(),
+                MonitoredFileDetails { last_modified: entry.last_modified.clone(), size: entry.size },
+            );
+        }
+    }
+    entries.into_iter().map(|(path, _)| path).collect()
+}
+
+// Drops deleted files from the cache and the persisted tree, stops monitoring
+// them, and emits `tree-node-removed` with the updated project totals.
+fn propagate_deletions(
+    app_handle: &AppHandle,
+    monitor_state_arc: &Arc<Mutex<MonitorState>>,
+    project_id: i32,
+    deleted_paths: &[String],
+    case_mode: CaseMode,
+) {
+    if let Ok(mut state_guard) = monitor_state_arc.lock() {
+        for path in deleted_paths {
+            state_guard.monitored_files.remove(path);
+            state_guard.journaled_files.remove(path);
+        }
+    }
+
+    let app_state = app_handle.state::<AppState>();
+    let conn = match app_state.conn.lock() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("[Monitor] DB lock failed while propagating deletions: {}", e);
+            return;
+        }
+    };
+
+    let mut tree = match scan_results::load_scan_result(&conn, project_id) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("[Monitor] {}", e);
+            None
+        }
+    };
+
+    for path in deleted_paths {
+        if let Err(e) = change_journal::record_change(&conn, project_id, path, change_journal::CHANGE_DELETED) {
+            eprintln!("[Monitor] {}", e);
+        }
+        if let Err(e) = scan_cache::delete_cache_entry(&conn, path, case_mode) {
+            eprintln!("[Monitor] Failed to delete cache entry for '{}': {}", path, e);
+        }
+        let removed = tree.as_mut().and_then(|t| scan_results::remove_node(t, path, case_mode));
+        let payload = serde_json::json!({
+            "project_id": project_id,
+            "path": path,
+            "removed_tokens": removed.as_ref().map_or(0, |n| n.tokens),
+            "removed_lines": removed.as_ref().map_or(0, |n| n.lines),
+            "total_tokens": tree.as_ref().map(|t| t.tokens),
+            "total_lines": tree.as_ref().map(|t| t.lines),
+            "total_size": tree.as_ref().map(|t| t.size),
+        });
+        emit_to_project(app_handle, project_id, "tree-node-removed", payload);
+    }
+
+    if let Some(t) = &tree {
+        if let Err(e) = scan_results::save_scan_result(&conn, project_id, t) {
+            eprintln!("[Monitor] {}", e);
         }
     }
 }
@@ -101,17 +313,31 @@ pub fn start_monitoring_project_cmd(
     files_to_monitor: HashMap<String, MonitoredFileDetails>, 
     monitor_state: State<'_, Arc<Mutex<MonitorState>>>,
     app_handle: AppHandle, 
+    window: Window,
 ) -> Result<(), String> {
+    // The window that starts monitoring receives the project's monitor events
+    if let Some(registry) = app_handle.try_state::<WindowRegistry>() {
+        registry.subscribe(window.label(), project_id);
+    }
+    let case_mode = {
+        let app_state = app_handle.state::<AppState>();
+        let conn = app_state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        projects::load_project_by_id(&conn, project_id)
+            .map(|p| CaseMode::for_project(&p))
+            .unwrap_or_default()
+    };
     let mut state_guard = monitor_state
         .lock()
         .map_err(|e| format!("Failed to lock monitor state: {}", e))?;
 
     state_guard.current_project_id = Some(project_id);
-    state_guard.monitored_files = files_to_monitor;
+    // Keyed like the cache and tree, so lookups by scanned path hit
+    state_guard.monitored_files =
+        files_to_monitor.into_iter().map(|(path, details)| (canonical_path(&path), details)).collect();
+    state_guard.journaled_files.clear();
+    state_guard.case_mode = case_mode;
 
-    if let Err(e) = app_handle.emit("file-freshness-update", Vec::<String>::new()) {
-        eprintln!("[Monitor CMD] Failed to emit initial clear event for start_monitoring: {}", e);
-    }
+    emit_to_project(&app_handle, project_id, "file-freshness-update", Vec::<String>::new());
     Ok(())
 }
 
@@ -124,11 +350,17 @@ pub fn stop_monitoring_project_cmd(
         .lock()
         .map_err(|e| format!("Failed to lock monitor state: {}", e))?;
 
-    state_guard.current_project_id = None;
+    let previous_project_id = state_guard.current_project_id.take();
     state_guard.monitored_files.clear();
+    state_guard.journaled_files.clear();
 
-    if let Err(e) = app_handle.emit("file-freshness-update", Vec::<String>::new()) {
-        eprintln!("[Monitor CMD] Failed to emit clear event for stop_monitoring: {}", e);
+    match previous_project_id {
+        Some(project_id) => emit_to_project(&app_handle, project_id, "file-freshness-update", Vec::<String>::new()),
+        None => {
+            if let Err(e) = app_handle.emit("file-freshness-update", Vec::<String>::new()) {
+                eprintln!("[Monitor CMD] Failed to emit clear event for stop_monitoring: {}", e);
+            }
+        }
     }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+// src-tauri/src/file_notes.rs
+// User notes attached to a project's files ("legacy module, don't extend it").
+// When contents are read for a context with a `project_id`, each note is
+// injected as a comment block above that file's content.
+
+use crate::db::AppState;
+use crate::path_canon::canonical_path;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileNote {
+    pub project_id: i32,
+    pub path: String,
+    pub note: String,
+    pub updated_at: String,
+}
+
+/// Notes of a project keyed by file path.
+pub fn load_notes(conn: &Connection, project_id: i32) -> Result<HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT path, note FROM file_notes WHERE project_id = ?1")
+        .map_err(|e| format!("Prepare file notes query failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Query file notes failed: {}", e))?;
+    rows.collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| format!("Failed to map file note row: {}", e))
+}
+
+/// Notes to inject for an optional project; none without a project or on a poisoned lock.
+pub fn notes_for(state: &AppState, project_id: Option<i32>) -> HashMap<String, String> {
+    let Some(project_id) = project_id else { return HashMap::new() };
+    match state.conn.lock() {
+        Ok(conn) => load_notes(&conn, project_id).unwrap_or_else(|e| {
+            eprintln!("[NOTES] {}", e);
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+// (line prefix, block open, block close) by extension
+fn comment_style(path: &str) -> (&'static str, &'static str, &'static str) {
+    let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "py" | "rb" | "sh" | "bash" | "zsh" | "yaml" | "yml" | "toml" | "r" | "pl" | "ps1" | "dockerfile" | "cmake"
+        | "ini" | "cfg" | "conf" | "env" | "tf" | "ex" | "exs" => ("# ", "", ""),
+        "sql" | "lua" | "hs" | "elm" => ("-- ", "", ""),
+        "html" | "htm" | "xml" | "svg" | "vue" | "svelte" | "md" | "markdown" => ("", "<!--", "-->"),
+        "css" | "scss" | "less" => (" * ", "/*", " */"),
+        "bat" | "cmd" => ("REM ", "", ""),
+        _ => ("// ", "", ""),
+    }
+}
+
+/// The note as a comment in the file's own syntax, followed by a blank line.
+pub fn note_block(path: &str, note: &str) -> String {
+    let (prefix, open, close) = comment_style(path);
+    let mut block = String::new();
+    if !open.is_empty() {
+        block.push_str(open);
+        block.push('\n');
+    }
+    for (i, line) in note.trim().lines().enumerate() {
+        block.push_str(prefix);
+        if i == 0 {
+            block.push_str("NOTE: ");
+        }
+        block.push_str(line);
+        block.push('\n');
+    }
+    if !close.is_empty() {
+        block.push_str(close);
+        block.push('\n');
+    }
+    block.push('\n');
+    block
+}
+
+/// `content` with the file's note (if any) on top.
+pub fn with_note(path: &str, content: String, notes: &HashMap<String, String>) -> String {
+    match notes.get(path).or_else(|| notes.get(&canonical_path(path))) {
+        Some(note) if !note.trim().is_empty() => note_block(path, note) + &content,
+        _ => content,
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn list_file_notes(state: State<AppState>, project_id: i32) -> Result<Vec<FileNote>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare("SELECT project_id, path, note, updated_at FROM file_notes WHERE project_id = ?1 ORDER BY path")
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| {
+            Ok(FileNote { project_id: row.get(0)?, path: row.get(1)?, note: row.get(2)?, updated_at: row.get(3)? })
+        })
+        .map_err(|e| format!("Query file notes failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map file note row: {}", e))
+}
+
+/// Creates or replaces the note of a file. An empty note deletes it.
+#[command]
+pub fn set_file_note(state: State<AppState>, project_id: i32, path: String, note: String) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let path = canonical_path(&path);
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    if note.trim().is_empty() {
+        conn.execute("DELETE FROM file_notes WHERE project_id = ?1 AND path = ?2", params![project_id, path])
+            .map_err(|e| format!("Failed to delete note for '{}': {}", path, e))?;
+        return Ok(());
+    }
+    conn.execute(
+        r#"
+        INSERT INTO file_notes (project_id, path, note, updated_at) VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT(project_id, path) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at
+        "#,
+        params![project_id, path, note, Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to save note for '{}': {}", path, e))?;
+    Ok(())
+}
+
+#[command]
+pub fn delete_file_note(state: State<AppState>, project_id: i32, path: String) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let path = canonical_path(&path);
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let rows = conn
+        .execute("DELETE FROM file_notes WHERE project_id = ?1 AND path = ?2", params![project_id, path])
+        .map_err(|e| format!("Failed to delete note for '{}': {}", path, e))?;
+    if rows == 0 {
+        eprintln!("Warning: Attempted to delete note for '{}' in project {}, but it was not found.", path, project_id);
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+// src-tauri/src/file_preview.rs
+// `preview_file`: the first and last lines of a file plus its cached stats, for
+// hover previews. Only a window at each end is read, so previewing a large log
+// or dump costs the same as a small file and never ships it whole over IPC.
+
+use crate::db::AppState;
+use crate::file_types::{binary_placeholder, is_binary_content};
+use crate::path_canon::canonical_path;
+use crate::redaction::Redactor;
+use crate::scan_cache;
+use crate::scan_tree::file_modified_timestamp;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use tauri::{command, State};
+
+const DEFAULT_HEAD_LINES: usize = 40;
+const DEFAULT_TAIL_LINES: usize = 20;
+const MAX_PREVIEW_LINES: usize = 500;
+// Bytes read at each end; lines past the window are cut
+const PREVIEW_WINDOW_BYTES: u64 = 64 * 1024;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CachedFileStats {
+    pub lines: usize,
+    pub tokens: usize,
+    pub skipped_reason: Option<String>,
+    /// The row matches the file's current size and modification time
+    pub fresh: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FilePreview {
+    pub path: String,
+    pub size: u64,
+    pub last_modified: String,
+    pub is_binary: bool,
+    /// The first lines, or a placeholder for binary files
+    pub head: String,
+    /// The last lines; empty when `head` already reaches the end
+    pub tail: String,
+    /// Whether anything between head and tail was left out
+    pub truncated: bool,
+    /// Lines left out, when the whole file was small enough to count them
+    pub omitted_lines: Option<usize>,
+    /// None when the file hasn't been scanned
+    pub cached: Option<CachedFileStats>,
+}
+
+fn read_window(file: &mut File, from: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(from))?;
+    let mut buf = Vec::new();
+    file.take(len).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn head_and_tail(path: &Path, size: u64, head_lines: usize, tail_lines: usize) -> Result<(String, String, bool, Option<usize>, bool), String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    let read_err = |e: std::io::Error| format!("Failed to read '{}': {}", path.display(), e);
+    let start = read_window(&mut file, 0, PREVIEW_WINDOW_BYTES).map_err(read_err)?;
+    if is_binary_content(&start) {
+        return Ok((binary_placeholder(path, &start), String::new(), false, None, true));
+    }
+
+    // Small enough to take whole: exact line counts
+    if size <= 2 * PREVIEW_WINDOW_BYTES {
+        let mut bytes = start;
+        if size > PREVIEW_WINDOW_BYTES {
+            bytes.extend(read_window(&mut file, PREVIEW_WINDOW_BYTES, size).map_err(read_err)?);
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        if lines.len() <= head_lines + tail_lines {
+            return Ok((text.to_string(), String::new(), false, Some(0), false));
+        }
+        let head = lines[..head_lines].concat();
+        let tail = lines[lines.len() - tail_lines..].concat();
+        return Ok((head, tail, true, Some(lines.len() - head_lines - tail_lines), false));
+    }
+
+    let head_text = String::from_utf8_lossy(&start);
+    let head: String = head_text.split_inclusive('\n').take(head_lines).collect();
+    let end = read_window(&mut file, size - PREVIEW_WINDOW_BYTES, PREVIEW_WINDOW_BYTES).map_err(read_err)?;
+    let end_text = String::from_utf8_lossy(&end);
+    // The window most likely starts mid-line
+    let end_text = end_text.split_once('\n').map_or(end_text.as_ref(), |(_, rest)| rest);
+    let lines: Vec<&str> = end_text.split_inclusive('\n').collect();
+    let tail = lines[lines.len().saturating_sub(tail_lines)..].concat();
+    Ok((head, tail, true, None, false))
+}
+
+// --- Exposed Tauri Commands ---
+
+/// The first `head_lines` (default 40) and last `tail_lines` (default 20) lines
+/// of a file, at most 500 each, redacted like any read, with the file's cached
+/// line and token counts.
+#[command(async)]
+pub async fn preview_file(
+    state: State<'_, AppState>,
+    path: String,
+    head_lines: Option<usize>,
+    tail_lines: Option<usize>,
+) -> Result<FilePreview, String> {
+    let head_lines = head_lines.unwrap_or(DEFAULT_HEAD_LINES).min(MAX_PREVIEW_LINES);
+    let tail_lines = tail_lines.unwrap_or(DEFAULT_TAIL_LINES).min(MAX_PREVIEW_LINES);
+    // Cache rows are keyed by the canonical path
+    let path = canonical_path(&path);
+    let cache_entry = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        scan_cache::load_cache_entry(&conn, &path)?
+    };
+    let redactor = Redactor::for_state(&state);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_path = Path::new(&path);
+        let meta = fs::metadata(file_path).map_err(|e| format!("Failed to stat '{}': {}", path, e))?;
+        if meta.is_dir() {
+            return Err(format!("Path is a directory, not a file: {}", path));
+        }
+        let size = meta.len();
+        let last_modified = file_modified_timestamp(&meta);
+        let (head, tail, truncated, omitted_lines, is_binary) = head_and_tail(file_path, size, head_lines, tail_lines)?;
+        let (head, tail) = if is_binary { (head, tail) } else { (redactor.redact(&head).content, redactor.redact(&tail).content) };
+        let cached = cache_entry.map(|entry| CachedFileStats {
+            fresh: entry.size == size && entry.last_modified == last_modified,
+            lines: entry.lines,
+            tokens: entry.tokens,
+            skipped_reason: entry.skipped_reason,
+        });
+        Ok(FilePreview { path, size, last_modified, is_binary, head, tail, truncated, omitted_lines, cached })
+    })
+    .await
+    .map_err(|e| format!("Preview task failed: {}", e))?
+}
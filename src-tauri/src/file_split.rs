@@ -0,0 +1,247 @@
+// src-tauri/src/file_split.rs
+// Splits one file that is too big for a model's context into chunks under a
+// token limit, cutting between top-level definitions where possible: tree-sitter
+// for Python and TypeScript/JavaScript, blank-line separated top-level blocks
+// for other text (SQL statements, Rust/Go items...). A definition that alone is
+// over the limit falls back to line windows. Chunks carry their line range and
+// a "part N of M" label so exports can name them.
+
+use crate::db::AppState;
+use crate::file_types::{read_for_context, ReadOutcome};
+use crate::redaction::Redactor;
+use crate::utils::{count_tokens_with, DEFAULT_TOKENIZER};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{command, State};
+use tree_sitter::{Language, Parser};
+
+const MIN_MAX_TOKENS: usize = 64;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SplitChunk {
+    /// 1-based
+    pub index: usize,
+    /// "part 2 of 5"
+    pub label: String,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    pub tokens: usize,
+    /// "definitions", "blocks" or "lines" (a definition over the limit cut by lines)
+    pub method: String,
+    /// Names of the top-level definitions starting in this chunk, where known
+    pub symbols: Vec<String>,
+    /// A single line over the limit can't be cut further
+    pub over_limit: bool,
+    pub content: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileSplit {
+    pub path: String,
+    pub tokenizer: String,
+    pub max_tokens: usize,
+    pub total_tokens: usize,
+    pub total_lines: usize,
+    pub chunks: Vec<SplitChunk>,
+}
+
+// A run of whole lines [start, end) that should stay together
+struct Unit {
+    start: usize,
+    end: usize,
+    symbol: Option<String>,
+}
+
+fn grammar_for(path: &str) -> Option<Language> {
+    match Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("py") => Some(tree_sitter_python::language()),
+        Some("ts" | "mts" | "cts") => Some(tree_sitter_typescript::language_typescript()),
+        Some("tsx" | "js" | "jsx" | "mjs" | "cjs") => Some(tree_sitter_typescript::language_tsx()),
+        _ => None,
+    }
+}
+
+// Top-level syntax nodes as line runs; comments and blank lines before a
+// definition stay with it. None when the file doesn't parse.
+fn definition_units(source: &str, language: Language, line_count: usize) -> Option<Vec<Unit>> {
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+    let mut units: Vec<Unit> = Vec::new();
+    let mut next_start = 0;
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        if child.kind() == "comment" {
+            continue; // attached to whatever follows
+        }
+        let end_row = child.end_position().row;
+        // A node ending at column 0 ends on the previous line
+        let end = if child.end_position().column == 0 { end_row } else { end_row + 1 };
+        if end <= next_start {
+            continue; // several nodes on one line
+        }
+        let name_node = child
+            .child_by_field_name("name")
+            .or_else(|| child.child_by_field_name("declaration").and_then(|d| d.child_by_field_name("name")))
+            .or_else(|| child.child_by_field_name("definition").and_then(|d| d.child_by_field_name("name")));
+        let symbol = name_node
+            .filter(|_| !child.kind().contains("import"))
+            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+            .map(str::to_string);
+        units.push(Unit { start: next_start, end: end.min(line_count), symbol });
+        next_start = end.min(line_count);
+    }
+    match units.last_mut() {
+        Some(last) => last.end = line_count, // trailing comments / blank lines
+        None => units.push(Unit { start: 0, end: line_count, symbol: None }),
+    }
+    Some(units)
+}
+
+// A block starts at a non-indented line after a blank line
+fn block_units(lines: &[&str]) -> Vec<Unit> {
+    let mut units = Vec::new();
+    let mut start = 0;
+    for i in 1..lines.len() {
+        let starts_block = lines[i - 1].trim().is_empty()
+            && lines[i].starts_with(|c: char| !c.is_whitespace() && !matches!(c, '}' | ')' | ']'));
+        if starts_block && i > start {
+            units.push(Unit { start, end: i, symbol: None });
+            start = i;
+        }
+    }
+    units.push(Unit { start, end: lines.len(), symbol: None });
+    units
+}
+
+struct Packer<'a> {
+    line_tokens: &'a [usize],
+    max_tokens: usize,
+    method: &'static str,
+    chunks: Vec<(usize, usize, &'static str, Vec<String>)>,
+    current: Option<(usize, usize, Vec<String>)>,
+    current_tokens: usize,
+}
+
+impl Packer<'_> {
+    fn tokens(&self, start: usize, end: usize) -> usize {
+        self.line_tokens[start..end].iter().sum()
+    }
+
+    fn flush(&mut self) {
+        if let Some((start, end, symbols)) = self.current.take() {
+            self.chunks.push((start, end, self.method, symbols));
+        }
+        self.current_tokens = 0;
+    }
+
+    fn push_unit(&mut self, unit: &Unit) {
+        let tokens = self.tokens(unit.start, unit.end);
+        if tokens > self.max_tokens {
+            self.flush();
+            self.push_lines(unit);
+            return;
+        }
+        if self.current_tokens + tokens > self.max_tokens {
+            self.flush();
+        }
+        let current = self.current.get_or_insert((unit.start, unit.start, Vec::new()));
+        current.1 = unit.end;
+        current.2.extend(unit.symbol.clone());
+        self.current_tokens += tokens;
+    }
+
+    // Line windows for a unit over the limit
+    fn push_lines(&mut self, unit: &Unit) {
+        let mut start = unit.start;
+        let mut tokens = 0;
+        for line in unit.start..unit.end {
+            if tokens + self.line_tokens[line] > self.max_tokens && line > start {
+                let symbols = if start == unit.start { unit.symbol.clone().into_iter().collect() } else { Vec::new() };
+                self.chunks.push((start, line, "lines", symbols));
+                start = line;
+                tokens = 0;
+            }
+            tokens += self.line_tokens[line];
+        }
+        let symbols = if start == unit.start { unit.symbol.clone().into_iter().collect() } else { Vec::new() };
+        self.chunks.push((start, unit.end, "lines", symbols));
+    }
+}
+
+/// Splits `content` (the text of `path`) into chunks of at most about
+/// `max_tokens` tokens. Chunk counts are exact; packing uses per-line counts,
+/// which can differ from a chunk's count by a few tokens.
+pub fn split_content(path: &str, content: &str, max_tokens: usize, tokenizer: &str, include_content: bool) -> Result<FileSplit, String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let line_tokens: Vec<usize> =
+        lines.iter().map(|l| count_tokens_with(l, Some(tokenizer))).collect::<Result<_, _>>()?;
+    let (units, method) = match grammar_for(path).and_then(|lang| definition_units(content, lang, lines.len())) {
+        Some(units) => (units, "definitions"),
+        None => (block_units(&lines), "blocks"),
+    };
+
+    let mut packer = Packer { line_tokens: &line_tokens, max_tokens, method, chunks: Vec::new(), current: None, current_tokens: 0 };
+    for unit in units.iter().filter(|u| u.end > u.start) {
+        packer.push_unit(unit);
+    }
+    packer.flush();
+
+    let total = packer.chunks.len();
+    let mut chunks = Vec::with_capacity(total);
+    for (i, (start, end, method, symbols)) in packer.chunks.into_iter().enumerate() {
+        let text: String = lines[start..end].concat();
+        let tokens = count_tokens_with(&text, Some(tokenizer))?;
+        chunks.push(SplitChunk {
+            index: i + 1,
+            label: format!("part {} of {}", i + 1, total),
+            start_line: start + 1,
+            end_line: end,
+            tokens,
+            method: method.to_string(),
+            symbols,
+            over_limit: tokens > max_tokens && end - start == 1,
+            content: include_content.then_some(text),
+        });
+    }
+    Ok(FileSplit {
+        path: path.to_string(),
+        tokenizer: tokenizer.to_string(),
+        max_tokens,
+        total_tokens: count_tokens_with(content, Some(tokenizer))?,
+        total_lines: lines.len(),
+        chunks,
+    })
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Splits a text file into chunks of at most `max_tokens` tokens (minimum 64)
+/// along top-level definitions, with redaction applied like any export. Pass
+/// `include_content: false` for just the chunk metadata.
+#[command(async)]
+pub async fn split_file_by_tokens(
+    state: State<'_, AppState>,
+    path: String,
+    max_tokens: usize,
+    tokenizer: Option<String>,
+    include_content: Option<bool>,
+) -> Result<FileSplit, String> {
+    let max_tokens = max_tokens.max(MIN_MAX_TOKENS);
+    let tokenizer = tokenizer.unwrap_or_else(|| DEFAULT_TOKENIZER.to_string());
+    let include_content = include_content.unwrap_or(true);
+    let redactor = Redactor::for_state(&state);
+    tauri::async_runtime::spawn_blocking(move || {
+        let content = match read_for_context(Path::new(&path)) {
+            Ok(ReadOutcome::Text(text)) => text,
+            Ok(ReadOutcome::Binary(_)) => return Err(format!("'{}' is a binary file and can't be split", path)),
+            Err(e) => return Err(format!("Failed to read file '{}': {}", path, e)),
+        };
+        let content = redactor.redact(&content).content;
+        split_content(&path, &content, max_tokens, &tokenizer, include_content)
+    })
+    .await
+    .map_err(|e| format!("Split task failed: {}", e))?
+}
@@ -0,0 +1,137 @@
+// src-tauri/src/file_stream.rs
+// `stream_file_contents`: sends a file to the calling window as a series of
+// `file-chunk` events instead of one response, so previewing a 100 MB log
+// never holds the whole file in memory or in a single IPC message. Chunks end
+// on line boundaries (a longer line is split at a UTF-8 boundary) and are
+// redacted like every other read. Streams can be stopped with
+// `cancel_batch_read(stream_id)`.
+
+use crate::db::AppState;
+use crate::file_types::is_binary_content;
+use crate::redaction::Redactor;
+use crate::scan_state::begin_batch_read;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{command, Emitter, State, Window};
+
+pub const CHUNK_EVENT: &str = "file-chunk";
+const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+const MAX_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileChunk {
+    pub stream_id: String,
+    pub path: String,
+    pub index: usize,
+    /// Byte offset of the chunk in the file
+    pub offset: u64,
+    pub data: String,
+    pub is_last: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileStreamSummary {
+    pub stream_id: String,
+    pub path: String,
+    pub total_bytes: u64,
+    pub chunk_count: usize,
+    pub cancelled: bool,
+}
+
+// Where to end a chunk of `buf`: after its last newline, else at the last
+// complete UTF-8 character; the rest is carried into the next chunk.
+fn chunk_end(buf: &[u8], at_eof: bool) -> usize {
+    if at_eof {
+        return buf.len();
+    }
+    if let Some(newline) = buf.iter().rposition(|&b| b == b'\n') {
+        return newline + 1;
+    }
+    match std::str::from_utf8(buf) {
+        Ok(_) => buf.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(), // incomplete trailing character
+        Err(_) => buf.len(),                                   // invalid bytes; decoded lossily
+    }
+}
+
+fn stream_file(
+    window: &Window,
+    path: &str,
+    chunk_size: usize,
+    stream_id: &str,
+    redactor: &Redactor,
+) -> Result<FileStreamSummary, String> {
+    let token = begin_batch_read(Some(stream_id.to_string()));
+    let mut file = File::open(path).map_err(|e| format!("Failed to open '{}': {}", path, e))?;
+    let target = window.label().to_string();
+    let mut pending: Vec<u8> = Vec::with_capacity(chunk_size * 2);
+    let mut read_buf = vec![0u8; chunk_size];
+    let mut offset: u64 = 0;
+    let mut index = 0;
+    let mut at_eof = false;
+
+    while !at_eof || !pending.is_empty() {
+        if token.is_cancelled() {
+            println!("[STREAM] '{}' cancelled after {} chunk(s).", path, index);
+            return Ok(FileStreamSummary { stream_id: stream_id.to_string(), path: path.to_string(), total_bytes: offset, chunk_count: index, cancelled: true });
+        }
+        while !at_eof && pending.len() < chunk_size {
+            let n = file.read(&mut read_buf).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+            if n == 0 {
+                at_eof = true;
+            }
+            pending.extend_from_slice(&read_buf[..n]);
+        }
+        if index == 0 && is_binary_content(&pending) {
+            return Err(format!("'{}' is a binary file and can't be streamed as text.", path));
+        }
+
+        let window_len = pending.len().min(chunk_size);
+        let end = chunk_end(&pending[..window_len], at_eof && window_len == pending.len());
+        let end = if end == 0 { window_len } else { end };
+        let bytes: Vec<u8> = pending.drain(..end).collect();
+        let data = redactor.redact(&String::from_utf8_lossy(&bytes)).content;
+        let chunk = FileChunk {
+            stream_id: stream_id.to_string(),
+            path: path.to_string(),
+            index,
+            offset,
+            data,
+            is_last: at_eof && pending.is_empty(),
+        };
+        window
+            .emit_to(target.as_str(), CHUNK_EVENT, &chunk)
+            .map_err(|e| format!("Failed to emit chunk {} of '{}': {}", index, path, e))?;
+        offset += bytes.len() as u64;
+        index += 1;
+    }
+
+    Ok(FileStreamSummary { stream_id: stream_id.to_string(), path: path.to_string(), total_bytes: offset, chunk_count: index, cancelled: false })
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Streams a text file to the calling window as `file-chunk` events of about
+/// `chunk_size` bytes (default 256 KiB, 4 KiB - 8 MiB) and resolves once the
+/// last chunk was sent; an empty file sends one empty last chunk. Pass
+/// `stream_id` to be able to cancel; otherwise one is generated.
+#[command(async)]
+pub async fn stream_file_contents(
+    window: Window,
+    state: State<'_, AppState>,
+    path: String,
+    chunk_size: Option<usize>,
+    stream_id: Option<String>,
+) -> Result<FileStreamSummary, String> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE);
+    let stream_id = stream_id.unwrap_or_else(|| format!("stream-{}", NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed)));
+    let redactor = Redactor::for_state(&state);
+    tauri::async_runtime::spawn_blocking(move || stream_file(&window, &path, chunk_size, &stream_id, &redactor))
+        .await
+        .map_err(|e| format!("Stream task failed: {}", e))?
+}
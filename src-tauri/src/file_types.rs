@@ -0,0 +1,226 @@
+// src-tauri/src/file_types.rs
+// Content sniffing for files pulled into a context: binary detection and
+// compact placeholders for assets that can't be included as text.
+
+use crate::archives;
+use crate::scan_tree::special_kind;
+use encoding_rs::{Encoding, SHIFT_JIS, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// How many leading bytes are inspected when deciding if a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+pub enum ReadOutcome {
+    Text(String),
+    /// Binary file; carries a one-line placeholder like `[binary: logo.png, 24KB, PNG 512x512]`.
+    Binary(String),
+}
+
+/// Reads a file for inclusion in a built context. Text comes back as-is,
+/// binaries (images, archives, executables...) come back as a placeholder so
+/// the LLM still knows the asset exists. Non-UTF-8 text is decoded (see `decode_text`).
+/// A virtual path inside a scanned archive is read from the archive.
+pub fn read_for_context(path: &Path) -> io::Result<ReadOutcome> {
+    // Reading a named pipe or device can block forever
+    if let Some(kind) = fs::metadata(path).ok().and_then(|m| special_kind(&m.file_type())) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Not a regular file ({})", kind)));
+    }
+    let bytes = match fs::read(path) {
+        Err(_) if archives::is_virtual_path(path) => archives::read_virtual(path)?,
+        read => read?,
+    };
+    if is_binary_content(&bytes) {
+        return Ok(ReadOutcome::Binary(binary_placeholder(path, &bytes)));
+    }
+    Ok(ReadOutcome::Text(decode_text(bytes)))
+}
+
+pub fn is_binary_content(bytes: &[u8]) -> bool {
+    // UTF-16 text is full of NULs; its BOM says it's text
+    if utf16_bom(bytes).is_some() {
+        return false;
+    }
+    // Two-byte signatures (BM, MZ) are too weak on their own; those files
+    // are caught by the NUL check instead.
+    let strong_magic = detect_binary_format(bytes).is_some_and(|f| !matches!(f, "BMP" | "PE"));
+    strong_magic || bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+fn utf16_bom(bytes: &[u8]) -> Option<&'static Encoding> {
+    match bytes {
+        [0xFF, 0xFE, ..] => Some(UTF_16LE),
+        [0xFE, 0xFF, ..] => Some(UTF_16BE),
+        _ => None,
+    }
+}
+
+/// Text of a file that isn't necessarily UTF-8: UTF-8 as-is, UTF-16 when it
+/// has a BOM, Shift-JIS when it decodes cleanly and reads like Japanese (kana
+/// and full-width punctuation, which Latin-1 bytes misread as Shift-JIS
+/// rarely produce), and Windows-1252 (a superset of Latin-1) otherwise,
+/// which accepts any bytes.
+pub fn decode_text(bytes: Vec<u8>) -> String {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return text,
+        Err(e) => e.into_bytes(),
+    };
+    if let Some(encoding) = utf16_bom(&bytes) {
+        return encoding.decode_with_bom_removal(&bytes).0.into_owned();
+    }
+    if let Some(text) = SHIFT_JIS.decode_without_bom_handling_and_without_replacement(&bytes) {
+        let non_ascii = text.chars().filter(|c| !c.is_ascii()).count();
+        let japanese = text
+            .chars()
+            .filter(|c| matches!(c, '\u{3000}'..='\u{30FF}' | '\u{FF01}'..='\u{FF5E}'))
+            .count();
+        if japanese * 4 >= non_ascii {
+            return text.into_owned();
+        }
+    }
+    WINDOWS_1252.decode_without_bom_handling(&bytes).0.into_owned()
+}
+
+pub fn binary_placeholder(path: &Path, bytes: &[u8]) -> String {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string());
+    let mut parts = vec![name, format_size(bytes.len() as u64)];
+    match (detect_binary_format(bytes), image_dimensions(bytes)) {
+        (Some(format), Some((w, h))) => parts.push(format!("{} {}x{}", format, w, h)),
+        (Some(format), None) => parts.push(format.to_string()),
+        _ => (),
+    }
+    format!("[binary: {}]", parts.join(", "))
+}
+
+/// Language of a file by name/extension, using common editor identifiers.
+pub fn language_for_path(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    match name {
+        "Dockerfile" | "Containerfile" => return Some("dockerfile"),
+        "Makefile" | "GNUmakefile" => return Some("makefile"),
+        "CMakeLists.txt" => return Some("cmake"),
+        "Gemfile" | "Rakefile" => return Some("ruby"),
+        _ => {}
+    }
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let language = match extension.as_str() {
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "typescriptreact",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "javascriptreact",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "scala" => "scala",
+        "swift" => "swift",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "dart" => "dart",
+        "lua" => "lua",
+        "r" => "r",
+        "sh" | "bash" | "zsh" => "shellscript",
+        "ps1" | "psm1" => "powershell",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" | "sass" => "scss",
+        "less" => "less",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        "json" | "jsonc" => "json",
+        "jsonl" | "ndjson" => "jsonl",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "xml" => "xml",
+        "md" | "markdown" => "markdown",
+        "graphql" | "graphqls" | "gql" => "graphql",
+        "proto" => "proto",
+        "csv" | "tsv" => "csv",
+        "ini" | "cfg" | "conf" => "ini",
+        "pdf" => "pdf",
+        "docx" => "docx",
+        "txt" => "plaintext",
+        _ => return None,
+    };
+    Some(language)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{}B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{}KB", (bytes + 512) / 1024)
+    } else {
+        format!("{:.1}MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+fn detect_binary_format(bytes: &[u8]) -> Option<&'static str> {
+    let magic: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "PNG"),
+        (b"\xff\xd8\xff", "JPEG"),
+        (b"GIF87a", "GIF"),
+        (b"GIF89a", "GIF"),
+        (b"BM", "BMP"),
+        (b"\x00\x00\x01\x00", "ICO"),
+        (b"%PDF-", "PDF"),
+        (b"PK\x03\x04", "ZIP"),
+        (b"\x1f\x8b", "GZIP"),
+        (b"\x7fELF", "ELF"),
+        (b"MZ", "PE"),
+        (b"\x00asm", "WASM"),
+        (b"SQLite format 3\x00", "SQLite"),
+    ];
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("WEBP");
+    }
+    magic.iter().find(|(sig, _)| bytes.starts_with(sig)).map(|(_, name)| *name)
+}
+
+/// Width/height for the common raster formats, read straight from the header.
+fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let le16 = |i: usize| bytes.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as u32);
+    let le32 = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    match detect_binary_format(bytes)? {
+        "PNG" => Some((be32(16)?, be32(20)?)),
+        "GIF" => Some((le16(6)?, le16(8)?)),
+        "BMP" => Some((le32(18)?, (le32(22)? as i32).unsigned_abs())), // negative height = top-down rows
+        "JPEG" => jpeg_dimensions(bytes),
+        "WEBP" => match bytes.get(12..16)? {
+            b"VP8X" => Some(((le32(24)? & 0xFF_FFFF) + 1, (le32(27)? & 0xFF_FFFF) + 1)),
+            b"VP8 " => Some((le16(26)? & 0x3FFF, le16(28)? & 0x3FFF)),
+            b"VP8L" => {
+                let bits = le32(21)?;
+                Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 9 < bytes.len() {
+        if bytes[i] != 0xFF {
+            return None;
+        }
+        let marker = bytes[i + 1];
+        let len = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        // SOF0..SOF15, excluding DHT (C4), JPG (C8) and DAC (CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let h = u16::from_be_bytes([bytes[i + 5], bytes[i + 6]]) as u32;
+            let w = u16::from_be_bytes([bytes[i + 7], bytes[i + 8]]) as u32;
+            return Some((w, h));
+        }
+        i += 2 + len;
+    }
+    None
+}
@@ -0,0 +1,210 @@
+// src-tauri/src/git_sources.rs
+// `scan_git_url_cmd`: context from a git repository without cloning it by hand.
+// The URL is shallow-cloned (depth 1, one branch) into a clone cache next to the
+// database, one folder per URL and ref, and scanned like any project: the first
+// scan of a URL creates a project rooted at its clone (with .gitignore
+// respected), later scans reuse both. `refresh` replaces the cached clone with a
+// fresh one. Git runs as the `git` executable on PATH, with terminal prompts off
+// so a repository that needs credentials fails instead of hanging.
+
+use crate::db::{self, AppState};
+use crate::path_canon::canonical_path;
+use crate::projects;
+use crate::scan_tree::fnv1a;
+use crate::scanner;
+use crate::types::{FileNode, Project};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{command, State, Window};
+
+// Folder of the clone cache, inside the database folder (see db::db_dir)
+const CLONES_FOLDER: &str = "git_clones";
+// A clone is made here first and renamed into place once complete
+const PARTIAL_SUFFIX: &str = ".partial";
+const URL_SCHEMES: &[&str] = &["https://", "http://", "ssh://", "git://", "file://"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitScanOutput {
+    /// The project scanning this clone (created on the URL's first scan)
+    pub project_id: i32,
+    pub url: String,
+    pub git_ref: Option<String>,
+    /// Full hash of the commit that was scanned
+    pub commit: String,
+    pub clone_path: String,
+    /// True when an existing clone was scanned instead of cloning again
+    pub reused_clone: bool,
+    pub tree: FileNode,
+}
+
+// Something git accepts as a remote (a URL or scp-like `user@host:path`), and
+// nothing it would take as an option
+fn validate_url(url: &str) -> Result<(), String> {
+    if url.is_empty() || url.starts_with('-') || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Not a git URL: '{}'", url));
+    }
+    let scp_like = url.split_once(':').is_some_and(|(host, _)| host.contains('@') && !host.contains('/'));
+    if !scp_like && !URL_SCHEMES.iter().any(|scheme| url.to_ascii_lowercase().starts_with(scheme)) {
+        return Err(format!("Unsupported git URL '{}': use https://, ssh://, git://, file:// or user@host:path.", url));
+    }
+    Ok(())
+}
+
+fn validate_ref(git_ref: &str) -> Result<(), String> {
+    if git_ref.is_empty() || git_ref.starts_with('-') || git_ref.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Not a branch or tag name: '{}'", git_ref));
+    }
+    Ok(())
+}
+
+// Repository name from the URL's last segment: `https://host/owner/repo.git` -> `repo`
+fn repo_name(url: &str) -> String {
+    let last = url.trim_end_matches('/').rsplit(['/', ':']).next().unwrap_or_default();
+    let name: String = last
+        .trim_end_matches(".git")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    if name.trim_matches('.').is_empty() { "repo".to_string() } else { name }
+}
+
+// One folder per URL and ref; the hash keeps different owners' `repo` apart
+fn clone_dir(url: &str, git_ref: Option<&str>) -> Result<PathBuf, String> {
+    let key = format!("{}#{}", url.trim_end_matches('/'), git_ref.unwrap_or_default());
+    let folder = format!("{}-{:016x}", repo_name(url), fnv1a(key.as_bytes()));
+    Ok(db::db_dir()?.join(CLONES_FOLDER).join(folder))
+}
+
+// Runs git and returns its trimmed stdout; `what` names the step in errors
+fn run_git<I, S>(args: I, what: &str) -> Result<String, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git")
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run git (is it installed and on PATH?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", what, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// The clone of `url` at `dir`, cloned unless a complete one is already there
+// (or `refresh` asks for a new one). Returns (commit hash, reused).
+fn ensure_clone(url: &str, git_ref: Option<&str>, dir: &Path, refresh: bool) -> Result<(String, bool), String> {
+    let reused = !refresh && dir.join(".git").is_dir();
+    if !reused {
+        let mut partial = dir.as_os_str().to_owned();
+        partial.push(PARTIAL_SUFFIX);
+        let partial = PathBuf::from(partial);
+        for stale in [&partial, &dir.to_path_buf()] {
+            if stale.exists() {
+                fs::remove_dir_all(stale).map_err(|e| format!("Failed to remove old clone '{}': {}", stale.display(), e))?;
+            }
+        }
+        if let Some(parent) = dir.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create clone folder '{}': {}", parent.display(), e))?;
+        }
+        let mut args: Vec<&OsStr> = ["clone", "--depth", "1", "--single-branch"].map(OsStr::new).to_vec();
+        if let Some(git_ref) = git_ref {
+            args.extend([OsStr::new("--branch"), OsStr::new(git_ref)]);
+        }
+        args.extend([OsStr::new("--"), OsStr::new(url), partial.as_os_str()]);
+        println!("[GIT_SCAN] Cloning '{}' into '{}'...", url, dir.display());
+        if let Err(e) = run_git(&args, "clone") {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, dir).map_err(|e| format!("Failed to move clone into '{}': {}", dir.display(), e))?;
+    }
+    let commit = run_git([OsStr::new("-C"), dir.as_os_str(), OsStr::new("rev-parse"), OsStr::new("HEAD")], "rev-parse")?;
+    Ok((commit, reused))
+}
+
+// The project rooted at the clone, created on first use
+fn project_for_clone(state: &State<'_, AppState>, url: &str, git_ref: Option<&str>, dir: &Path) -> Result<i32, String> {
+    let root = canonical_path(&dir.to_string_lossy());
+    {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        if let Some(existing) = projects::load_all_projects(&conn)?.into_iter().find(|p| p.root_folder.as_deref() == Some(root.as_str())) {
+            return Ok(existing.id);
+        }
+    }
+    let title = match git_ref {
+        Some(git_ref) => format!("{}@{}", repo_name(url), git_ref),
+        None => repo_name(url),
+    };
+    let project = Project {
+        id: 0,
+        title,
+        root_folder: Some(root),
+        ignore_patterns: Vec::new(),
+        updated_at: None,
+        prefix: String::new(),
+        fs_mode: None,
+        case_sensitivity: None,
+        respect_gitignore: Some(true),
+        follow_symlinks: None,
+        max_scan_depth: None,
+        include_hidden: None,
+        min_file_size: None,
+        max_file_size: None,
+        max_file_age_days: None,
+        include_patterns: None,
+    };
+    projects::save_code_context_builder_project(state.clone(), project)
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Shallow-clones `url` (at branch or tag `git_ref`, default the remote HEAD)
+/// into the clone cache, or reuses the cached clone, and scans it like a
+/// project. Returns the tree with the commit it was built from.
+#[allow(clippy::too_many_arguments)]
+#[command(async)]
+pub async fn scan_git_url_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    url: String,
+    git_ref: Option<String>,
+    refresh: Option<bool>, // Clone again even when a cached clone exists
+    sort_by: Option<String>,
+    child_limit: Option<usize>,
+    prune_empty: Option<bool>,
+) -> Result<GitScanOutput, String> {
+    db::ensure_writable(&state)?;
+    let url = url.trim().to_string();
+    validate_url(&url)?;
+    let git_ref = git_ref.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if let Some(git_ref) = &git_ref {
+        validate_ref(git_ref)?;
+    }
+    let dir = clone_dir(&url, git_ref.as_deref())?;
+
+    let (commit, reused_clone) = {
+        let (url, git_ref, dir) = (url.clone(), git_ref.clone(), dir.clone());
+        tauri::async_runtime::spawn_blocking(move || ensure_clone(&url, git_ref.as_deref(), &dir, refresh.unwrap_or(false)))
+            .await
+            .map_err(|e| format!("Clone task failed: {}", e))??
+    };
+    let project_id = project_for_clone(&state, &url, git_ref.as_deref(), &dir)?;
+    println!("[GIT_SCAN] Scanning '{}' at {} as project {}.", url, commit, project_id);
+
+    let output = scanner::run_scan(window, state, project_id, sort_by, child_limit, prune_empty, None, None).await?;
+    Ok(GitScanOutput {
+        project_id,
+        url,
+        git_ref,
+        commit,
+        clone_path: dir.to_string_lossy().to_string(),
+        reused_clone,
+        tree: output.tree,
+    })
+}
@@ -0,0 +1,186 @@
+// src-tauri/src/global_search.rs
+// Search across every project at once, for finding where a symbol lives without
+// remembering which repo it's in. The file sets come from each project's last
+// persisted scan (no rescans); contents are read from disk, skipping binaries
+// and files the scan left out.
+
+use crate::db::AppState;
+use crate::projects::load_all_projects;
+use crate::redaction::Redactor;
+use crate::scan_results::{self, load_scan_result};
+use crate::scanner::MAX_FILE_SIZE_BYTES;
+use crate::types::FileNode;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::{command, State};
+
+const DEFAULT_MAX_RESULTS: usize = 500;
+const MAX_MATCHES_PER_FILE: usize = 20;
+const PREVIEW_CHARS: usize = 200;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchMatch {
+    pub path: String,
+    /// 1-based; 0 for a match on the path itself
+    pub line: usize,
+    /// 1-based character column of the match in the line (or path)
+    pub column: usize,
+    /// The matching line (redacted, clipped), or the project-relative path
+    pub preview: String,
+    /// "path" or "content"
+    pub kind: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProjectSearchResult {
+    pub project_id: i32,
+    pub project_title: String,
+    pub root_folder: Option<String>,
+    pub matches: Vec<SearchMatch>,
+    pub files_searched: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GlobalSearchResult {
+    pub query: String,
+    pub projects: Vec<ProjectSearchResult>,
+    pub total_matches: usize,
+    /// Projects never scanned, so not searched
+    pub unscanned_projects: Vec<i32>,
+    /// True when `max_results` cut the search short
+    pub truncated: bool,
+}
+
+fn build_pattern(query: &str, is_regex: bool, case_sensitive: bool) -> Result<Regex, String> {
+    let pattern = if is_regex { query.to_string() } else { regex::escape(query) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid search pattern '{}': {}", query, e))
+}
+
+fn preview(line: &str, redactor: &Redactor) -> String {
+    let redacted = redactor.redact(line.trim_end()).content;
+    if redacted.chars().count() <= PREVIEW_CHARS {
+        return redacted;
+    }
+    let clipped: String = redacted.chars().take(PREVIEW_CHARS).collect();
+    format!("{}...", clipped)
+}
+
+fn column_of(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].chars().count() + 1
+}
+
+fn search_file(file: &FileNode, root: &str, pattern: &Regex, redactor: &Redactor) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+    let relative = file.path.strip_prefix(root).unwrap_or(&file.path).trim_start_matches(['/', '\\']);
+    if let Some(m) = pattern.find(relative) {
+        matches.push(SearchMatch {
+            path: file.path.clone(),
+            line: 0,
+            column: column_of(relative, m.start()),
+            preview: relative.to_string(),
+            kind: "path".to_string(),
+        });
+    }
+    if file.is_binary || file.skipped_reason.is_some() || file.size > MAX_FILE_SIZE_BYTES {
+        return matches;
+    }
+    let Ok(content) = fs::read_to_string(&file.path) else { return matches };
+    for (index, line) in content.lines().enumerate() {
+        if let Some(m) = pattern.find(line) {
+            matches.push(SearchMatch {
+                path: file.path.clone(),
+                line: index + 1,
+                column: column_of(line, m.start()),
+                preview: preview(line, redactor),
+                kind: "content".to_string(),
+            });
+            if matches.len() >= MAX_MATCHES_PER_FILE {
+                break;
+            }
+        }
+    }
+    matches
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Searches file paths and contents of every scanned project for `query`
+/// (literal unless `is_regex`; case-insensitive unless `case_sensitive`), with
+/// results grouped by project. Stops after `max_results` matches (default 500).
+#[command(async)]
+pub async fn global_search(
+    state: State<'_, AppState>,
+    query: String,
+    is_regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    max_results: Option<usize>,
+) -> Result<GlobalSearchResult, String> {
+    if query.trim().is_empty() {
+        return Err("Search query is empty.".to_string());
+    }
+    let pattern = build_pattern(&query, is_regex.unwrap_or(false), case_sensitive.unwrap_or(false))?;
+    let max_results = max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1);
+    let redactor = Redactor::for_state(&state);
+
+    let mut result = GlobalSearchResult { query, ..Default::default() };
+    let mut trees = Vec::new();
+    {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        for project in load_all_projects(&conn)? {
+            match load_scan_result(&conn, project.id) {
+                Ok(Some(tree)) => trees.push((project, tree)),
+                Ok(None) => result.unscanned_projects.push(project.id),
+                Err(e) => eprintln!("[GLOBAL SEARCH] Skipping project {}: {}", project.id, e),
+            }
+        }
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let found = AtomicUsize::new(0);
+        for (project, tree) in &trees {
+            let files = scan_results::collect_files(tree);
+            let mut matches: Vec<SearchMatch> = files
+                .par_iter()
+                .filter(|_| found.load(Ordering::Relaxed) < max_results)
+                .flat_map_iter(|file| {
+                    let file_matches = search_file(file, &tree.path, &pattern, &redactor);
+                    found.fetch_add(file_matches.len(), Ordering::Relaxed);
+                    file_matches
+                })
+                .collect();
+            if matches.is_empty() {
+                continue;
+            }
+            matches.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+            result.projects.push(ProjectSearchResult {
+                project_id: project.id,
+                project_title: project.title.clone(),
+                root_folder: project.root_folder.clone(),
+                matches,
+                files_searched: files.len(),
+            });
+        }
+
+        // Parallel files can overshoot the budget; trim in result order
+        let mut remaining = max_results;
+        for project in &mut result.projects {
+            if project.matches.len() > remaining {
+                project.matches.truncate(remaining);
+                result.truncated = true;
+            }
+            remaining -= project.matches.len();
+        }
+        result.projects.retain(|p| !p.matches.is_empty());
+        result.truncated |= found.load(Ordering::Relaxed) >= max_results;
+        result.total_matches = result.projects.iter().map(|p| p.matches.len()).sum();
+        result
+    })
+    .await
+    .map_err(|e| format!("Global search task failed: {}", e))
+}
@@ -1,13 +1,74 @@
 
 // src-tauri/src/ignore_handler.rs
-use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use crate::include_globs::literal_base;
+use ignore::gitignore::{Gitignore, GitignoreBuilder, Glob};
 use ignore::Match;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-#[derive(Debug)]
+/// `.gitignore` files of the folders above a path in a walk, outermost first;
+/// built (and shared between sibling entries) by `scan_tree::gather_valid_items`.
+pub type GitignoreStack = Vec<Arc<(PathBuf, Gitignore)>>;
+
+// Compiles a gitignore-format file whose patterns are relative to `dir`
+fn compile_gitignore_file(dir: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(file) {
+        eprintln!("[IGNORE_PATTERN_COMPILE_ERROR] Problem in '{}': {}", file.display(), e);
+    }
+    match builder.build() {
+        Ok(gitignore) => Some(gitignore),
+        Err(e) => {
+            eprintln!("[IGNORE_PATTERNS_FATAL] Failed to build rules from '{}': {}", file.display(), e);
+            None
+        }
+    }
+}
+
+/// Why a path is left out of scans: the rule that decided it (see `explain`).
+#[derive(Serialize, Debug, Clone)]
+pub struct Exclusion {
+    /// "include_patterns", "dir_rule", "ignore_pattern", "hidden" or "gitignore";
+    /// walks add "symlink" for linked folders they don't enter
+    pub source: String,
+    /// The deciding pattern as written; None for a hidden entry, or a path no
+    /// include pattern covers
+    pub pattern: Option<String>,
+    /// The .gitignore file, or the folder of a per-directory rule, the pattern comes from
+    pub origin: Option<String>,
+}
+
+// The rule behind an exclusion, borrowed from the compiled matchers
+struct Verdict<'a> {
+    source: &'static str,
+    glob: Option<&'a Glob>,
+    origin: Option<&'a Path>,
+}
+
+// Cloned into each parallel walk (the compiled matchers share their regexes)
+#[derive(Debug, Clone)]
 pub struct CompiledIgnorePatterns {
     gitignore: Gitignore,
-    #[allow(dead_code)] // It's used logically by the gitignore crate, but not directly read
+    // Per-directory rules (see dir_ignore_rules), deepest directory first
+    subtrees: Vec<(PathBuf, Gitignore)>,
+    // The repository's own .gitignore files, deepest directory first; consulted
+    // only when the configured patterns have no opinion on a path
+    gitignores: Vec<(PathBuf, Gitignore)>,
+    // Whether walks pick up .gitignore files in subfolders
+    nested_gitignores: bool,
+    // Whether walks descend into symlinked folders
+    follow_symlinks: bool,
+    // Folder levels walks descend into; None = scan_tree's default
+    max_depth: Option<usize>,
+    // Whether dot-named entries are scanned without a `!` pattern keeping them
+    include_hidden: bool,
+    // The project's include-only patterns and the folders they can match in
+    // (see set_include_patterns); None = everything is included
+    includes: Option<(Gitignore, Vec<PathBuf>)>,
     project_root: PathBuf,
 }
 
@@ -43,24 +104,222 @@ impl CompiledIgnorePatterns {
 
         CompiledIgnorePatterns { 
             gitignore, 
+            subtrees: Vec::new(),
+            gitignores: Vec::new(),
+            nested_gitignores: false,
+            follow_symlinks: false,
+            max_depth: None,
+            include_hidden: true,
+            includes: None,
             project_root: project_root.to_path_buf() 
         }
     }
 
+    /// Adds gitignore `lines` that apply only below `dir`. They are checked
+    /// before the project-wide patterns, deeper directories first, and a
+    /// whitelist (`!pattern`) match there keeps a path the project patterns
+    /// would ignore.
+    pub fn add_subtree_rules(&mut self, dir: &Path, lines: &[String]) {
+        let mut builder = GitignoreBuilder::new(dir);
+        for line in lines {
+            if let Err(e) = builder.add_line(None, line) {
+                eprintln!("[IGNORE_PATTERN_COMPILE_ERROR] Failed to add pattern '{}' for '{}': {}", line, dir.display(), e);
+            }
+        }
+        match builder.build() {
+            Ok(gitignore) => {
+                self.subtrees.push((dir.to_path_buf(), gitignore));
+                self.subtrees.sort_by_key(|(d, _)| std::cmp::Reverse(d.components().count()));
+            }
+            Err(e) => eprintln!("[IGNORE_PATTERNS_FATAL] Failed to build rules for '{}': {}", dir.display(), e),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.project_root
+    }
+
+    /// Loads a gitignore-format `file` whose patterns are relative to `dir`
+    /// (a `.gitignore`, or `.git/info/exclude` for the root). Returns false when
+    /// the file doesn't exist.
+    pub fn add_gitignore_file(&mut self, dir: &Path, file: &Path) -> bool {
+        let Some(gitignore) = compile_gitignore_file(dir, file) else { return false };
+        self.gitignores.push((dir.to_path_buf(), gitignore));
+        self.gitignores.sort_by_key(|(d, _)| std::cmp::Reverse(d.components().count()));
+        true
+    }
+
+    /// Loads the root `.gitignore` and `.git/info/exclude` of a repository at
+    /// `root`; the .gitignore takes precedence, as in git. Walks then also
+    /// apply the .gitignore files they find in subfolders.
+    pub fn add_repository_gitignore(&mut self, root: &Path) {
+        self.nested_gitignores = true;
+        // Same directory: the file added first is checked first
+        self.add_gitignore_file(root, &root.join(".gitignore"));
+        self.add_gitignore_file(root, &root.join(".git").join("info").join("exclude"));
+    }
+
+    /// Has walks descend into symlinked folders (the project's `follow_symlinks`).
+    pub fn set_follow_symlinks(&mut self, follow: bool) {
+        self.follow_symlinks = follow;
+    }
+
+    pub fn follows_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+
+    /// Limits walks to `depth` folder levels below the root (the project's `max_scan_depth`).
+    pub fn set_max_depth(&mut self, depth: Option<usize>) {
+        self.max_depth = depth;
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Whether dot-named files and folders are scanned (the project's
+    /// `include_hidden`). When off, one is ignored unless a configured or
+    /// per-directory `!` pattern matches it.
+    pub fn set_include_hidden(&mut self, include: bool) {
+        self.include_hidden = include;
+    }
+
+    /// Include-only patterns (the project's `include_patterns`), in gitignore
+    /// syntax relative to the root: only matching files are kept, and `!pattern`
+    /// leaves a match out again. They're checked before the ignore patterns.
+    /// Folders are kept while they may hold a match; walks drop the ones that end up empty.
+    pub fn set_include_patterns(&mut self, patterns: &[String]) {
+        let lines: Vec<&str> = patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty() && !p.starts_with('#')).collect();
+        let bases: Vec<PathBuf> =
+            lines.iter().filter(|l| !l.starts_with('!')).map(|l| self.project_root.join(literal_base(l))).collect();
+        if bases.is_empty() {
+            self.includes = None;
+            return;
+        }
+        let mut builder = GitignoreBuilder::new(&self.project_root);
+        for line in &lines {
+            if let Err(e) = builder.add_line(None, line) {
+                eprintln!("[IGNORE_PATTERN_COMPILE_ERROR] Failed to add include pattern '{}': {}", line, e);
+            }
+        }
+        match builder.build() {
+            Ok(matcher) => self.includes = Some((matcher, bases)),
+            Err(e) => eprintln!("[IGNORE_PATTERNS_FATAL] Failed to build include patterns: {}. Including everything.", e),
+        }
+    }
+
+    pub fn has_include_patterns(&self) -> bool {
+        self.includes.is_some()
+    }
+
+    pub fn follows_nested_gitignores(&self) -> bool {
+        self.nested_gitignores
+    }
+
+    /// `dir/.gitignore` for a walk's stack, when walks follow nested
+    /// .gitignore files (the root's is loaded up front, so it's skipped).
+    pub fn nested_gitignore(&self, dir: &Path) -> Option<Arc<(PathBuf, Gitignore)>> {
+        if !self.nested_gitignores || dir == self.project_root {
+            return None;
+        }
+        compile_gitignore_file(dir, &dir.join(".gitignore")).map(|gitignore| Arc::new((dir.to_path_buf(), gitignore)))
+    }
+
     /// Checks if the given path is ignored.
     pub fn is_ignored(&self, absolute_path: &Path, is_dir: bool) -> bool {
-        match self.gitignore.matched(absolute_path, is_dir) {
-            Match::None => {
-                false
+        self.is_ignored_in(absolute_path, is_dir, &[])
+    }
+
+    /// `is_ignored` inside a walk that has met the `nested` .gitignore files.
+    pub fn is_ignored_in(&self, absolute_path: &Path, is_dir: bool, nested: &[Arc<(PathBuf, Gitignore)>]) -> bool {
+        self.exclusion(absolute_path, is_dir, nested).is_some()
+    }
+
+    /// Why `is_ignored_in` ignores the path, or None when it doesn't.
+    pub fn explain(&self, absolute_path: &Path, is_dir: bool, nested: &[Arc<(PathBuf, Gitignore)>]) -> Option<Exclusion> {
+        self.exclusion(absolute_path, is_dir, nested).map(|verdict| Exclusion {
+            source: verdict.source.to_string(),
+            pattern: verdict.glob.map(|glob| glob.original().to_string()),
+            origin: verdict.origin.map(|origin| origin.to_string_lossy().to_string()),
+        })
+    }
+
+    // Include patterns, then the configured rules, then hidden entries, then the
+    // repository's .gitignore files
+    fn exclusion<'a>(&'a self, absolute_path: &Path, is_dir: bool, nested: &'a [Arc<(PathBuf, Gitignore)>]) -> Option<Verdict<'a>> {
+        if let Some(verdict) = self.include_exclusion(absolute_path, is_dir) {
+            return Some(verdict);
+        }
+        match self.configured_match(absolute_path, is_dir) {
+            Match::Ignore(verdict) => Some(verdict),
+            Match::Whitelist(_) => None,
+            Match::None if self.is_hidden_excluded(absolute_path, is_dir) => Some(Verdict { source: "hidden", glob: None, origin: None }),
+            Match::None => self.git_exclusion(absolute_path, is_dir, nested),
+        }
+    }
+
+    // Include patterns first: a file must match one; a folder is kept when it
+    // matches or lies on the way to (or inside) a pattern's literal base folder
+    fn include_exclusion(&self, absolute_path: &Path, is_dir: bool) -> Option<Verdict<'_>> {
+        let (matcher, bases) = self.includes.as_ref()?;
+        if absolute_path == self.project_root || !absolute_path.starts_with(&self.project_root) {
+            return None;
+        }
+        match matcher.matched_path_or_any_parents(absolute_path, is_dir) {
+            Match::Ignore(_) => None,
+            Match::Whitelist(glob) => Some(Verdict { source: "include_patterns", glob: Some(glob), origin: None }),
+            Match::None if is_dir && bases.iter().any(|base| base.starts_with(absolute_path) || absolute_path.starts_with(base)) => None,
+            Match::None => Some(Verdict { source: "include_patterns", glob: None, origin: None }),
+        }
+    }
+
+    // The per-directory rules, then the project-wide patterns
+    fn configured_match(&self, absolute_path: &Path, is_dir: bool) -> Match<Verdict<'_>> {
+        for (dir, rules) in &self.subtrees {
+            if absolute_path == dir || !absolute_path.starts_with(dir) {
+                continue;
             }
-            // Corrected: silenced unused variable warning
-            Match::Ignore(_glob) => {
-                true
+            match rules.matched(absolute_path, is_dir) {
+                Match::None => (),
+                matched => return matched.map(|glob| Verdict { source: "dir_rule", glob: Some(glob), origin: Some(dir.as_path()) }),
+            }
+        }
+        self.gitignore.matched(absolute_path, is_dir).map(|glob| Verdict { source: "ignore_pattern", glob: Some(glob), origin: None })
+    }
+
+    // With hidden entries off: the path, or a folder between it and the root, is
+    // dot-named and not whitelisted. Walks never get past a hidden folder, but
+    // single-path checks (the file monitor, health checks) can.
+    fn is_hidden_excluded(&self, absolute_path: &Path, is_dir: bool) -> bool {
+        if self.include_hidden {
+            return false;
+        }
+        let Ok(relative) = absolute_path.strip_prefix(&self.project_root) else { return false };
+        let depth = relative.components().count();
+        let mut current = self.project_root.clone();
+        for (index, component) in relative.components().enumerate() {
+            current.push(component);
+            let hidden = component.as_os_str().to_string_lossy().starts_with('.');
+            let component_is_dir = is_dir || index + 1 < depth;
+            if hidden && !self.configured_match(&current, component_is_dir).is_whitelist() {
+                return true;
+            }
+        }
+        false
+    }
+
+    // The deepest .gitignore with an opinion decides, as in git
+    fn git_exclusion<'a>(&'a self, absolute_path: &Path, is_dir: bool, nested: &'a [Arc<(PathBuf, Gitignore)>]) -> Option<Verdict<'a>> {
+        for (dir, rules) in nested.iter().rev().map(|n| &**n).chain(&self.gitignores) {
+            if absolute_path == dir || !absolute_path.starts_with(dir) {
+                continue;
             }
-            // Corrected: silenced unused variable warning
-            Match::Whitelist(_glob) => {
-                false
+            match rules.matched(absolute_path, is_dir) {
+                Match::Ignore(glob) => return Some(Verdict { source: "gitignore", glob: Some(glob), origin: glob.from() }),
+                Match::Whitelist(_) => return None,
+                Match::None => (),
             }
         }
+        None
     }
 }
\ No newline at end of file
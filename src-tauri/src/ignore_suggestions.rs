@@ -0,0 +1,164 @@
+// src-tauri/src/ignore_suggestions.rs
+// Post-scan analysis of the persisted tree: proposes ignore patterns for build
+// output, generated code, minified files and token-heavy directories, with the
+// tokens each pattern would save.
+
+use crate::db::AppState;
+use crate::tree_queries::load_tree;
+use crate::types::FileNode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{command, State};
+
+// Directory names that are almost always build output or vendored/generated code
+const GENERATED_DIR_NAMES: &[&str] = &[
+    "dist", "build", "out", "target", ".next", ".nuxt", ".svelte-kit", ".turbo", ".cache",
+    "coverage", "node_modules", "vendor", "__generated__", "generated", ".gradle", "obj",
+];
+
+// File-name suffixes of generated artifacts (matched case-insensitively)
+const GENERATED_FILE_SUFFIXES: &[&str] = &[
+    ".generated.ts", ".generated.js", ".generated.cs", ".g.dart", ".freezed.dart", ".g.cs",
+    ".pb.go", "_pb2.py", "_pb2_grpc.py", ".pb.ts", ".min.js", ".min.css", ".js.map", ".css.map",
+    ".bundle.js", ".chunk.js", ".designer.cs",
+];
+
+// A directory is "heavy" when it holds at least this share of the project tokens...
+const HEAVY_DIR_SHARE: f64 = 0.25;
+// ...and at least this many tokens in absolute terms
+const HEAVY_DIR_MIN_TOKENS: usize = 50_000;
+// Minified files: long average line length on a non-trivial file
+const MINIFIED_MIN_SIZE: u64 = 10 * 1024;
+const MINIFIED_MIN_AVG_LINE: u64 = 500;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct IgnoreSuggestion {
+    pub pattern: String,
+    /// "generated_dir" | "generated_files" | "minified" | "heavy_dir"
+    pub kind: String,
+    pub reason: String,
+    pub estimated_tokens_saved: usize,
+    pub file_count: usize,
+}
+
+#[command]
+pub fn suggest_ignores(state: State<'_, AppState>, project_id: i32) -> Result<Vec<IgnoreSuggestion>, String> {
+    let tree = load_tree(&state, project_id)?;
+    Ok(analyze_tree(&tree))
+}
+
+pub fn analyze_tree(root: &FileNode) -> Vec<IgnoreSuggestion> {
+    let root_path = Path::new(&root.path);
+    let mut suggestions: Vec<IgnoreSuggestion> = Vec::new();
+    let mut suffix_groups: HashMap<&'static str, (usize, usize)> = HashMap::new(); // suffix -> (tokens, files)
+
+    for child in &root.children {
+        walk(child, root_path, root.tokens, &mut suggestions, &mut suffix_groups);
+    }
+
+    for (suffix, (tokens, files)) in suffix_groups {
+        suggestions.push(IgnoreSuggestion {
+            pattern: format!("*{}", suffix),
+            kind: "generated_files".to_string(),
+            reason: format!("{} generated/bundled file(s) ending in '{}'", files, suffix),
+            estimated_tokens_saved: tokens,
+            file_count: files,
+        });
+    }
+
+    // The same directory name can occur several times ("dist/" in each package)
+    let mut merged: Vec<IgnoreSuggestion> = Vec::new();
+    for suggestion in suggestions {
+        match merged.iter_mut().find(|m| m.pattern == suggestion.pattern) {
+            Some(existing) => {
+                existing.estimated_tokens_saved += suggestion.estimated_tokens_saved;
+                existing.file_count += suggestion.file_count;
+                existing.reason = format!("'{}' directories look like build output or vendored code", suggestion.pattern);
+            }
+            None => merged.push(suggestion),
+        }
+    }
+    let mut suggestions = merged;
+
+    suggestions.retain(|s| s.estimated_tokens_saved > 0);
+    suggestions.sort_by(|a, b| b.estimated_tokens_saved.cmp(&a.estimated_tokens_saved).then_with(|| a.pattern.cmp(&b.pattern)));
+    suggestions
+}
+
+fn walk(
+    node: &FileNode,
+    root: &Path,
+    total_tokens: usize,
+    suggestions: &mut Vec<IgnoreSuggestion>,
+    suffix_groups: &mut HashMap<&'static str, (usize, usize)>,
+) {
+    if node.is_dir {
+        let lower_name = node.name.to_lowercase();
+        if GENERATED_DIR_NAMES.contains(&lower_name.as_str()) {
+            // Matches at any depth, like the default patterns do
+            suggestions.push(IgnoreSuggestion {
+                pattern: format!("{}/", node.name),
+                kind: "generated_dir".to_string(),
+                reason: format!("'{}' looks like build output or vendored code", relative(root, &node.path)),
+                estimated_tokens_saved: node.tokens,
+                file_count: node.file_count,
+            });
+            return; // Everything below is covered
+        }
+        let share = if total_tokens > 0 { node.tokens as f64 / total_tokens as f64 } else { 0.0 };
+        if node.tokens >= HEAVY_DIR_MIN_TOKENS && share >= HEAVY_DIR_SHARE && !has_heavy_child(node, total_tokens) {
+            suggestions.push(IgnoreSuggestion {
+                pattern: format!("/{}/", relative(root, &node.path)),
+                kind: "heavy_dir".to_string(),
+                reason: format!("holds {:.0}% of the project's tokens", share * 100.0),
+                estimated_tokens_saved: node.tokens,
+                file_count: node.file_count,
+            });
+            return;
+        }
+        for child in &node.children {
+            walk(child, root, total_tokens, suggestions, suffix_groups);
+        }
+        return;
+    }
+
+    let lower_name = node.name.to_lowercase();
+    if let Some(suffix) = GENERATED_FILE_SUFFIXES.iter().find(|s| lower_name.ends_with(*s)) {
+        let group = suffix_groups.entry(suffix).or_insert((0, 0));
+        group.0 += node.tokens;
+        group.1 += 1;
+        return;
+    }
+    if is_minified(node) {
+        suggestions.push(IgnoreSuggestion {
+            pattern: format!("/{}", relative(root, &node.path)),
+            kind: "minified".to_string(),
+            reason: format!("minified: {} line(s) over {} bytes", node.lines, node.size),
+            estimated_tokens_saved: node.tokens,
+            file_count: 1,
+        });
+    }
+}
+
+// Prefer suggesting the deepest heavy directory rather than all of its ancestors
+fn has_heavy_child(node: &FileNode, total_tokens: usize) -> bool {
+    node.children.iter().any(|c| {
+        c.is_dir
+            && c.tokens >= HEAVY_DIR_MIN_TOKENS
+            && total_tokens > 0
+            && c.tokens as f64 / total_tokens as f64 >= HEAVY_DIR_SHARE
+    })
+}
+
+fn is_minified(node: &FileNode) -> bool {
+    node.size >= MINIFIED_MIN_SIZE && node.size / (node.lines.max(1) as u64) >= MINIFIED_MIN_AVG_LINE
+}
+
+// Root-relative, forward-slash path; callers anchor it with a leading '/'
+fn relative(root: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.replace('\\', "/"))
+}
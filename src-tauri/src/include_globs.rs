@@ -0,0 +1,117 @@
+// src-tauri/src/include_globs.rs
+// Per-scan include globs (`src/**/*.rs`, `docs/`) for a quick scan of one slice
+// of a project without touching its saved configuration. Enumeration starts at
+// the literal folders the globs name, so `src/**` never walks the rest of the
+// tree; ignore patterns still apply. Globs use gitignore syntax relative to the
+// root, so a glob without a slash matches at any depth and `!glob` excludes.
+
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::scan_tree::gather_valid_items;
+use crate::types::{ScanError, SpecialFile};
+use crate::tree_queries::build_glob_matcher;
+use ignore::gitignore::Gitignore;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const GLOB_CHARS: &[char] = &['*', '?', '[', '{', '\\'];
+
+pub struct IncludeFilter {
+    root: PathBuf,
+    matcher: Gitignore,
+    start_dirs: Vec<PathBuf>,
+}
+
+// The folder a glob can't match outside of: its leading literal components,
+// never counting the last one (which may name a file). Unanchored globs and
+// globs starting with a wildcard give the root.
+pub fn literal_base(glob: &str) -> PathBuf {
+    let trimmed = glob.trim_end_matches('/');
+    let anchored = trimmed.trim_start_matches('/');
+    if !anchored.contains('/') {
+        return PathBuf::new();
+    }
+    let components: Vec<&str> = anchored.split('/').collect();
+    components[..components.len() - 1]
+        .iter()
+        .take_while(|c| !c.is_empty() && !c.contains(GLOB_CHARS) && **c != "." && **c != "..")
+        .collect()
+}
+
+impl IncludeFilter {
+    /// None when there are no globs (a normal full scan).
+    pub fn new(root: &Path, globs: &[String]) -> Result<Option<Self>, String> {
+        let globs: Vec<String> = globs.iter().map(|g| g.trim().to_string()).filter(|g| !g.is_empty()).collect();
+        if globs.is_empty() {
+            return Ok(None);
+        }
+        let matcher = build_glob_matcher(root, &globs)?;
+        let mut bases: Vec<PathBuf> =
+            globs.iter().filter(|g| !g.starts_with('!')).map(|g| root.join(literal_base(g))).collect();
+        if bases.is_empty() {
+            return Err("Include globs need at least one pattern that isn't a '!' exclusion.".to_string());
+        }
+        bases.sort();
+        bases.dedup();
+        // Nested bases are covered by their ancestor
+        let mut start_dirs: Vec<PathBuf> = Vec::new();
+        for base in bases {
+            if !start_dirs.iter().any(|s| base.starts_with(s)) {
+                start_dirs.push(base);
+            }
+        }
+        Ok(Some(IncludeFilter { root: root.to_path_buf(), matcher, start_dirs }))
+    }
+
+    pub fn matches_file(&self, path: &Path) -> bool {
+        path.starts_with(&self.root) && self.matcher.matched_path_or_any_parents(path, false).is_ignore()
+    }
+
+    /// Enumerates only the glob's start folders (and the folders leading to
+    /// them), then keeps the matching files and the folders that contain them.
+    /// Returns the folders cut off by the depth limit.
+    pub fn gather(
+        &self,
+        compiled: &CompiledIgnorePatterns,
+        collected: &mut Vec<PathBuf>,
+        dir_paths: &mut HashSet<PathBuf>,
+        errors: &mut Vec<ScanError>,
+        special: &mut Vec<SpecialFile>,
+    ) -> Vec<PathBuf> {
+        let mut truncated = Vec::new();
+        for start in &self.start_dirs {
+            let Ok(relative) = start.strip_prefix(&self.root) else { continue };
+            let mut ancestor = self.root.clone();
+            let mut reachable = true;
+            let mut depth = 0;
+            for component in relative.components() {
+                if !collected.contains(&ancestor) {
+                    collected.push(ancestor.clone());
+                }
+                dir_paths.insert(ancestor.clone());
+                ancestor.push(component);
+                depth += 1;
+                if !ancestor.is_dir() || compiled.is_ignored(&ancestor, true) {
+                    reachable = false;
+                    break;
+                }
+            }
+            if reachable {
+                truncated.extend(gather_valid_items(start, compiled, collected, dir_paths, errors, special, depth));
+            }
+        }
+
+        let kept: Vec<PathBuf> = collected.iter().filter(|p| !dir_paths.contains(*p) && self.matches_file(p)).cloned().collect();
+        let mut kept_dirs: HashSet<PathBuf> = HashSet::new();
+        kept_dirs.insert(self.root.clone());
+        for file in &kept {
+            for dir in file.ancestors().skip(1).take_while(|d| d.starts_with(&self.root)) {
+                if !kept_dirs.insert(dir.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+        collected.retain(|p| kept_dirs.contains(p) || (!dir_paths.contains(p) && self.matches_file(p)));
+        dir_paths.retain(|d| kept_dirs.contains(d));
+        truncated
+    }
+}
@@ -0,0 +1,168 @@
+// src-tauri/src/inclusion_rules.rs
+// Per-project "always include" / "never include" lists. Unlike ignore patterns
+// they don't affect scanning: the files stay in the tree, but selection
+// commands (trace selection, ranking, `apply_inclusion_rules`) force them in or
+// out. Patterns use gitignore syntax relative to the project root, so a bare
+// `schema.prisma` matches at any depth.
+
+use crate::db::AppState;
+use crate::path_canon::canonical_paths;
+use crate::scan_results;
+use crate::tree_queries::{build_glob_matcher, load_tree};
+use crate::types::FileNode;
+use ignore::gitignore::Gitignore;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use tauri::{command, State};
+
+pub const MODE_ALWAYS: &str = "always";
+pub const MODE_NEVER: &str = "never";
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct InclusionRuleLists {
+    pub always: Vec<String>,
+    pub never: Vec<String>,
+}
+
+/// Compiled lists for one project root.
+pub struct InclusionRules {
+    always: Option<Gitignore>,
+    never: Option<Gitignore>,
+}
+
+impl InclusionRules {
+    pub fn compile(root: &Path, lists: &InclusionRuleLists) -> Result<Self, String> {
+        let compile = |patterns: &[String]| -> Result<Option<Gitignore>, String> {
+            if patterns.is_empty() {
+                Ok(None)
+            } else {
+                build_glob_matcher(root, patterns).map(Some)
+            }
+        };
+        Ok(InclusionRules { always: compile(&lists.always)?, never: compile(&lists.never)? })
+    }
+
+    // Paths outside the root (stale selections, other projects) never match;
+    // the matcher asserts they are under it
+    fn matches(matcher: &Option<Gitignore>, path: &str) -> bool {
+        let path = Path::new(path);
+        matcher
+            .as_ref()
+            .is_some_and(|m| path.starts_with(m.path()) && m.matched_path_or_any_parents(path, false).is_ignore())
+    }
+
+    pub fn is_always(&self, path: &str) -> bool {
+        Self::matches(&self.always, path)
+    }
+
+    /// "never" wins over "always" when a file matches both.
+    pub fn is_never(&self, path: &str) -> bool {
+        Self::matches(&self.never, path)
+    }
+
+    /// Files of the tree that must be part of every selection.
+    pub fn always_files(&self, tree: &FileNode) -> Vec<String> {
+        if self.always.is_none() {
+            return Vec::new();
+        }
+        scan_results::collect_files(tree)
+            .into_iter()
+            .filter(|f| self.is_always(&f.path) && !self.is_never(&f.path))
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    /// `paths` minus "never" files plus the tree's "always" files, sorted.
+    pub fn apply(&self, tree: &FileNode, paths: &[String]) -> Vec<String> {
+        let mut selected: BTreeSet<String> = paths.iter().filter(|p| !self.is_never(p)).cloned().collect();
+        selected.extend(self.always_files(tree));
+        selected.into_iter().collect()
+    }
+}
+
+pub fn load_rule_lists(conn: &Connection, project_id: i32) -> Result<InclusionRuleLists, String> {
+    let mut stmt = conn
+        .prepare("SELECT pattern, mode FROM inclusion_rules WHERE project_id = ?1 ORDER BY pattern")
+        .map_err(|e| format!("Prepare inclusion rules query failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Query inclusion rules failed: {}", e))?;
+    let mut lists = InclusionRuleLists::default();
+    for row in rows {
+        let (pattern, mode) = row.map_err(|e| format!("Failed to map inclusion rule row: {}", e))?;
+        match mode.as_str() {
+            MODE_ALWAYS => lists.always.push(pattern),
+            MODE_NEVER => lists.never.push(pattern),
+            other => eprintln!("[RULES] Ignoring inclusion rule '{}' with unknown mode '{}'.", pattern, other),
+        }
+    }
+    Ok(lists)
+}
+
+/// The project's compiled rules, for a tree rooted at `root`.
+pub fn load_rules(state: &AppState, project_id: i32, root: &Path) -> Result<InclusionRules, String> {
+    let lists = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        load_rule_lists(&conn, project_id)?
+    };
+    InclusionRules::compile(root, &lists)
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn get_inclusion_rules(state: State<AppState>, project_id: i32) -> Result<InclusionRuleLists, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_rule_lists(&conn, project_id)
+}
+
+/// Replaces both lists of the project.
+#[command]
+pub fn set_inclusion_rules(
+    state: State<AppState>,
+    project_id: i32,
+    always: Vec<String>,
+    never: Vec<String>,
+) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let clean = |patterns: Vec<String>| -> Vec<String> {
+        let set: BTreeSet<String> = patterns.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+        set.into_iter().collect()
+    };
+    let lists = InclusionRuleLists { always: clean(always), never: clean(never) };
+    if let Some(pattern) = lists.always.iter().find(|p| lists.never.contains(p)) {
+        return Err(format!("Pattern '{}' can't be in both the always and never lists.", pattern));
+    }
+    // Reject invalid globs before anything is stored
+    InclusionRules::compile(Path::new(""), &lists)?;
+
+    let mut conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    tx.execute("DELETE FROM inclusion_rules WHERE project_id = ?1", params![project_id])
+        .map_err(|e| format!("Failed to clear inclusion rules: {}", e))?;
+    for (patterns, mode) in [(&lists.always, MODE_ALWAYS), (&lists.never, MODE_NEVER)] {
+        for pattern in patterns {
+            tx.execute(
+                "INSERT INTO inclusion_rules (project_id, pattern, mode) VALUES (?1, ?2, ?3)",
+                params![project_id, pattern, mode],
+            )
+            .map_err(|e| format!("Failed to save inclusion rule '{}': {}", pattern, e))?;
+        }
+    }
+    tx.commit().map_err(|e| format!("Failed to commit inclusion rules: {}", e))
+}
+
+/// Applies the project's rules to a selection: drops "never" files and adds the
+/// scanned "always" files.
+#[command]
+pub fn apply_inclusion_rules(
+    state: State<'_, AppState>,
+    project_id: i32,
+    paths: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let tree = load_tree(&state, project_id)?;
+    let rules = load_rules(&state, project_id, Path::new(&tree.path))?;
+    Ok(rules.apply(&tree, &canonical_paths(&paths)))
+}
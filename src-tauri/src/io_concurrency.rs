@@ -0,0 +1,58 @@
+// src-tauri/src/io_concurrency.rs
+// How many files a batch read may have in flight. Unbounded rayon parallelism
+// is right for SSDs, but thrashes spinning disks (seeks) and network shares
+// (one request per core). Callers can pass a limit; otherwise it follows the
+// drive type of the first path: network and rotational drives get a small
+// dedicated pool, everything else the global one.
+
+use crate::net_fs;
+use std::path::Path;
+
+/// Read threads for rotational (spinning) disks, where parallel reads mostly add seeks.
+pub const ROTATIONAL_READ_THREADS: usize = 2;
+
+/// Whether `path` lives on a spinning disk. Only detectable on Linux; elsewhere false.
+#[cfg(target_os = "linux")]
+pub fn is_rotational_path(path: &Path) -> bool {
+    let Some((device, _)) = net_fs::mount_entry(path) else { return false };
+    let Some(name) = device.strip_prefix("/dev/") else { return false };
+    // Partitions (sda1) have no queue of their own; their parent disk (sda) does
+    let block = Path::new("/sys/class/block").join(name);
+    [block.join("queue/rotational"), block.join("../queue/rotational")]
+        .iter()
+        .find_map(|flag| std::fs::read_to_string(flag).ok())
+        .is_some_and(|flag| flag.trim() == "1")
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_rotational_path(_path: &Path) -> bool {
+    false
+}
+
+/// Thread limit for reading `paths`: `requested` when given (0 = unbounded),
+/// else a default for the drive of the first path. None means the global pool.
+pub fn resolve_read_concurrency(requested: Option<usize>, paths: &[String]) -> Option<usize> {
+    match requested {
+        Some(0) => None,
+        Some(limit) => Some(limit),
+        None => {
+            let sample = Path::new(paths.first()?);
+            if net_fs::is_network_path(sample) {
+                Some(net_fs::NETWORK_SCAN_THREADS)
+            } else if is_rotational_path(sample) {
+                Some(ROTATIONAL_READ_THREADS)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Runs `op` (rayon work) on a pool of `threads`, or on the global pool for None.
+pub fn run_with_concurrency<R: Send>(threads: Option<usize>, op: impl FnOnce() -> R + Send) -> R {
+    let pool = threads.and_then(|n| rayon::ThreadPoolBuilder::new().num_threads(n).build().ok());
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
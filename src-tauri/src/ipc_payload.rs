@@ -0,0 +1,63 @@
+// src-tauri/src/ipc_payload.rs
+// Binary command responses for large payloads. A JSON response of hundreds of
+// files can be several MB, which stalls the webview while it is parsed as one
+// string; "packed" commands return raw bytes instead: one flag byte, then the
+// JSON either as is or gzipped when it exceeds the threshold. The frontend
+// decodes them with `decodePackedResponse` (src/utils/ipcPayload.ts).
+
+use crate::db::AppState;
+use crate::scanner::read_multiple_file_contents;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::io::Write;
+use tauri::ipc::Response;
+use tauri::{command, State};
+
+/// First byte of a packed response: the rest is plain UTF-8 JSON.
+pub const FLAG_PLAIN: u8 = 0;
+/// First byte of a packed response: the rest is gzip-compressed JSON.
+pub const FLAG_GZIP: u8 = 1;
+/// JSON smaller than this is sent uncompressed; compressing it costs more than it saves.
+pub const DEFAULT_THRESHOLD_BYTES: usize = 256 * 1024;
+
+/// Flag byte + JSON of `value`, gzipped when the JSON is at least `threshold` bytes.
+pub fn pack_json<T: Serialize>(value: &T, threshold: usize) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(value).map_err(|e| format!("Failed to serialize response: {}", e))?;
+    if json.len() < threshold {
+        let mut packed = Vec::with_capacity(json.len() + 1);
+        packed.push(FLAG_PLAIN);
+        packed.extend_from_slice(&json);
+        return Ok(packed);
+    }
+    // Fast level: the point is a smaller IPC transfer, not the smallest output
+    let mut encoder = GzEncoder::new(vec![FLAG_GZIP], Compression::fast());
+    encoder.write_all(&json).map_err(|e| format!("Failed to compress response: {}", e))?;
+    encoder.finish().map_err(|e| format!("Failed to compress response: {}", e))
+}
+
+/// `pack_json` as a raw IPC response (an ArrayBuffer on the frontend).
+pub fn packed_response<T: Serialize>(value: &T, threshold: Option<usize>) -> Result<Response, String> {
+    pack_json(value, threshold.unwrap_or(DEFAULT_THRESHOLD_BYTES)).map(Response::new)
+}
+
+// --- Exposed Tauri Commands ---
+
+/// `read_multiple_file_contents` as a packed response; `threshold` is the JSON
+/// size in bytes from which it's gzipped (default 256 KiB, 0 = always).
+#[command(async)]
+#[allow(clippy::too_many_arguments)]
+pub async fn read_multiple_file_contents_packed(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    project_id: Option<i32>,
+    request_id: Option<String>,
+    max_bytes_per_file: Option<usize>,
+    max_total_bytes: Option<usize>,
+    threshold: Option<usize>,
+    concurrency: Option<usize>,
+) -> Result<Response, String> {
+    let results =
+        read_multiple_file_contents(state, paths, project_id, request_id, max_bytes_per_file, max_total_bytes, concurrency).await?;
+    packed_response(&results, threshold)
+}
@@ -7,6 +7,7 @@ mod projects;
 mod types;
 mod scanner;
 mod scan_cache;
+mod scan_results;
 mod scan_state;
 mod scan_tree;
 mod utils;
@@ -14,23 +15,166 @@ mod file_monitor;
 mod app_settings; // Correct location
 mod ignore_handler;
 mod compress; // + add this
+mod file_types;
+mod document_extract;
+mod change_journal;
+mod snapshots;
+mod cloud_files;
+mod net_fs;
+mod path_case;
+mod window_registry;
+mod ignore_suggestions;
+mod tree_queries;
+mod prompt_templates;
+mod model_presets;
+mod relevance;
+mod trace_select;
+mod context_exports;
+mod redaction;
+mod sensitive_files;
+mod file_notes;
+mod inclusion_rules;
+mod cache_maintenance;
+mod project_history;
+mod profiles;
+mod pinned_files;
+mod single_instance;
+mod scan_metrics;
+mod benchmark;
+mod errors;
+mod ipc_payload;
+mod read_limits;
+mod file_stream;
+mod dependency_closure;
+mod global_search;
+mod editor_launch;
+mod path_canon;
+mod project_health;
+mod file_edits;
+mod io_concurrency;
+mod dir_ignore_rules;
+mod file_split;
+mod include_globs;
+mod file_preview;
+mod context_share;
+mod compression_defaults;
+mod scan_queue;
+mod export_forecast;
+mod scan_watchdog;
+mod selection_expr;
+mod directory_headers;
+mod export_layout;
+mod scan_diff;
+mod portable;
+mod scan_report;
+mod scan_progress;
+mod scan_filters;
+mod scan_preview;
+mod scan_chunks;
+mod scan_history;
+mod archives;
+mod git_sources;
 
 // Import necessary items
-use db::{AppState, init_connection, init_db_tables};
+use db::{AppState, init_connection};
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
 // Use crate::app_settings explicitly if needed outside module scope
 // use crate::app_settings;
 
+// Seeds the default settings, prompt templates and model presets of a freshly
+// opened database (at startup and when switching to a new profile)
+pub fn seed_defaults(conn: &rusqlite::Connection) {
+    // --- Seed Default Ignore Patterns ---
+    // This block checks and potentially seeds the 'default_ignore_patterns' setting
+    match crate::app_settings::get_setting_internal(conn, "default_ignore_patterns") {
+        Ok(maybe_value) => {
+            let needs_seeding = match maybe_value {
+                None => true, // Doesn't exist, needs seeding
+                Some(val) => val.trim().is_empty() || val.trim() == "[]", // Exists but is empty, needs seeding
+            };
+
+            if needs_seeding {
+                println!("[SETUP] 'default_ignore_patterns' not found or empty in app_settings. Seeding...");
+                let hardcoded_defaults = crate::app_settings::get_hardcoded_default_ignore_patterns();
+                if hardcoded_defaults.is_empty() {
+                     eprintln!("[SETUP_WARN] Attempted to seed defaults, but hardcoded list is empty!");
+                } else {
+                    match serde_json::to_string(&hardcoded_defaults) {
+                        Ok(defaults_json) => {
+                            // Use INSERT OR REPLACE to handle both missing key and empty value cases
+                            if let Err(e) = conn.execute(
+                                "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+                                rusqlite::params!["default_ignore_patterns", defaults_json],
+                            ) {
+                                eprintln!("[SETUP_ERROR] Failed to seed/replace default_ignore_patterns: {}", e);
+                                // Consider if this should panic
+                            } else {
+                                println!("[SETUP] Successfully seeded/replaced 'default_ignore_patterns' with {} defaults.", hardcoded_defaults.len());
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("[SETUP_ERROR] Failed to serialize hardcoded default ignore patterns: {}. Defaults not seeded.", e);
+                             // Consider if this should panic
+                        }
+                    }
+                }
+            } else {
+                println!("[SETUP] 'default_ignore_patterns' found and populated in app_settings.");
+            }
+        }
+        Err(e) => {
+            // This indicates a more fundamental DB query issue
+            eprintln!("[SETUP_ERROR] Failed to query default_ignore_patterns during seeding check: {}. Cannot ensure defaults are seeded.", e);
+            // Consider if this should panic
+        }
+    }
+    // --- End Seeding ---
+
+    if let Err(e) = prompt_templates::seed_default_templates(conn) {
+        eprintln!("[SETUP_ERROR] {}", e);
+    }
+    if let Err(e) = model_presets::seed_default_presets(conn) {
+        eprintln!("[SETUP_ERROR] {}", e);
+    }
+    if let Err(e) = projects::purge_expired_projects(conn) {
+        eprintln!("[SETUP_ERROR] {}", e);
+    }
+}
+
 fn main() {
     let context = tauri::generate_context!();
 
+    // --- Portable mode: keep every write in the data folder next to the executable ---
+    if let Err(e) = portable::prepare() {
+        eprintln!("[PORTABLE] {}", e);
+        std::process::exit(1);
+    }
+
+    // --- Single instance: hand this launch to a running instance if there is one ---
+    let instance_listener = match single_instance::acquire() {
+        Ok(single_instance::Launch::Primary(listener)) => listener,
+        Ok(single_instance::Launch::HandedOff(message)) => {
+            println!("[INSTANCE] {}", message);
+            return;
+        }
+        Err(e) => {
+            eprintln!("[INSTANCE] {}", e);
+            std::process::exit(1);
+        }
+    };
+
     tauri::Builder::default()
-        .setup(|app| {
+        .setup(move |app| {
             let app_handle = app.handle().clone();
 
+            // --- Main window (built here so portable mode can place its WebView data) ---
+            portable::create_main_window(app)?;
+
             // --- Initialize DB Connection ---
-            let conn = match init_connection(&app_handle) {
+            // Tables are initialized here too; an unwritable database file falls
+            // back to a read-only, in-memory copy instead of failing
+            let (conn, read_only_reason) = match init_connection(&app_handle) {
                 Ok(c) => c,
                 Err(e) => {
                     eprintln!("FATAL: DB connection failed during setup: {}", e);
@@ -38,67 +182,30 @@ fn main() {
                 }
             };
 
-            // --- Initialize DB Tables ---
-            if let Err(e) = init_db_tables(&conn) {
-                 eprintln!("FATAL: DB table init failed during setup: {}", e);
-                 panic!("DB table init failed: {}", e); // Panic early if tables fail
-            }
-
-            // --- Seed Default Ignore Patterns ---
-            // This block checks and potentially seeds the 'default_ignore_patterns' setting
-            match crate::app_settings::get_setting_internal(&conn, "default_ignore_patterns") {
-                Ok(maybe_value) => {
-                    let needs_seeding = match maybe_value {
-                        None => true, // Doesn't exist, needs seeding
-                        Some(val) => val.trim().is_empty() || val.trim() == "[]", // Exists but is empty, needs seeding
-                    };
-
-                    if needs_seeding {
-                        println!("[SETUP] 'default_ignore_patterns' not found or empty in app_settings. Seeding...");
-                        let hardcoded_defaults = crate::app_settings::get_hardcoded_default_ignore_patterns();
-                        if hardcoded_defaults.is_empty() {
-                             eprintln!("[SETUP_WARN] Attempted to seed defaults, but hardcoded list is empty!");
-                        } else {
-                            match serde_json::to_string(&hardcoded_defaults) {
-                                Ok(defaults_json) => {
-                                    // Use INSERT OR REPLACE to handle both missing key and empty value cases
-                                    if let Err(e) = conn.execute(
-                                        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
-                                        rusqlite::params!["default_ignore_patterns", defaults_json],
-                                    ) {
-                                        eprintln!("[SETUP_ERROR] Failed to seed/replace default_ignore_patterns: {}", e);
-                                        // Consider if this should panic
-                                    } else {
-                                        println!("[SETUP] Successfully seeded/replaced 'default_ignore_patterns' with {} defaults.", hardcoded_defaults.len());
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("[SETUP_ERROR] Failed to serialize hardcoded default ignore patterns: {}. Defaults not seeded.", e);
-                                     // Consider if this should panic
-                                }
-                            }
-                        }
-                    } else {
-                        println!("[SETUP] 'default_ignore_patterns' found and populated in app_settings.");
-                    }
-                }
-                Err(e) => {
-                    // This indicates a more fundamental DB query issue
-                    eprintln!("[SETUP_ERROR] Failed to query default_ignore_patterns during seeding check: {}. Cannot ensure defaults are seeded.", e);
-                    // Consider if this should panic
-                }
-            }
-            // --- End Seeding ---
-
+            seed_defaults(&conn);
 
             // --- Manage App State ---
-            let app_db_state = AppState { conn: Arc::new(Mutex::new(conn)) }; // Pass the connection ownership
+            let app_db_state = AppState {
+                conn: Arc::new(Mutex::new(conn)), // Pass the connection ownership
+                read_only_reason: Arc::new(Mutex::new(read_only_reason)),
+            };
             app.manage(app_db_state);
 
             // --- Initialize and manage MonitorState ---
             let monitor_state = Arc::new(Mutex::new(file_monitor::MonitorState::default()));
             app.manage(monitor_state.clone());
 
+            // --- Window/project event routing ---
+            app.manage(window_registry::WindowRegistry::default());
+
+            // --- Load the tokenizer now (warns the UI if counts fall back) ---
+            utils::init_tokenizer(&app_handle);
+
+            // --- Serve open requests from later launches ---
+            if let Some(listener) = instance_listener {
+                single_instance::serve(app_handle.clone(), listener);
+            }
+
             // --- Spawn the monitoring thread ---
             let app_handle_for_monitor_thread = app_handle.clone();
             std::thread::spawn(move || {
@@ -107,23 +214,126 @@ fn main() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Some(registry) = window.try_state::<window_registry::WindowRegistry>() {
+                    registry.unsubscribe(window.label(), None);
+                }
+            }
+        })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())  
         .invoke_handler(tauri::generate_handler![
             projects::list_code_context_builder_projects,
             projects::save_code_context_builder_project,
             projects::delete_code_context_builder_project,
+            projects::list_deleted_projects,
+            projects::restore_deleted_project,
+            projects::purge_deleted_projects,
             scanner::scan_code_context_builder_project,
+            scanner::scan_project_with_summary,
+            scanner::rescan_subtree_cmd,
             scanner::cancel_code_context_builder_scan,
+            scanner::pause_code_context_builder_scan,
+            scanner::resume_code_context_builder_scan,
+            scanner::cancel_batch_read,
             scanner::read_file_contents,
             scanner::read_multiple_file_contents,
+            scanner::read_multiple_file_contents_with_redactions,
+            scanner::get_file_stats,
             utils::get_text_token_count,
             file_monitor::start_monitoring_project_cmd,
             file_monitor::stop_monitoring_project_cmd,
+            window_registry::subscribe_window_to_project,
+            window_registry::unsubscribe_window_from_project,
+            change_journal::get_changes_since,
+            ignore_suggestions::suggest_ignores,
+            tree_queries::get_flat_file_list,
+            tree_queries::filter_tree,
+            tree_queries::get_tree_children,
+            relevance::rank_files,
+            trace_select::select_files_from_trace,
+            context_exports::record_context_export,
+            context_exports::list_context_exports,
+            context_exports::build_delta_context,
+            redaction::list_redaction_rules,
+            redaction::save_redaction_rules,
+            redaction::redact_text,
+            sensitive_files::check_sensitive_files,
+            file_notes::list_file_notes,
+            file_notes::set_file_note,
+            file_notes::delete_file_note,
+            inclusion_rules::get_inclusion_rules,
+            inclusion_rules::set_inclusion_rules,
+            inclusion_rules::apply_inclusion_rules,
+            cache_maintenance::clear_project_cache,
+            cache_maintenance::cleanup_orphaned_cache,
+            cache_maintenance::get_cache_stats,
+            project_history::get_project_history,
+            profiles::list_profiles,
+            profiles::switch_profile,
+            db::get_database_mode,
+            pinned_files::list_pinned_files,
+            pinned_files::pin_file,
+            pinned_files::unpin_file,
+            pinned_files::pack_files_for_budget,
+            single_instance::take_launch_request,
+            scan_metrics::get_scan_metrics,
+            benchmark::benchmark_scan,
+            utils::get_tokenizer_health,
+            utils::reload_tokenizer,
+            errors::get_error_catalog,
+            errors::localize_error,
+            ipc_payload::read_multiple_file_contents_packed,
+            file_stream::stream_file_contents,
+            dependency_closure::suggest_dependency_selection,
+            global_search::global_search,
+            editor_launch::list_editor_presets,
+            editor_launch::open_in_editor,
+            project_health::check_project_health,
+            file_edits::apply_file_edits,
+            dir_ignore_rules::get_dir_ignore_rules,
+            dir_ignore_rules::set_dir_ignore_rules,
+            file_split::split_file_by_tokens,
+            file_preview::preview_file,
+            context_share::share_context,
+            compression_defaults::get_compression_defaults,
+            compression_defaults::save_compression_defaults,
+            scan_queue::get_scan_queue,
+            export_forecast::forecast_export,
+            selection_expr::resolve_selection,
+            directory_headers::get_directory_headers,
+            export_layout::get_export_layout,
+            scan_diff::diff_scan_cmd,
+            portable::get_portable_mode,
+            scan_report::export_scan_report,
+            scan_preview::preview_scan_cmd,
+            scan_history::list_scan_history_cmd,
+            git_sources::scan_git_url_cmd,
+            snapshots::save_tree_snapshot,
+            snapshots::list_tree_snapshots,
+            snapshots::delete_tree_snapshot,
+            snapshots::diff_tree_snapshots,
+            prompt_templates::list_prompt_templates,
+            prompt_templates::save_prompt_template,
+            prompt_templates::delete_prompt_template,
+            prompt_templates::render_prompt_template,
+            model_presets::list_model_presets,
+            model_presets::save_model_preset,
+            model_presets::delete_model_preset,
+            model_presets::select_model_preset,
+            model_presets::get_selected_model_preset,
+            model_presets::estimate_context_cost,
             app_settings::get_app_setting_cmd,
             app_settings::set_app_setting_cmd,
             compress::read_multiple_file_contents_compressed, // <-- NEW
+            compress::read_multiple_file_contents_compressed_report,
         ])
-        .run(context)
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                single_instance::release();
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,273 @@
+// src-tauri/src/model_presets.rs
+// Model presets: context window, tokens reserved for the reply, and pricing.
+// The selected preset (app setting `selected_model_preset_id`) supplies the
+// token budget and the per-token prices used by cost estimates.
+
+use crate::app_settings;
+use crate::db::AppState;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{command, State};
+
+pub const SELECTED_PRESET_SETTING: &str = "selected_model_preset_id";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelPreset {
+    pub id: i64,
+    pub name: String,
+    pub model: String,
+    pub context_window: usize,
+    pub reserved_output_tokens: usize,
+    /// USD per million input tokens
+    pub input_price_per_mtok: f64,
+    /// USD per million output tokens
+    pub output_price_per_mtok: f64,
+}
+
+impl ModelPreset {
+    /// Tokens available for the prompt/context once the reply is reserved.
+    pub fn input_budget(&self) -> usize {
+        self.context_window.saturating_sub(self.reserved_output_tokens)
+    }
+
+    pub fn input_cost(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.input_price_per_mtok / 1_000_000.0
+    }
+
+    pub fn output_cost(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.output_price_per_mtok / 1_000_000.0
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CostEstimate {
+    pub preset: ModelPreset,
+    pub input_tokens: usize,
+    pub input_budget: usize,
+    pub fits: bool,
+    /// Negative when the context is over budget
+    pub remaining_tokens: i64,
+    pub input_cost: f64,
+    /// Cost if the reply uses all reserved output tokens
+    pub max_output_cost: f64,
+    pub max_total_cost: f64,
+}
+
+const PRESET_COLUMNS: &str =
+    "id, name, model, context_window, reserved_output_tokens, input_price_per_mtok, output_price_per_mtok";
+
+// (name, model, context window, reserved output, $/Mtok in, $/Mtok out); editable by the user
+const DEFAULT_PRESETS: &[(&str, &str, usize, usize, f64, f64)] = &[
+    ("GPT-4o", "gpt-4o", 128_000, 16_384, 2.50, 10.00),
+    ("GPT-4o mini", "gpt-4o-mini", 128_000, 16_384, 0.15, 0.60),
+    ("Claude 3.5 Sonnet", "claude-3-5-sonnet", 200_000, 8_192, 3.00, 15.00),
+];
+
+fn map_row_to_preset(row: &rusqlite::Row<'_>) -> rusqlite::Result<ModelPreset> {
+    Ok(ModelPreset {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        model: row.get(2)?,
+        context_window: row.get::<_, i64>(3)? as usize,
+        reserved_output_tokens: row.get::<_, i64>(4)? as usize,
+        input_price_per_mtok: row.get(5)?,
+        output_price_per_mtok: row.get(6)?,
+    })
+}
+
+/// Inserts the built-in presets when the table has none yet.
+pub fn seed_default_presets(conn: &Connection) -> Result<(), String> {
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM model_presets", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to count model presets: {}", e))?;
+    if count > 0 {
+        return Ok(());
+    }
+    for (name, model, window, reserved, input_price, output_price) in DEFAULT_PRESETS {
+        conn.execute(
+            r#"
+            INSERT INTO model_presets
+                (name, model, context_window, reserved_output_tokens, input_price_per_mtok, output_price_per_mtok)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![name, model, *window as i64, *reserved as i64, input_price, output_price],
+        )
+        .map_err(|e| format!("Failed to seed model preset '{}': {}", name, e))?;
+    }
+    println!("[SETUP] Seeded {} default model presets.", DEFAULT_PRESETS.len());
+    Ok(())
+}
+
+pub fn load_preset(conn: &Connection, preset_id: i64) -> Result<ModelPreset, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM model_presets WHERE id = ?1", PRESET_COLUMNS),
+        params![preset_id],
+        map_row_to_preset,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to query model preset {}: {}", preset_id, e))?
+    .ok_or_else(|| format!("Model preset with ID {} not found.", preset_id))
+}
+
+/// The selected preset, falling back to the first one when none (or a deleted one) is selected.
+pub fn selected_preset(conn: &Connection) -> Result<Option<ModelPreset>, String> {
+    let selected_id = app_settings::get_setting_internal(conn, SELECTED_PRESET_SETTING)
+        .map_err(|e| format!("Failed to read selected model preset: {}", e))?
+        .and_then(|v| v.trim().parse::<i64>().ok());
+    if let Some(id) = selected_id {
+        if let Ok(preset) = load_preset(conn, id) {
+            return Ok(Some(preset));
+        }
+    }
+    conn.query_row(
+        &format!("SELECT {} FROM model_presets ORDER BY id LIMIT 1", PRESET_COLUMNS),
+        [],
+        map_row_to_preset,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to query model presets: {}", e))
+}
+
+/// Explicit preset when given, otherwise the selected one.
+pub fn resolve_preset(conn: &Connection, preset_id: Option<i64>) -> Result<ModelPreset, String> {
+    match preset_id {
+        Some(id) => load_preset(conn, id),
+        None => selected_preset(conn)?.ok_or_else(|| "No model presets are defined.".to_string()),
+    }
+}
+
+pub fn estimate_cost(preset: &ModelPreset, input_tokens: usize) -> CostEstimate {
+    let input_budget = preset.input_budget();
+    let input_cost = preset.input_cost(input_tokens);
+    let max_output_cost = preset.output_cost(preset.reserved_output_tokens);
+    CostEstimate {
+        preset: preset.clone(),
+        input_tokens,
+        input_budget,
+        fits: input_tokens <= input_budget,
+        remaining_tokens: input_budget as i64 - input_tokens as i64,
+        input_cost,
+        max_output_cost,
+        max_total_cost: input_cost + max_output_cost,
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn list_model_presets(state: State<AppState>) -> Result<Vec<ModelPreset>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM model_presets ORDER BY name COLLATE NOCASE", PRESET_COLUMNS))
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map([], map_row_to_preset)
+        .map_err(|e| format!("Query model presets failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map model preset row: {}", e))
+}
+
+/// Creates a preset when `preset.id <= 0`, otherwise updates it. Returns its ID.
+#[command]
+pub fn save_model_preset(state: State<AppState>, preset: ModelPreset) -> Result<i64, String> {
+    crate::db::ensure_writable(&state)?;
+    if preset.name.trim().is_empty() {
+        return Err("Preset name cannot be empty.".to_string());
+    }
+    if preset.context_window == 0 || preset.reserved_output_tokens >= preset.context_window {
+        return Err("Context window must be positive and larger than the reserved output tokens.".to_string());
+    }
+    if preset.input_price_per_mtok < 0.0 || preset.output_price_per_mtok < 0.0 {
+        return Err("Prices cannot be negative.".to_string());
+    }
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+
+    if preset.id <= 0 {
+        conn.execute(
+            r#"
+            INSERT INTO model_presets
+                (name, model, context_window, reserved_output_tokens, input_price_per_mtok, output_price_per_mtok)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                preset.name.trim(),
+                preset.model.trim(),
+                preset.context_window as i64,
+                preset.reserved_output_tokens as i64,
+                preset.input_price_per_mtok,
+                preset.output_price_per_mtok
+            ],
+        )
+        .map_err(|e| format!("Failed to insert model preset: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    } else {
+        let rows = conn
+            .execute(
+                r#"
+                UPDATE model_presets
+                SET name = ?1, model = ?2, context_window = ?3, reserved_output_tokens = ?4,
+                    input_price_per_mtok = ?5, output_price_per_mtok = ?6
+                WHERE id = ?7
+                "#,
+                params![
+                    preset.name.trim(),
+                    preset.model.trim(),
+                    preset.context_window as i64,
+                    preset.reserved_output_tokens as i64,
+                    preset.input_price_per_mtok,
+                    preset.output_price_per_mtok,
+                    preset.id
+                ],
+            )
+            .map_err(|e| format!("Failed to update model preset {}: {}", preset.id, e))?;
+        if rows == 0 {
+            return Err(format!("Failed to update model preset: ID {} not found.", preset.id));
+        }
+        Ok(preset.id)
+    }
+}
+
+#[command]
+pub fn delete_model_preset(state: State<AppState>, preset_id: i64) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let rows = conn
+        .execute("DELETE FROM model_presets WHERE id = ?1", params![preset_id])
+        .map_err(|e| format!("Failed to delete model preset {}: {}", preset_id, e))?;
+    if rows == 0 {
+        eprintln!("Warning: Attempted to delete model preset ID {}, but it was not found.", preset_id);
+    }
+    Ok(())
+}
+
+#[command]
+pub fn select_model_preset(state: State<AppState>, preset_id: i64) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_preset(&conn, preset_id)?; // must exist
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        params![SELECTED_PRESET_SETTING, preset_id.to_string()],
+    )
+    .map_err(|e| format!("Failed to save selected model preset: {}", e))?;
+    Ok(())
+}
+
+#[command]
+pub fn get_selected_model_preset(state: State<AppState>) -> Result<Option<ModelPreset>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    selected_preset(&conn)
+}
+
+/// Budget fit and cost of a context of `token_count` tokens under a preset
+/// (the selected one when `preset_id` is omitted).
+#[command]
+pub fn estimate_context_cost(
+    state: State<AppState>,
+    token_count: usize,
+    preset_id: Option<i64>,
+) -> Result<CostEstimate, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let preset = resolve_preset(&conn, preset_id)?;
+    Ok(estimate_cost(&preset, token_count))
+}
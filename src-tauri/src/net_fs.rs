@@ -0,0 +1,140 @@
+// src-tauri/src/net_fs.rs
+// Network filesystem (SMB/NFS/...) support for scans: root detection, retry
+// with backoff for transient IO errors, and the bounded "network mode" used
+// so a share isn't hammered by one request per rayon thread.
+
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+// Parallel file processing threads used for network roots.
+pub const NETWORK_SCAN_THREADS: usize = 4;
+
+const RETRY_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY_MS: u64 = 50;
+
+/// Resolves a project's `fs_mode` ("auto" | "local" | "network") for its root.
+pub fn is_network_mode(fs_mode: Option<&str>, root: &Path) -> bool {
+    match fs_mode.map(str::trim) {
+        Some("network") => true,
+        Some("local") => false,
+        _ => is_network_path(root),
+    }
+}
+
+/// Runs an IO operation, retrying transient failures with exponential backoff
+/// (50ms, 100ms, 200ms). Permanent errors (not found, permission) return at once.
+pub fn retry_io<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Ok(v) => return Ok(v),
+            Err(e) if attempt + 1 < RETRY_ATTEMPTS && is_transient(&e) => {
+                thread::sleep(Duration::from_millis(RETRY_BASE_DELAY_MS << attempt));
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether `e` is worth retrying (network hiccups, stale handles, timeouts).
+pub fn is_transient(e: &io::Error) -> bool {
+    use io::ErrorKind::*;
+    if matches!(e.kind(), TimedOut | Interrupted | WouldBlock | ConnectionReset | ConnectionAborted | NotConnected | BrokenPipe | UnexpectedEof) {
+        return true;
+    }
+    match e.raw_os_error() {
+        // ERROR_BAD_NETPATH, ERROR_UNEXP_NET_ERR, ERROR_NETNAME_DELETED, ERROR_SEM_TIMEOUT
+        #[cfg(windows)]
+        Some(53 | 59 | 64 | 121) => true,
+        // Stale NFS handle and friends; the numbers differ between Linux and macOS
+        #[cfg(unix)]
+        Some(libc::EIO | libc::EAGAIN | libc::ESTALE | libc::EHOSTUNREACH) => true,
+        _ => false,
+    }
+}
+
+#[cfg(windows)]
+pub fn is_network_path(path: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    let text = path.to_string_lossy();
+    if (text.starts_with(r"\\") && !text.starts_with(r"\\?\")) || text.starts_with(r"\\?\UNC\") {
+        return true; // UNC share
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDriveTypeW(root_path_name: *const u16) -> u32;
+    }
+    const DRIVE_REMOTE: u32 = 4;
+    let drive_root = match path.components().next() {
+        Some(std::path::Component::Prefix(prefix)) => {
+            let mut root = prefix.as_os_str().to_os_string();
+            root.push("\\");
+            root
+        }
+        _ => return false,
+    };
+    let wide: Vec<u16> = drive_root.encode_wide().chain(std::iter::once(0)).collect();
+    // SAFETY: `wide` is a NUL-terminated UTF-16 string that outlives the call.
+    unsafe { GetDriveTypeW(wide.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_network_path(path: &Path) -> bool {
+    mount_entry(path).is_some_and(|(_, fs_type)| is_network_fs_type(&fs_type))
+}
+
+/// (device, filesystem type) of the mount owning `path`, from /proc/mounts.
+#[cfg(target_os = "linux")]
+pub fn mount_entry(path: &Path) -> Option<(String, String)> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    // The mount with the longest matching mount point owns the path
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point).then(|| (mount_point.len(), device.to_string(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, device, fs_type)| (device, fs_type))
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_network_path(path: &Path) -> bool {
+    // `mount` prints "//user@host/share on /Volumes/share (smbfs, nodev, ...)"
+    let output = match std::process::Command::new("mount").output() {
+        Ok(o) => String::from_utf8_lossy(&o.stdout).to_string(),
+        Err(_) => return false,
+    };
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount_point, opts) = rest.rsplit_once(" (")?;
+            let fs_type = opts.split([',', ')']).next()?.trim();
+            path.starts_with(mount_point).then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .is_some_and(|(_, fs_type)| is_network_fs_type(&fs_type))
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub fn is_network_path(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn is_network_fs_type(fs_type: &str) -> bool {
+    matches!(
+        fs_type,
+        "nfs" | "nfs4" | "cifs" | "smbfs" | "smb3" | "afpfs" | "webdav" | "davfs" | "9p" | "afs"
+            | "fuse.sshfs" | "sshfs" | "fuse.rclone" | "ceph" | "glusterfs" | "fuse.glusterfs"
+    )
+}
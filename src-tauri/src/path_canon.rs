@@ -0,0 +1,184 @@
+// src-tauri/src/path_canon.rs
+// One canonical form for paths, whatever they came from (project config, file
+// dialogs, scans, the monitor, selection commands): no trailing separator, no
+// `.`/`..` or doubled separators, native separators, and on Windows no `\\?\`
+// prefix and no 8.3 short names. Letter case is left alone (CaseMode decides
+// how to compare); symlinks aren't resolved, so a root stays where the user
+// picked it. `migrate_db_paths` brings paths stored by older versions in line.
+
+use crate::scan_results;
+use crate::types::FileNode;
+use rusqlite::{params, Connection};
+use std::path::{Component, Path, PathBuf};
+
+/// Bumped when the canonical form changes; stored as the DB's `user_version`.
+pub const PATH_FORMAT_VERSION: i32 = 1;
+
+// `\\?\C:\x` -> `C:\x`, `\\?\UNC\server\share` -> `\\server\share`
+fn strip_verbatim(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+fn normalize_components(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            // `..` above the root stays (relative paths); otherwise it drops the last part
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => (),
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// The canonical form of `path`. Purely lexical except on Windows, where a path
+/// with a `~` component is resolved on disk to expand 8.3 short names.
+pub fn canonical_path(path: &str) -> String {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    let native = if cfg!(windows) { strip_verbatim(&trimmed.replace('/', "\\")) } else { trimmed.to_string() };
+    let normalized = normalize_components(Path::new(&native));
+    if cfg!(windows) && native.contains('~') {
+        if let Ok(long) = normalized.canonicalize() {
+            return strip_verbatim(&long.to_string_lossy());
+        }
+    }
+    let canonical = normalized.to_string_lossy().to_string();
+    if canonical.is_empty() { ".".to_string() } else { canonical }
+}
+
+/// `canonical_path` of each path, without empty paths and repeats (in first-seen order).
+pub fn canonical_paths(paths: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    paths.iter().map(|p| canonical_path(p)).filter(|p| !p.is_empty() && seen.insert(p.clone())).collect()
+}
+
+/// `canonical_path` for an optional value, keeping None and empty strings as None.
+pub fn canonical_opt(path: Option<&str>) -> Option<String> {
+    path.map(canonical_path).filter(|p| !p.is_empty())
+}
+
+/// Canonicalizes every node path of a persisted tree. Returns true when any changed.
+pub fn canonicalize_tree(node: &mut FileNode) -> bool {
+    let canonical = canonical_path(&node.path);
+    let mut changed = canonical != node.path;
+    node.path = canonical;
+    for child in &mut node.children {
+        changed |= canonicalize_tree(child);
+    }
+    changed
+}
+
+// Rewrites one path column; a row whose canonical path already exists is a
+// duplicate and is dropped (the canonical row wins).
+fn migrate_column(conn: &Connection, table: &str, column: &str) -> Result<usize, String> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare(&format!("SELECT rowid, {} FROM {} WHERE {} IS NOT NULL", column, table, column))
+            .map_err(|e| format!("Failed to read {}.{}: {}", table, column, e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to read {}.{}: {}", table, column, e))?
+            .filter_map(Result::ok)
+            .collect();
+        rows
+    };
+    let mut changed = 0;
+    for (rowid, path) in rows {
+        let canonical = canonical_path(&path);
+        if canonical == path {
+            continue;
+        }
+        let updated = conn
+            .execute(&format!("UPDATE OR IGNORE {} SET {} = ?1 WHERE rowid = ?2", table, column), params![canonical, rowid])
+            .map_err(|e| format!("Failed to update {}.{}: {}", table, column, e))?;
+        if updated == 0 {
+            conn.execute(&format!("DELETE FROM {} WHERE rowid = ?1", table), params![rowid])
+                .map_err(|e| format!("Failed to drop duplicate in {}: {}", table, e))?;
+        }
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+fn migrate_scan_trees(conn: &Connection) -> Result<usize, String> {
+    let project_ids: Vec<i32> = {
+        let mut stmt = conn
+            .prepare("SELECT project_id FROM code_context_builder_scan_results")
+            .map_err(|e| format!("Failed to list scan results: {}", e))?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to list scan results: {}", e))?
+            .filter_map(Result::ok)
+            .collect();
+        ids
+    };
+    let mut changed = 0;
+    for project_id in project_ids {
+        // A corrupt tree is left for the next scan to replace
+        let Ok(Some(mut tree)) = scan_results::load_scan_result(conn, project_id) else { continue };
+        if canonicalize_tree(&mut tree) {
+            let json = serde_json::to_string(&tree).map_err(|e| format!("Failed to serialize scan tree: {}", e))?;
+            conn.execute(
+                "UPDATE code_context_builder_scan_results SET tree_json = ?1 WHERE project_id = ?2",
+                params![json, project_id],
+            )
+            .map_err(|e| format!("Failed to update scan tree of project {}: {}", project_id, e))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Canonicalizes the paths stored by versions before `PATH_FORMAT_VERSION`, once.
+pub fn migrate_db_paths(conn: &Connection) -> Result<(), String> {
+    let version: i32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read database version: {}", e))?;
+    if version >= PATH_FORMAT_VERSION {
+        return Ok(());
+    }
+    conn.execute_batch("BEGIN").map_err(|e| format!("Path migration start failed: {}", e))?;
+    let result = (|| {
+        let mut changed = 0;
+        for (table, column) in [
+            ("code_context_builder_projects", "root_folder"),
+            ("code_context_builder_file_cache", "file_path"),
+            ("change_journal", "path"),
+            ("file_notes", "path"),
+            ("pinned_files", "path"),
+        ] {
+            changed += migrate_column(conn, table, column)?;
+        }
+        changed += migrate_scan_trees(conn)?;
+        conn.execute_batch(&format!("PRAGMA user_version = {};", PATH_FORMAT_VERSION))
+            .map_err(|e| format!("Failed to set database version: {}", e))?;
+        Ok::<usize, String>(changed)
+    })();
+    match result {
+        Ok(changed) => {
+            conn.execute_batch("COMMIT").map_err(|e| format!("Path migration commit failed: {}", e))?;
+            println!("[DB] Path migration v{}: {} path(s) canonicalized.", PATH_FORMAT_VERSION, changed);
+            Ok(())
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// src-tauri/src/path_case.rs
+// Case-sensitivity of a project's filesystem. On case-insensitive volumes
+// (Windows/macOS defaults) "Src/Foo.rs" and "src/foo.rs" are the same file, so
+// cache keys, monitor lookups and tree matching must compare them as equal.
+
+use crate::types::Project;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CaseMode {
+    #[default]
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseMode {
+    /// Resolves a project's `case_sensitivity` setting ("auto" | "sensitive" | "insensitive").
+    pub fn for_project(project: &Project) -> Self {
+        let root = project.root_folder.as_deref().map(Path::new);
+        match project.case_sensitivity.as_deref().map(str::trim) {
+            Some("sensitive") => CaseMode::Sensitive,
+            Some("insensitive") => CaseMode::Insensitive,
+            _ => root.map_or_else(platform_default, detect),
+        }
+    }
+
+    /// Comparison key for a path: lowercased when the filesystem ignores case.
+    pub fn key(self, path: &str) -> String {
+        match self {
+            CaseMode::Sensitive => path.to_string(),
+            CaseMode::Insensitive => path.to_lowercase(),
+        }
+    }
+
+    pub fn eq(self, a: &str, b: &str) -> bool {
+        match self {
+            CaseMode::Sensitive => a == b,
+            CaseMode::Insensitive => a.to_lowercase() == b.to_lowercase(),
+        }
+    }
+
+    pub fn starts_with(self, path: &str, prefix: &str) -> bool {
+        match self {
+            CaseMode::Sensitive => path.starts_with(prefix),
+            CaseMode::Insensitive => path.to_lowercase().starts_with(&prefix.to_lowercase()),
+        }
+    }
+}
+
+fn platform_default() -> CaseMode {
+    if cfg!(any(windows, target_os = "macos")) { CaseMode::Insensitive } else { CaseMode::Sensitive }
+}
+
+/// Probes the volume: if the root's name with its letter case flipped resolves
+/// to an existing path, the filesystem is case-insensitive.
+fn detect(root: &Path) -> CaseMode {
+    let name = match root.file_name().and_then(|n| n.to_str()) {
+        Some(n) if n.chars().any(|c| c.is_alphabetic()) => n,
+        _ => return platform_default(),
+    };
+    let flipped: String = name
+        .chars()
+        .map(|c| if c.is_uppercase() { c.to_lowercase().next().unwrap_or(c) } else { c.to_uppercase().next().unwrap_or(c) })
+        .collect();
+    if flipped == name {
+        return platform_default();
+    }
+    match root.parent() {
+        Some(parent) if root.exists() => {
+            if parent.join(&flipped).exists() { CaseMode::Insensitive } else { CaseMode::Sensitive }
+        }
+        _ => platform_default(),
+    }
+}
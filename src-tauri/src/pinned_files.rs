@@ -0,0 +1,177 @@
+// src-tauri/src/pinned_files.rs
+// Favorite files per project. Pins are stored separately from the scan tree and
+// flagged on trees as they're returned, so pinning never needs a rescan.
+// `pack_files_for_budget` fits a selection into a token budget, taking the
+// project's "always include" files first, then pinned files.
+
+use crate::db::AppState;
+use crate::inclusion_rules;
+use crate::model_presets;
+use crate::path_canon::canonical_path;
+use crate::scan_results;
+use crate::tree_queries::load_tree;
+use crate::types::FileNode;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PinnedFile {
+    pub path: String,
+    pub pinned_at: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PackedSelection {
+    /// Files that fit, in packing order ("always" files, then pinned unless disabled)
+    pub included: Vec<String>,
+    /// Files left out because they didn't fit
+    pub excluded: Vec<String>,
+    /// Requested paths that aren't in the last scan
+    pub unknown: Vec<String>,
+    pub total_tokens: usize,
+    pub budget: usize,
+    /// Pinned files that didn't fit even when packed first
+    pub pinned_excluded: Vec<String>,
+}
+
+/// Pins of a project, oldest first.
+pub fn load_pins(conn: &Connection, project_id: i32) -> Result<Vec<PinnedFile>, String> {
+    let mut stmt = conn
+        .prepare("SELECT path, pinned_at FROM pinned_files WHERE project_id = ?1 ORDER BY pinned_at, path")
+        .map_err(|e| format!("Prepare pinned files query failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| Ok(PinnedFile { path: row.get(0)?, pinned_at: row.get(1)? }))
+        .map_err(|e| format!("Query pinned files failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map pinned file row: {}", e))
+}
+
+fn set_flags(node: &mut FileNode, pins: &HashSet<String>) {
+    if node.is_dir {
+        for child in &mut node.children {
+            set_flags(child, pins);
+        }
+    } else {
+        node.pinned = pins.contains(&node.path);
+    }
+}
+
+/// Sets `pinned` on the tree's files from the project's pins. Best-effort: a
+/// failed lookup leaves the tree unflagged.
+pub fn mark_pinned(conn: &Connection, project_id: i32, tree: &mut FileNode) {
+    match load_pins(conn, project_id) {
+        Ok(pins) => set_flags(tree, &pins.into_iter().map(|p| p.path).collect()),
+        Err(e) => eprintln!("[PINS] {}", e),
+    }
+}
+
+// Greedy packing in order; files that don't fit are skipped so smaller ones
+// later in the list can still use the remaining budget.
+fn pack(
+    order: Vec<String>,
+    tokens: &HashMap<&str, usize>,
+    pins: &HashSet<String>,
+    budget: usize,
+) -> PackedSelection {
+    let mut packed = PackedSelection { budget, ..Default::default() };
+    for path in order {
+        let Some(&file_tokens) = tokens.get(path.as_str()) else {
+            packed.unknown.push(path);
+            continue;
+        };
+        if packed.total_tokens + file_tokens <= budget {
+            packed.total_tokens += file_tokens;
+            packed.included.push(path);
+        } else {
+            if pins.contains(&path) {
+                packed.pinned_excluded.push(path.clone());
+            }
+            packed.excluded.push(path);
+        }
+    }
+    packed
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn list_pinned_files(state: State<AppState>, project_id: i32) -> Result<Vec<PinnedFile>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_pins(&conn, project_id)
+}
+
+#[command]
+pub fn pin_file(state: State<AppState>, project_id: i32, path: String) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let path = canonical_path(&path);
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    conn.execute(
+        "INSERT OR IGNORE INTO pinned_files (project_id, path, pinned_at) VALUES (?1, ?2, ?3)",
+        params![project_id, path, Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to pin '{}': {}", path, e))?;
+    Ok(())
+}
+
+#[command]
+pub fn unpin_file(state: State<AppState>, project_id: i32, path: String) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let path = canonical_path(&path);
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let rows = conn
+        .execute("DELETE FROM pinned_files WHERE project_id = ?1 AND path = ?2", params![project_id, path])
+        .map_err(|e| format!("Failed to unpin '{}': {}", path, e))?;
+    if rows == 0 {
+        eprintln!("Warning: Attempted to unpin '{}' in project {}, but it was not pinned.", path, project_id);
+    }
+    Ok(())
+}
+
+/// Fits `paths` into a token budget using the last scan's counts. The project's
+/// inclusion rules apply first: "never" files are left out and "always" files
+/// are packed before anything else. With `pinned_first` (the default) pinned
+/// files come next, whether or not they were selected. The budget is
+/// `token_budget`, or else the input budget of the given/selected model preset.
+#[command]
+pub fn pack_files_for_budget(
+    state: State<'_, AppState>,
+    project_id: i32,
+    paths: Vec<String>,
+    token_budget: Option<usize>,
+    preset_id: Option<i64>,
+    pinned_first: Option<bool>,
+) -> Result<PackedSelection, String> {
+    let tree = load_tree(&state, project_id)?;
+    let rules = inclusion_rules::load_rules(&state, project_id, Path::new(&tree.path))?;
+    let (pins, budget) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let pins = load_pins(&conn, project_id)?;
+        let budget = match token_budget {
+            Some(budget) => budget,
+            None => model_presets::resolve_preset(&conn, preset_id)?.input_budget(),
+        };
+        (pins, budget)
+    };
+
+    let tokens: HashMap<&str, usize> = scan_results::collect_files(&tree)
+        .into_iter()
+        .map(|f| (f.path.as_str(), f.tokens))
+        .collect();
+    let pin_set: HashSet<String> = pins.iter().map(|p| p.path.clone()).collect();
+
+    let mut seen = HashSet::new();
+    let mut order = Vec::with_capacity(paths.len() + pins.len());
+    order.extend(rules.always_files(&tree).into_iter().filter(|p| seen.insert(p.clone())));
+    if pinned_first.unwrap_or(true) {
+        // Pins missing from the tree (deleted or ignored since) are skipped silently
+        let present = pins.into_iter().map(|p| p.path).filter(|p| tokens.contains_key(p.as_str()));
+        order.extend(present.filter(|p| !rules.is_never(p) && seen.insert(p.clone())));
+    }
+    order.extend(paths.iter().map(|p| canonical_path(p)).filter(|p| !rules.is_never(p) && seen.insert(p.clone())));
+
+    Ok(pack(order, &tokens, &pin_set, budget))
+}
@@ -0,0 +1,114 @@
+// src-tauri/src/portable.rs
+// Portable mode: everything the app writes goes into one folder next to the
+// executable, so the app (folder included) can be copied to a USB stick or run
+// on a machine where the user profile is locked down. It's on when a
+// `code_context_builder.portable` file sits next to the executable or the app
+// is launched with `--portable`.
+//
+// The folder holds the databases, profile and instance files (see db::db_dir),
+// the WebView's storage, cache and logs, and the process temp folder. The main
+// window is created in `setup` (`create: false` in tauri.conf.json) so its
+// WebView data directory can be set: on Windows Tauri otherwise forces it to
+// the per-user app data folder, whatever WEBVIEW2_USER_DATA_FOLDER says. The
+// tokenizer ranks are compiled into the binary, so there is no tokenizer cache
+// to move.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, App, WebviewWindowBuilder};
+
+const PORTABLE_MARKER: &str = "code_context_builder.portable";
+const PORTABLE_SWITCH: &str = "--portable";
+const DATA_FOLDER: &str = "code_context_builder_data";
+// Subfolders of the data folder
+const WEBVIEW_FOLDER: &str = "webview";
+const TEMP_FOLDER: &str = "temp";
+const MAIN_WINDOW: &str = "main";
+
+// (data folder, what turned portable mode on); None when not portable
+static PORTABLE: Lazy<Option<(PathBuf, &'static str)>> = Lazy::new(detect);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PortableMode {
+    pub enabled: bool,
+    /// "marker" (the file next to the executable) or "switch" (`--portable`)
+    pub source: Option<String>,
+    pub data_dir: Option<String>,
+}
+
+fn detect() -> Option<(PathBuf, &'static str)> {
+    let exe_dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    let source = if env::args().skip(1).any(|arg| arg == PORTABLE_SWITCH) {
+        "switch"
+    } else if exe_dir.join(PORTABLE_MARKER).is_file() {
+        "marker"
+    } else {
+        return None;
+    };
+    Some((exe_dir.join(DATA_FOLDER), source))
+}
+
+/// The data folder when running portable.
+pub fn data_dir() -> Option<&'static Path> {
+    PORTABLE.as_ref().map(|(dir, _)| dir.as_path())
+}
+
+/// Creates the data folder and points the WebView and temp files into it. Call
+/// first thing in `main`, before any thread or window exists. Does nothing when
+/// not portable.
+pub fn prepare() -> Result<(), String> {
+    let Some(dir) = data_dir() else { return Ok(()) };
+    let webview = dir.join(WEBVIEW_FOLDER);
+    let temp = dir.join(TEMP_FOLDER);
+    for folder in [dir, webview.as_path(), temp.as_path()] {
+        fs::create_dir_all(folder).map_err(|e| format!("Failed to create portable folder '{}': {}", folder.display(), e))?;
+    }
+    // WebView2 (Windows) takes its user data folder from the environment;
+    // WebKitGTK (Linux) follows the XDG base directories
+    env::set_var("WEBVIEW2_USER_DATA_FOLDER", &webview);
+    env::set_var("XDG_DATA_HOME", webview.join("data"));
+    env::set_var("XDG_CACHE_HOME", webview.join("cache"));
+    env::set_var("XDG_CONFIG_HOME", webview.join("config"));
+    for var in ["TMP", "TEMP", "TMPDIR"] {
+        env::set_var(var, &temp);
+    }
+    println!("[PORTABLE] Portable mode: all data in '{}'.", dir.display());
+    Ok(())
+}
+
+/// Creates the main window from its tauri.conf.json entry, with the WebView
+/// data in the data folder when portable. Call at the start of `setup`.
+pub fn create_main_window(app: &App) -> Result<(), String> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW)
+        .ok_or_else(|| format!("No '{}' window in tauri.conf.json", MAIN_WINDOW))?;
+    let mut builder =
+        WebviewWindowBuilder::from_config(app, config).map_err(|e| format!("Failed to configure the main window: {}", e))?;
+    if let Some(dir) = data_dir() {
+        builder = builder.data_directory(dir.join(WEBVIEW_FOLDER));
+    }
+    builder.build().map_err(|e| format!("Failed to create the main window: {}", e))?;
+    Ok(())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Whether the app runs in portable mode, and where its data folder is.
+#[command]
+pub fn get_portable_mode() -> Result<PortableMode, String> {
+    Ok(match PORTABLE.as_ref() {
+        Some((dir, source)) => PortableMode {
+            enabled: true,
+            source: Some(source.to_string()),
+            data_dir: Some(dir.to_string_lossy().to_string()),
+        },
+        None => PortableMode { enabled: false, source: None, data_dir: None },
+    })
+}
@@ -0,0 +1,117 @@
+// src-tauri/src/profiles.rs
+// Named configuration profiles (e.g. work vs personal). Each profile is its own
+// SQLite file next to the executable; switching swaps the connection inside
+// the managed AppState, so every command sees the new profile without a restart.
+
+use crate::db::{self, AppState, DEFAULT_PROFILE};
+use crate::errors::{self, app_error};
+use crate::file_monitor::MonitorState;
+use crate::scan_state::cancel_all_scans;
+use serde::Serialize;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, State};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub db_path: String,
+    pub size_bytes: u64,
+    pub active: bool,
+}
+
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid profile name '{}'; use letters, digits, '-' and '_' (max 64).", name))
+    }
+}
+
+fn profile_info(name: &str, active: &str) -> Result<ProfileInfo, String> {
+    let path = db::db_path_for_profile(name)?;
+    Ok(ProfileInfo {
+        name: name.to_string(),
+        size_bytes: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        db_path: path.to_string_lossy().to_string(),
+        active: name == active,
+    })
+}
+
+// Profile name of a database file name, if it is one of ours
+fn profile_from_file_name(file_name: &str) -> Option<String> {
+    if file_name == "code_context_builder.db" {
+        return Some(DEFAULT_PROFILE.to_string());
+    }
+    let name = file_name.strip_prefix("code_context_builder.")?.strip_suffix(".db")?;
+    validate_profile_name(name).ok().map(|_| name.to_string())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Profiles with a database file, plus the active one (which may not be created yet).
+#[command]
+pub fn list_profiles() -> Result<Vec<ProfileInfo>, String> {
+    let active = db::active_profile();
+    let dir = db::db_dir()?;
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to list '{}': {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| profile_from_file_name(&entry.file_name().to_string_lossy()))
+        .collect();
+    if !names.contains(&active) {
+        names.push(active.clone());
+    }
+    names.sort();
+    names.into_iter().map(|name| profile_info(&name, &active)).collect()
+}
+
+/// Switches every command to another profile's database. A profile that doesn't
+/// exist yet is only created when `create` is true. Monitoring stops and running
+/// scans are cancelled, since their project IDs belong to the old profile;
+/// windows get a `profile_switched` event to reload their data. A profile whose
+/// file can't be written opens read-only, like at startup.
+#[command]
+pub fn switch_profile(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    monitor_state: State<'_, Arc<Mutex<MonitorState>>>,
+    name: String,
+    create: Option<bool>,
+) -> Result<ProfileInfo, String> {
+    let name = name.trim().to_string();
+    validate_profile_name(&name)?;
+    let db_path = db::db_path_for_profile(&name)?;
+    if !db_path.exists() && !create.unwrap_or(false) {
+        return Err(app_error(errors::PROFILE_NOT_FOUND, &[("name", name)]));
+    }
+
+    let (new_conn, read_only_reason) = db::open_database(&db_path)?;
+    crate::seed_defaults(&new_conn);
+
+    cancel_all_scans();
+    {
+        let mut monitor = monitor_state.lock().map_err(|e| format!("Failed to lock monitor state: {}", e))?;
+        monitor.current_project_id = None;
+        monitor.monitored_files.clear();
+        monitor.journaled_files.clear();
+    }
+    {
+        let mut conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        *conn = new_conn; // The old connection closes here
+    }
+    {
+        let mut reason = state.read_only_reason.lock().map_err(|e| format!("DB mode lock failed: {}", e))?;
+        *reason = read_only_reason;
+    }
+    db::set_active_profile(&name)?;
+    println!("[PROFILES] Switched to profile '{}' ({}).", name, db_path.display());
+
+    if let Err(e) = app_handle.emit("profile_switched", &name) {
+        eprintln!("[PROFILES] Failed to emit profile_switched: {}", e);
+    }
+    profile_info(&name, &name)
+}
@@ -0,0 +1,308 @@
+// src-tauri/src/project_health.rs
+// `check_project_health`: a pre-scan report of everything that commonly makes a
+// scan fail or surprise: a missing root, ignore patterns that don't compile, a
+// stale cache, the state of .gitignore files, and the size of what a scan
+// would pick up. Nothing is written; the enumeration is the scan's own.
+
+use crate::app_settings;
+use crate::db::AppState;
+use crate::dir_ignore_rules;
+use crate::errors::{self, app_error};
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::path_case::CaseMode;
+use crate::projects;
+use crate::scan_cache;
+use crate::scan_tree::gather_valid_items;
+use crate::scanner::MAX_FILE_SIZE_BYTES;
+use chrono::{DateTime, Utc};
+use ignore::gitignore::GitignoreBuilder;
+use rayon::prelude::*;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+const DEFAULT_STALE_AFTER_HOURS: i64 = 24 * 7;
+// Uncached files are estimated at this many bytes per token
+const BYTES_PER_TOKEN_ESTIMATE: u64 = 4;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthIssue {
+    /// "error" (the scan will fail) or "warning"
+    pub severity: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct InvalidPattern {
+    pub pattern: String,
+    /// "project" or "global"
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CacheFreshness {
+    pub last_scanned_at: Option<String>,
+    pub age_hours: Option<i64>,
+    pub stale_after_hours: i64,
+    pub stale: bool,
+    pub cached_rows: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GitignoreStatus {
+    pub is_git_repository: bool,
+    pub root_gitignore: bool,
+    pub root_gitignore_patterns: usize,
+    /// .gitignore files below the root (among the folders a scan would enter)
+    pub nested_gitignores: usize,
+    /// Whether scans apply .gitignore files (the project's `respect_gitignore` flag)
+    pub applied_by_scanner: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanEstimate {
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub total_bytes: u64,
+    /// Over the per-file size limit; listed but not counted
+    pub oversized_files: usize,
+    /// Cached token counts plus a bytes-based guess for uncached files
+    pub estimated_tokens: usize,
+    pub cached_files: usize,
+    /// Folders at the depth limit whose contents a scan would leave out
+    pub truncated_subtrees: usize,
+    /// Folders and files the walk couldn't read (permissions, I/O errors)
+    pub unreadable_entries: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProjectHealthReport {
+    pub project_id: i32,
+    pub root_folder: Option<String>,
+    /// "ok", "warning" or "error": the worst issue's severity
+    pub status: String,
+    pub issues: Vec<HealthIssue>,
+    pub root_exists: bool,
+    pub root_readable: bool,
+    pub invalid_patterns: Vec<InvalidPattern>,
+    pub cache: CacheFreshness,
+    pub gitignore: GitignoreStatus,
+    /// None when the root can't be enumerated
+    pub estimate: Option<ScanEstimate>,
+}
+
+fn issue(severity: &str, code: &str, message: String) -> HealthIssue {
+    HealthIssue { severity: severity.to_string(), code: code.to_string(), message }
+}
+
+// Each non-comment line compiled on its own, so every bad pattern is reported
+fn invalid_patterns(root: &Path, patterns: &[String], source: &str) -> Vec<InvalidPattern> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty() && !p.starts_with('#'))
+        .filter_map(|pattern| {
+            let mut builder = GitignoreBuilder::new(root);
+            let error = match builder.add_line(None, pattern) {
+                Err(e) => e.to_string(),
+                Ok(_) => builder.build().err()?.to_string(),
+            };
+            Some(InvalidPattern { pattern: pattern.to_string(), source: source.to_string(), error })
+        })
+        .collect()
+}
+
+fn gitignore_status(root: &Path, dirs: &HashSet<PathBuf>, applied: bool) -> GitignoreStatus {
+    let root_gitignore = root.join(".gitignore");
+    let root_gitignore_patterns = fs::read_to_string(&root_gitignore)
+        .map(|content| content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')).count())
+        .unwrap_or(0);
+    GitignoreStatus {
+        is_git_repository: root.join(".git").exists(),
+        root_gitignore: root_gitignore.is_file(),
+        root_gitignore_patterns,
+        nested_gitignores: dirs.iter().filter(|d| d.as_path() != root && d.join(".gitignore").is_file()).count(),
+        applied_by_scanner: applied,
+    }
+}
+
+fn estimate_scan(files: &[PathBuf], dir_count: usize, cache: &HashMap<String, scan_cache::CacheEntry>) -> ScanEstimate {
+    let (total_bytes, oversized_files, estimated_tokens, cached_files) = files
+        .par_iter()
+        .filter_map(|p| {
+            let size = fs::metadata(p).ok()?.len();
+            if size > MAX_FILE_SIZE_BYTES {
+                return Some((size, 1, 0, 0));
+            }
+            let path = p.to_string_lossy();
+            Some(match cache.get(path.as_ref()).filter(|e| e.size == size) {
+                Some(entry) => (size, 0, entry.tokens, 1),
+                None => (size, 0, (size / BYTES_PER_TOKEN_ESTIMATE) as usize, 0),
+            })
+        })
+        .reduce(|| (0, 0, 0, 0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3));
+    ScanEstimate { file_count: files.len(), dir_count, total_bytes, oversized_files, estimated_tokens, cached_files, truncated_subtrees: 0, unreadable_entries: 0 }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Checks a project before scanning: root folder, ignore patterns, cache age
+/// (stale after `stale_after_hours`, default one week), .gitignore files and the
+/// estimated scan size.
+#[command(async)]
+pub async fn check_project_health(
+    state: State<'_, AppState>,
+    project_id: i32,
+    stale_after_hours: Option<i64>,
+) -> Result<ProjectHealthReport, String> {
+    let stale_after_hours = stale_after_hours.unwrap_or(DEFAULT_STALE_AFTER_HOURS).max(0);
+    let (project, global_patterns, scanned_at, cache, dir_rules) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let project = projects::load_project_by_id(&conn, project_id)
+            .map_err(|_| app_error(errors::PROJECT_NOT_FOUND, &[("project_id", project_id.to_string())]))?;
+        let global_patterns: Vec<String> = app_settings::get_setting_internal(&conn, "default_ignore_patterns")
+            .map_err(|e| format!("Failed to query default_ignore_patterns from app_settings: {}", e))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let scanned_at: Option<String> = conn
+            .query_row(
+                "SELECT scanned_at FROM code_context_builder_scan_results WHERE project_id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query last scan of project {}: {}", project_id, e))?;
+        let cache = match project.root_folder.as_deref() {
+            Some(root) => scan_cache::load_cache_entries(&conn, root, CaseMode::for_project(&project))?,
+            None => Default::default(),
+        };
+        let dir_rules = dir_ignore_rules::load_dir_rules(&conn, project_id)?;
+        (project, global_patterns, scanned_at, cache, dir_rules)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut issues = Vec::new();
+        let respect_gitignore = project.respect_gitignore.unwrap_or(false);
+        let root = project.root_folder.clone().map(PathBuf::from);
+        let root_exists = root.as_ref().is_some_and(|r| r.is_dir());
+        let root_readable = root_exists && root.as_ref().is_some_and(|r| fs::read_dir(r).is_ok());
+        match (&project.root_folder, root_exists, root_readable) {
+            (None, _, _) => issues.push(issue("error", errors::PROJECT_NO_ROOT, "The project has no root folder.".to_string())),
+            (Some(r), false, _) => {
+                issues.push(issue("error", errors::ROOT_NOT_DIRECTORY, format!("Root folder '{}' doesn't exist or isn't a folder.", r)))
+            }
+            (Some(r), true, false) => {
+                issues.push(issue("error", "root_unreadable", format!("Root folder '{}' can't be read.", r)))
+            }
+            _ => (),
+        }
+
+        let pattern_root = root.clone().unwrap_or_default();
+        let mut invalid = invalid_patterns(&pattern_root, &project.ignore_patterns, "project");
+        invalid.extend(invalid_patterns(&pattern_root, &global_patterns, "global"));
+        if !invalid.is_empty() {
+            issues.push(issue(
+                "warning",
+                "invalid_ignore_patterns",
+                format!("{} ignore pattern(s) don't compile and will be skipped.", invalid.len()),
+            ));
+        }
+
+        let age_hours = scanned_at
+            .as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| (Utc::now() - at.with_timezone(&Utc)).num_hours());
+        let cache_freshness = CacheFreshness {
+            stale: age_hours.is_some_and(|age| age > stale_after_hours),
+            last_scanned_at: scanned_at,
+            age_hours,
+            stale_after_hours,
+            cached_rows: cache.len(),
+        };
+        match cache_freshness.age_hours {
+            None => issues.push(issue("warning", errors::NO_SCAN_RESULT, "The project hasn't been scanned yet.".to_string())),
+            Some(age) if cache_freshness.stale => issues.push(issue(
+                "warning",
+                "stale_cache",
+                format!("The last scan is {} hours old (threshold {} hours).", age, stale_after_hours),
+            )),
+            _ => (),
+        }
+
+        let (gitignore, estimate) = match root.as_ref().filter(|_| root_readable) {
+            Some(root) => {
+                let mut patterns = global_patterns.clone();
+                patterns.extend_from_slice(&project.ignore_patterns);
+                let mut compiled = CompiledIgnorePatterns::new(root, &patterns);
+                dir_ignore_rules::apply_dir_rules(&mut compiled, &dir_rules);
+                if respect_gitignore {
+                    compiled.add_repository_gitignore(root);
+                }
+                compiled.set_follow_symlinks(project.follow_symlinks.unwrap_or(false));
+                compiled.set_max_depth(project.max_scan_depth);
+                compiled.set_include_hidden(project.include_hidden.unwrap_or(true));
+                compiled.set_include_patterns(project.include_patterns.as_deref().unwrap_or_default());
+                let mut paths = Vec::new();
+                let mut dirs: HashSet<PathBuf> = HashSet::new();
+                let mut unreadable = Vec::new();
+                let truncated = gather_valid_items(root, &compiled, &mut paths, &mut dirs, &mut unreadable, &mut Vec::new(), 0);
+                let files: Vec<PathBuf> = paths.into_iter().filter(|p| !dirs.contains(p)).collect();
+                let mut estimate = estimate_scan(&files, dirs.len(), &cache);
+                estimate.truncated_subtrees = truncated.len();
+                estimate.unreadable_entries = unreadable.len();
+                (gitignore_status(root, &dirs, respect_gitignore), Some(estimate))
+            }
+            None => (GitignoreStatus::default(), None),
+        };
+        if gitignore.root_gitignore && !gitignore.applied_by_scanner {
+            issues.push(issue(
+                "warning",
+                "gitignore_not_applied",
+                "The project has a .gitignore, but scans only apply the configured ignore patterns. Enable 'respect .gitignore' to use it.".to_string(),
+            ));
+        }
+
+        if let Some(truncated) = estimate.as_ref().map(|e| e.truncated_subtrees).filter(|t| *t > 0) {
+            issues.push(issue(
+                "warning",
+                "depth_limit_reached",
+                format!("{} folder(s) are deeper than the scan depth limit; their contents won't be scanned.", truncated),
+            ));
+        }
+
+        if let Some(unreadable) = estimate.as_ref().map(|e| e.unreadable_entries).filter(|u| *u > 0) {
+            issues.push(issue(
+                "warning",
+                "unreadable_entries",
+                format!("{} folder(s) or file(s) can't be read; a scan will leave their contents out.", unreadable),
+            ));
+        }
+
+        let status = if issues.iter().any(|i| i.severity == "error") {
+            "error"
+        } else if issues.is_empty() {
+            "ok"
+        } else {
+            "warning"
+        };
+        ProjectHealthReport {
+            project_id,
+            root_folder: project.root_folder.clone(),
+            status: status.to_string(),
+            issues,
+            root_exists,
+            root_readable,
+            invalid_patterns: invalid,
+            cache: cache_freshness,
+            gitignore,
+            estimate,
+        }
+    })
+    .await
+    .map_err(|e| format!("Health check task failed: {}", e))
+}
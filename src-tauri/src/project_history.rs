@@ -0,0 +1,180 @@
+// src-tauri/src/project_history.rs
+// Audit log of project configuration changes (ignore patterns, root folder,
+// prefix, ...), so a jump in token counts can be traced back to the edit
+// that caused it.
+
+use crate::db::AppState;
+use crate::types::Project;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectChange {
+    pub id: i64,
+    pub project_id: i32,
+    pub changed_at: String,
+    /// "created", "title", "root_folder", "ignore_patterns", "prefix", "fs_mode", "case_sensitivity", "respect_gitignore", "follow_symlinks", "max_scan_depth", "include_hidden", "min_file_size", "max_file_size", "max_file_age_days" or "include_patterns"
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// Human-readable description, e.g. "+dist/, -*.log"
+    pub summary: String,
+}
+
+fn insert_change(
+    conn: &Connection,
+    project_id: i32,
+    field: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    summary: &str,
+) -> Result<(), String> {
+    conn.execute(
+        r#"
+        INSERT INTO project_history (project_id, changed_at, field, old_value, new_value, summary)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![project_id, Utc::now().to_rfc3339(), field, old_value, new_value, summary],
+    )
+    .map_err(|e| format!("Failed to record change of '{}' for project {}: {}", field, project_id, e))?;
+    Ok(())
+}
+
+pub fn record_created(conn: &Connection, project_id: i32, project: &Project) -> Result<(), String> {
+    let summary = format!(
+        "Created '{}' at {}",
+        project.title,
+        project.root_folder.as_deref().unwrap_or("(no root folder)")
+    );
+    insert_change(conn, project_id, "created", None, project.root_folder.as_deref(), &summary)
+}
+
+fn pattern_summary(old: &[String], new: &[String]) -> String {
+    let old_set: BTreeSet<&String> = old.iter().collect();
+    let new_set: BTreeSet<&String> = new.iter().collect();
+    let added = new_set.difference(&old_set).map(|p| format!("+{}", p));
+    let removed = old_set.difference(&new_set).map(|p| format!("-{}", p));
+    let parts: Vec<String> = added.chain(removed).collect();
+    if parts.is_empty() {
+        "Reordered patterns".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Logs every configuration field that differs between `old` and the saved `new`.
+/// Optional fields left out of `new` keep their stored value and aren't changes.
+pub fn record_changes(conn: &Connection, old: &Project, new: &Project) -> Result<(), String> {
+    let id = old.id;
+    let scalar = |field: &str, before: Option<&str>, after: Option<&str>| -> Result<(), String> {
+        if before == after {
+            return Ok(());
+        }
+        let summary = format!("{} -> {}", before.unwrap_or("(none)"), after.unwrap_or("(none)"));
+        insert_change(conn, id, field, before, after, &summary)
+    };
+    scalar("title", Some(&old.title), Some(&new.title))?;
+    scalar("root_folder", old.root_folder.as_deref(), new.root_folder.as_deref())?;
+    scalar("prefix", Some(&old.prefix), Some(&new.prefix))?;
+    if new.fs_mode.is_some() {
+        scalar("fs_mode", old.fs_mode.as_deref(), new.fs_mode.as_deref())?;
+    }
+    if new.case_sensitivity.is_some() {
+        scalar("case_sensitivity", old.case_sensitivity.as_deref(), new.case_sensitivity.as_deref())?;
+    }
+    if new.respect_gitignore.is_some() {
+        let flag = |value: Option<bool>| value.map(|v| v.to_string());
+        scalar("respect_gitignore", flag(old.respect_gitignore).as_deref(), flag(new.respect_gitignore).as_deref())?;
+    }
+    if new.follow_symlinks.is_some() {
+        let flag = |value: Option<bool>| value.map(|v| v.to_string());
+        scalar("follow_symlinks", flag(old.follow_symlinks).as_deref(), flag(new.follow_symlinks).as_deref())?;
+    }
+    if new.max_scan_depth.is_some() {
+        let depth = |value: Option<usize>| value.map(|v| v.to_string());
+        scalar("max_scan_depth", depth(old.max_scan_depth).as_deref(), depth(new.max_scan_depth).as_deref())?;
+    }
+    if new.include_hidden.is_some() {
+        let flag = |value: Option<bool>| value.map(|v| v.to_string());
+        scalar("include_hidden", flag(old.include_hidden).as_deref(), flag(new.include_hidden).as_deref())?;
+    }
+    let number = |value: Option<u64>| value.map(|v| v.to_string());
+    for (field, old_value, new_value) in [
+        ("min_file_size", old.min_file_size, new.min_file_size),
+        ("max_file_size", old.max_file_size, new.max_file_size),
+        ("max_file_age_days", old.max_file_age_days, new.max_file_age_days),
+    ] {
+        if new_value.is_some() {
+            scalar(field, number(old_value).as_deref(), number(new_value).as_deref())?;
+        }
+    }
+
+    if old.ignore_patterns != new.ignore_patterns {
+        let to_json = |patterns: &[String]| serde_json::to_string(patterns).unwrap_or_default();
+        insert_change(
+            conn,
+            id,
+            "ignore_patterns",
+            Some(&to_json(&old.ignore_patterns)),
+            Some(&to_json(&new.ignore_patterns)),
+            &pattern_summary(&old.ignore_patterns, &new.ignore_patterns),
+        )?;
+    }
+    if let Some(new_includes) = &new.include_patterns {
+        let old_includes = old.include_patterns.clone().unwrap_or_default();
+        if &old_includes != new_includes {
+            let to_json = |patterns: &[String]| serde_json::to_string(patterns).unwrap_or_default();
+            insert_change(
+                conn,
+                id,
+                "include_patterns",
+                Some(&to_json(&old_includes)),
+                Some(&to_json(new_includes)),
+                &pattern_summary(&old_includes, new_includes),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Configuration changes of a project, newest first.
+#[command]
+pub fn get_project_history(
+    state: State<AppState>,
+    project_id: i32,
+    limit: Option<usize>,
+) -> Result<Vec<ProjectChange>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, project_id, changed_at, field, old_value, new_value, summary
+            FROM project_history
+            WHERE project_id = ?1
+            ORDER BY changed_at DESC, id DESC
+            LIMIT ?2
+            "#,
+        )
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let limit = limit.map_or(-1, |l| l as i64); // SQLite: negative LIMIT = no limit
+    let rows = stmt
+        .query_map(params![project_id, limit], |row| {
+            Ok(ProjectChange {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                changed_at: row.get(2)?,
+                field: row.get(3)?,
+                old_value: row.get(4)?,
+                new_value: row.get(5)?,
+                summary: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Query project history failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map project history row: {}", e))
+}
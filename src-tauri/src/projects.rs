@@ -2,13 +2,31 @@
 
 // ... (other use statements and map_row_to_project function) ...
 use crate::db::AppState;
+use crate::errors::{self, app_error};
+use crate::path_canon::canonical_opt;
+use crate::project_history;
 use crate::types::Project;
 // REMOVE: use crate::app_settings; // No longer needed here for default pattern fetching during save
 use chrono::Utc;
 use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult, Transaction};
+use serde::Serialize;
 use serde_json;
 use tauri::{command, State};
 
+/// Days a deleted project stays restorable before it's purged for good.
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DeletedProject {
+    pub project: Project,
+    pub deleted_at: String,
+    /// When the project will be purged
+    pub purge_after: String,
+}
+
+
+// Column list shared by every project SELECT; map_row_to_project reads by these indices
+const PROJECT_COLUMNS: &str = "id, title, root_folder, ignore_patterns, updated_at, prefix, fs_mode, case_sensitivity, respect_gitignore, follow_symlinks, max_scan_depth, include_hidden, min_file_size, max_file_size, max_file_age_days, include_patterns";
 
 // Helper function to map a database row to a Project struct
 // Adjust column indices based on the SELECT query
@@ -19,36 +37,52 @@ fn map_row_to_project(row: &rusqlite::Row<'_>) -> SqlResult<Project> {
     let ignore_json: String = row.get(3)?;
     let updated_at: Option<String> = row.get(4)?; 
     let prefix: Option<String> = row.get(5)?; 
+    let fs_mode: Option<String> = row.get(6)?;
+    let case_sensitivity: Option<String> = row.get(7)?;
+    let respect_gitignore: Option<bool> = row.get(8)?;
+    let follow_symlinks: Option<bool> = row.get(9)?;
+    let max_scan_depth: Option<i64> = row.get(10)?;
+    let include_hidden: Option<bool> = row.get(11)?;
+    let min_file_size: Option<i64> = row.get(12)?;
+    let max_file_size: Option<i64> = row.get(13)?;
+    let max_file_age_days: Option<i64> = row.get(14)?;
+    let include_json: Option<String> = row.get(15)?;
 
     let ignore_patterns: Vec<String> = serde_json::from_str(&ignore_json).unwrap_or_default();
+    let include_patterns: Vec<String> = include_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
 
     Ok(Project {
         id,
         title,
-        root_folder,
+        root_folder: canonical_opt(root_folder.as_deref()),
         ignore_patterns,
         updated_at,
         prefix: prefix.unwrap_or_default(), 
+        fs_mode,
+        case_sensitivity,
+        respect_gitignore,
+        follow_symlinks,
+        max_scan_depth: max_scan_depth.map(|d| d.max(0) as usize),
+        include_hidden,
+        min_file_size: min_file_size.map(|s| s.max(0) as u64),
+        max_file_size: max_file_size.map(|s| s.max(0) as u64),
+        max_file_age_days: max_file_age_days.map(|d| d.max(0) as u64),
+        include_patterns: Some(include_patterns),
     })
 }
 
-// --- Exposed Tauri Commands ---
-
-
-#[command]
-pub fn list_code_context_builder_projects(state: State<AppState>) -> Result<Vec<Project>, String> {
-    // ... (this function remains the same) ...
-    let conn_guard = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
-    let conn = &*conn_guard; 
-
+/// Every project, ordered by title.
+pub fn load_all_projects(conn: &Connection) -> Result<Vec<Project>, String> {
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             r#"
-            SELECT id, title, root_folder, ignore_patterns, updated_at, prefix
+            SELECT {}
             FROM code_context_builder_projects
+            WHERE deleted_at IS NULL
             ORDER BY title COLLATE NOCASE
             "#,
-        )
+            PROJECT_COLUMNS
+        ))
         .map_err(|e| format!("Prepare statement failed: {}", e))?;
 
     let project_iter = stmt
@@ -65,15 +99,34 @@ pub fn list_code_context_builder_projects(state: State<AppState>) -> Result<Vec<
     Ok(projects)
 }
 
+// --- Exposed Tauri Commands ---
+
+
+#[command]
+pub fn list_code_context_builder_projects(state: State<AppState>) -> Result<Vec<Project>, String> {
+    let conn_guard = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    load_all_projects(&conn_guard)
+}
+
 #[command]
 pub fn save_code_context_builder_project(
     state: State<AppState>,
-    project: Project, // Project object from frontend
+    mut project: Project, // Project object from frontend
 ) -> Result<i32, String> {
+    crate::db::ensure_writable(&state)?;
+    // Dialog and typed paths vary (trailing slashes, `..`, 8.3 names); store one form
+    project.root_folder = canonical_opt(project.root_folder.as_deref());
     let conn_guard = state.conn.lock().map_err(|e| format!("DB lock failed for save: {}", e))?;
     let conn = &*conn_guard;
     let now = Utc::now().to_rfc3339();
     let prefix_val = project.prefix.clone();
+    // None leaves the stored include patterns as they are
+    let include_patterns_json = project
+        .include_patterns
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("Failed to serialize include_patterns: {}", e))?;
 
     if project.id <= 0 {
         // --- Create new project ---
@@ -87,19 +140,35 @@ pub fn save_code_context_builder_project(
         let result = conn.execute(
             r#"
             INSERT INTO code_context_builder_projects
-                (title, root_folder, ignore_patterns, updated_at, prefix)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+                (title, root_folder, ignore_patterns, updated_at, prefix, fs_mode, case_sensitivity, respect_gitignore, follow_symlinks, max_scan_depth, include_hidden, min_file_size, max_file_size, max_file_age_days, include_patterns)
+            VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, 'auto'), COALESCE(?7, 'auto'), COALESCE(?8, 0), COALESCE(?9, 0), COALESCE(?10, 30), COALESCE(?11, 1), COALESCE(?12, 0), COALESCE(?13, 0), COALESCE(?14, 0), COALESCE(?15, '[]'))
             "#,
             params![
                 project.title,
                 project.root_folder,
                 project_specific_ignore_patterns_json, // Store only project-specific patterns
                 now,
-                prefix_val
+                prefix_val,
+                project.fs_mode,
+                project.case_sensitivity,
+                project.respect_gitignore,
+                project.follow_symlinks,
+                project.max_scan_depth.map(|d| d as i64),
+                project.include_hidden,
+                project.min_file_size.map(|s| s as i64),
+                project.max_file_size.map(|s| s as i64),
+                project.max_file_age_days.map(|d| d as i64),
+                include_patterns_json
             ],
         );
         match result {
-            Ok(_) => Ok(conn.last_insert_rowid() as i32),
+            Ok(_) => {
+                let new_id = conn.last_insert_rowid() as i32;
+                if let Err(e) = project_history::record_created(conn, new_id, &project) {
+                    eprintln!("[PROJECTS] {}", e); // History is best-effort; the save succeeded
+                }
+                Ok(new_id)
+            }
             Err(e) => Err(format!("Failed to insert new project: {}", e)),
         }
     } else {
@@ -108,12 +177,23 @@ pub fn save_code_context_builder_project(
         // as edited by the user.
         let project_specific_ignore_patterns_json = serde_json::to_string(&project.ignore_patterns)
             .map_err(|e| format!("Failed to serialize project-specific ignore_patterns: {}", e))?;
+        let previous = load_project_by_id(conn, project.id).ok(); // For the history log
 
         let result = conn.execute(
             r#"
             UPDATE code_context_builder_projects
-            SET title = ?1, root_folder = ?2, ignore_patterns = ?3, updated_at = ?4, prefix = ?5
-            WHERE id = ?6
+            SET title = ?1, root_folder = ?2, ignore_patterns = ?3, updated_at = ?4, prefix = ?5,
+                fs_mode = COALESCE(?7, fs_mode),
+                case_sensitivity = COALESCE(?8, case_sensitivity),
+                respect_gitignore = COALESCE(?9, respect_gitignore),
+                follow_symlinks = COALESCE(?10, follow_symlinks),
+                max_scan_depth = COALESCE(?11, max_scan_depth),
+                include_hidden = COALESCE(?12, include_hidden),
+                min_file_size = COALESCE(?13, min_file_size),
+                max_file_size = COALESCE(?14, max_file_size),
+                max_file_age_days = COALESCE(?15, max_file_age_days),
+                include_patterns = COALESCE(?16, include_patterns)
+            WHERE id = ?6 AND deleted_at IS NULL
             "#,
             params![
                 project.title,
@@ -121,7 +201,17 @@ pub fn save_code_context_builder_project(
                 project_specific_ignore_patterns_json, // Store only project-specific patterns
                 now,
                 prefix_val,
-                project.id
+                project.id,
+                project.fs_mode,
+                project.case_sensitivity,
+                project.respect_gitignore,
+                project.follow_symlinks,
+                project.max_scan_depth.map(|d| d as i64),
+                project.include_hidden,
+                project.min_file_size.map(|s| s as i64),
+                project.max_file_size.map(|s| s as i64),
+                project.max_file_age_days.map(|d| d as i64),
+                include_patterns_json
             ],
         );
          match result {
@@ -129,6 +219,11 @@ pub fn save_code_context_builder_project(
                  if rows_affected == 0 {
                      Err(format!("Failed to update project: ID {} not found.", project.id))
                  } else {
+                     if let Some(previous) = previous {
+                         if let Err(e) = project_history::record_changes(conn, &previous, &project) {
+                             eprintln!("[PROJECTS] {}", e);
+                         }
+                     }
                      Ok(project.id)
                  }
              },
@@ -137,17 +232,19 @@ pub fn save_code_context_builder_project(
     }
 }
 
+// Moves the project to the trash; it (and its notes, pins, history...) stays
+// restorable for TRASH_RETENTION_DAYS before purge_deleted_projects removes it.
 #[command]
 pub fn delete_code_context_builder_project(
     state: State<AppState>,
     project_id: i32,
 ) -> Result<(), String> {
-    // ... (this function remains the same) ...
+    crate::db::ensure_writable(&state)?;
     let conn = state.conn.lock().map_err(|e| format!("DB lock failed for delete: {}", e))?;
 
     let rows_affected = conn.execute(
-            "DELETE FROM code_context_builder_projects WHERE id = ?1",
-             params![project_id]
+            "UPDATE code_context_builder_projects SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+             params![project_id, Utc::now().to_rfc3339()]
         )
         .map_err(|e| format!("Failed to execute delete for project ID {}: {}", project_id, e))?;
 
@@ -159,23 +256,96 @@ pub fn delete_code_context_builder_project(
     Ok(())
 }
 
+#[command]
+pub fn list_deleted_projects(state: State<AppState>) -> Result<Vec<DeletedProject>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, deleted_at FROM code_context_builder_projects WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+            PROJECT_COLUMNS
+        ))
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((map_row_to_project(row)?, row.get::<_, String>("deleted_at")?)))
+        .map_err(|e| format!("Query deleted projects failed: {}", e))?;
+    let mut deleted = Vec::new();
+    for row in rows {
+        let (project, deleted_at) = row.map_err(|e| format!("Failed to map project row: {}", e))?;
+        let purge_after = chrono::DateTime::parse_from_rfc3339(&deleted_at)
+            .map(|at| (at + chrono::Duration::days(TRASH_RETENTION_DAYS)).to_rfc3339())
+            .unwrap_or_default();
+        deleted.push(DeletedProject { project, deleted_at, purge_after });
+    }
+    Ok(deleted)
+}
+
+#[command]
+pub fn restore_deleted_project(state: State<AppState>, project_id: i32) -> Result<Project, String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let restored = conn
+        .execute(
+            "UPDATE code_context_builder_projects SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![project_id],
+        )
+        .map_err(|e| format!("Failed to restore project ID {}: {}", project_id, e))?;
+    if restored == 0 {
+        return Err(format!("Project {} isn't in the trash (it may have been purged).", project_id));
+    }
+    println!("[PROJECTS] Restored project {} from the trash.", project_id);
+    load_project_by_id(&conn, project_id)
+}
+
+/// Permanently removes trashed projects deleted more than `older_than_days`
+/// days ago (default: the retention window; 0 empties the trash). Returns how
+/// many were removed.
+#[command]
+pub fn purge_deleted_projects(state: State<AppState>, older_than_days: Option<i64>) -> Result<usize, String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    purge_projects_deleted_before(&conn, older_than_days.unwrap_or(TRASH_RETENTION_DAYS).max(0))
+}
+
 // --- Internal Helper Functions ---
+
+// Dependent rows (notes, pins, history...) go with the project via ON DELETE CASCADE
+fn purge_projects_deleted_before(conn: &Connection, days: i64) -> Result<usize, String> {
+    let cutoff = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+    let purged = conn
+        .execute(
+            "DELETE FROM code_context_builder_projects WHERE deleted_at IS NOT NULL AND deleted_at <= ?1",
+            params![cutoff],
+        )
+        .map_err(|e| format!("Failed to purge deleted projects: {}", e))?;
+    if purged > 0 {
+        println!("[PROJECTS] Purged {} deleted project(s).", purged);
+    }
+    Ok(purged)
+}
+
+/// Purges projects past the trash retention window; run at startup.
+pub fn purge_expired_projects(conn: &Connection) -> Result<usize, String> {
+    purge_projects_deleted_before(conn, TRASH_RETENTION_DAYS)
+}
+
+/// A project that isn't in the trash.
 pub fn load_project_by_id(conn: &Connection, project_id: i32) -> Result<Project, String> {
     // ... (this function remains the same, it loads the project including its specific ignores) ...
      let mut stmt = conn
-         .prepare(
+         .prepare(&format!(
               r#"
-              SELECT id, title, root_folder, ignore_patterns, updated_at, prefix
+              SELECT {}
               FROM code_context_builder_projects
-              WHERE id = ?1
+              WHERE id = ?1 AND deleted_at IS NULL
               "#,
-          )
+              PROJECT_COLUMNS
+          ))
           .map_err(|e| format!("Failed to prepare statement for project ID {}: {}", project_id, e))?;
 
       stmt.query_row(params![project_id], map_row_to_project)
           .optional() 
           .map_err(|e| format!("Failed to query project ID {}: {}", project_id, e))?
-          .ok_or_else(|| format!("Project with ID {} not found.", project_id)) 
+          .ok_or_else(|| app_error(errors::PROJECT_NOT_FOUND, &[("project_id", project_id.to_string())])) 
 }
 
 // rename_project_prefix function remains the same (and unused currently)
@@ -0,0 +1,195 @@
+// src-tauri/src/prompt_templates.rs
+// Reusable prompt snippets (review, refactor, explain, ...) that the context
+// builder prepends or appends to a built context. Bodies may contain
+// `{{placeholder}}` variables such as `{{language}}` or `{{file_count}}`.
+
+use crate::db::AppState;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptTemplate {
+    pub id: i64,
+    pub name: String,
+    pub body: String,
+    /// Where the rendered snippet goes relative to the context: "prepend" or "append".
+    pub position: String,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+const TEMPLATE_COLUMNS: &str = "id, name, body, position, updated_at";
+
+// Templates created the first time the table is empty
+const DEFAULT_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "Review",
+        "Review the following {{language}} code ({{file_count}} files). Point out bugs, risky patterns and missing error handling, most important first.",
+        "prepend",
+    ),
+    (
+        "Refactor",
+        "Suggest refactorings for the {{language}} code below that improve readability and structure without changing behavior. Show the changed code.",
+        "prepend",
+    ),
+    (
+        "Explain",
+        "Explain what the following code does, how the {{file_count}} files fit together, and any non-obvious design decisions.",
+        "prepend",
+    ),
+    (
+        "Write tests",
+        "Write unit tests for the code above using the project's existing test conventions. Cover edge cases and error paths.",
+        "append",
+    ),
+];
+
+fn map_row_to_template(row: &rusqlite::Row<'_>) -> rusqlite::Result<PromptTemplate> {
+    Ok(PromptTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        body: row.get(2)?,
+        position: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn validate_position(position: &str) -> Result<(), String> {
+    match position {
+        "prepend" | "append" => Ok(()),
+        other => Err(format!("Invalid template position '{}'; use 'prepend' or 'append'.", other)),
+    }
+}
+
+/// Inserts the built-in templates when the table has none yet.
+pub fn seed_default_templates(conn: &Connection) -> Result<(), String> {
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM prompt_templates", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to count prompt templates: {}", e))?;
+    if count > 0 {
+        return Ok(());
+    }
+    let now = Utc::now().to_rfc3339();
+    for (name, body, position) in DEFAULT_TEMPLATES {
+        conn.execute(
+            "INSERT INTO prompt_templates (name, body, position, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![name, body, position, now],
+        )
+        .map_err(|e| format!("Failed to seed prompt template '{}': {}", name, e))?;
+    }
+    println!("[SETUP] Seeded {} default prompt templates.", DEFAULT_TEMPLATES.len());
+    Ok(())
+}
+
+/// Replaces `{{name}}` placeholders (whitespace inside the braces is allowed).
+/// Placeholders without a value are left as-is so they stay visible.
+pub fn render_template(body: &str, variables: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match variables.get(key) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn load_template(conn: &Connection, template_id: i64) -> Result<PromptTemplate, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM prompt_templates WHERE id = ?1", TEMPLATE_COLUMNS),
+        params![template_id],
+        map_row_to_template,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to query prompt template {}: {}", template_id, e))?
+    .ok_or_else(|| format!("Prompt template with ID {} not found.", template_id))
+}
+
+// --- Exposed Tauri Commands ---
+
+#[command]
+pub fn list_prompt_templates(state: State<AppState>) -> Result<Vec<PromptTemplate>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM prompt_templates ORDER BY name COLLATE NOCASE", TEMPLATE_COLUMNS))
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map([], map_row_to_template)
+        .map_err(|e| format!("Query prompt templates failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map prompt template row: {}", e))
+}
+
+/// Creates a template when `template.id <= 0`, otherwise updates it. Returns its ID.
+#[command]
+pub fn save_prompt_template(state: State<AppState>, template: PromptTemplate) -> Result<i64, String> {
+    crate::db::ensure_writable(&state)?;
+    if template.name.trim().is_empty() {
+        return Err("Template name cannot be empty.".to_string());
+    }
+    validate_position(&template.position)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let now = Utc::now().to_rfc3339();
+
+    if template.id <= 0 {
+        conn.execute(
+            "INSERT INTO prompt_templates (name, body, position, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![template.name.trim(), template.body, template.position, now],
+        )
+        .map_err(|e| format!("Failed to insert prompt template: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    } else {
+        let rows = conn
+            .execute(
+                "UPDATE prompt_templates SET name = ?1, body = ?2, position = ?3, updated_at = ?4 WHERE id = ?5",
+                params![template.name.trim(), template.body, template.position, now, template.id],
+            )
+            .map_err(|e| format!("Failed to update prompt template {}: {}", template.id, e))?;
+        if rows == 0 {
+            return Err(format!("Failed to update prompt template: ID {} not found.", template.id));
+        }
+        Ok(template.id)
+    }
+}
+
+#[command]
+pub fn delete_prompt_template(state: State<AppState>, template_id: i64) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let rows = conn
+        .execute("DELETE FROM prompt_templates WHERE id = ?1", params![template_id])
+        .map_err(|e| format!("Failed to delete prompt template {}: {}", template_id, e))?;
+    if rows == 0 {
+        eprintln!("Warning: Attempted to delete prompt template ID {}, but it was not found.", template_id);
+    }
+    Ok(())
+}
+
+/// Renders a stored template with the given placeholder values.
+#[command]
+pub fn render_prompt_template(
+    state: State<AppState>,
+    template_id: i64,
+    variables: HashMap<String, String>,
+) -> Result<String, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let template = load_template(&conn, template_id)?;
+    Ok(render_template(&template.body, &variables))
+}
@@ -0,0 +1,101 @@
+// src-tauri/src/read_limits.rs
+// Optional size caps for batch reads, so selecting a folder of huge generated
+// files can't push gigabytes into the webview. Content over a cap is cut at a
+// line boundary and ends with an explicit marker; structured results also
+// carry a `Truncation` record.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+pub const REASON_PER_FILE: &str = "max_bytes_per_file";
+pub const REASON_TOTAL: &str = "max_total_bytes";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Truncation {
+    pub original_bytes: usize,
+    /// Content bytes kept, not counting the marker
+    pub returned_bytes: usize,
+    /// "max_bytes_per_file" or "max_total_bytes"
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadLimits {
+    pub max_bytes_per_file: Option<usize>,
+    pub max_total_bytes: Option<usize>,
+}
+
+impl ReadLimits {
+    pub fn new(max_bytes_per_file: Option<usize>, max_total_bytes: Option<usize>) -> Self {
+        ReadLimits { max_bytes_per_file, max_total_bytes }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes_per_file.is_none() && self.max_total_bytes.is_none()
+    }
+}
+
+/// The line appended to truncated content.
+pub fn truncation_marker(truncation: &Truncation) -> String {
+    format!(
+        "\n[... truncated: {} of {} bytes shown ({}) ...]\n",
+        truncation.returned_bytes, truncation.original_bytes, truncation.reason
+    )
+}
+
+// Largest cut <= max at a char boundary, preferring the end of a line
+fn cut_point(content: &str, max: usize) -> usize {
+    let mut end = max.min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    match content[..end].rfind('\n') {
+        // Don't give up more than a quarter of the allowance for a clean line end
+        Some(newline) if newline + 1 >= end - end / 4 => newline + 1,
+        _ => end,
+    }
+}
+
+/// Cuts `content` to at most `max` bytes plus the marker; None when it fits.
+pub fn truncate_content(content: &mut String, max: usize, reason: &str) -> Option<Truncation> {
+    if content.len() <= max {
+        return None;
+    }
+    let original_bytes = content.len();
+    content.truncate(cut_point(content, max));
+    let truncation = Truncation { original_bytes, returned_bytes: content.len(), reason: reason.to_string() };
+    content.push_str(&truncation_marker(&truncation));
+    Some(truncation)
+}
+
+/// Applies the caps to successful results. The total budget is spent in the
+/// order of `paths` (the caller's order), so which files get cut is
+/// deterministic even though the reads ran in parallel.
+pub fn apply_limits<T>(
+    paths: &[String],
+    results: &mut HashMap<String, Result<T, String>>,
+    limits: ReadLimits,
+    content_of: impl Fn(&mut T) -> &mut String,
+    mut on_truncated: impl FnMut(&mut T, Truncation),
+) {
+    if limits.is_unlimited() {
+        return;
+    }
+    let mut remaining = limits.max_total_bytes.unwrap_or(usize::MAX);
+    let mut seen = HashSet::new();
+    for path in paths {
+        if !seen.insert(path) {
+            continue;
+        }
+        let Some(Ok(item)) = results.get_mut(path) else { continue };
+        let content = content_of(item);
+        let per_file = limits.max_bytes_per_file.unwrap_or(usize::MAX);
+        let (max, reason) = if remaining < per_file { (remaining, REASON_TOTAL) } else { (per_file, REASON_PER_FILE) };
+        let truncation = truncate_content(content, max, reason);
+        let kept = truncation.as_ref().map_or(content.len(), |t| t.returned_bytes);
+        remaining = remaining.saturating_sub(kept);
+        if let Some(truncation) = truncation {
+            on_truncated(item, truncation);
+        }
+    }
+}
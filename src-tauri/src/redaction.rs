@@ -0,0 +1,263 @@
+// src-tauri/src/redaction.rs
+// Secret/credential redaction applied while a context is assembled: matches of
+// the built-in rules (API keys, AWS secrets, private keys, `.env`-style values,
+// high-entropy strings) and of user rules become `[REDACTED:<type>]`.
+// Settings: `redact_secrets` ("false" turns it off; on by default) and
+// `redaction_custom_rules` (JSON array of `{"name": ..., "pattern": ...}`).
+
+use crate::app_settings;
+use crate::db::AppState;
+use crate::read_limits::Truncation;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+pub const REDACTION_SETTING: &str = "redact_secrets";
+pub const CUSTOM_RULES_SETTING: &str = "redaction_custom_rules";
+
+const ENTROPY_MIN_LENGTH: usize = 24;
+const ENTROPY_THRESHOLD: f64 = 4.0;
+
+struct Rule {
+    name: String,
+    regex: Regex,
+    /// Capture group holding the secret; 0 redacts the whole match
+    group: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CustomRedactionRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RedactionHit {
+    pub kind: String,
+    /// 1-based line of the redacted value in the original text
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RedactedFile {
+    pub content: String,
+    pub redactions: Vec<RedactionHit>,
+    /// Set when a batch read size cap cut the content (see read_limits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RedactionRuleInfo {
+    pub name: String,
+    pub pattern: String,
+    pub builtin: bool,
+}
+
+// (type, pattern, secret group)
+const BUILTIN_RULES: &[(&str, &str, usize)] = &[
+    (
+        "private_key",
+        r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----[\s\S]*?-----END (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----",
+        0,
+    ),
+    ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b", 0),
+    ("aws_secret", r#"(?i)aws_?secret_?(?:access_?)?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})\b"#, 1),
+    ("github_token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b", 0),
+    ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}", 0),
+    ("api_key", r"\bsk-(?:ant-|proj-)?[A-Za-z0-9_-]{20,}", 0),
+    ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}\b", 0),
+    ("stripe_key", r"\b[rs]k_(?:live|test)_[0-9A-Za-z]{16,}\b", 0),
+    ("jwt", r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}", 0),
+    ("connection_string_password", r"\b[a-z][a-z0-9+.-]*://[^:/\s@]+:([^@\s/]+)@", 1),
+    // KEY=value lines whose name says secret; values that look like expressions are left alone
+    (
+        "env_value",
+        r#"(?im)^[ \t]*(?:export[ \t]+)?[A-Z0-9_.-]*(?:SECRET|PASSWORD|PASSWD|TOKEN|API_?KEY|PRIVATE_?KEY|CREDENTIALS?)[A-Z0-9_.-]*[ \t]*[=:][ \t]*["']?([^\s"'#(){}\[\]$<>]{4,})["']?[ \t]*(?:#.*)?$"#,
+        1,
+    ),
+];
+
+static BUILTIN: Lazy<Vec<Rule>> = Lazy::new(|| {
+    BUILTIN_RULES
+        .iter()
+        .map(|(name, pattern, group)| Rule {
+            name: name.to_string(),
+            regex: Regex::new(pattern).expect("builtin redaction rule"),
+            group: *group,
+        })
+        .collect()
+});
+
+static QUOTED_TOKEN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"["'`]([A-Za-z0-9+/=_-]{24,})["'`]"#).expect("quoted token regex"));
+
+/// Shannon entropy in bits per character.
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let len = text.chars().count() as f64;
+    counts.values().map(|&n| n as f64 / len).map(|p| -p * p.log2()).sum()
+}
+
+fn looks_random(token: &str) -> bool {
+    token.len() >= ENTROPY_MIN_LENGTH
+        && token.chars().any(|c| c.is_ascii_digit())
+        && token.chars().any(|c| c.is_ascii_alphabetic())
+        && shannon_entropy(token) >= ENTROPY_THRESHOLD
+}
+
+// `MAX_TOKENS=4096`, `DEBUG_PASSWORD_CHECK=false`: named like secrets, but not secret
+fn is_trivial_value(value: &str) -> bool {
+    value.chars().all(|c| c.is_ascii_digit() || c == '.')
+        || matches!(value.to_ascii_lowercase().as_str(), "true" | "false" | "null" | "none" | "undefined")
+}
+
+pub struct Redactor {
+    enabled: bool,
+    custom: Vec<Rule>,
+}
+
+impl Redactor {
+    /// Built-in rules only, regardless of settings.
+    pub fn builtin() -> Self {
+        Redactor { enabled: true, custom: Vec::new() }
+    }
+
+    pub fn from_conn(conn: &rusqlite::Connection) -> Self {
+        let enabled = !matches!(
+            app_settings::get_setting_internal(conn, REDACTION_SETTING),
+            Ok(Some(v)) if v.trim().eq_ignore_ascii_case("false")
+        );
+        let custom = if enabled { load_custom_rules(conn) } else { Vec::new() }
+            .into_iter()
+            .filter_map(|r| match Regex::new(&r.pattern) {
+                Ok(regex) => Some(Rule { name: r.name, regex, group: 0 }),
+                Err(e) => {
+                    eprintln!("[REDACT] Skipping invalid custom rule '{}': {}", r.name, e);
+                    None
+                }
+            })
+            .collect();
+        Redactor { enabled, custom }
+    }
+
+    /// Same as `from_conn`, for commands holding the managed state.
+    /// A poisoned lock falls back to the built-in rules.
+    pub fn for_state(state: &AppState) -> Self {
+        match state.conn.lock() {
+            Ok(conn) => Self::from_conn(&conn),
+            Err(_) => Self::builtin(),
+        }
+    }
+
+    pub fn redact(&self, text: &str) -> RedactedFile {
+        if !self.enabled {
+            return RedactedFile { content: text.to_string(), redactions: Vec::new(), truncation: None };
+        }
+        // (start, end, kind) of every candidate secret
+        let mut spans: Vec<(usize, usize, &str)> = Vec::new();
+        for rule in BUILTIN.iter().chain(&self.custom) {
+            for cap in rule.regex.captures_iter(text) {
+                if let Some(m) = cap.get(rule.group).or_else(|| cap.get(0)) {
+                    let trivial = rule.group > 0 && is_trivial_value(m.as_str());
+                    if !m.as_str().is_empty() && !trivial {
+                        spans.push((m.start(), m.end(), &rule.name));
+                    }
+                }
+            }
+        }
+        for cap in QUOTED_TOKEN.captures_iter(text) {
+            let m = cap.get(1).expect("group 1");
+            if looks_random(m.as_str()) {
+                spans.push((m.start(), m.end(), "high_entropy"));
+            }
+        }
+        if spans.is_empty() {
+            return RedactedFile { content: text.to_string(), redactions: Vec::new(), truncation: None };
+        }
+
+        // Earliest first, longest first on ties; overlapping later spans are dropped
+        spans.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        let mut content = String::with_capacity(text.len());
+        let mut redactions = Vec::new();
+        let mut cursor = 0;
+        let mut line = 1;
+        for (start, end, kind) in spans {
+            if start < cursor {
+                continue;
+            }
+            line += text[cursor..start].matches('\n').count();
+            content.push_str(&text[cursor..start]);
+            content.push_str(&format!("[REDACTED:{}]", kind));
+            redactions.push(RedactionHit { kind: kind.to_string(), line });
+            line += text[start..end].matches('\n').count();
+            cursor = end;
+        }
+        content.push_str(&text[cursor..]);
+        RedactedFile { content, redactions, truncation: None }
+    }
+}
+
+fn load_custom_rules(conn: &rusqlite::Connection) -> Vec<CustomRedactionRule> {
+    match app_settings::get_setting_internal(conn, CUSTOM_RULES_SETTING) {
+        Ok(Some(json)) if !json.trim().is_empty() => serde_json::from_str(&json).unwrap_or_else(|e| {
+            eprintln!("[REDACT] Ignoring malformed '{}' setting: {}", CUSTOM_RULES_SETTING, e);
+            Vec::new()
+        }),
+        _ => Vec::new(),
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Built-in rules plus the user's custom rules.
+#[command]
+pub fn list_redaction_rules(state: State<AppState>) -> Result<Vec<RedactionRuleInfo>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut rules: Vec<RedactionRuleInfo> = BUILTIN_RULES
+        .iter()
+        .map(|(name, pattern, _)| RedactionRuleInfo { name: name.to_string(), pattern: pattern.to_string(), builtin: true })
+        .collect();
+    rules.push(RedactionRuleInfo {
+        name: "high_entropy".to_string(),
+        pattern: format!("quoted token of {}+ chars with entropy >= {}", ENTROPY_MIN_LENGTH, ENTROPY_THRESHOLD),
+        builtin: true,
+    });
+    rules.extend(
+        load_custom_rules(&conn)
+            .into_iter()
+            .map(|r| RedactionRuleInfo { name: r.name, pattern: r.pattern, builtin: false }),
+    );
+    Ok(rules)
+}
+
+/// Validates and stores the custom rules (replaces the previous list).
+#[command]
+pub fn save_redaction_rules(state: State<AppState>, rules: Vec<CustomRedactionRule>) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    for rule in &rules {
+        if rule.name.trim().is_empty() {
+            return Err("Redaction rule name cannot be empty.".to_string());
+        }
+        Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern for rule '{}': {}", rule.name, e))?;
+    }
+    let json = serde_json::to_string(&rules).map_err(|e| format!("Failed to serialize redaction rules: {}", e))?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params![CUSTOM_RULES_SETTING, json],
+    )
+    .map_err(|e| format!("Failed to save redaction rules: {}", e))?;
+    Ok(())
+}
+
+/// Redacts arbitrary text (e.g. a pasted snippet) and reports what was replaced.
+#[command]
+pub fn redact_text(state: State<AppState>, text: String) -> Result<RedactedFile, String> {
+    Ok(Redactor::for_state(&state).redact(&text))
+}
@@ -0,0 +1,218 @@
+// src-tauri/src/relevance.rs
+// Cheap relevance ranking of a project's files against a natural-language
+// query: path/name matches, symbol definitions, and IDF-weighted term
+// frequency. Powers "auto-select relevant files"; no embeddings involved.
+
+use crate::db::AppState;
+use crate::inclusion_rules::load_rules;
+use crate::scan_results;
+use crate::scanner::MAX_FILE_SIZE_BYTES;
+use crate::tree_queries::load_tree;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::{command, State};
+
+const DEFAULT_RANK_LIMIT: usize = 50;
+
+// Signal weights: a path hit says more than a definition, which says more than a mention
+const PATH_WEIGHT: f64 = 3.0;
+const SYMBOL_WEIGHT: f64 = 2.0;
+const TERM_WEIGHT: f64 = 1.0;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "code", "do", "does", "file", "files", "for", "from",
+    "how", "in", "is", "it", "of", "on", "or", "that", "the", "this", "to", "where", "which", "with",
+];
+
+// Definitions across the common languages: `fn foo`, `class Foo`, `def foo`, `interface Foo`...
+pub static SYMBOL_DEF_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:fn|def|class|struct|enum|trait|interface|type|function|func|impl|module|record)\s+([A-Za-z_][A-Za-z0-9_]*)")
+        .expect("symbol definition regex")
+});
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RankedFile {
+    pub path: String,
+    pub score: f64,
+    pub path_score: f64,
+    pub symbol_score: f64,
+    pub term_score: f64,
+    pub tokens: usize,
+    pub matched_terms: Vec<String>,
+    /// In the project's "always include" list; listed regardless of score
+    pub always_included: bool,
+}
+
+// Per-file raw signals before IDF weighting
+struct FileSignals {
+    path: String,
+    tokens: usize,
+    path_hits: HashSet<String>,
+    symbol_hits: HashSet<String>,
+    term_counts: HashMap<String, usize>,
+}
+
+/// Splits identifiers and prose into lowercase words: "parseHTTPRequest_v2" -> parse, http, request, v2.
+pub fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current).to_lowercase());
+            }
+            continue;
+        }
+        let boundary = !current.is_empty()
+            && c.is_uppercase()
+            && (chars[i - 1].is_lowercase()
+                || (chars[i - 1].is_uppercase() && chars.get(i + 1).is_some_and(|n| n.is_lowercase())));
+        if boundary {
+            words.push(std::mem::take(&mut current).to_lowercase());
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current.to_lowercase());
+    }
+    words
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    split_words(query)
+        .into_iter()
+        .filter(|w| w.chars().count() >= 2 && !STOPWORDS.contains(&w.as_str()))
+        .filter(|w| seen.insert(w.clone()))
+        .collect()
+}
+
+fn collect_signals(root: &str, path: &str, tokens: usize, terms: &[String]) -> FileSignals {
+    // Only the project-relative part counts; the root's own folders would match every file
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let path_words: HashSet<String> = split_words(relative).into_iter().collect();
+    let path_hits = terms.iter().filter(|t| path_words.contains(*t)).cloned().collect();
+
+    let mut symbol_hits = HashSet::new();
+    let mut term_counts = HashMap::new();
+    if let Ok(content) = fs::read_to_string(path) {
+        for cap in SYMBOL_DEF_REGEX.captures_iter(&content) {
+            let symbol_words = split_words(&cap[1]);
+            for term in terms {
+                if symbol_words.contains(term) {
+                    symbol_hits.insert(term.clone());
+                }
+            }
+        }
+        for word in split_words(&content) {
+            if terms.contains(&word) {
+                *term_counts.entry(word).or_insert(0) += 1;
+            }
+        }
+    }
+    FileSignals { path: path.to_string(), tokens, path_hits, symbol_hits, term_counts }
+}
+
+/// Scores every file of the tree against `query`; highest score first, zero scores dropped.
+pub fn rank_tree_files(tree: &crate::types::FileNode, query: &str, limit: usize) -> Vec<RankedFile> {
+    let terms = query_terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let signals: Vec<FileSignals> = scan_results::collect_files(tree)
+        .into_par_iter()
+        .filter(|f| !f.is_binary && f.skipped_reason.is_none() && f.size <= MAX_FILE_SIZE_BYTES)
+        .map(|f| collect_signals(&tree.path, &f.path, f.tokens, &terms))
+        .collect();
+
+    // Rare terms discriminate better than ones every file mentions
+    let file_count = signals.len().max(1) as f64;
+    let idf: HashMap<&String, f64> = terms
+        .iter()
+        .map(|t| {
+            let df = signals.iter().filter(|s| s.term_counts.contains_key(t) || s.path_hits.contains(t)).count();
+            (t, (file_count / (1.0 + df as f64)).ln().max(0.0) + 1.0)
+        })
+        .collect();
+    let term_total: f64 = idf.values().sum();
+
+    let mut ranked: Vec<RankedFile> = signals
+        .into_iter()
+        .map(|s| {
+            let weight = |hits: &HashSet<String>| hits.iter().map(|t| idf[t]).sum::<f64>() / term_total;
+            let path_score = weight(&s.path_hits);
+            let symbol_score = weight(&s.symbol_hits);
+            let term_score = s.term_counts.iter().map(|(t, &n)| idf[t] * (1.0 + n as f64).ln()).sum::<f64>() / term_total;
+            let mut matched: Vec<String> = terms
+                .iter()
+                .filter(|t| s.path_hits.contains(*t) || s.term_counts.contains_key(*t))
+                .cloned()
+                .collect();
+            matched.sort();
+            RankedFile {
+                score: PATH_WEIGHT * path_score + SYMBOL_WEIGHT * symbol_score + TERM_WEIGHT * term_score,
+                path: s.path,
+                path_score,
+                symbol_score,
+                term_score,
+                tokens: s.tokens,
+                matched_terms: matched,
+                always_included: false,
+            }
+        })
+        .filter(|r| r.score > 0.0)
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    ranked.truncate(limit);
+    ranked
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Ranks the files of the project's last scan by relevance to `query`.
+#[command(async)]
+pub async fn rank_files(
+    state: State<'_, AppState>,
+    project_id: i32,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<RankedFile>, String> {
+    let tree = load_tree(&state, project_id)?;
+    let rules = load_rules(&state, project_id, Path::new(&tree.path))?;
+    let limit = limit.unwrap_or(DEFAULT_RANK_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut ranked = rank_tree_files(&tree, &query, usize::MAX);
+        ranked.retain(|r| !rules.is_never(&r.path));
+        ranked.truncate(limit);
+        // "Always include" files come along even when they scored nothing or fell past the limit
+        let files = scan_results::collect_files(&tree);
+        for path in rules.always_files(&tree) {
+            match ranked.iter_mut().find(|r| r.path == path) {
+                Some(entry) => entry.always_included = true,
+                None => {
+                    let tokens = files.iter().find(|f| f.path == path).map_or(0, |f| f.tokens);
+                    ranked.push(RankedFile {
+                        path,
+                        score: 0.0,
+                        path_score: 0.0,
+                        symbol_score: 0.0,
+                        term_score: 0.0,
+                        tokens,
+                        matched_terms: Vec::new(),
+                        always_included: true,
+                    });
+                }
+            }
+        }
+        ranked
+    })
+    .await
+    .map_err(|e| format!("Ranking task failed: {}", e))
+}
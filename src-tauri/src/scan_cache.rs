@@ -1,10 +1,13 @@
 // src-tauri/src/scan_cache.rs
 
 // Use Connection or Transaction depending on context
-use rusqlite::{params, Connection, Transaction};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
 use std::collections::HashMap;
 use std::path::PathBuf; // Keep PathBuf if needed for cleanup
 use std::collections::HashSet; // Keep HashSet if needed for cleanup
+use crate::path_canon::canonical_path;
+use crate::path_case::CaseMode;
+use chrono::Utc;
 
 // --- CacheEntry Definition ---
 #[derive(Clone, Debug)]
@@ -13,38 +16,73 @@ pub struct CacheEntry {
     pub size: u64,
     pub lines: usize,
     pub tokens: usize,
+    pub is_binary: bool,
+    pub skipped_reason: Option<String>,
+}
+
+impl CacheEntry {
+    /// A 0/0 entry for a file whose content wasn't counted.
+    pub fn skipped(last_modified: String, size: u64, reason: &str) -> Self {
+        CacheEntry { last_modified, size, lines: 0, tokens: 0, is_binary: false, skipped_reason: Some(reason.to_string()) }
+    }
 }
 // ------------------------------------
 
-/// Loads all existing file cache entries from the DB into a HashMap.
-/// Uses the PDK table name.
+/// `root` with a trailing separator, so a project at "/a/proj" doesn't claim "/a/proj2".
+pub fn root_prefix(root: &str) -> String {
+    if root.ends_with('/') || root.ends_with('\\') {
+        root.to_string()
+    } else {
+        format!("{}{}", root, std::path::MAIN_SEPARATOR)
+    }
+}
+
+// WHERE clause selecting the cache rows under a root prefix (?1 = prefix, ?2 = its length in chars)
+fn under_root_clause(case_mode: CaseMode) -> &'static str {
+    match case_mode {
+        CaseMode::Sensitive => "substr(file_path, 1, ?2) = ?1",
+        CaseMode::Insensitive => "substr(file_path, 1, ?2) = ?1 COLLATE NOCASE",
+    }
+}
+
+/// Loads the file cache entries under a project root from the DB into a HashMap.
+/// The cache table is shared by all projects; rows are scoped by path, so a
+/// scan only sees (and cleans up) its own project's rows.
 pub fn load_cache_entries(
     conn: &Connection,
+    root: &str,
+    case_mode: CaseMode,
 ) -> Result<HashMap<String, CacheEntry>, String> {
     let mut map = HashMap::new();
+    // Rows are stored canonical (see path_canon), so the root must be too
+    let prefix = root_prefix(&canonical_path(root));
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             r#"
-            SELECT file_path, last_modified, size, lines, tokens
+            SELECT file_path, last_modified, size, lines, tokens, is_binary, skipped_reason
             FROM code_context_builder_file_cache
+            WHERE {}
             "#, // <-- UPDATED Table Name
-        )
+            under_root_clause(case_mode)
+        ))
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
-        .query_map([], |row| {
+        .query_map(params![prefix, prefix.chars().count() as i64], |row| {
             Ok((
                 row.get::<_, String>(0)?, // file_path
                 row.get::<_, String>(1)?, // last_modified
                 row.get::<_, i64>(2)?,    // size
                 row.get::<_, i64>(3)?,    // lines
                 row.get::<_, i64>(4)?,    // tokens
+                row.get::<_, bool>(5)?,   // is_binary
+                row.get::<_, Option<String>>(6)?, // skipped_reason
             ))
         })
         .map_err(|e| e.to_string())?;
 
     for row_result in rows {
-        let (fp, lm, sz, ln, tk) = row_result.map_err(|e| e.to_string())?;
+        let (fp, lm, sz, ln, tk, bin, skip) = row_result.map_err(|e| e.to_string())?;
         map.insert(
             fp,
             CacheEntry {
@@ -52,41 +90,152 @@ pub fn load_cache_entries(
                 size: sz as u64,
                 lines: ln as usize,
                 tokens: tk as usize,
+                is_binary: bin,
+                skipped_reason: skip,
             },
         );
     }
     Ok(map)
 }
 
-/// Saves (or updates) a single cache entry to the DB within a transaction.
+/// The cache row of one file, if it has been counted.
+pub fn load_cache_entry(conn: &Connection, file_path: &str) -> Result<Option<CacheEntry>, String> {
+    conn.query_row(
+        "SELECT last_modified, size, lines, tokens, is_binary, skipped_reason FROM code_context_builder_file_cache WHERE file_path = ?1",
+        params![file_path],
+        |row| {
+            Ok(CacheEntry {
+                last_modified: row.get(0)?,
+                size: row.get::<_, i64>(1)? as u64,
+                lines: row.get::<_, i64>(2)? as usize,
+                tokens: row.get::<_, i64>(3)? as usize,
+                is_binary: row.get(4)?,
+                skipped_reason: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Saves (or updates) a single cache entry to the DB (usually within a transaction).
 /// Uses the PDK table name.
 pub fn save_cache_entry(
-    tx: &Transaction, // Use Transaction
+    conn: &Connection, // A Transaction derefs to this
     file_path: &str,
     entry: &CacheEntry,
 ) -> Result<(), String> {
-    tx.execute(
+    conn.execute(
         r#"
-        INSERT INTO code_context_builder_file_cache (file_path, last_modified, size, lines, tokens)
-        VALUES (?1, ?2, ?3, ?4, ?5)
+        INSERT INTO code_context_builder_file_cache (file_path, last_modified, size, lines, tokens, is_binary, skipped_reason, cached_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
         ON CONFLICT(file_path) DO UPDATE SET
             last_modified = excluded.last_modified,
             size = excluded.size,
             lines = excluded.lines,
-            tokens = excluded.tokens
+            tokens = excluded.tokens,
+            is_binary = excluded.is_binary,
+            skipped_reason = excluded.skipped_reason,
+            cached_at = excluded.cached_at
         "#, // <-- UPDATED Table Name
         params![
             file_path,
             entry.last_modified,
             entry.size as i64,   // Ensure conversion for DB
             entry.lines as i64,  // Ensure conversion for DB
-            entry.tokens as i64 // Ensure conversion for DB
+            entry.tokens as i64, // Ensure conversion for DB
+            entry.is_binary,
+            entry.skipped_reason,
+            Utc::now().to_rfc3339()
         ],
     )
     .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// Deletes the cache row of a single file (e.g. after the monitor saw it deleted).
+/// On case-insensitive projects rows differing only in (ASCII) casing go too.
+pub fn delete_cache_entry(conn: &Connection, file_path: &str, case_mode: CaseMode) -> Result<(), String> {
+    let sql = match case_mode {
+        CaseMode::Sensitive => "DELETE FROM code_context_builder_file_cache WHERE file_path = ?1",
+        CaseMode::Insensitive => "DELETE FROM code_context_builder_file_cache WHERE file_path = ?1 COLLATE NOCASE",
+    };
+    conn.execute(sql, params![file_path]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deletes every cache row under a project root. Returns the number of rows removed.
+pub fn delete_entries_under(conn: &Connection, root: &str, case_mode: CaseMode) -> Result<usize, String> {
+    let prefix = root_prefix(root);
+    conn.execute(
+        &format!("DELETE FROM code_context_builder_file_cache WHERE {}", under_root_clause(case_mode)),
+        params![prefix, prefix.chars().count() as i64],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// (row count, total tokens) of the cache rows under a project root.
+pub fn count_entries_under(conn: &Connection, root: &str, case_mode: CaseMode) -> Result<(usize, usize), String> {
+    let prefix = root_prefix(root);
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*), COALESCE(SUM(tokens), 0) FROM code_context_builder_file_cache WHERE {}",
+            under_root_clause(case_mode)
+        ),
+        params![prefix, prefix.chars().count() as i64],
+        |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// On case-insensitive projects, moves cache rows whose path differs from a
+/// scanned path only by casing onto the scanned spelling, so a renamed-case
+/// file reuses its counts and doesn't leave a duplicate row behind.
+/// Returns the number of rows re-keyed or dropped as duplicates.
+pub fn reconcile_path_case(
+    tx: &Transaction,
+    valid_paths: &[PathBuf],
+    cache_map: &mut HashMap<String, CacheEntry>,
+    case_mode: CaseMode,
+) -> Result<usize, String> {
+    if case_mode == CaseMode::Sensitive {
+        return Ok(0);
+    }
+    let scanned: HashMap<String, String> = valid_paths
+        .iter()
+        .filter_map(|p| p.to_str())
+        .map(|p| (case_mode.key(p), p.to_string()))
+        .collect();
+
+    let mut stale: Vec<(String, String)> = Vec::new(); // (cached spelling, scanned spelling)
+    for cached in cache_map.keys() {
+        if let Some(actual) = scanned.get(&case_mode.key(cached)) {
+            if actual != cached {
+                stale.push((cached.clone(), actual.clone()));
+            }
+        }
+    }
+    if stale.is_empty() {
+        return Ok(0);
+    }
+
+    let mut delete_stmt = tx
+        .prepare("DELETE FROM code_context_builder_file_cache WHERE file_path = ?1")
+        .map_err(|e| e.to_string())?;
+    for (cached, actual) in &stale {
+        let entry = cache_map.remove(cached);
+        delete_stmt.execute([cached]).map_err(|e| e.to_string())?;
+        // An entry under the exact spelling wins; otherwise carry the old counts over
+        if let Some(entry) = entry {
+            if !cache_map.contains_key(actual) {
+                save_cache_entry(tx, actual, &entry)?;
+                cache_map.insert(actual.clone(), entry);
+            }
+        }
+    }
+    Ok(stale.len())
+}
+
 /// Removes cache entries for files that are no longer valid (within a transaction).
 /// Uses the PDK table name.
 pub fn cleanup_removed_files(
@@ -0,0 +1,169 @@
+// src-tauri/src/scan_chunks.rs
+// `scan_tree_chunk` events: the parts of the tree that are already final,
+// emitted while a scan is still processing, so large projects don't show a
+// blank tree until the end. A folder is complete once every file below it has
+// been processed. Every CHUNK_INTERVAL the newly completed folders whose parent
+// is still pending are emitted as whole subtrees; a chunk replaces any earlier
+// chunk for a folder inside it. The root is never sent as a chunk: the tree the
+// scan returns is the final version and reconciles everything (token
+// percentiles and pins are only set there).
+
+use crate::scan_cache::CacheEntry;
+use crate::scan_progress;
+use crate::scan_state::is_scan_cancelled;
+use crate::scan_tree::{build_tree_from_paths, node_id, TreeSort};
+use crate::types::FileNode;
+use crate::window_registry::emit_to_origin_and_project;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Window;
+
+pub const CHUNK_EVENT: &str = "scan_tree_chunk";
+const CHUNK_INTERVAL: Duration = Duration::from_millis(250);
+
+struct ChunkState {
+    // Folder -> direct children not yet complete
+    pending: HashMap<PathBuf, usize>,
+    // Counts of the processed files, by path
+    entries: HashMap<String, CacheEntry>,
+    // Files the scan leaves out of the tree (filters, skipped placeholders)
+    left_out: HashSet<PathBuf>,
+    // Completed since the last emit
+    completed: Vec<PathBuf>,
+    last_emit: Instant,
+}
+
+/// Tracks which folders a scan has finished and emits them as they complete.
+pub struct TreeChunker<'a> {
+    window: Window,
+    project_id: i32,
+    root: &'a Path,
+    // Every enumerated path, sorted (a folder's contents follow it)
+    paths: Vec<&'a PathBuf>,
+    dir_paths: &'a HashSet<PathBuf>,
+    sort: TreeSort,
+    state: Mutex<ChunkState>,
+}
+
+impl<'a> TreeChunker<'a> {
+    pub fn new(window: Window, project_id: i32, root: &'a Path, paths: &'a [PathBuf], dir_paths: &'a HashSet<PathBuf>, sort: TreeSort) -> Self {
+        let mut sorted: Vec<&PathBuf> = paths.iter().collect();
+        sorted.sort();
+        let mut pending: HashMap<PathBuf, usize> =
+            paths.iter().filter(|p| dir_paths.contains(*p)).map(|d| (d.clone(), 0)).collect();
+        pending.entry(root.to_path_buf()).or_insert(0);
+        for path in paths.iter().filter(|p| p.as_path() != root) {
+            if let Some(count) = path.parent().and_then(|parent| pending.get_mut(parent)) {
+                *count += 1;
+            }
+        }
+        let chunker = TreeChunker {
+            window,
+            project_id,
+            root,
+            paths: sorted,
+            dir_paths,
+            sort,
+            state: Mutex::new(ChunkState {
+                pending,
+                entries: HashMap::new(),
+                left_out: HashSet::new(),
+                completed: Vec::new(),
+                last_emit: Instant::now(),
+            }),
+        };
+        // Empty folders are complete from the start
+        if let Ok(mut state) = chunker.state.lock() {
+            let empty: Vec<PathBuf> = state.pending.iter().filter(|(_, count)| **count == 0).map(|(d, _)| d.clone()).collect();
+            for dir in empty {
+                chunker.complete(&mut state, &dir);
+            }
+        }
+        chunker
+    }
+
+    /// A file is processed; `entry` holds its counts (None when it has none,
+    /// e.g. its metadata couldn't be read).
+    pub fn file_done(&self, path: &Path, entry: Option<CacheEntry>) {
+        let Ok(mut state) = self.state.lock() else { return };
+        if let Some(entry) = entry {
+            state.entries.insert(path.to_string_lossy().to_string(), entry);
+        }
+        self.finish_file(&mut state, path);
+    }
+
+    /// A file is processed and won't be in the tree.
+    pub fn file_left_out(&self, path: &Path) {
+        let Ok(mut state) = self.state.lock() else { return };
+        state.left_out.insert(path.to_path_buf());
+        self.finish_file(&mut state, path);
+    }
+
+    fn finish_file(&self, state: &mut ChunkState, path: &Path) {
+        self.child_done(state, path);
+        if state.last_emit.elapsed() >= CHUNK_INTERVAL && !state.completed.is_empty() && !is_scan_cancelled() {
+            self.emit(state);
+        }
+    }
+
+    // `path` is done; completes its parent when it was the last child pending
+    fn child_done(&self, state: &mut ChunkState, path: &Path) {
+        let Some(parent) = path.parent().filter(|_| path != self.root) else { return };
+        let Some(count) = state.pending.get_mut(parent) else { return };
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            let parent = parent.to_path_buf();
+            self.complete(state, &parent);
+        }
+    }
+
+    fn complete(&self, state: &mut ChunkState, dir: &Path) {
+        if dir != self.root {
+            state.completed.push(dir.to_path_buf());
+        }
+        self.child_done(state, dir);
+    }
+
+    // One event with the subtrees of the completed folders whose parent is
+    // still pending (the others are inside one of those)
+    fn emit(&self, state: &mut ChunkState) {
+        let completed = std::mem::take(&mut state.completed);
+        let chunks: Vec<FileNode> = completed
+            .iter()
+            .filter(|dir| dir.parent().and_then(|parent| state.pending.get(parent)).is_some_and(|pending| *pending > 0))
+            .map(|dir| self.subtree(state, dir))
+            .collect();
+        state.last_emit = Instant::now();
+        if chunks.is_empty() {
+            return;
+        }
+        let payload = serde_json::json!({
+            "seq": scan_progress::next_sequence(),
+            "project_id": self.project_id,
+            "chunks": chunks,
+        });
+        emit_to_origin_and_project(&self.window, self.project_id, CHUNK_EVENT, payload);
+    }
+
+    fn subtree(&self, state: &ChunkState, dir: &Path) -> FileNode {
+        let start = self.paths.partition_point(|p| p.as_path() < dir);
+        let below: Vec<PathBuf> = self.paths[start..]
+            .iter()
+            .take_while(|p| p.starts_with(dir))
+            .filter(|p| !state.left_out.contains(p.as_path()))
+            .map(|p| (*p).clone())
+            .collect();
+        let mut node = build_tree_from_paths(dir, &below, self.dir_paths, &state.entries, self.sort);
+        // Built with `dir` as its root; ids are relative to the project root
+        fn reassign_ids(node: &mut FileNode, root: &Path) {
+            node.id = node_id(root, Path::new(&node.path));
+            for child in &mut node.children {
+                reassign_ids(child, root);
+            }
+        }
+        reassign_ids(&mut node, self.root);
+        node
+    }
+}
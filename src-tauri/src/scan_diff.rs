@@ -0,0 +1,197 @@
+// src-tauri/src/scan_diff.rs
+// `diff_scan_cmd`: what a rescan would change, as a compact list instead of a
+// whole new tree. The project is enumerated with the scanner's ignore rules and
+// compared against its rows in the file cache; added and modified files are
+// counted so their token deltas are exact. The cache itself isn't touched.
+
+use crate::app_settings;
+use crate::archives::{self, ExpandedArchive};
+use crate::cloud_files::{self, PlaceholderPolicy};
+use crate::db::AppState;
+use crate::dir_ignore_rules;
+use crate::document_extract;
+use crate::errors::{self, app_error};
+use crate::ignore_handler::CompiledIgnorePatterns;
+use crate::path_case::CaseMode;
+use crate::projects;
+use crate::scan_cache::{self, CacheEntry};
+use crate::scan_filters::ScanFilters;
+use crate::scan_tree::{file_modified_timestamp, gather_valid_items};
+use crate::scanner::{compute_cache_entry, MAX_FILE_SIZE_BYTES};
+use crate::types::{SKIP_CLOUD_PLACEHOLDER, SKIP_TOO_LARGE};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{command, State};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FileDelta {
+    pub path: String,
+    /// None for an added file
+    pub old_tokens: Option<usize>,
+    /// None for a removed file
+    pub new_tokens: Option<usize>,
+    pub token_delta: i64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanDiff {
+    pub project_id: i32,
+    /// Each list sorted by path
+    pub added: Vec<FileDelta>,
+    pub removed: Vec<FileDelta>,
+    /// Size or modification time differs from the cache
+    pub modified: Vec<FileDelta>,
+    pub unchanged_count: usize,
+    /// Sum of all token deltas
+    pub token_delta: i64,
+}
+
+// (path, cached entry, fresh entry when it differs); (path, None, None) for a
+// file the size/age filters leave out, which a rescan neither adds nor removes
+type Compared<'a> = (String, Option<&'a CacheEntry>, Option<CacheEntry>);
+
+fn delta(path: String, old: Option<usize>, new: Option<usize>) -> FileDelta {
+    FileDelta { path, old_tokens: old, new_tokens: new, token_delta: new.unwrap_or(0) as i64 - old.unwrap_or(0) as i64 }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Compares a fresh enumeration of `project_id` against its file cache (the
+/// last scan): added, removed and modified files with token deltas. Nothing
+/// is written, so the next scan still sees (and records) the same changes.
+#[command(async)]
+pub async fn diff_scan_cmd(state: State<'_, AppState>, project_id: i32) -> Result<ScanDiff, String> {
+    let (project, global_patterns, cache, dir_rules, extract_documents, scan_archives, placeholder_policy) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let project = projects::load_project_by_id(&conn, project_id)?;
+        let global_patterns: Vec<String> = app_settings::get_setting_internal(&conn, "default_ignore_patterns")
+            .map_err(|e| format!("Failed to query default_ignore_patterns from app_settings: {}", e))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let cache = match project.root_folder.as_deref() {
+            Some(root) => scan_cache::load_cache_entries(&conn, root, CaseMode::for_project(&project))?,
+            None => HashMap::new(),
+        };
+        let dir_rules = dir_ignore_rules::load_dir_rules(&conn, project_id)?;
+        let scan_archives = archives::archives_enabled(&conn);
+        (project, global_patterns, cache, dir_rules, document_extract::extraction_enabled(&conn), scan_archives, cloud_files::load_policy(&conn))
+    };
+    let root_folder = project
+        .root_folder
+        .clone()
+        .ok_or_else(|| app_error(errors::PROJECT_NO_ROOT, &[("project_id", project_id.to_string())]))?;
+    let root = PathBuf::from(&root_folder);
+    if !root.is_dir() {
+        return Err(app_error(errors::ROOT_NOT_DIRECTORY, &[("path", root_folder)]));
+    }
+    let case_mode = CaseMode::for_project(&project);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut patterns = global_patterns;
+        patterns.extend_from_slice(&project.ignore_patterns);
+        let mut compiled = CompiledIgnorePatterns::new(&root, &patterns);
+        dir_ignore_rules::apply_dir_rules(&mut compiled, &dir_rules);
+        if project.respect_gitignore.unwrap_or(false) {
+            compiled.add_repository_gitignore(&root);
+        }
+        compiled.set_follow_symlinks(project.follow_symlinks.unwrap_or(false));
+        compiled.set_max_depth(project.max_scan_depth);
+        compiled.set_include_hidden(project.include_hidden.unwrap_or(true));
+        compiled.set_include_patterns(project.include_patterns.as_deref().unwrap_or_default());
+        let filters = ScanFilters::for_project(&project);
+        let mut paths = Vec::new();
+        let mut dirs: HashSet<PathBuf> = HashSet::new();
+        gather_valid_items(&root, &compiled, &mut paths, &mut dirs, &mut Vec::new(), &mut Vec::new(), 0);
+        let expanded: Vec<ExpandedArchive> = if scan_archives {
+            archives::expand_archives(&mut paths, &mut dirs, &compiled, &mut Vec::new())
+        } else {
+            Vec::new()
+        };
+        let archived: HashSet<&PathBuf> = expanded.iter().flat_map(|a| a.files.iter().map(|e| &e.path)).collect();
+
+        // Cache rows by case key, so a renamed-case file on a case-insensitive volume isn't added + removed
+        let cached: HashMap<String, (&String, &CacheEntry)> = cache.iter().map(|(p, e)| (case_mode.key(p), (p, e))).collect();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        let mut compared: Vec<Compared> = paths
+            .par_iter()
+            .filter(|p| !dirs.contains(*p) && !archived.contains(p))
+            .filter_map(|p| {
+                let meta = fs::metadata(p).ok()?;
+                let is_placeholder = placeholder_policy != PlaceholderPolicy::Read && cloud_files::is_cloud_placeholder(&meta);
+                if is_placeholder && placeholder_policy == PlaceholderPolicy::Skip {
+                    return None;
+                }
+                let path = p.to_string_lossy().to_string();
+                if filters.exclusion(&meta).is_some() {
+                    return Some((path, None, None));
+                }
+                let last_modified = file_modified_timestamp(&meta);
+                let old = cached.get(&case_mode.key(&path)).map(|(_, e)| *e);
+                if old.is_some_and(|e| e.last_modified == last_modified && e.size == meta.len()) {
+                    return Some((path, old, None));
+                }
+                let new = if meta.len() > MAX_FILE_SIZE_BYTES {
+                    CacheEntry::skipped(last_modified, meta.len(), SKIP_TOO_LARGE)
+                } else if is_placeholder {
+                    CacheEntry::skipped(last_modified, meta.len(), SKIP_CLOUD_PLACEHOLDER)
+                } else {
+                    compute_cache_entry(p, meta.len(), last_modified, extract_documents)
+                };
+                Some((path, old, Some(new)))
+            })
+            .collect();
+        // Archive entries, compared against the archive's modification time
+        let cached_entry = |path: &str| cached.get(&case_mode.key(path)).map(|(_, e)| *e);
+        let from_archives: Vec<Vec<Compared>> = expanded
+            .par_iter()
+            .map(|archive| {
+                let mut entries = Vec::new();
+                let counted = archives::count_entries(archive, cached_entry, |entry, fresh| {
+                    let path = entry.path.to_string_lossy().to_string();
+                    let old = cached_entry(&path);
+                    entries.push((path, old, fresh));
+                });
+                if let Err(e) = counted {
+                    eprintln!("[SCAN_DIFF] Couldn't read archive '{}': {}", archive.archive.display(), e);
+                }
+                entries
+            })
+            .collect();
+        compared.extend(from_archives.into_iter().flatten());
+
+        let mut diff = ScanDiff { project_id, ..Default::default() };
+        for (path, old, new) in compared {
+            seen.insert(case_mode.key(&path));
+            match (old, new) {
+                (None, None) => (),
+                (Some(_), None) => diff.unchanged_count += 1,
+                (Some(old), Some(new)) => diff.modified.push(delta(path, Some(old.tokens), Some(new.tokens))),
+                (None, new) => diff.added.push(delta(path, None, new.map(|n| n.tokens))),
+            }
+        }
+        for (key, (path, entry)) in &cached {
+            if !seen.contains(key) {
+                diff.removed.push(delta((*path).clone(), Some(entry.tokens), None));
+            }
+        }
+        for list in [&mut diff.added, &mut diff.removed, &mut diff.modified] {
+            list.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        diff.token_delta = diff.added.iter().chain(&diff.removed).chain(&diff.modified).map(|d| d.token_delta).sum();
+        println!(
+            "[SCAN_DIFF] Project {}: {} added, {} removed, {} modified, {:+} tokens",
+            project_id,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.modified.len(),
+            diff.token_delta
+        );
+        diff
+    })
+    .await
+    .map_err(|e| format!("Scan diff task failed: {}", e))
+}
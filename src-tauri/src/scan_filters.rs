@@ -0,0 +1,56 @@
+// src-tauri/src/scan_filters.rs
+// A project's size and age filters (min_file_size, max_file_size,
+// max_file_age_days). The scanner checks each file's metadata against them in
+// its parallel processing stage: a file they exclude isn't read, is left out of
+// the tree, and is listed in the scan summary's `filtered_files` instead.
+
+use crate::types::Project;
+use std::fs::Metadata;
+use std::time::{Duration, SystemTime};
+
+// Values of FilteredFile::reason
+pub const FILTER_BELOW_MIN_SIZE: &str = "below_min_size";
+pub const FILTER_ABOVE_MAX_SIZE: &str = "above_max_size";
+pub const FILTER_OLDER_THAN_MAX_AGE: &str = "older_than_max_age";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanFilters {
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    // Files last modified before this are left out
+    modified_after: Option<SystemTime>,
+}
+
+impl ScanFilters {
+    /// The project's filters; a limit of 0 (or none) is off. The age cutoff is
+    /// taken now, so it stays the same for the whole scan.
+    pub fn for_project(project: &Project) -> Self {
+        let limit = |value: Option<u64>| value.filter(|v| *v > 0);
+        ScanFilters {
+            min_size: limit(project.min_file_size),
+            max_size: limit(project.max_file_size),
+            modified_after: limit(project.max_file_age_days)
+                .and_then(|days| SystemTime::now().checked_sub(Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)))),
+        }
+    }
+
+    /// Why a file with this metadata is left out (a FILTER_* reason), or None
+    /// when it's scanned. Files without a readable modification time pass the age filter.
+    pub fn exclusion(&self, meta: &Metadata) -> Option<&'static str> {
+        let size = meta.len();
+        if self.min_size.is_some_and(|min| size < min) {
+            return Some(FILTER_BELOW_MIN_SIZE);
+        }
+        if self.max_size.is_some_and(|max| size > max) {
+            return Some(FILTER_ABOVE_MAX_SIZE);
+        }
+        if let Some(cutoff) = self.modified_after {
+            if meta.modified().is_ok_and(|modified| modified < cutoff) {
+                return Some(FILTER_OLDER_THAN_MAX_AGE);
+            }
+        }
+        None
+    }
+}
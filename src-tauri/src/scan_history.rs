@@ -0,0 +1,133 @@
+// src-tauri/src/scan_history.rs
+// One row per scan run, whatever its outcome: when it ran, how it ended, and
+// the project's size it found (files, tokens), to follow a project's token
+// footprint over time. scan_metrics keeps the timing breakdown of full scans.
+
+use crate::db::AppState;
+use crate::types::ScanSummary;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::{command, State};
+
+// Runs kept per project; older ones are dropped on insert
+const MAX_RUNS_PER_PROJECT: i64 = 1000;
+
+// Values of ScanRun::status
+pub const RUN_DONE: &str = "done";
+pub const RUN_CANCELLED: &str = "cancelled";
+pub const RUN_FAILED: &str = "failed";
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ScanRun {
+    pub id: i64,
+    pub project_id: i32,
+    pub started_at: String,
+    pub finished_at: String,
+    /// "done", "cancelled" or "failed"
+    pub status: String,
+    /// "full", "subtree" (a rescan merged into the tree) or "targeted" (include globs; partial totals)
+    pub scope: String,
+    /// Totals of the returned tree; 0 when the scan ended without one
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub total_tokens: usize,
+    pub total_lines: usize,
+    pub total_bytes: u64,
+    pub duration_ms: u64,
+    /// The failure message of a failed run
+    pub error: Option<String>,
+}
+
+impl ScanRun {
+    /// Copies the totals of a finished scan's summary.
+    pub fn with_summary(mut self, summary: &ScanSummary) -> Self {
+        self.file_count = summary.total_files;
+        self.dir_count = summary.total_dirs;
+        self.total_tokens = summary.total_tokens;
+        self.total_lines = summary.total_lines;
+        self.total_bytes = summary.total_bytes;
+        self
+    }
+}
+
+pub fn record_scan_run(conn: &Connection, run: &ScanRun) -> Result<(), String> {
+    conn.execute(
+        r#"
+        INSERT INTO scan_history (
+            project_id, started_at, finished_at, status, scope, file_count, dir_count,
+            total_tokens, total_lines, total_bytes, duration_ms, error
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        "#,
+        params![
+            run.project_id,
+            run.started_at,
+            run.finished_at,
+            run.status,
+            run.scope,
+            run.file_count as i64,
+            run.dir_count as i64,
+            run.total_tokens as i64,
+            run.total_lines as i64,
+            run.total_bytes as i64,
+            run.duration_ms as i64,
+            run.error,
+        ],
+    )
+    .map_err(|e| format!("Failed to record scan run for project {}: {}", run.project_id, e))?;
+    conn.execute(
+        r#"
+        DELETE FROM scan_history WHERE project_id = ?1 AND id NOT IN (
+            SELECT id FROM scan_history WHERE project_id = ?1 ORDER BY id DESC LIMIT ?2
+        )
+        "#,
+        params![run.project_id, MAX_RUNS_PER_PROJECT],
+    )
+    .map_err(|e| format!("Failed to prune scan history for project {}: {}", run.project_id, e))?;
+    Ok(())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Scan runs of a project, newest first.
+#[command]
+pub fn list_scan_history_cmd(
+    state: State<AppState>,
+    project_id: i32,
+    limit: Option<usize>,
+) -> Result<Vec<ScanRun>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, project_id, started_at, finished_at, status, scope, file_count, dir_count,
+                   total_tokens, total_lines, total_bytes, duration_ms, error
+            FROM scan_history
+            WHERE project_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#,
+        )
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let limit = limit.map_or(-1, |l| l as i64); // SQLite: negative LIMIT = no limit
+    let rows = stmt
+        .query_map(params![project_id, limit], |row| {
+            Ok(ScanRun {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                started_at: row.get(2)?,
+                finished_at: row.get(3)?,
+                status: row.get(4)?,
+                scope: row.get(5)?,
+                file_count: row.get::<_, i64>(6)? as usize,
+                dir_count: row.get::<_, i64>(7)? as usize,
+                total_tokens: row.get::<_, i64>(8)? as usize,
+                total_lines: row.get::<_, i64>(9)? as usize,
+                total_bytes: row.get::<_, i64>(10)? as u64,
+                duration_ms: row.get::<_, i64>(11)? as u64,
+                error: row.get(12)?,
+            })
+        })
+        .map_err(|e| format!("Query scan history failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map scan history row: {}", e))
+}
@@ -0,0 +1,137 @@
+// src-tauri/src/scan_metrics.rs
+// Local timing history of full scans (enumeration, processing, DB commit...),
+// to spot performance regressions and compare settings. Never leaves the machine.
+
+use crate::db::AppState;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::{command, State};
+
+// Rows kept per project; older ones are dropped on insert
+const MAX_METRICS_PER_PROJECT: i64 = 200;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ScanMetrics {
+    pub id: i64,
+    pub project_id: i32,
+    pub scanned_at: String,
+    pub total_ms: u64,
+    /// Walking the tree and applying ignore patterns
+    pub enumeration_ms: u64,
+    /// Stat + cache lookup + counting of every file
+    pub processing_ms: u64,
+    /// Cache transaction plus saving the scan tree
+    pub db_commit_ms: u64,
+    pub tree_build_ms: u64,
+    pub file_count: usize,
+    pub dir_count: usize,
+    /// Files processed per second of processing time
+    pub files_per_sec: f64,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    /// hits / (hits + misses); 0 when no file was looked up
+    pub cache_hit_ratio: f64,
+    pub network_mode: bool,
+}
+
+impl ScanMetrics {
+    /// Fills the derived rates from the counts and timings.
+    pub fn with_rates(mut self) -> Self {
+        self.files_per_sec = if self.processing_ms == 0 {
+            0.0
+        } else {
+            self.file_count as f64 * 1000.0 / self.processing_ms as f64
+        };
+        let looked_up = self.cache_hits + self.cache_misses;
+        self.cache_hit_ratio = if looked_up == 0 { 0.0 } else { self.cache_hits as f64 / looked_up as f64 };
+        self
+    }
+}
+
+pub fn record_scan_metrics(conn: &Connection, metrics: &ScanMetrics) -> Result<(), String> {
+    conn.execute(
+        r#"
+        INSERT INTO scan_metrics (
+            project_id, scanned_at, total_ms, enumeration_ms, processing_ms, db_commit_ms, tree_build_ms,
+            file_count, dir_count, files_per_sec, cache_hits, cache_misses, cache_hit_ratio, network_mode
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        "#,
+        params![
+            metrics.project_id,
+            Utc::now().to_rfc3339(),
+            metrics.total_ms as i64,
+            metrics.enumeration_ms as i64,
+            metrics.processing_ms as i64,
+            metrics.db_commit_ms as i64,
+            metrics.tree_build_ms as i64,
+            metrics.file_count as i64,
+            metrics.dir_count as i64,
+            metrics.files_per_sec,
+            metrics.cache_hits as i64,
+            metrics.cache_misses as i64,
+            metrics.cache_hit_ratio,
+            metrics.network_mode,
+        ],
+    )
+    .map_err(|e| format!("Failed to record scan metrics for project {}: {}", metrics.project_id, e))?;
+    conn.execute(
+        r#"
+        DELETE FROM scan_metrics WHERE project_id = ?1 AND id NOT IN (
+            SELECT id FROM scan_metrics WHERE project_id = ?1 ORDER BY id DESC LIMIT ?2
+        )
+        "#,
+        params![metrics.project_id, MAX_METRICS_PER_PROJECT],
+    )
+    .map_err(|e| format!("Failed to prune scan metrics for project {}: {}", metrics.project_id, e))?;
+    Ok(())
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Recorded scans of a project, newest first.
+#[command]
+pub fn get_scan_metrics(
+    state: State<AppState>,
+    project_id: i32,
+    limit: Option<usize>,
+) -> Result<Vec<ScanMetrics>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, project_id, scanned_at, total_ms, enumeration_ms, processing_ms, db_commit_ms,
+                   tree_build_ms, file_count, dir_count, files_per_sec, cache_hits, cache_misses,
+                   cache_hit_ratio, network_mode
+            FROM scan_metrics
+            WHERE project_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#,
+        )
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let limit = limit.map_or(-1, |l| l as i64); // SQLite: negative LIMIT = no limit
+    let rows = stmt
+        .query_map(params![project_id, limit], |row| {
+            Ok(ScanMetrics {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                scanned_at: row.get(2)?,
+                total_ms: row.get::<_, i64>(3)? as u64,
+                enumeration_ms: row.get::<_, i64>(4)? as u64,
+                processing_ms: row.get::<_, i64>(5)? as u64,
+                db_commit_ms: row.get::<_, i64>(6)? as u64,
+                tree_build_ms: row.get::<_, i64>(7)? as u64,
+                file_count: row.get::<_, i64>(8)? as usize,
+                dir_count: row.get::<_, i64>(9)? as usize,
+                files_per_sec: row.get(10)?,
+                cache_hits: row.get::<_, i64>(11)? as usize,
+                cache_misses: row.get::<_, i64>(12)? as usize,
+                cache_hit_ratio: row.get(13)?,
+                network_mode: row.get(14)?,
+            })
+        })
+        .map_err(|e| format!("Query scan metrics failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to map scan metrics row: {}", e))
+}
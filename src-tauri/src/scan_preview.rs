@@ -0,0 +1,122 @@
+// src-tauri/src/scan_preview.rs
+// `preview_scan_cmd`: a dry run of a scan for debugging ignore rules. The
+// project is enumerated with the scanner's rules but no file is read; the result
+// lists what a scan would keep and what it would leave out, each exclusion with
+// the rule that decided it (see ignore_handler::Exclusion). Files the size/age
+// filters would drop are listed with their scan_filters::FILTER_* reason as the
+// source. Nothing is written.
+
+use crate::app_settings;
+use crate::db::AppState;
+use crate::dir_ignore_rules;
+use crate::errors::{self, app_error};
+use crate::ignore_handler::{CompiledIgnorePatterns, Exclusion};
+use crate::projects;
+use crate::scan_filters::ScanFilters;
+use crate::scan_tree::{explain_valid_items, ExcludedPath};
+use crate::types::{ScanError, SpecialFile};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{command, State};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PreviewEntry {
+    pub path: String,
+    pub is_dir: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanPreview {
+    pub project_id: i32,
+    /// What a scan would keep, by path (the root itself isn't listed)
+    pub included: Vec<PreviewEntry>,
+    /// What it would leave out, by path; an excluded folder's contents aren't listed
+    pub excluded: Vec<ExcludedPath>,
+    /// Folders at the depth limit whose contents a scan wouldn't visit
+    pub truncated_subtrees: Vec<String>,
+    /// Folders and files that couldn't be read
+    pub errors: Vec<ScanError>,
+    /// Named pipes, sockets and device files a scan would skip
+    pub special_files: Vec<SpecialFile>,
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Enumerates `project_id` like a scan would and returns the included paths and
+/// the excluded ones with the pattern (or other rule) that matched each.
+#[command(async)]
+pub async fn preview_scan_cmd(state: State<'_, AppState>, project_id: i32) -> Result<ScanPreview, String> {
+    let (project, global_patterns, dir_rules) = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        let project = projects::load_project_by_id(&conn, project_id)?;
+        let global_patterns: Vec<String> = app_settings::get_setting_internal(&conn, "default_ignore_patterns")
+            .map_err(|e| format!("Failed to query default_ignore_patterns from app_settings: {}", e))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let dir_rules = dir_ignore_rules::load_dir_rules(&conn, project_id)?;
+        (project, global_patterns, dir_rules)
+    };
+    let root_folder = project
+        .root_folder
+        .clone()
+        .ok_or_else(|| app_error(errors::PROJECT_NO_ROOT, &[("project_id", project_id.to_string())]))?;
+    let root = PathBuf::from(&root_folder);
+    if !root.is_dir() {
+        return Err(app_error(errors::ROOT_NOT_DIRECTORY, &[("path", root_folder)]));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut patterns = global_patterns;
+        patterns.extend_from_slice(&project.ignore_patterns);
+        let mut compiled = CompiledIgnorePatterns::new(&root, &patterns);
+        dir_ignore_rules::apply_dir_rules(&mut compiled, &dir_rules);
+        if project.respect_gitignore.unwrap_or(false) {
+            compiled.add_repository_gitignore(&root);
+        }
+        compiled.set_follow_symlinks(project.follow_symlinks.unwrap_or(false));
+        compiled.set_max_depth(project.max_scan_depth);
+        compiled.set_include_hidden(project.include_hidden.unwrap_or(true));
+        compiled.set_include_patterns(project.include_patterns.as_deref().unwrap_or_default());
+        let filters = ScanFilters::for_project(&project);
+
+        let mut paths = Vec::new();
+        let mut dirs: HashSet<PathBuf> = HashSet::new();
+        let mut preview = ScanPreview { project_id, ..Default::default() };
+        let truncated = explain_valid_items(&root, &compiled, &mut paths, &mut dirs, &mut preview.errors, &mut preview.special_files, &mut preview.excluded);
+        preview.truncated_subtrees = truncated.iter().map(|p| p.to_string_lossy().to_string()).collect();
+
+        // The size/age filters need each file's metadata, but not its contents
+        let filtered: Vec<ExcludedPath> = paths
+            .par_iter()
+            .filter(|p| !dirs.contains(*p))
+            .filter_map(|p| {
+                let reason = filters.exclusion(&fs::metadata(p).ok()?)?;
+                let exclusion = Exclusion { source: reason.to_string(), pattern: None, origin: None };
+                Some(ExcludedPath { path: p.to_string_lossy().to_string(), is_dir: false, exclusion })
+            })
+            .collect();
+        let filtered_paths: HashSet<&str> = filtered.iter().map(|f| f.path.as_str()).collect();
+        preview.included = paths
+            .iter()
+            .filter(|p| **p != root)
+            .map(|p| PreviewEntry { path: p.to_string_lossy().to_string(), is_dir: dirs.contains(p) })
+            .filter(|entry| !filtered_paths.contains(entry.path.as_str()))
+            .collect();
+        preview.excluded.extend(filtered);
+        preview.excluded.sort_by(|a, b| a.path.cmp(&b.path));
+
+        println!(
+            "[SCAN_PREVIEW] Project {}: {} included, {} excluded, {} unreadable",
+            project_id,
+            preview.included.len(),
+            preview.excluded.len(),
+            preview.errors.len()
+        );
+        preview
+    })
+    .await
+    .map_err(|e| format!("Scan preview task failed: {}", e))
+}
@@ -0,0 +1,143 @@
+// src-tauri/src/scan_progress.rs
+// `scan_progress` events of the processing phase come from one aggregator
+// thread instead of whichever worker wins a try_lock: workers only bump
+// counters, and the aggregator emits a snapshot every EMIT_INTERVAL or every
+// BATCH_ITEMS items, plus a final one when processing ends. Every progress
+// event carries a `seq` that only grows, so the UI can drop stale events.
+
+use crate::window_registry::emit_to_origin_and_project;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::Window;
+
+pub const PROGRESS_EVENT: &str = "scan_progress";
+const EMIT_INTERVAL: Duration = Duration::from_millis(100);
+const BATCH_ITEMS: usize = 500;
+
+// Sequence of all progress events, across scans
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+pub fn next_sequence() -> u64 {
+    SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// The `scan_progress` payload: percentage and the latest path, plus the
+/// throughput since `started` (items and bytes per second) and an ETA
+/// extrapolated from it.
+pub fn progress_payload(current_path: &Path, count: usize, total_items: usize, bytes_processed: u64, started: Instant) -> serde_json::Value {
+    let percentage = if total_items > 0 { (count as f64 / total_items as f64) * 100.0 } else { 100.0 };
+    let short_path = current_path
+        .file_name()
+        .map(|os| os.to_string_lossy())
+        .unwrap_or_else(|| current_path.display().to_string().into());
+
+    let elapsed = started.elapsed();
+    let seconds = elapsed.as_secs_f64();
+    let files_per_sec = if seconds > 0.0 { count as f64 / seconds } else { 0.0 };
+    let bytes_per_sec = if seconds > 0.0 { bytes_processed as f64 / seconds } else { 0.0 };
+    // None until there's a rate to extrapolate from
+    let eta_ms = (files_per_sec > 0.0).then(|| (total_items.saturating_sub(count) as f64 / files_per_sec * 1000.0) as u64);
+
+    serde_json::json!({
+        "seq": next_sequence(),
+        "progress": percentage,
+        "current_path": short_path,
+        "processed": count,
+        "total": total_items,
+        "files_per_sec": files_per_sec,
+        "bytes_processed": bytes_processed,
+        "bytes_per_sec": bytes_per_sec,
+        "elapsed_ms": elapsed.as_millis() as u64,
+        "eta_ms": eta_ms,
+    })
+}
+
+#[derive(Default)]
+struct Counters {
+    processed: AtomicUsize,
+    bytes: AtomicU64,
+    current: Mutex<Option<PathBuf>>,
+    // Set when processing ends; the aggregator then emits its last snapshot
+    done: Mutex<bool>,
+    wake: Condvar,
+}
+
+/// Progress of the processing phase; emits from its own thread until finished or dropped.
+pub struct ProgressAggregator {
+    counters: Arc<Counters>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProgressAggregator {
+    pub fn start(window: Window, project_id: i32, total_items: usize) -> Self {
+        let counters = Arc::new(Counters::default());
+        let shared = counters.clone();
+        let started = Instant::now();
+        let thread = std::thread::spawn(move || {
+            let mut emitted = usize::MAX; // Nothing emitted yet
+            let emit = |emitted: &mut usize| {
+                let count = shared.processed.load(Ordering::SeqCst);
+                let current = shared.current.lock().ok().and_then(|c| c.clone());
+                if count == *emitted {
+                    return;
+                }
+                let Some(current) = current else { return };
+                let payload = progress_payload(&current, count, total_items, shared.bytes.load(Ordering::SeqCst), started);
+                emit_to_origin_and_project(&window, project_id, PROGRESS_EVENT, payload);
+                *emitted = count;
+            };
+            let Ok(mut done) = shared.done.lock() else { return };
+            while !*done {
+                done = match shared.wake.wait_timeout(done, EMIT_INTERVAL) {
+                    Ok((guard, _)) => guard,
+                    Err(_) => return,
+                };
+                emit(&mut emitted);
+            }
+            drop(done);
+            emit(&mut emitted);
+        });
+        ProgressAggregator { counters, thread: Some(thread) }
+    }
+
+    /// Counts one item (file or folder) as processed.
+    pub fn record_item(&self, path: &Path) {
+        let count = self.counters.processed.fetch_add(1, Ordering::SeqCst) + 1;
+        // The path is only shown; skip it rather than wait for the lock
+        if let Ok(mut current) = self.counters.current.try_lock() {
+            *current = Some(path.to_path_buf());
+        }
+        if count.is_multiple_of(BATCH_ITEMS) {
+            self.counters.wake.notify_one();
+        }
+    }
+
+    /// Adds the size of a stat'ed file to the bytes processed.
+    pub fn add_bytes(&self, bytes: u64) {
+        self.counters.bytes.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    /// Stops the aggregator after it has emitted the final snapshot.
+    pub fn finish(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Ok(mut done) = self.counters.done.lock() {
+            *done = true;
+        }
+        self.counters.wake.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ProgressAggregator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
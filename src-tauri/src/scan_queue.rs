@@ -0,0 +1,198 @@
+// src-tauri/src/scan_queue.rs
+// Scans run one at a time: they share the file cache, and the scan code checks
+// cancellation and pauses of "the running scan" (see scan_state's registry).
+// Each request becomes a job that waits for its turn. A request identical to a
+// job that is still queued (same project and options) doesn't queue again; it
+// waits for that job and gets the same result.
+
+use crate::types::ScanOutput;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use tauri::command;
+
+// Finished jobs kept for get_scan_queue
+const MAX_FINISHED_JOBS: usize = 20;
+
+pub const STATE_QUEUED: &str = "queued";
+pub const STATE_RUNNING: &str = "running";
+pub const STATE_DONE: &str = "done";
+pub const STATE_CANCELLED: &str = "cancelled";
+pub const STATE_FAILED: &str = "failed";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanJobInfo {
+    pub id: u64,
+    pub project_id: i32,
+    /// "queued", "running", "done", "cancelled" or "failed"
+    pub state: String,
+    pub requested_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub error: Option<String>,
+    /// Identical requests that joined this job instead of queueing their own
+    pub joined_requests: usize,
+}
+
+struct ScanJob {
+    info: ScanJobInfo,
+    // Project + options; equal keys are redundant scans
+    key: String,
+    // Kept for joined requests until each has taken a copy
+    result: Option<Result<ScanOutput, String>>,
+    pending_readers: usize,
+}
+
+impl ScanJob {
+    fn is_finished(&self) -> bool {
+        !matches!(self.info.state.as_str(), STATE_QUEUED | STATE_RUNNING)
+    }
+}
+
+#[derive(Default)]
+struct ScanQueue {
+    jobs: Vec<ScanJob>,
+    next_id: u64,
+}
+
+static QUEUE: Lazy<(Mutex<ScanQueue>, Condvar)> = Lazy::new(|| (Mutex::new(ScanQueue::default()), Condvar::new()));
+
+fn lock_queue() -> MutexGuard<'static, ScanQueue> {
+    QUEUE.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn wait(guard: MutexGuard<'static, ScanQueue>) -> MutexGuard<'static, ScanQueue> {
+    QUEUE.1.wait(guard).unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub enum ScanTicket {
+    /// Run the scan once `wait_for_turn` returns
+    Owner(u64),
+    /// An identical scan is already queued; `wait_for_result` returns its result
+    Joined(u64),
+}
+
+/// Queues a scan of `project_id` with options summarized by `key`.
+pub fn enqueue(project_id: i32, key: String) -> ScanTicket {
+    let mut queue = lock_queue();
+    if let Some(job) = queue.jobs.iter_mut().find(|j| j.key == key && j.info.state == STATE_QUEUED) {
+        job.info.joined_requests += 1;
+        job.pending_readers += 1;
+        return ScanTicket::Joined(job.info.id);
+    }
+    queue.next_id += 1;
+    let id = queue.next_id;
+    queue.jobs.push(ScanJob {
+        info: ScanJobInfo {
+            id,
+            project_id,
+            state: STATE_QUEUED.to_string(),
+            requested_at: Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+            joined_requests: 0,
+        },
+        key,
+        result: None,
+        pending_readers: 0,
+    });
+    ScanTicket::Owner(id)
+}
+
+/// The running job's slot; finishing (or dropping it after a panic) lets the next job start.
+pub struct ScanTurn {
+    id: u64,
+    finished: bool,
+}
+
+impl ScanTurn {
+    pub fn finish(mut self, result: &Result<ScanOutput, String>, cancelled: bool) {
+        let state = match (result, cancelled) {
+            (Err(_), _) => STATE_FAILED,
+            (Ok(_), true) => STATE_CANCELLED,
+            (Ok(_), false) => STATE_DONE,
+        };
+        complete(self.id, state, result.as_ref().err().cloned(), Some(result));
+        self.finished = true;
+    }
+}
+
+impl Drop for ScanTurn {
+    fn drop(&mut self) {
+        if !self.finished {
+            let error = "The scan task stopped unexpectedly.".to_string();
+            complete(self.id, STATE_FAILED, Some(error.clone()), Some(&Err(error)));
+        }
+    }
+}
+
+fn complete(id: u64, state: &str, error: Option<String>, result: Option<&Result<ScanOutput, String>>) {
+    let mut queue = lock_queue();
+    if let Some(job) = queue.jobs.iter_mut().find(|j| j.info.id == id) {
+        job.info.state = state.to_string();
+        job.info.finished_at = Some(Utc::now().to_rfc3339());
+        job.info.error = error;
+        if job.pending_readers > 0 {
+            job.result = result.cloned();
+        }
+    }
+    prune(&mut queue);
+    QUEUE.1.notify_all();
+}
+
+// Drops the oldest finished jobs nobody is still waiting on
+fn prune(queue: &mut ScanQueue) {
+    let finished = queue.jobs.iter().filter(|j| j.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+    queue.jobs.retain(|job| {
+        if excess > 0 && job.is_finished() && job.pending_readers == 0 {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Blocks until job `id` is the oldest queued job and nothing is running, then marks it running.
+pub fn wait_for_turn(id: u64) -> ScanTurn {
+    let mut queue = lock_queue();
+    loop {
+        let running = queue.jobs.iter().any(|j| j.info.state == STATE_RUNNING);
+        let next = queue.jobs.iter().find(|j| j.info.state == STATE_QUEUED).map(|j| j.info.id);
+        if !running && next == Some(id) {
+            break;
+        }
+        queue = wait(queue);
+    }
+    if let Some(job) = queue.jobs.iter_mut().find(|j| j.info.id == id) {
+        job.info.state = STATE_RUNNING.to_string();
+        job.info.started_at = Some(Utc::now().to_rfc3339());
+    }
+    ScanTurn { id, finished: false }
+}
+
+/// Blocks until the joined job `id` finishes and returns a copy of its result.
+pub fn wait_for_result(id: u64) -> Result<ScanOutput, String> {
+    let mut queue = lock_queue();
+    loop {
+        let Some(job) = queue.jobs.iter_mut().find(|j| j.info.id == id) else {
+            return Err(format!("Scan job {} is no longer in the queue.", id));
+        };
+        if job.is_finished() {
+            job.pending_readers = job.pending_readers.saturating_sub(1);
+            let result = if job.pending_readers == 0 { job.result.take() } else { job.result.clone() };
+            return result.unwrap_or_else(|| Err(format!("Scan job {} finished without a result.", id)));
+        }
+        queue = wait(queue);
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// The most recent finished scans plus the running and queued ones, in request order.
+#[command]
+pub fn get_scan_queue() -> Result<Vec<ScanJobInfo>, String> {
+    Ok(lock_queue().jobs.iter().map(|j| j.info.clone()).collect())
+}
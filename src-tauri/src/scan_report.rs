@@ -0,0 +1,157 @@
+// src-tauri/src/scan_report.rs
+// `export_scan_report`: the last scan's files as a flat CSV or JSON file, one
+// row per file with its counts, for spreadsheets and other tooling.
+
+use crate::db::{self, AppState};
+use crate::projects;
+use crate::scan_results::collect_files;
+use crate::tree_queries::load_tree;
+use chrono::Utc;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+// Default destination, next to the database files
+const REPORTS_FOLDER: &str = "scan_reports";
+const CSV_HEADER: &str = "path,relative_path,language,extension,lines,tokens,size,last_modified,is_binary,skipped_reason";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanReportRow {
+    pub path: String,
+    /// Forward slashes, relative to the project root
+    pub relative_path: String,
+    pub language: Option<String>,
+    pub extension: Option<String>,
+    pub lines: usize,
+    pub tokens: usize,
+    pub size: u64,
+    pub last_modified: String,
+    pub is_binary: bool,
+    pub skipped_reason: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct JsonReport<'a> {
+    project_id: i32,
+    project_name: &'a str,
+    root_folder: &'a str,
+    exported_at: String,
+    files: &'a [ScanReportRow],
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanReportResult {
+    pub path: String,
+    pub format: String,
+    pub file_count: usize,
+    pub bytes: usize,
+}
+
+// Quoted when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[ScanReportRow]) -> String {
+    let mut out = format!("{}\n", CSV_HEADER);
+    for row in rows {
+        let fields = [
+            csv_field(&row.path),
+            csv_field(&row.relative_path),
+            csv_field(row.language.as_deref().unwrap_or("")),
+            csv_field(row.extension.as_deref().unwrap_or("")),
+            row.lines.to_string(),
+            row.tokens.to_string(),
+            row.size.to_string(),
+            csv_field(&row.last_modified),
+            row.is_binary.to_string(),
+            csv_field(row.skipped_reason.as_deref().unwrap_or("")),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// "scan-my-app-20250101-120000.csv" in the reports folder
+fn default_report_path(project_name: &str, extension: &str) -> Result<PathBuf, String> {
+    let slug = project_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+    let name = if slug.is_empty() { format!("scan-{}.{}", stamp, extension) } else { format!("scan-{}-{}.{}", slug, stamp, extension) };
+    Ok(db::db_dir()?.join(REPORTS_FOLDER).join(name))
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Writes the last scan of `project_id` as a flat file list to `path` (default:
+/// a timestamped file in the `scan_reports` folder next to the database).
+/// `format` is "csv" or "json".
+#[command]
+pub fn export_scan_report(
+    state: State<'_, AppState>,
+    project_id: i32,
+    format: String,
+    path: Option<String>,
+) -> Result<ScanReportResult, String> {
+    let format = format.trim().to_lowercase();
+    if format != "csv" && format != "json" {
+        return Err(format!("Unknown report format '{}'; use \"csv\" or \"json\".", format));
+    }
+    let project = {
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        projects::load_project_by_id(&conn, project_id)?
+    };
+    let tree = load_tree(&state, project_id)?;
+    let root = Path::new(&tree.path);
+
+    let mut rows: Vec<ScanReportRow> = collect_files(&tree)
+        .into_iter()
+        .map(|file| ScanReportRow {
+            relative_path: Path::new(&file.path).strip_prefix(root).map(|r| r.to_string_lossy().replace('\\', "/")).unwrap_or_default(),
+            path: file.path.clone(),
+            language: file.language.clone(),
+            extension: file.extension.clone(),
+            lines: file.lines,
+            tokens: file.tokens,
+            size: file.size,
+            last_modified: file.last_modified.clone(),
+            is_binary: file.is_binary,
+            skipped_reason: file.skipped_reason.clone(),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let content = if format == "csv" {
+        to_csv(&rows)
+    } else {
+        let report = JsonReport {
+            project_id,
+            project_name: &project.title,
+            root_folder: &tree.path,
+            exported_at: Utc::now().to_rfc3339(),
+            files: &rows,
+        };
+        serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize scan report: {}", e))?
+    };
+
+    let path = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => default_report_path(&project.title, &format)?,
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder '{}': {}", parent.display(), e))?;
+    }
+    fs::write(&path, &content).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    println!("[SCAN_REPORT] Project {}: {} file(s) as {} to '{}'", project_id, rows.len(), format, path.display());
+    Ok(ScanReportResult { path: path.to_string_lossy().to_string(), format, file_count: rows.len(), bytes: content.len() })
+}
@@ -0,0 +1,216 @@
+// src-tauri/src/scan_results.rs
+// Persists the most recent scan tree per project so it can be patched between
+// scans (monitor deletions, recounts) and queried without rescanning.
+
+use crate::path_case::CaseMode;
+use crate::scan_cache::CacheEntry;
+use crate::scan_tree::{assign_token_percentiles, reaggregate_node, TreeSort};
+use crate::types::{FileEntry, FileNode, ScanStatistics, ScanSummary};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Stores (or replaces) the last scan tree of a project.
+pub fn save_scan_result(conn: &Connection, project_id: i32, tree: &FileNode) -> Result<(), String> {
+    let tree_json = serde_json::to_string(tree)
+        .map_err(|e| format!("Failed to serialize scan tree for project {}: {}", project_id, e))?;
+    conn.execute(
+        r#"
+        INSERT INTO code_context_builder_scan_results (project_id, tree_json, scanned_at)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT(project_id) DO UPDATE SET
+            tree_json = excluded.tree_json,
+            scanned_at = excluded.scanned_at
+        "#,
+        params![project_id, tree_json, Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| format!("Failed to save scan result for project {}: {}", project_id, e))?;
+    Ok(())
+}
+
+/// Records how many files the last full scan took from the cache (hits) vs recounted (misses).
+/// Kept apart from `save_scan_result`, which monitor patches call too.
+pub fn record_cache_usage(conn: &Connection, project_id: i32, hits: usize, misses: usize) -> Result<(), String> {
+    conn.execute(
+        "UPDATE code_context_builder_scan_results SET cache_hits = ?1, cache_misses = ?2 WHERE project_id = ?3",
+        params![hits as i64, misses as i64, project_id],
+    )
+    .map_err(|e| format!("Failed to record cache usage for project {}: {}", project_id, e))?;
+    Ok(())
+}
+
+/// Loads the last scan tree of a project, if it has been scanned before.
+pub fn load_scan_result(conn: &Connection, project_id: i32) -> Result<Option<FileNode>, String> {
+    let tree_json: Option<String> = conn
+        .query_row(
+            "SELECT tree_json FROM code_context_builder_scan_results WHERE project_id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load scan result for project {}: {}", project_id, e))?;
+
+    match tree_json {
+        Some(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Stored scan tree for project {} is corrupt: {}", project_id, e)),
+        None => Ok(None),
+    }
+}
+
+/// Removes the node at `path` (file or directory) and re-aggregates the
+/// totals of its ancestors. Returns the removed node, if it was found.
+pub fn remove_node(root: &mut FileNode, path: &str, case_mode: CaseMode) -> Option<FileNode> {
+    let removed = remove_node_recursive(root, path, case_mode);
+    if removed.is_some() {
+        reaggregate_node(root);
+        assign_token_percentiles(root);
+    }
+    removed
+}
+
+fn remove_node_recursive(node: &mut FileNode, path: &str, case_mode: CaseMode) -> Option<FileNode> {
+    if !node.is_dir {
+        return None;
+    }
+    if let Some(index) = node.children.iter().position(|c| case_mode.eq(&c.path, path)) {
+        return Some(node.children.remove(index));
+    }
+    node.children
+        .iter_mut()
+        .filter(|c| c.is_dir && case_mode.starts_with(path, &c.path))
+        .find_map(|c| remove_node_recursive(c, path, case_mode))
+}
+
+/// Replaces the stats of the file node at `path` and re-aggregates ancestors.
+/// Returns false when the path isn't in the tree.
+pub fn update_file_node(root: &mut FileNode, path: &str, entry: &CacheEntry, case_mode: CaseMode) -> bool {
+    let updated = match find_node_mut(root, path, case_mode) {
+        Some(node) if !node.is_dir => {
+            node.lines = entry.lines;
+            node.tokens = entry.tokens;
+            node.size = entry.size;
+            node.last_modified = entry.last_modified.clone();
+            node.is_binary = entry.is_binary;
+            node.skipped_reason = entry.skipped_reason.clone();
+            true
+        }
+        _ => false,
+    };
+    if updated {
+        reaggregate_node(root);
+        assign_token_percentiles(root);
+    }
+    updated
+}
+
+pub fn find_node_mut<'a>(node: &'a mut FileNode, path: &str, case_mode: CaseMode) -> Option<&'a mut FileNode> {
+    if case_mode.eq(&node.path, path) {
+        return Some(node);
+    }
+    node.children
+        .iter_mut()
+        .filter(|c| case_mode.eq(&c.path, path) || (c.is_dir && case_mode.starts_with(path, &c.path)))
+        .find_map(|c| find_node_mut(c, path, case_mode))
+}
+
+// `path` is `dir` or inside it
+fn is_within(path: &str, dir: &str, case_mode: CaseMode) -> bool {
+    case_mode.eq(path, dir)
+        || (case_mode.starts_with(path, dir) && path.get(dir.len()..).is_some_and(|rest| rest.starts_with(['/', '\\'])))
+}
+
+/// Puts the result of a scan limited to the folder `sub_path` into the saved
+/// tree: `scanned` holds the project root, the folders leading down to
+/// `sub_path` and its contents. A `sub_path` missing from `scanned` (deleted
+/// or now ignored) is removed from `saved`. Totals are re-aggregated.
+pub fn merge_subtree(saved: &mut FileNode, mut scanned: FileNode, sub_path: &str, case_mode: CaseMode, sort: TreeSort) {
+    // Deepest saved folder on the way to sub_path
+    let mut anchor = &mut *saved;
+    while !case_mode.eq(&anchor.path, sub_path) {
+        match anchor.children.iter().position(|c| c.is_dir && is_within(sub_path, &c.path, case_mode)) {
+            Some(index) => anchor = &mut anchor.children[index],
+            None => break,
+        }
+    }
+
+    if case_mode.eq(&anchor.path, sub_path) {
+        match find_node_mut(&mut scanned, sub_path, case_mode).filter(|n| n.is_dir) {
+            Some(fresh) => *anchor = std::mem::replace(fresh, FileNode::new_dir(String::new(), String::new())),
+            None => {
+                remove_node(saved, sub_path, case_mode);
+                return;
+            }
+        }
+    } else {
+        // A new folder: graft the scanned branch below the anchor
+        let anchor_path = anchor.path.clone();
+        let branch = find_node_mut(&mut scanned, &anchor_path, case_mode).and_then(|scanned_anchor| {
+            let index = scanned_anchor.children.iter().position(|c| is_within(sub_path, &c.path, case_mode))?;
+            Some(scanned_anchor.children.remove(index))
+        });
+        if let Some(branch) = branch {
+            anchor.children.push(branch);
+            anchor.children.sort_by(|a, b| sort.compare(a, b));
+        }
+    }
+    reaggregate_node(saved);
+    assign_token_percentiles(saved);
+}
+
+/// Per-extension and per-language breakdown of a scanned tree, with its
+/// `top` heaviest files and the scan's cache hits/misses.
+pub fn scan_statistics(project_id: i32, root: &FileNode, top: usize, cache_hits: usize, cache_misses: usize) -> ScanStatistics {
+    let mut statistics = ScanStatistics { project_id, cache_hits, cache_misses, ..Default::default() };
+    let mut files = collect_files(root);
+    for file in &files {
+        *statistics.files_by_extension.entry(file.extension.clone().unwrap_or_default()).or_insert(0) += 1;
+        let language = file.language.clone().unwrap_or_else(|| "other".to_string());
+        *statistics.tokens_by_language.entry(language).or_insert(0) += file.tokens;
+    }
+    files.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+    statistics.largest_files = files.into_iter().take(top).map(FileEntry::from).collect();
+    statistics
+}
+
+/// All file (non-directory) nodes of a tree, depth-first.
+pub fn collect_files(node: &FileNode) -> Vec<&FileNode> {
+    let mut files = Vec::new();
+    collect_files_into(node, &mut files);
+    files
+}
+
+fn collect_files_into<'a>(node: &'a FileNode, files: &mut Vec<&'a FileNode>) {
+    if node.is_dir {
+        for child in &node.children {
+            collect_files_into(child, files);
+        }
+    } else {
+        files.push(node);
+    }
+}
+
+/// Totals of a complete scan tree. `left_out` counts files dropped from the
+/// tree entirely (e.g. skipped cloud placeholders) by reason.
+pub fn summarize_tree(root: &FileNode, left_out: &[(&str, usize)], duration_ms: u64) -> ScanSummary {
+    fn count_dirs(node: &FileNode) -> usize {
+        node.children.iter().filter(|c| c.is_dir).map(|c| 1 + count_dirs(c)).sum()
+    }
+    let mut summary = ScanSummary {
+        total_dirs: count_dirs(root),
+        total_lines: root.lines,
+        total_tokens: root.tokens,
+        total_bytes: root.size,
+        duration_ms,
+        ..Default::default()
+    };
+    for file in collect_files(root) {
+        summary.total_files += 1;
+        if let Some(reason) = &file.skipped_reason {
+            *summary.skipped_by_reason.entry(reason.clone()).or_insert(0) += 1;
+        }
+    }
+    for (reason, count) in left_out.iter().filter(|(_, c)| *c > 0) {
+        *summary.skipped_by_reason.entry(reason.to_string()).or_insert(0) += count;
+    }
+    summary
+}
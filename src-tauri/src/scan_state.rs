@@ -1,15 +1,177 @@
 // src-tauri/src/scan_state.rs
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 
-// Global flag for cancellation
-static CANCEL_SCAN: AtomicBool = AtomicBool::new(false);
+// --- Scan Registry ---
+// Every scan gets a token under its scan id (the scan queue's job id) when it's
+// queued, so cancelling or pausing targets that one scan. Scans run one at a
+// time (see scan_queue); the scan code asks about "the running scan" through
+// `is_scan_cancelled` / `wait_while_paused` instead of passing its token down.
 
-// Set the cancellation flag
-pub fn set_cancel_scan(value: bool) {
-    CANCEL_SCAN.store(value, Ordering::SeqCst);
+/// Cancellation and pause state of one scan.
+#[derive(Default)]
+pub struct ScanToken {
+    cancelled: AtomicBool,
+    // Workers wait on the condvar while paused
+    paused: Mutex<bool>,
+    resumed: Condvar,
 }
 
-// Check if cancellation has been requested
+impl ScanToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn cancel(&self) {
+        // Under the pause lock, so a worker between its check and its wait
+        // can't miss the wake-up; a paused scan wakes to see it was cancelled
+        let _paused = self.paused.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.resumed.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.lock().map(|p| *p).unwrap_or(false)
+    }
+
+    fn set_paused(&self, value: bool) {
+        if let Ok(mut paused) = self.paused.lock() {
+            *paused = value;
+        }
+        if !value {
+            self.resumed.notify_all();
+        }
+    }
+
+    fn wait_while_paused(&self) {
+        let Ok(guard) = self.paused.lock() else { return };
+        let _guard = self.resumed.wait_while(guard, |paused| *paused && !self.is_cancelled());
+    }
+}
+
+#[derive(Default)]
+struct ScanRegistry {
+    scans: HashMap<u64, Arc<ScanToken>>,
+    running: Option<u64>,
+}
+
+static SCANS: Lazy<Mutex<ScanRegistry>> = Lazy::new(|| Mutex::new(ScanRegistry::default()));
+
+fn with_registry<T>(f: impl FnOnce(&mut ScanRegistry) -> T) -> T {
+    let mut registry = SCANS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut registry)
+}
+
+/// A registered scan; unregistered when dropped.
+pub struct ScanRegistration {
+    id: u64,
+}
+
+impl ScanRegistration {
+    /// Marks this scan as the running one (call once it has its turn).
+    pub fn start(&self) {
+        with_registry(|r| r.running = Some(self.id));
+    }
+}
+
+impl Drop for ScanRegistration {
+    fn drop(&mut self) {
+        with_registry(|r| {
+            r.scans.remove(&self.id);
+            if r.running == Some(self.id) {
+                r.running = None;
+            }
+        });
+    }
+}
+
+/// Registers scan `id`; it can be cancelled or paused from now on, even while queued.
+pub fn register_scan(id: u64) -> ScanRegistration {
+    with_registry(|r| r.scans.insert(id, Arc::new(ScanToken::default())));
+    ScanRegistration { id }
+}
+
+fn token(id: Option<u64>) -> Option<Arc<ScanToken>> {
+    with_registry(|r| id.or(r.running).and_then(|id| r.scans.get(&id).cloned()))
+}
+
+/// Cancels scan `id`; false when no such scan is registered.
+pub fn cancel_scan(id: u64) -> bool {
+    token(Some(id)).map(|t| t.cancel()).is_some()
+}
+
+/// Cancels every registered scan, running or queued; returns how many.
+pub fn cancel_all_scans() -> usize {
+    let tokens: Vec<Arc<ScanToken>> = with_registry(|r| r.scans.values().cloned().collect());
+    tokens.iter().for_each(|t| t.cancel());
+    tokens.len()
+}
+
+/// Pauses (or resumes) scan `id`, or the running scan when None. Returns the
+/// previous pause state; None when there is no such scan.
+pub fn set_scan_paused(id: Option<u64>, value: bool) -> Option<bool> {
+    let token = token(id)?;
+    let was_paused = token.is_paused();
+    token.set_paused(value);
+    Some(was_paused)
+}
+
+/// Whether the running scan has been cancelled.
 pub fn is_scan_cancelled() -> bool {
-    CANCEL_SCAN.load(Ordering::SeqCst)
-}
\ No newline at end of file
+    token(None).is_some_and(|t| t.is_cancelled())
+}
+
+/// Blocks the calling worker while the running scan is paused (and not cancelled).
+pub fn wait_while_paused() {
+    if let Some(token) = token(None) {
+        token.wait_while_paused();
+    }
+}
+
+// Cancellation flags of running batch reads, by the caller's request ID
+static BATCH_READS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Cancellation flag of one batch read; unregistered when dropped.
+pub struct BatchReadToken {
+    request_id: Option<String>,
+    flag: Arc<AtomicBool>,
+}
+
+impl BatchReadToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for BatchReadToken {
+    fn drop(&mut self) {
+        let Some(id) = &self.request_id else { return };
+        if let Ok(mut reads) = BATCH_READS.lock() {
+            // A newer read may have reused the ID; only remove our own flag
+            if reads.get(id).is_some_and(|flag| Arc::ptr_eq(flag, &self.flag)) {
+                reads.remove(id);
+            }
+        }
+    }
+}
+
+// Registers a batch read; without a request ID it can't be cancelled
+pub fn begin_batch_read(request_id: Option<String>) -> BatchReadToken {
+    let flag = Arc::new(AtomicBool::new(false));
+    if let Some(id) = &request_id {
+        if let Ok(mut reads) = BATCH_READS.lock() {
+            reads.insert(id.clone(), flag.clone());
+        }
+    }
+    BatchReadToken { request_id, flag }
+}
+
+// Flags the batch read; false when no read with that ID is running
+pub fn cancel_batch_read_request(request_id: &str) -> bool {
+    match BATCH_READS.lock() {
+        Ok(reads) => reads.get(request_id).map(|flag| flag.store(true, Ordering::Relaxed)).is_some(),
+        Err(_) => false,
+    }
+}
@@ -1,55 +1,211 @@
 
 // src-tauri/src/scan_tree.rs
 
-use crate::types::FileNode;
+use crate::types::{FileNode, ScanError, SpecialFile};
 use crate::scan_cache::CacheEntry;
 use crate::scan_state::is_scan_cancelled;
-use crate::ignore_handler::CompiledIgnorePatterns; // <--- ADD THIS
+use crate::ignore_handler::{CompiledIgnorePatterns, Exclusion, GitignoreStack}; // <--- ADD THIS
+use serde::Serialize;
+use ignore::{DirEntry, ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkState};
 use std::fs;
 use std::path::{Path, PathBuf, Component};
 use std::time::SystemTime;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use crate::net_fs::{is_transient, retry_io};
+use crate::file_types::language_for_path;
 
-// --- finalize_node (This version is simplified, assuming aggregation logic is fine for now) ---
-fn finalize_node(node: &mut FileNode) {
+// --- Tree ordering ---
+// Order of siblings in a finalized tree. Files always come before directories;
+// within each group nodes are ordered by the chosen key, ties broken by name.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TreeSort {
+    #[default]
+    Name,
+    TokensDesc,
+    SizeDesc,
+    ModifiedDesc,
+}
+
+impl TreeSort {
+    /// Parses a scan `sort_by` option: "name" | "tokens" | "size" | "modified".
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("") | Some("name") => Ok(TreeSort::Name),
+            Some("tokens") => Ok(TreeSort::TokensDesc),
+            Some("size") => Ok(TreeSort::SizeDesc),
+            Some("modified") => Ok(TreeSort::ModifiedDesc),
+            Some(other) => Err(format!("Unknown sort option '{}'. Use name, tokens, size or modified.", other)),
+        }
+    }
+
+    pub fn compare(self, a: &FileNode, b: &FileNode) -> std::cmp::Ordering {
+        let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        let mtime = |n: &FileNode| n.last_modified.parse::<u64>().unwrap_or(0);
+        match (a.is_dir, b.is_dir) {
+            (false, true) => std::cmp::Ordering::Less,
+            (true, false) => std::cmp::Ordering::Greater,
+            _ => match self {
+                TreeSort::Name => by_name(),
+                TreeSort::TokensDesc => b.tokens.cmp(&a.tokens).then_with(by_name),
+                TreeSort::SizeDesc => b.size.cmp(&a.size).then_with(by_name),
+                TreeSort::ModifiedDesc => mtime(b).cmp(&mtime(a)).then_with(by_name),
+            },
+        }
+    }
+}
+
+// --- finalize_node ---
+// Sorts children by name and aggregates lines/tokens/size, the newest descendant
+// mtime (as `last_modified`) and direct/recursive file counts onto directories.
+pub fn finalize_node(node: &mut FileNode) {
+    finalize(node, Some(TreeSort::Name));
+}
+
+// Same as finalize_node with a caller-chosen sibling order.
+pub fn finalize_node_sorted(node: &mut FileNode, sort: TreeSort) {
+    finalize(node, Some(sort));
+}
+
+// Re-aggregates directory totals after a patch (node removed/updated) while
+// keeping the existing sibling order.
+pub fn reaggregate_node(node: &mut FileNode) {
+    finalize(node, None);
+}
+
+fn finalize(node: &mut FileNode, sort: Option<TreeSort>) {
     if node.is_dir {
         for child in &mut node.children {
-            finalize_node(child); 
+            finalize(child, sort); 
+        }
+        if let Some(sort) = sort {
+            node.children.sort_by(|a, b| sort.compare(a, b));
         }
-        node.children.sort_by(|a, b| {
-            match (a.is_dir, b.is_dir) {
-                (false, true) => std::cmp::Ordering::Less,
-                (true, false) => std::cmp::Ordering::Greater,
-                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            }
-        });
         // Reset parent stats before summing
         node.lines = 0;
         node.tokens = 0;
         node.size = 0;
+        node.direct_file_count = 0;
+        node.file_count = 0;
+        let mut newest: Option<u64> = None;
         for child in &node.children { 
             node.lines += child.lines;
             node.tokens += child.tokens;
             node.size += child.size;
+            if child.is_dir {
+                node.file_count += child.file_count;
+            } else {
+                node.direct_file_count += 1;
+                node.file_count += 1;
+            }
+            // Timestamps are unix seconds; empty/unparsable ones don't count
+            if let Ok(secs) = child.last_modified.parse::<u64>() {
+                newest = Some(newest.map_or(secs, |n| n.max(secs)));
+            }
+        }
+        node.last_modified = newest.map(|s| s.to_string()).unwrap_or_default();
+    }
+}
+
+// --- assign_token_percentiles ---
+// Sets each file's `token_percentile` within the whole tree: the percentage of
+// files whose token count is <= its own (the heaviest file gets 100).
+pub fn assign_token_percentiles(root: &mut FileNode) {
+    fn collect(node: &FileNode, tokens: &mut Vec<usize>) {
+        if node.is_dir {
+            node.children.iter().for_each(|c| collect(c, tokens));
+        } else {
+            tokens.push(node.tokens);
+        }
+    }
+    fn assign(node: &mut FileNode, sorted: &[usize]) {
+        if node.is_dir {
+            node.children.iter_mut().for_each(|c| assign(c, sorted));
+        } else {
+            let at_or_below = sorted.partition_point(|&t| t <= node.tokens);
+            node.token_percentile = at_or_below as f64 * 100.0 / sorted.len() as f64;
+        }
+    }
+    let mut sorted = Vec::new();
+    collect(root, &mut sorted);
+    if sorted.is_empty() {
+        return;
+    }
+    sorted.sort_unstable();
+    assign(root, &sorted);
+}
+
+// --- prune_empty_dirs ---
+// Drops directories that contribute no tokens (empty, or only ignored/oversized/
+// binary content below them) and re-aggregates. The root itself is kept, and so
+// are folders that couldn't be read.
+pub fn prune_empty_dirs(root: &mut FileNode) {
+    fn prune(node: &mut FileNode) {
+        node.children.retain(|c| !c.is_dir || c.tokens > 0 || c.error.is_some());
+        for child in node.children.iter_mut().filter(|c| c.is_dir) {
+            prune(child);
         }
     }
+    if root.is_dir {
+        prune(root);
+        reaggregate_node(root);
+    }
+}
+
+// --- mark_errors ---
+// Sets `error` on the nodes of the entries a scan couldn't read.
+pub fn mark_errors(root: &mut FileNode, errors: &[ScanError]) {
+    fn mark(node: &mut FileNode, by_path: &HashMap<&str, &str>) {
+        if let Some(message) = by_path.get(node.path.as_str()) {
+            node.error = Some(message.to_string());
+        }
+        for child in &mut node.children {
+            mark(child, by_path);
+        }
+    }
+    if !errors.is_empty() {
+        let by_path: HashMap<&str, &str> = errors.iter().map(|e| (e.path.as_str(), e.message.as_str())).collect();
+        mark(root, &by_path);
+    }
+}
+
+// --- node_id ---
+// FNV-1a (64-bit) of the root-relative, '/'-separated path. Deterministic across
+// runs and platforms, so successive scans of a project give a node the same id.
+pub fn node_id(root_path: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root_path).unwrap_or(path);
+    let key = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{:016x}", fnv1a(key.as_bytes()))
+}
+
+/// 64-bit FNV-1a; stable across runs, unlike `DefaultHasher`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
 }
 
 // --- build_tree_from_paths ---
 pub fn build_tree_from_paths(
     root_path: &Path,
     valid_paths: &[PathBuf],
+    dir_paths: &HashSet<PathBuf>, // Directories seen during enumeration (avoids re-stat'ing)
     cache_map: &HashMap<String, CacheEntry>,
+    sort: TreeSort,
 ) -> FileNode {
     let root_path_str = root_path.to_string_lossy().to_string();
-    let mut root_node = FileNode {
-        path: root_path_str.clone(),
-        name: root_path.file_name().map(|os| os.to_string_lossy().to_string()).unwrap_or_else(|| root_path_str.clone()),
-        is_dir: true,
-        lines: 0, tokens: 0, size: 0, 
-        last_modified: "".to_string(), 
-        children: Vec::new(),
-    };
+    let mut root_node = FileNode::new_dir(
+        root_path_str.clone(),
+        root_path.file_name().map(|os| os.to_string_lossy().to_string()).unwrap_or_else(|| root_path_str.clone()),
+    );
+    root_node.id = node_id(root_path, root_path);
 
     if valid_paths.is_empty() {
         finalize_node(&mut root_node); 
@@ -61,15 +217,36 @@ pub fn build_tree_from_paths(
     for path_buf in valid_paths {
          let path_str = path_buf.to_string_lossy().to_string();
          let name = path_buf.file_name().map(|os| os.to_string_lossy().to_string()).unwrap_or_else(|| path_str.clone());
-         let is_dir = path_buf.is_dir();
-         let (lines, tokens, size, last_modified) = if !is_dir {
-             cache_map.get(&path_str).map_or((0, 0, 0, "".to_string()), |entry| (entry.lines, entry.tokens, entry.size, entry.last_modified.clone()))
+         let is_dir = dir_paths.contains(path_buf);
+         let node = if is_dir {
+             let mut dir = FileNode::new_dir(path_str.clone(), name);
+             dir.id = node_id(root_path, path_buf);
+             dir
          } else {
-             (0, 0, 0, "".to_string()) 
+             let entry = cache_map.get(&path_str);
+             FileNode {
+                 id: node_id(root_path, path_buf),
+                 path: path_str.clone(),
+                 name,
+                 is_dir,
+                 lines: entry.map_or(0, |e| e.lines),
+                 tokens: entry.map_or(0, |e| e.tokens),
+                 size: entry.map_or(0, |e| e.size),
+                 last_modified: entry.map_or_else(String::new, |e| e.last_modified.clone()),
+                 children: Vec::new(),
+                 extension: path_buf.extension().map(|e| e.to_string_lossy().to_lowercase()),
+                 language: language_for_path(path_buf).map(String::from),
+                 is_binary: entry.is_some_and(|e| e.is_binary),
+                 skipped_reason: entry.and_then(|e| e.skipped_reason.clone()),
+                 direct_file_count: 0,
+                 file_count: 0,
+                 has_more: false,
+                 token_percentile: 0.0,
+                 pinned: false,
+                 error: None,
+             }
          };
-         node_data_map.insert(path_str.clone(), FileNode {
-             path: path_str, name, is_dir, lines, tokens, size, last_modified, children: Vec::new(),
-         });
+         node_data_map.insert(path_str, node);
     }
 
     let mut sorted_paths = valid_paths.to_vec();
@@ -98,7 +275,8 @@ pub fn build_tree_from_paths(
         }
     }
     
-    finalize_node(&mut root_node);
+    finalize_node_sorted(&mut root_node, sort);
+    assign_token_percentiles(&mut root_node);
     // println!("[BUILD_TREE_POST_FINALIZE] Root Node '{}' Final L/T/S: {}/{}/{}", root_node.name, root_node.lines, root_node.tokens, root_node.size);
     root_node
 }
@@ -133,55 +311,393 @@ fn insert_node_recursive(
     }
 }
 
+// Identity of a folder, so a walk that follows symlinks enters each one once
+#[cfg(unix)]
+type DirIdentity = (u64, u64);
+#[cfg(not(unix))]
+type DirIdentity = PathBuf;
+
+#[cfg(unix)]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_identity(path: &Path) -> Option<DirIdentity> {
+    fs::canonicalize(path).ok()
+}
+
+/// Folder levels below the root a walk descends into, unless the project sets its own.
+pub const DEFAULT_MAX_SCAN_DEPTH: usize = 30;
+
+// Shared by the walker's threads: the ignore rules, the .gitignore stacks of
+// the folders seen so far and, when symlinks are followed, the linked folders entered
+struct WalkFilter {
+    ignores: CompiledIgnorePatterns,
+    // Stack applying to the entries of a folder (its own .gitignore included)
+    stacks: Mutex<HashMap<PathBuf, Arc<GitignoreStack>>>,
+    // Where the walk started, resolved; links back into it are left out
+    start: Option<PathBuf>,
+    visited: Option<Mutex<HashSet<DirIdentity>>>,
+    // Collects what the walk leaves out and why (explain_valid_items only)
+    excluded: Option<Mutex<Vec<ExcludedPath>>>,
+}
+
+/// An entry a walk left out, with the rule that excluded it (explain_valid_items).
+#[derive(Serialize, Debug, Clone)]
+pub struct ExcludedPath {
+    pub path: String,
+    pub is_dir: bool,
+    #[serde(flatten)]
+    pub exclusion: Exclusion,
+}
+
+impl WalkFilter {
+    fn stack_inside(&self, dir: &Path) -> Arc<GitignoreStack> {
+        let root = self.ignores.root();
+        if !self.ignores.follows_nested_gitignores() || !dir.starts_with(root) {
+            return Arc::default();
+        }
+        if let Some(stack) = self.stacks.lock().ok().and_then(|s| s.get(dir).cloned()) {
+            return stack;
+        }
+        let mut stack = match dir.parent() {
+            Some(parent) if dir != root => (*self.stack_inside(parent)).clone(),
+            _ => Vec::new(),
+        };
+        stack.extend(self.ignores.nested_gitignore(dir));
+        let stack = Arc::new(stack);
+        if let Ok(mut stacks) = self.stacks.lock() {
+            stacks.insert(dir.to_path_buf(), stack.clone());
+        }
+        stack
+    }
+
+    fn keep(&self, path: &Path, is_dir: bool, is_symlink: bool) -> bool {
+        let stack = path.parent().map(|parent| self.stack_inside(parent)).unwrap_or_default();
+        let ignored = match &self.excluded {
+            Some(_) => self.ignores.explain(path, is_dir, &stack).map(|exclusion| self.record(path, is_dir, exclusion)).is_some(),
+            None => self.ignores.is_ignored_in(path, is_dir, &stack),
+        };
+        if ignored {
+            return false;
+        }
+        if !(is_dir && is_symlink) || self.enters_link(path) {
+            return true;
+        }
+        self.record(path, is_dir, Exclusion { source: "symlink".to_string(), pattern: None, origin: None });
+        false
+    }
+
+    fn record(&self, path: &Path, is_dir: bool, exclusion: Exclusion) {
+        if let Some(Ok(mut excluded)) = self.excluded.as_ref().map(|e| e.lock()) {
+            excluded.push(ExcludedPath { path: path.to_string_lossy().to_string(), is_dir, exclusion });
+        }
+    }
+
+    fn enters_link(&self, path: &Path) -> bool {
+        let Some(visited) = &self.visited else { return false };
+        // Threads race, so a linked folder inside the walk is always left to its
+        // real path; linked folders elsewhere are entered once
+        let target = fs::canonicalize(path).ok();
+        if target.as_ref().zip(self.start.as_ref()).is_some_and(|(target, start)| target.starts_with(start)) {
+            return false;
+        }
+        match (dir_identity(path), visited.lock()) {
+            (Some(identity), Ok(mut visited)) => visited.insert(identity),
+            _ => true,
+        }
+    }
+}
+
+// (path, is_dir) of every kept entry, the folders cut off by the depth limit,
+// the entries that couldn't be read, the special files left out and the
+// folders whose listing failed with a transient error (walked again after)
+type Gathered = Mutex<(Vec<(PathBuf, bool)>, Vec<PathBuf>, Vec<ScanError>, Vec<SpecialFile>, Vec<(PathBuf, ScanError)>)>;
+
+// What one walker thread found; merged into the shared results when it's dropped
+struct GatherVisitor<'a> {
+    results: &'a Gathered,
+    found: Vec<(PathBuf, bool)>,
+    truncated: Vec<PathBuf>,
+    errors: Vec<ScanError>,
+    special: Vec<SpecialFile>,
+    retry: Vec<(PathBuf, ScanError)>,
+    // Entry depth at which folders aren't descended into
+    depth_left: usize,
+}
+
+// The path and cause of a walk error; None for symlink loops, which the walk
+// expects when following links
+fn walk_error(error: &ignore::Error) -> Option<ScanError> {
+    match error {
+        ignore::Error::WithPath { path, err } => {
+            let message = match &**err {
+                ignore::Error::Io(io) => io.to_string(),
+                other => other.to_string(),
+            };
+            Some(ScanError { path: path.to_string_lossy().to_string(), message })
+        }
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => walk_error(err),
+        ignore::Error::Partial(errors) => errors.iter().find_map(walk_error),
+        _ => None,
+    }
+}
+
+// The folder of a listing that failed with a transient IO error (see net_fs)
+fn transient_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, err } => match &**err {
+            ignore::Error::Io(io) if is_transient(io) => Some(path.as_path()),
+            _ => None,
+        },
+        ignore::Error::WithDepth { err, .. } => transient_path(err),
+        _ => None,
+    }
+}
+
+impl ParallelVisitor for GatherVisitor<'_> {
+    fn visit(&mut self, entry: Result<DirEntry, ignore::Error>) -> WalkState {
+        if is_scan_cancelled() {
+            return WalkState::Quit;
+        }
+        // Unreadable folders and entries are recorded; symlink loops are left out
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                match (transient_path(&e), walk_error(&e)) {
+                    (Some(path), Some(error)) => self.retry.push((path.to_path_buf(), error)),
+                    (_, error) => self.errors.extend(error),
+                }
+                return WalkState::Continue;
+            }
+        };
+        let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+        // Named pipes, sockets and devices (or links to them) would block or never end when read
+        if let Some(kind) = entry_special_kind(&entry).filter(|_| !is_dir) {
+            self.special.push(SpecialFile { path: entry.path().to_string_lossy().to_string(), kind: kind.to_string() });
+            return WalkState::Continue;
+        }
+        if is_dir
+            && entry.depth() >= self.depth_left
+            && retry_io(|| fs::read_dir(entry.path())).is_ok_and(|mut entries| entries.next().is_some())
+        {
+            self.truncated.push(entry.path().to_path_buf());
+        }
+        self.found.push((entry.into_path(), is_dir));
+        WalkState::Continue
+    }
+}
+
+impl Drop for GatherVisitor<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut results) = self.results.lock() {
+            results.0.append(&mut self.found);
+            results.1.append(&mut self.truncated);
+            results.2.append(&mut self.errors);
+            results.3.append(&mut self.special);
+            results.4.append(&mut self.retry);
+        }
+    }
+}
+
+struct GatherBuilder<'a> {
+    results: &'a Gathered,
+    depth_left: usize,
+}
+
+impl<'s> ParallelVisitorBuilder<'s> for GatherBuilder<'s> {
+    fn build(&mut self) -> Box<dyn ParallelVisitor + 's> {
+        Box::new(GatherVisitor {
+            results: self.results,
+            found: Vec::new(),
+            truncated: Vec::new(),
+            errors: Vec::new(),
+            special: Vec::new(),
+            retry: Vec::new(),
+            depth_left: self.depth_left,
+        })
+    }
+}
+
+/// Kind of an entry that is neither a file, a folder nor a symlink: "fifo",
+/// "socket", "block_device", "char_device" or "other"; None for the other three.
+pub fn special_kind(file_type: &fs::FileType) -> Option<&'static str> {
+    if file_type.is_file() || file_type.is_dir() || file_type.is_symlink() {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_fifo() {
+            return Some("fifo");
+        } else if file_type.is_socket() {
+            return Some("socket");
+        } else if file_type.is_block_device() {
+            return Some("block_device");
+        } else if file_type.is_char_device() {
+            return Some("char_device");
+        }
+    }
+    Some("other")
+}
+
+// A symlink's kind is its target's; a dangling link isn't special
+fn entry_special_kind(entry: &DirEntry) -> Option<&'static str> {
+    let file_type = entry.file_type()?;
+    if file_type.is_symlink() {
+        return special_kind(&fs::metadata(entry.path()).ok()?.file_type());
+    }
+    special_kind(&file_type)
+}
+
 // --- UPDATED gather_valid_items ---
+// Enumerates `path` (at `depth` folder levels below the root) with
+// ignore::WalkParallel. The walker's own filters are off: every entry goes
+// through the configured patterns, per-directory rules and (when enabled) the
+// repository's .gitignore files, in that order of precedence, so a configured
+// `!pattern` can still keep a path git ignores. Each folder's .gitignore
+// applies to everything below it. Symlinked folders are skipped unless the
+// patterns follow symlinks; then links into the walked folder are skipped (its
+// contents are reached directly) and other linked folders are entered once,
+// which ends link cycles. New paths are appended to `collected` in path order (a
+// folder before its contents); folders and files that couldn't be read go to
+// `errors`, named pipes, sockets and devices to `special` (they're never kept).
+// Returns the folders cut off by the depth limit.
 pub fn gather_valid_items(
     path: &PathBuf,
     compiled_ignores: &CompiledIgnorePatterns, // <--- MODIFIED: Pass CompiledIgnorePatterns
     collected: &mut Vec<PathBuf>,
+    dir_paths: &mut HashSet<PathBuf>,
+    errors: &mut Vec<ScanError>,
+    special: &mut Vec<SpecialFile>,
     depth: usize,
-) {
-    if is_scan_cancelled() { return; }
+) -> Vec<PathBuf> {
+    walk(path, compiled_ignores, collected, dir_paths, errors, special, depth, None)
+}
 
-    const MAX_DEPTH: usize = 30;
-    if depth > MAX_DEPTH {
-        // println!("[GATHER DEPTH_LIMIT] Path: {}", path.display());
-        return;
-    }
+/// `gather_valid_items` from the root that also lists, in `excluded`, every
+/// entry it leaves out and the rule that did (for previews; the contents of an
+/// excluded folder aren't visited, so only the folder is listed).
+pub fn explain_valid_items(
+    path: &PathBuf,
+    compiled_ignores: &CompiledIgnorePatterns,
+    collected: &mut Vec<PathBuf>,
+    dir_paths: &mut HashSet<PathBuf>,
+    errors: &mut Vec<ScanError>,
+    special: &mut Vec<SpecialFile>,
+    excluded: &mut Vec<ExcludedPath>,
+) -> Vec<PathBuf> {
+    walk(path, compiled_ignores, collected, dir_paths, errors, special, 0, Some(excluded))
+}
 
-    // Use the new compiled_ignores.is_ignored method
-    if compiled_ignores.is_ignored(path, path.is_dir()) { 
-        // println!("[GATHER IGNORE] Path: {}", path.display()); // For debugging
-        return;
+#[allow(clippy::too_many_arguments)]
+fn walk(
+    path: &PathBuf,
+    compiled_ignores: &CompiledIgnorePatterns,
+    collected: &mut Vec<PathBuf>,
+    dir_paths: &mut HashSet<PathBuf>,
+    errors: &mut Vec<ScanError>,
+    special: &mut Vec<SpecialFile>,
+    depth: usize,
+    excluded: Option<&mut Vec<ExcludedPath>>,
+) -> Vec<PathBuf> {
+    let max_depth = compiled_ignores.max_depth().unwrap_or(DEFAULT_MAX_SCAN_DEPTH);
+    if is_scan_cancelled() || depth > max_depth {
+        return Vec::new();
+    }
+    let follow_symlinks = compiled_ignores.follows_symlinks();
+    let filter = Arc::new(WalkFilter {
+        ignores: compiled_ignores.clone(),
+        stacks: Mutex::new(HashMap::new()),
+        start: fs::canonicalize(path).ok(),
+        visited: follow_symlinks.then(|| Mutex::new(HashSet::new())),
+        excluded: excluded.is_some().then(|| Mutex::new(Vec::new())),
+    });
+    // The walker doesn't filter its starting point
+    let start_kept = filter.keep(path, path.is_dir(), false);
+    let merge_excluded = |filter: &WalkFilter, extra: Vec<ExcludedPath>| {
+        if let (Some(excluded), Some(Ok(mut recorded))) = (excluded, filter.excluded.as_ref().map(|e| e.lock())) {
+            excluded.append(&mut recorded);
+            excluded.extend(extra);
+            excluded.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+    };
+    if !start_kept {
+        merge_excluded(&filter, Vec::new());
+        return Vec::new();
     }
 
-    // If not ignored, add it. Check for duplicates might not be strictly necessary
-    // if the traversal logic ensures each path is visited once, but doesn't hurt.
-    if !collected.contains(path) { 
-        // println!("[GATHER KEEP] Path: {}", path.display()); // For debugging
-        collected.push(path.clone());
-    }
-
-    if path.is_dir() {
-        match fs::read_dir(path) {
-            Ok(entries) => {
-                for entry_result in entries {
-                    if is_scan_cancelled() { return; }
-                    match entry_result {
-                        Ok(entry) => {
-                            gather_valid_items( // Recursive call
-                                &entry.path(),
-                                compiled_ignores, // Pass it down
-                                collected,
-                                depth + 1,
-                            );
-                        }
-                        Err(_e) => { /* eprintln!("[GATHER READ_ENTRY_ERROR] For path {:?}: {}", entry.path(), _e); */ }
-                    }
+    let results: Gathered = Mutex::new((Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()));
+    let depth_left = max_depth - depth;
+    let entry_filter = filter.clone();
+    WalkBuilder::new(path)
+        .standard_filters(false)
+        .follow_links(follow_symlinks)
+        .max_depth(Some(depth_left))
+        .filter_entry(move |entry| {
+            // Following symlinks, the entry's type is its target's; otherwise a
+            // symlinked folder is left out entirely and a symlinked file kept
+            let is_symlink = entry.path_is_symlink();
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir()) || (is_symlink && entry.path().is_dir());
+            entry_filter.keep(entry.path(), is_dir, is_symlink)
+        })
+        .build_parallel()
+        .visit(&mut GatherBuilder { results: &results, depth_left });
+
+    let (mut found, mut truncated, mut walk_errors, mut walk_special, retry) = results.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+    walk_special.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    special.append(&mut walk_special);
+    // Include patterns keep every folder that might hold a match; drop those that don't
+    let mut emptied = Vec::new();
+    if compiled_ignores.has_include_patterns() {
+        let mut holding: HashSet<PathBuf> = HashSet::new();
+        for (found_path, _) in found.iter().filter(|(_, is_dir)| !is_dir) {
+            for dir in found_path.ancestors().skip(1) {
+                if !holding.insert(dir.to_path_buf()) {
+                    break;
                 }
             }
-            Err(_e) => { /* eprintln!("[GATHER READ_DIR_ERROR] For path {}: {}", path.display(), _e); */ }
+        }
+        found.retain(|(found_path, is_dir)| {
+            let keep = !is_dir || found_path == path || holding.contains(found_path);
+            if !keep && filter.excluded.is_some() {
+                let exclusion = Exclusion { source: "include_patterns".to_string(), pattern: None, origin: None };
+                emptied.push(ExcludedPath { path: found_path.to_string_lossy().to_string(), is_dir: true, exclusion });
+            }
+            keep
+        });
+    }
+    merge_excluded(&filter, emptied);
+    found.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    truncated.sort_unstable();
+    let mut seen: HashSet<PathBuf> = collected.iter().cloned().collect();
+    for (found_path, is_dir) in found {
+        if is_dir {
+            dir_paths.insert(found_path.clone());
+        }
+        if seen.insert(found_path.clone()) {
+            collected.push(found_path);
+        }
+    }
+
+    // A folder whose listing failed transiently (SMB/NFS hiccup) is walked
+    // again once it lists, instead of losing the subtree for this scan
+    for (dir, error) in retry {
+        let relisted = retry_io(|| fs::read_dir(&dir)).is_ok();
+        match dir.strip_prefix(path) {
+            Ok(relative) if relisted => {
+                let dir_depth = depth + relative.components().count();
+                truncated.extend(walk(&dir, compiled_ignores, collected, dir_paths, &mut walk_errors, special, dir_depth, None));
+            }
+            _ => walk_errors.push(error),
         }
     }
+    walk_errors.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    errors.append(&mut walk_errors);
+    truncated
 }
 
 // --- REMOVE THE OLD path_ignored_by_patterns FUNCTION ---
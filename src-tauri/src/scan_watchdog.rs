@@ -0,0 +1,132 @@
+// src-tauri/src/scan_watchdog.rs
+// Keeps a panicking scan from taking the app down with it. The scan reports
+// its phase and the last path it touched as it goes; a panic is caught, any
+// transaction it left open on the shared connection is rolled back and the
+// connection's lock un-poisoned (otherwise every later DB call fails), and the
+// scan ends with a `ScanFailure` that says where it stopped.
+
+use crate::types::ScanOutput;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub const PHASE_LOADING: &str = "loading";
+pub const PHASE_ENUMERATING: &str = "enumerating";
+pub const PHASE_RECONCILING: &str = "reconciling";
+pub const PHASE_PROCESSING: &str = "processing";
+pub const PHASE_COMMITTING: &str = "committing";
+pub const PHASE_BUILDING_TREE: &str = "building_tree";
+pub const PHASE_SAVING: &str = "saving";
+
+/// Where a running scan is; updated by the scan as it goes.
+#[derive(Default)]
+pub struct ScanWatch {
+    phase: Mutex<&'static str>,
+    last_path: Mutex<Option<String>>,
+}
+
+impl ScanWatch {
+    pub fn set_phase(&self, phase: &'static str) {
+        *self.phase.lock().unwrap_or_else(|p| p.into_inner()) = phase;
+    }
+
+    pub fn note_path(&self, path: &Path) {
+        *self.last_path.lock().unwrap_or_else(|p| p.into_inner()) = Some(path.to_string_lossy().to_string());
+    }
+
+    fn snapshot(&self) -> (String, Option<String>) {
+        let phase = self.phase.lock().unwrap_or_else(|p| p.into_inner()).to_string();
+        let last_path = self.last_path.lock().unwrap_or_else(|p| p.into_inner()).clone();
+        (phase, last_path)
+    }
+}
+
+/// Payload of the `scan_failed` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanFailure {
+    pub project_id: i32,
+    /// Phase the scan was in, e.g. "processing" or "committing"
+    pub phase: String,
+    /// The last file or folder the scan started on
+    pub last_path: Option<String>,
+    pub message: String,
+    /// A panic rather than a reported error
+    pub panicked: bool,
+    /// An open transaction was found and rolled back
+    pub rolled_back: bool,
+    pub failed_at: String,
+}
+
+impl ScanFailure {
+    /// The error string returned by the scan command.
+    pub fn describe(&self) -> String {
+        if !self.panicked {
+            return self.message.clone();
+        }
+        match &self.last_path {
+            Some(path) => format!("Scan crashed while {} (last path: {}): {}", self.phase, path, self.message),
+            None => format!("Scan crashed while {}: {}", self.phase, self.message),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+// Rolls back whatever the panic left open and clears the lock's poison.
+// Returns whether a transaction was open.
+fn recover_connection(conn_arc: &Arc<Mutex<Connection>>) -> bool {
+    let conn = conn_arc.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let rolled_back = if conn.is_autocommit() {
+        false
+    } else {
+        match conn.execute_batch("ROLLBACK") {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[SCANNER] Rollback after a crashed scan failed: {}", e);
+                false
+            }
+        }
+    };
+    drop(conn);
+    conn_arc.clear_poison();
+    rolled_back
+}
+
+/// Runs `scan` with a fresh `ScanWatch`, turning a panic or an error into a `ScanFailure`.
+pub fn run_watched<F>(project_id: i32, conn_arc: &Arc<Mutex<Connection>>, scan: F) -> Result<ScanOutput, ScanFailure>
+where
+    F: FnOnce(&ScanWatch) -> Result<ScanOutput, String>,
+{
+    let watch = ScanWatch::default();
+    watch.set_phase(PHASE_LOADING);
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| scan(&watch)));
+    let (phase, last_path) = watch.snapshot();
+    let failure = |message: String, panicked: bool, rolled_back: bool| ScanFailure {
+        project_id,
+        phase: phase.clone(),
+        last_path: last_path.clone(),
+        message,
+        panicked,
+        rolled_back,
+        failed_at: Utc::now().to_rfc3339(),
+    };
+    match outcome {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(message)) => Err(failure(message, false, false)),
+        Err(payload) => {
+            let rolled_back = recover_connection(conn_arc);
+            let failure = failure(panic_message(payload.as_ref()), true, rolled_back);
+            eprintln!("[SCANNER] {} (transaction rolled back: {})", failure.describe(), rolled_back);
+            Err(failure)
+        }
+    }
+}
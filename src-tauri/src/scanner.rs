@@ -2,112 +2,395 @@
 // src-tauri/src/scanner.rs
 // Main scan command orchestration, progress emission, cache interaction.
 
+use crate::archives::{self, ExpandedArchive};
 use crate::db::AppState;
+use crate::errors::{self, app_error};
+use crate::pinned_files;
 use crate::projects;
 use crate::scan_cache::{self, CacheEntry};
-use crate::scan_state::{is_scan_cancelled, set_cancel_scan};
-use crate::types::FileNode;
-use crate::utils::approximate_token_count;
+use crate::scan_results;
+use crate::scan_metrics::{self, ScanMetrics};
+use crate::scan_chunks::TreeChunker;
+use crate::scan_filters::ScanFilters;
+use crate::scan_history::{self, ScanRun, RUN_CANCELLED, RUN_DONE, RUN_FAILED};
+use crate::scan_progress::{self, ProgressAggregator};
+use crate::scan_queue::{self, ScanTicket};
+use crate::scan_watchdog::{self, ScanWatch, PHASE_BUILDING_TREE, PHASE_COMMITTING, PHASE_ENUMERATING, PHASE_PROCESSING, PHASE_RECONCILING, PHASE_SAVING};
+use crate::scan_state::{begin_batch_read, cancel_all_scans, cancel_batch_read_request, cancel_scan, is_scan_cancelled, register_scan, set_scan_paused, wait_while_paused};
+use crate::types::{FileNode, FileStats, FilteredFile, ScanError, ScanOutput, SpecialFile, SKIP_BINARY, SKIP_CLOUD_PLACEHOLDER, SKIP_NON_UTF8, SKIP_SPECIAL_FILE, SKIP_TOO_LARGE, SKIP_UNREADABLE};
+use crate::utils::{approximate_token_count, count_tokens_with, DEFAULT_TOKENIZER};
 use crate::ignore_handler::CompiledIgnorePatterns;
-use crate::scan_tree::{build_tree_from_paths, file_modified_timestamp, gather_valid_items};
+use crate::dir_ignore_rules::{self, DirIgnoreRules};
+use crate::include_globs::IncludeFilter;
+use crate::scan_tree::{build_tree_from_paths, file_modified_timestamp, gather_valid_items, mark_errors, node_id, prune_empty_dirs, special_kind, TreeSort};
 use crate::app_settings; 
+use crate::file_types::{decode_text, is_binary_content, read_for_context, ReadOutcome};
+use crate::document_extract;
+use crate::file_notes;
+use crate::cloud_files::{self, PlaceholderPolicy};
+use crate::net_fs;
+use crate::redaction::{RedactedFile, Redactor};
+use crate::read_limits::{apply_limits, ReadLimits};
+use crate::io_concurrency::{resolve_read_concurrency, run_with_concurrency};
+use crate::path_canon::{canonical_path, canonical_paths};
+use crate::path_case::CaseMode;
+use crate::tree_queries::limit_children;
+use crate::window_registry::{emit_to_origin_and_project, WindowRegistry};
 
+use chrono::{DateTime, Utc};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{command, AppHandle, Emitter, State, Window};
+use std::time::Instant;
+use tauri::{command, AppHandle, Manager, State, Window};
 
 // Constants
-const MAX_FILE_SIZE_BYTES: u64 = 5 * 1024 * 1024; // 5 MB limit
+pub const MAX_FILE_SIZE_BYTES: u64 = 5 * 1024 * 1024; // 5 MB limit
+const LARGEST_FILES_IN_SUMMARY: usize = 20; // Heaviest files listed in the `scan_summary` event
 
 // --- Command to Cancel Scan ---
+// Cancels scan `scan_id` (from the `scan_queued` event or get_scan_queue), or
+// every running and queued scan when None. False when that scan isn't registered.
 #[command]
-pub fn cancel_code_context_builder_scan() -> Result<(), String> {
-    println!("[CMD] Cancellation requested.");
-    set_cancel_scan(true);
-    Ok(())
+pub fn cancel_code_context_builder_scan(scan_id: Option<u64>) -> Result<bool, String> {
+    match scan_id {
+        Some(id) => {
+            println!("[CMD] Cancellation requested for scan {}.", id);
+            Ok(cancel_scan(id))
+        }
+        None => {
+            println!("[CMD] Cancellation requested for all scans.");
+            Ok(cancel_all_scans() > 0)
+        }
+    }
+}
+
+// --- Commands to Pause/Resume Scan ---
+// Workers finish the file they're on, then wait; nothing done so far is lost.
+// `scan_id` defaults to the running scan. Returns whether the scan was running
+// (not already paused) before; false when there is no such scan.
+#[command]
+pub fn pause_code_context_builder_scan(scan_id: Option<u64>) -> Result<bool, String> {
+    println!("[CMD] Scan pause requested ({:?}).", scan_id);
+    Ok(set_scan_paused(scan_id, true) == Some(false))
+}
+
+// Returns whether the scan was paused before.
+#[command]
+pub fn resume_code_context_builder_scan(scan_id: Option<u64>) -> Result<bool, String> {
+    println!("[CMD] Scan resume requested ({:?}).", scan_id);
+    Ok(set_scan_paused(scan_id, false) == Some(true))
+}
+
+// --- Command to Cancel a Batch Read ---
+// Returns false when no batch read with that request ID is running.
+#[command]
+pub fn cancel_batch_read(request_id: String) -> Result<bool, String> {
+    println!("[CMD] Batch read cancellation requested for '{}'.", request_id);
+    Ok(cancel_batch_read_request(&request_id))
 }
 
 // --- Command to Read File Contents ---
 #[command]
-pub fn read_file_contents(file_path: String) -> Result<String, String> {
+pub fn read_file_contents(state: State<'_, AppState>, file_path: String) -> Result<String, String> {
     // println!("[CMD] Reading file: {}", file_path);
     let path = Path::new(&file_path);
-    if !path.exists() {
+    if !path.exists() && !archives::is_virtual_path(path) {
         return Err(format!("File does not exist: {}", file_path));
     }
     if path.is_dir() {
         return Err(format!("Path is a directory, not a file: {}", file_path));
     }
-    fs::read_to_string(path).map_err(|e| format!("Failed to read file '{}': {}", file_path, e))
+    let extract_documents = document_extract::is_enabled(&state);
+    read_context_text(&file_path, extract_documents, &Redactor::for_state(&state)).map(|file| file.content)
 }
 
-// --- NEW Command to Read Multiple File Contents ---
+// --- Command to Refresh One File's Stats Without a Scan ---
+// Counts a single file and, with the default tokenizer, updates its cache row so
+// the next scan reuses the fresh numbers.
 #[command]
-pub fn read_multiple_file_contents(
+pub fn get_file_stats(
+    state: State<'_, AppState>,
+    path: String,
+    tokenizer: Option<String>,
+) -> Result<FileStats, String> {
+    // The cache row is keyed by the canonical path
+    let path = canonical_path(&path);
+    let file_path = Path::new(&path);
+    let meta = net_fs::retry_io(|| fs::metadata(file_path))
+        .map_err(|e| format!("Failed to stat '{}': {}", path, e))?;
+    if meta.is_dir() {
+        return Err(format!("Path is a directory, not a file: {}", path));
+    }
+    if let Some(kind) = special_kind(&meta.file_type()) {
+        return Err(format!("Not a regular file ({}): {}", kind, path));
+    }
+    let size = meta.len();
+    let last_modified = file_modified_timestamp(&meta);
+    let tokenizer_name = tokenizer.as_deref().unwrap_or(DEFAULT_TOKENIZER).to_string();
+    count_tokens_with("", Some(&tokenizer_name))?; // reject unknown tokenizers up front
+    let counted = size <= MAX_FILE_SIZE_BYTES;
+    let extract_documents = document_extract::is_enabled(&state);
+
+    let (lines, tokens) = if !counted {
+        (0, 0)
+    } else if tokenizer_name == DEFAULT_TOKENIZER {
+        let entry = compute_cache_entry(file_path, size, last_modified.clone(), extract_documents);
+        let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+        scan_cache::save_cache_entry(&conn, &path, &entry)
+            .map_err(|e| format!("Failed to update cache entry for '{}': {}", path, e))?;
+        (entry.lines, entry.tokens)
+    } else {
+        let text = if size == 0 { String::new() } else { read_countable_text(file_path, extract_documents)? };
+        (text.lines().count(), count_tokens_with(&text, Some(&tokenizer_name))?)
+    };
+
+    Ok(FileStats { path, size, last_modified, lines, tokens, tokenizer: tokenizer_name, counted })
+}
+
+// --- NEW Command to Read Multiple File Contents ---
+// Async, with the reading on a blocking thread, so `cancel_batch_read` can be
+// handled while the batch runs.
+#[command(async)]
+pub async fn read_multiple_file_contents(
+    state: State<'_, AppState>,
     paths: Vec<String>,
+    project_id: Option<i32>, // With a project, its file notes are injected above the contents
+    request_id: Option<String>, // With an ID, cancel_batch_read can stop the read
+    max_bytes_per_file: Option<usize>, // Size caps; cut content ends with a truncation marker
+    max_total_bytes: Option<usize>,
+    concurrency: Option<usize>, // Files read at once; default by drive type, 0 = unbounded
 ) -> Result<HashMap<String, Result<String, String>>, String> {
     // println!("[CMD] Reading {} files batch.", paths.len());
-    let results: HashMap<String, Result<String, String>> = paths
-        .par_iter()
-        .map(|path_str| {
-            let path = Path::new(path_str);
-            let content_result = if !path.exists() {
-                Err(format!("File does not exist: {}", path_str))
-            } else if path.is_dir() {
-                Err(format!("Path is a directory, not a file: {}", path_str))
-            } else {
-                fs::read_to_string(path)
-                    .map_err(|e| format!("Failed to read file '{}': {}", path_str, e))
-            };
-            (path_str.clone(), content_result)
-        })
-        .collect();
+    let paths = canonical_paths(&paths);
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+    let notes = file_notes::notes_for(&state, project_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut results = read_batch(&paths, request_id, concurrency, |path_str| {
+            read_context_text(path_str, extract_documents, &redactor)
+                .map(|file| file_notes::with_note(path_str, file.content, &notes))
+        })?;
+        apply_limits(&paths, &mut results, ReadLimits::new(max_bytes_per_file, max_total_bytes), |c| c, |_, _| ());
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Batch read task failed: {}", e))?
+}
+
+/// Runs `read` over `paths` in parallel, at most `concurrency` files at a time
+/// (see `io_concurrency`). Once the read is cancelled through
+/// `cancel_batch_read`, remaining files are skipped and the whole batch fails.
+pub fn read_batch<T: Send>(
+    paths: &[String],
+    request_id: Option<String>,
+    concurrency: Option<usize>,
+    read: impl Fn(&str) -> Result<T, String> + Sync,
+) -> Result<HashMap<String, Result<T, String>>, String> {
+    let token = begin_batch_read(request_id);
+    let threads = resolve_read_concurrency(concurrency, paths);
+    let results: HashMap<String, Result<T, String>> = run_with_concurrency(threads, || {
+        paths
+            .par_iter()
+            .filter(|_| !token.is_cancelled())
+            .map(|path_str| (path_str.clone(), read(path_str)))
+            .collect()
+    });
+    if token.is_cancelled() {
+        return Err(format!("Batch read cancelled after {} of {} files.", results.len(), paths.len()));
+    }
     Ok(results)
 }
 
+/// Same as `read_multiple_file_contents`, but each entry also reports what was redacted.
+#[command(async)]
+pub async fn read_multiple_file_contents_with_redactions(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    project_id: Option<i32>,
+    request_id: Option<String>,
+    max_bytes_per_file: Option<usize>,
+    max_total_bytes: Option<usize>,
+    concurrency: Option<usize>,
+) -> Result<HashMap<String, Result<RedactedFile, String>>, String> {
+    let paths = canonical_paths(&paths);
+    let extract_documents = document_extract::is_enabled(&state);
+    let redactor = Redactor::for_state(&state);
+    let notes = file_notes::notes_for(&state, project_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut results = read_batch(&paths, request_id, concurrency, |path_str| {
+            read_context_text(path_str, extract_documents, &redactor).map(|mut file| {
+                file.content = file_notes::with_note(path_str, file.content, &notes);
+                file
+            })
+        })?;
+        let limits = ReadLimits::new(max_bytes_per_file, max_total_bytes);
+        apply_limits(&paths, &mut results, limits, |f| &mut f.content, |f, t| f.truncation = Some(t));
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Batch read task failed: {}", e))?
+}
+
+/// Text of one file as it goes into a built context (document extraction,
+/// binary placeholders, secret redaction), with the same errors as `read_multiple_file_contents`.
+pub fn read_context_text(path_str: &str, extract_documents: bool, redactor: &Redactor) -> Result<RedactedFile, String> {
+    let path = Path::new(path_str);
+    if !path.exists() && !archives::is_virtual_path(path) {
+        Err(format!("File does not exist: {}", path_str))
+    } else if path.is_dir() {
+        Err(format!("Path is a directory, not a file: {}", path_str))
+    } else if extract_documents && document_extract::is_extractable(path) {
+        document_extract::extract_text(path).map(|text| redactor.redact(&text))
+    } else {
+        match read_for_context(path) {
+            Ok(ReadOutcome::Text(text)) => Ok(redactor.redact(&text)),
+            Ok(ReadOutcome::Binary(placeholder)) => Ok(RedactedFile { content: placeholder, redactions: Vec::new(), truncation: None }),
+            Err(e) => Err(format!("Failed to read file '{}': {}", path_str, e)),
+        }
+    }
+}
 
 // --- Main Scan Command ---
+#[allow(clippy::too_many_arguments)] // Tauri command arguments are the IPC parameters
 #[command(async)]
 pub async fn scan_code_context_builder_project(
     window: Window,
     _app_handle: AppHandle, // Keep if other plugins might need it, or remove if truly unused
     state: State<'_, AppState>,
     project_id: i32,
+    sort_by: Option<String>, // "name" (default) | "tokens" | "size" | "modified"
+    child_limit: Option<usize>, // children per directory in the returned tree; None or 0 = unlimited
+    prune_empty: Option<bool>, // drop directories without any counted tokens from the returned tree
+    include_globs: Option<Vec<String>>, // targeted scan of matching paths only, e.g. ["src/**/*.rs"]
 ) -> Result<FileNode, String> {
+    run_scan(window, state, project_id, sort_by, child_limit, prune_empty, include_globs, None).await.map(|output| output.tree)
+}
+
+// Same scan, returning the tree together with its ScanSummary
+#[command(async)]
+pub async fn scan_project_with_summary(
+    window: Window,
+    state: State<'_, AppState>,
+    project_id: i32,
+    sort_by: Option<String>,
+    child_limit: Option<usize>,
+    prune_empty: Option<bool>,
+    include_globs: Option<Vec<String>>,
+) -> Result<ScanOutput, String> {
+    run_scan(window, state, project_id, sort_by, child_limit, prune_empty, include_globs, None).await
+}
+
+// Rescans only the folder `sub_path` (absolute or relative to the project root),
+// merges it into the saved tree and returns the whole updated tree. Much faster
+// than a full rescan when one folder of a large project changed.
+#[command(async)]
+pub async fn rescan_subtree_cmd(
+    window: Window,
+    state: State<'_, AppState>,
+    project_id: i32,
+    sub_path: String,
+    sort_by: Option<String>,
+    child_limit: Option<usize>,
+    prune_empty: Option<bool>,
+) -> Result<FileNode, String> {
+    run_scan(window, state, project_id, sort_by, child_limit, prune_empty, None, Some(sub_path)).await.map(|output| output.tree)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run_scan(
+    window: Window,
+    state: State<'_, AppState>,
+    project_id: i32,
+    sort_by: Option<String>,
+    child_limit: Option<usize>,
+    prune_empty: Option<bool>,
+    include_globs: Option<Vec<String>>,
+    subtree: Option<String>,
+) -> Result<ScanOutput, String> {
     println!("[CMD] Starting scan_code_context_builder_project for ID: {}", project_id);
-    set_cancel_scan(false); // Reset cancellation flag
+    let sort = TreeSort::parse(sort_by.as_deref())?;
+    let subtree = subtree.map(|s| canonical_path(&s));
+    let queue_key = format!("{}|{:?}|{:?}|{:?}|{:?}|{:?}", project_id, sort, child_limit, prune_empty, include_globs, subtree);
+    let job_id = match scan_queue::enqueue(project_id, queue_key) {
+        ScanTicket::Owner(id) => id,
+        ScanTicket::Joined(id) => {
+            println!("[CMD] Identical scan of project {} already queued; joining job {}.", project_id, id);
+            return tauri::async_runtime::spawn_blocking(move || scan_queue::wait_for_result(id))
+                .await
+                .map_err(|e| format!("Scan task failed unexpectedly (panic or join error): {}", e))?;
+        }
+    };
+    // The job id is the scan id; cancel/pause commands target it from now on
+    let registration = register_scan(job_id);
     let conn_arc = state.conn.clone();
     let window_clone = window.clone();
+    // The scanning window follows this project's events (progress, monitor updates)
+    if let Some(registry) = window.try_state::<WindowRegistry>() {
+        registry.subscribe(window.label(), project_id);
+    }
+    emit_to_origin_and_project(&window, project_id, "scan_queued", serde_json::json!({ "scan_id": job_id, "project_id": project_id }));
+    let scope = match (&subtree, &include_globs) {
+        (Some(_), _) => "subtree",
+        (None, Some(globs)) if globs.iter().any(|g| !g.trim().is_empty()) => "targeted",
+        _ => "full",
+    };
 
     let scan_result = tauri::async_runtime::spawn_blocking(move || {
-        let result = do_actual_scan(&window_clone, conn_arc, project_id);
-        match &result {
-            Ok(_) => {
+        // One scan at a time; `is_scan_cancelled` and friends refer to the running one
+        let turn = scan_queue::wait_for_turn(job_id);
+        registration.start();
+        let started_at = Utc::now();
+        let result = scan_watchdog::run_watched(project_id, &conn_arc, |watch| {
+            do_actual_scan(
+                &window_clone,
+                conn_arc.clone(),
+                watch,
+                project_id,
+                sort,
+                child_limit.unwrap_or(0),
+                prune_empty.unwrap_or(false),
+                &include_globs.unwrap_or_default(),
+                subtree.as_deref(),
+            )
+        });
+        let result = match result {
+            Ok(output) => {
                 if is_scan_cancelled() {
                     // println!("[SCANNER] Scan process finished but was cancelled.");
-                    let _ = window_clone.emit("scan_complete", "cancelled");
+                    emit_to_origin_and_project(&window_clone, project_id, "scan_complete", "cancelled");
                 } else {
                     // println!("[SCANNER] Scan process completed successfully.");
-                    let _ = window_clone.emit("scan_complete", "done");
+                    emit_to_origin_and_project(&window_clone, project_id, "scan_complete", "done");
                 }
+                Ok(output)
             }
-            Err(e) => {
-                eprintln!("[SCANNER] Scan process failed: {}", e);
-                let short_error = e.chars().take(150).collect::<String>();
-                let _ = window_clone.emit("scan_complete", format!("failed: {}", short_error));
+            Err(failure) => {
+                let e = failure.describe();
+                // Catalog errors are JSON; the log and the event get their message
+                let message = errors::plain_message(&e);
+                eprintln!("[SCANNER] Scan process failed: {}", message);
+                let short_error = message.chars().take(150).collect::<String>();
+                emit_to_origin_and_project(&window_clone, project_id, "scan_complete", format!("failed: {}", short_error));
+                // A cancelled scan also ends in an error, but it didn't fail
+                if !is_scan_cancelled() {
+                    emit_to_origin_and_project(&window_clone, project_id, "scan_failed", failure);
+                }
+                Err(e)
             }
-        }
+        };
+        record_run(&conn_arc, project_id, scope, started_at, &result);
+        turn.finish(&result, is_scan_cancelled());
         result
     }).await;
 
     match scan_result {
-        Ok(Ok(file_node)) => {
+        Ok(Ok(output)) => {
             // println!("[CMD] Scan task completed successfully, returning FileNode.");
-            Ok(file_node)
+            Ok(output)
         },
         Ok(Err(scan_err)) => {
              eprintln!("[CMD] Scan task finished but reported an error: {}", scan_err);
@@ -116,21 +399,35 @@ pub async fn scan_code_context_builder_project(
         Err(join_err) => {
             let err_msg = format!("Scan task failed unexpectedly (panic or join error): {}", join_err);
              eprintln!("[CMD] {}", err_msg);
-            let _ = window.emit("scan_complete", format!("failed: Task Panic")); // Use original window
+            emit_to_origin_and_project(&window, project_id, "scan_complete", "failed: Task Panic"); // Use original window
             Err(err_msg)
         }
     }
 }
 
 // --- Core Scan Logic (Internal Function - blocking) ---
+#[allow(clippy::too_many_arguments)]
 fn do_actual_scan(
     window: &Window,
     conn_arc: Arc<Mutex<rusqlite::Connection>>,
+    watch: &ScanWatch,
     project_id: i32,
-) -> Result<FileNode, String> {
+    sort: TreeSort,
+    child_limit: usize,
+    prune_empty: bool,
+    include_globs: &[String],
+    subtree: Option<&str>,
+) -> Result<ScanOutput, String> {
+    if is_scan_cancelled() { return Err("Scan cancelled before it started.".to_string()); }
+    let scan_started = Instant::now();
     let project_details; // Store the fully loaded project, including its specific ignores
     let mut cache_map;
+    let case_mode;
     let global_default_patterns: Vec<String>; // To store global default patterns
+    let extract_documents: bool;
+    let scan_archives: bool;
+    let placeholder_policy: PlaceholderPolicy;
+    let dir_rules: Vec<DirIgnoreRules>;
 
     { // Scope for DB lock
         let conn_lock = conn_arc.lock().map_err(|e| format!("Initial DB lock failed: {}", e))?;
@@ -139,9 +436,13 @@ fn do_actual_scan(
         // println!("[SCANNER] Loading project details for ID: {}", project_id);
         project_details = projects::load_project_by_id(&conn_lock, project_id)?;
 
-        // 2. Load Existing File Cache
+        // 2. Load Existing File Cache (this project's rows only)
         // println!("[SCANNER] Loading cache entries...");
-        cache_map = scan_cache::load_cache_entries(&conn_lock)?;
+        case_mode = CaseMode::for_project(&project_details);
+        cache_map = match project_details.root_folder.as_deref() {
+            Some(root) => scan_cache::load_cache_entries(&conn_lock, root, case_mode)?,
+            None => HashMap::new(),
+        };
         // println!("[SCANNER] Loaded {} cache entries.", cache_map.len());
 
         // 3. Load Global Default Ignore Patterns
@@ -169,15 +470,39 @@ fn do_actual_scan(
             });
         // println!("[SCANNER] Loaded {} global default ignore patterns.", global_default_patterns.len());
 
+        extract_documents = document_extract::extraction_enabled(&conn_lock);
+        scan_archives = archives::archives_enabled(&conn_lock);
+        placeholder_policy = cloud_files::load_policy(&conn_lock);
+        dir_rules = dir_ignore_rules::load_dir_rules(&conn_lock, project_id)?;
+
     } // DB lock released
 
-    let root_folder = project_details.root_folder.as_ref().ok_or_else(|| format!("Project ID {} has no root folder set.", project_id))?;
+    let root_folder = project_details.root_folder.as_deref().ok_or_else(|| app_error(errors::PROJECT_NO_ROOT, &[("project_id", project_id.to_string())]))?;
+    // Scanned paths (tree nodes, cache keys) all start from the canonical root
+    let root_folder = &canonical_path(root_folder);
     let root_path = PathBuf::from(root_folder);
     if !root_path.is_dir() {
-        return Err(format!("Root folder is not a valid directory: {}", root_folder));
+        return Err(app_error(errors::ROOT_NOT_DIRECTORY, &[("path", root_folder.clone())]));
     }
     // println!("[SCANNER] Root folder: {}", root_folder);
 
+    // A subtree rescan is a targeted scan of that one folder
+    let subtree = match subtree {
+        Some(sub_path) if !include_globs.is_empty() => {
+            return Err(format!("Can't combine a subtree rescan of '{}' with include globs.", sub_path));
+        }
+        Some(sub_path) => subtree_target(&root_path, sub_path)?,
+        None => None,
+    };
+    let subtree_globs: Vec<String>;
+    let include_globs = match &subtree {
+        Some((_, glob)) => {
+            subtree_globs = vec![glob.clone()];
+            &subtree_globs[..]
+        }
+        None => include_globs,
+    };
+
     // 4. Combine global defaults and project-specific patterns
     let mut combined_ignore_patterns = global_default_patterns; // Start with global defaults
     combined_ignore_patterns.extend_from_slice(&project_details.ignore_patterns); // Add project-specific ones
@@ -190,36 +515,83 @@ fn do_actual_scan(
 
 
     // 5. Compile ignore patterns
-    let compiled_ignores = CompiledIgnorePatterns::new(&root_path, &combined_ignore_patterns);
+    let mut compiled_ignores = CompiledIgnorePatterns::new(&root_path, &combined_ignore_patterns);
+    dir_ignore_rules::apply_dir_rules(&mut compiled_ignores, &dir_rules);
+    if project_details.respect_gitignore.unwrap_or(false) {
+        compiled_ignores.add_repository_gitignore(&root_path);
+    }
+    compiled_ignores.set_follow_symlinks(project_details.follow_symlinks.unwrap_or(false));
+    compiled_ignores.set_max_depth(project_details.max_scan_depth);
+    compiled_ignores.set_include_hidden(project_details.include_hidden.unwrap_or(true));
+    compiled_ignores.set_include_patterns(project_details.include_patterns.as_deref().unwrap_or_default());
+    let include_filter = IncludeFilter::new(&root_path, include_globs)?;
+    let scan_filters = ScanFilters::for_project(&project_details);
 
+    watch.set_phase(PHASE_ENUMERATING);
     // 6. Emit Initial Progress
-    emit_progress_sync(window, &root_path, 0, 1, "Enumerating files...");
+    emit_progress_sync(window, project_id, &root_path, 0, 1, "Enumerating files...");
 
     // 7. Gather All Potential Items Recursively
+    let enumeration_started = Instant::now();
     // println!("[SCANNER] Gathering items (applying combined .gitignore-style patterns)...");
     let mut all_potential_paths = Vec::new();
-    gather_valid_items(
-        &root_path,
-        &compiled_ignores, // Pass the compiled patterns object
-        &mut all_potential_paths,
-        0,
-    );
+    let mut dir_paths: HashSet<PathBuf> = HashSet::new();
+    let mut scan_errors: Vec<ScanError> = Vec::new(); // Entries that couldn't be read
+    let mut special_files: Vec<SpecialFile> = Vec::new(); // Pipes, sockets, devices: never read
+    let truncated_subtrees = match &include_filter {
+        Some(filter) => {
+            println!("[SCANNER] Targeted scan of project {} limited to {:?}.", project_id, include_globs);
+            filter.gather(&compiled_ignores, &mut all_potential_paths, &mut dir_paths, &mut scan_errors, &mut special_files)
+        }
+        None => gather_valid_items(
+            &root_path,
+            &compiled_ignores, // Pass the compiled patterns object
+            &mut all_potential_paths,
+            &mut dir_paths,
+            &mut scan_errors,
+            &mut special_files,
+            0,
+        ),
+    };
+    let truncated_subtrees: Vec<String> = truncated_subtrees.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    if !special_files.is_empty() {
+        println!("[SCANNER] Skipped {} named pipe(s), socket(s) or device file(s).", special_files.len());
+    }
+    if !truncated_subtrees.is_empty() {
+        println!("[SCANNER] {} folder(s) are beyond the depth limit and weren't scanned.", truncated_subtrees.len());
+    }
     // println!("[SCANNER] Found {} potential items after combined filtering.", all_potential_paths.len());
+    let expanded_archives: Vec<ExpandedArchive> = if scan_archives {
+        archives::expand_archives(&mut all_potential_paths, &mut dir_paths, &compiled_ignores, &mut scan_errors)
+    } else {
+        Vec::new()
+    };
+    let enumeration_ms = enumeration_started.elapsed().as_millis() as u64;
 
     if is_scan_cancelled() { return Err("Scan cancelled after file enumeration.".to_string()); }
 
-    let final_valid_paths = all_potential_paths;
+    let mut final_valid_paths = all_potential_paths;
     // println!("[SCANNER] Using {} items directly.", final_valid_paths.len());
 
+    // Case-insensitive volumes: re-key cache rows whose casing no longer matches the disk
+    if case_mode == CaseMode::Insensitive {
+        watch.set_phase(PHASE_RECONCILING);
+        let mut conn_lock = conn_arc.lock().map_err(|e| format!("Case reconcile lock failed: {}", e))?;
+        let tx = conn_lock.transaction().map_err(|e| format!("Case reconcile transaction start failed: {}", e))?;
+        scan_cache::reconcile_path_case(&tx, &final_valid_paths, &mut cache_map, case_mode)?;
+        tx.commit().map_err(|e| format!("Commit case reconcile failed: {}", e))?;
+    }
+
     if is_scan_cancelled() { return Err("Scan cancelled before file processing.".to_string()); }
 
     let total_items = final_valid_paths.len();
     if total_items == 0 {
+        watch.set_phase(PHASE_COMMITTING);
         // println!("[SCANNER] No valid files or folders found after applying filters.");
         {
             let mut conn_lock = conn_arc.lock().map_err(|e| format!("Cleanup lock failed: {}", e))?;
             let tx_cleanup = conn_lock.transaction().map_err(|e| format!("Cleanup transaction start failed: {}", e))?;
-             match scan_cache::cleanup_removed_files(&tx_cleanup, &final_valid_paths, &mut cache_map) {
+             match cleanup_cache(&tx_cleanup, &final_valid_paths, &mut cache_map, include_filter.as_ref(), &scan_errors) {
                  Ok(_) => tx_cleanup.commit().map_err(|e| format!("Commit cleanup failed: {}", e))?,
                  Err(e) => {
                      eprintln!("Cache cleanup failed: {}. Rolling back cleanup.", e);
@@ -229,87 +601,157 @@ fn do_actual_scan(
              }
              // println!("[SCANNER] Cache cleanup performed for empty result set.");
         }
-        return Ok(FileNode {
-            path: root_folder.clone(), // Use the original root_folder string
-            is_dir: true,
-            name: root_path.file_name().map(|os| os.to_string_lossy().to_string()).unwrap_or_else(|| root_folder.clone()),
-            lines: 0, tokens: 0, size: 0, last_modified: "".to_string(), children: vec![],
-        });
+        let mut empty_root = FileNode::new_dir(
+            root_folder.clone(), // Use the original root_folder string
+            root_path.file_name().map(|os| os.to_string_lossy().to_string()).unwrap_or_else(|| root_folder.clone()),
+        );
+        empty_root.id = node_id(&root_path, &root_path);
+        mark_errors(&mut empty_root, &scan_errors);
+        let mut summary = scan_results::summarize_tree(&empty_root, &[(SKIP_SPECIAL_FILE, special_files.len())], scan_started.elapsed().as_millis() as u64);
+        summary.errors = scan_errors;
+        summary.special_files = special_files;
+        emit_to_origin_and_project(window, project_id, "scan_summary", scan_results::scan_statistics(project_id, &empty_root, 0, 0, 0));
+        return Ok(ScanOutput { tree: empty_root, summary });
     }
 
     // println!("[SCANNER] Processing {} items for cache updates/stats...", final_valid_paths.len());
     let changed_entries = Arc::new(Mutex::new(Vec::new()));
-    let processed_count = Arc::new(AtomicUsize::new(0));
-    let progress_lock = Arc::new(Mutex::new(()));
+    let skipped_placeholders: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+    let filtered_files: Mutex<Vec<FilteredFile>> = Mutex::new(Vec::new());
+    let unreadable_files: Mutex<Vec<ScanError>> = Mutex::new(Vec::new());
+    let counted_files = AtomicUsize::new(0); // Files looked up in the cache (hits + misses)
+
+    // Network roots get a small dedicated pool; shares degrade badly under one request per core
+    let network_mode = net_fs::is_network_mode(project_details.fs_mode.as_deref(), &root_path);
+    let network_pool = if network_mode {
+        println!("[SCANNER] Network filesystem mode for '{}'.", root_folder);
+        rayon::ThreadPoolBuilder::new().num_threads(net_fs::NETWORK_SCAN_THREADS).build().ok()
+    } else {
+        None
+    };
 
-    let parallel_result: Result<(), String> = final_valid_paths.par_iter().try_for_each(|p| {
+    let processing_started = Instant::now();
+    let progress = ProgressAggregator::start(window.clone(), project_id, total_items);
+    let chunks = TreeChunker::new(window.clone(), project_id, &root_path, &final_valid_paths, &dir_paths, sort);
+    let archived: HashSet<&Path> = expanded_archives.iter().flat_map(|a| a.files.iter().map(|e| e.path.as_path())).collect();
+    let process_all = || final_valid_paths.par_iter().try_for_each(|p| {
         // ... (parallel processing logic remains the same as before) ...
+        wait_while_paused();
         if is_scan_cancelled() { return Err("Scan cancelled during parallel processing.".to_string()); }
         
-        let current_processed_count = processed_count.fetch_add(1, Ordering::Relaxed) + 1;
-        if let Ok(_guard) = progress_lock.try_lock() {
-            emit_progress_payload(window, p, current_processed_count, total_items);
-        } else if current_processed_count == total_items {
-            emit_progress_payload(window, p, current_processed_count, total_items);
-        }
+        progress.record_item(p);
 
-        if p.is_dir() { return Ok(()); }
-        let meta = match fs::metadata(p) {
+        if dir_paths.contains(p) || archived.contains(p.as_path()) { return Ok(()); }
+        watch.note_path(p);
+        let meta = match net_fs::retry_io(|| fs::metadata(p)) {
             Ok(m) => m,
-            Err(_e) => { return Ok(()); }
+            Err(e) => {
+                // Kept in the tree without counts; the error says why
+                unreadable_files.lock().unwrap().push(ScanError { path: p.to_string_lossy().to_string(), message: e.to_string() });
+                chunks.file_done(p, None);
+                return Ok(());
+            }
         };
         let file_size = meta.len();
-        if file_size > MAX_FILE_SIZE_BYTES { return Ok(()); }
+        progress.add_bytes(file_size);
+        // Left out by the project's size/age filters: not read, listed in the summary
+        if let Some(reason) = scan_filters.exclusion(&meta) {
+            filtered_files.lock().unwrap().push(FilteredFile {
+                path: p.to_string_lossy().to_string(),
+                size: file_size,
+                last_modified: file_modified_timestamp(&meta),
+                reason: reason.to_string(),
+            });
+            chunks.file_left_out(p);
+            return Ok(());
+        }
+        let too_large = file_size > MAX_FILE_SIZE_BYTES;
+
+        // Cloud-sync placeholders: reading would hydrate (download) the file
+        let is_placeholder = placeholder_policy != PlaceholderPolicy::Read && cloud_files::is_cloud_placeholder(&meta);
+        if is_placeholder && placeholder_policy == PlaceholderPolicy::Skip {
+            skipped_placeholders.lock().unwrap().push(p.clone());
+            chunks.file_left_out(p);
+            return Ok(());
+        }
 
         let last_mod_str = file_modified_timestamp(&meta);
         let path_str = p.to_string_lossy().to_string();
+        counted_files.fetch_add(1, Ordering::Relaxed);
 
         // Decide if we need to update the cache entry at all
         let needs_update = match cache_map.get(&path_str) {
-            Some(entry) => entry.last_modified != last_mod_str || entry.size != file_size,
+            // Rows from before non-UTF-8 text was decoded are counted again
+            Some(entry) => entry.last_modified != last_mod_str || entry.size != file_size || entry.skipped_reason.as_deref() == Some(SKIP_NON_UTF8),
             None => true,
         };
-        if !needs_update { return Ok(()); }
-
-        // NEW: handle zero-byte files without reading
-        if file_size == 0 {
-            let new_entry = CacheEntry {
-                last_modified: last_mod_str,
-                size: 0,
-                lines: 0,
-                tokens: 0,
-            };
-            { let mut guard = changed_entries.lock().unwrap(); guard.push((path_str.clone(), new_entry)); }
+        if !needs_update {
+            chunks.file_done(p, cache_map.get(&path_str).cloned());
             return Ok(());
         }
 
-        // Non-empty files: read and compute stats as before
-        let content = match fs::read_to_string(p) {
-            Ok(c) => c,
-            Err(_e) => {
-                let error_entry = CacheEntry { last_modified: last_mod_str, size: file_size, lines: 0, tokens: 0 };
-                { let mut guard = changed_entries.lock().unwrap(); guard.push((path_str.clone(), error_entry)); }
-                return Ok(());
-            }
+        let new_entry = if too_large {
+            CacheEntry::skipped(last_mod_str, file_size, SKIP_TOO_LARGE) // counted as 0/0, kept for its size
+        } else if is_placeholder {
+            CacheEntry::skipped(last_mod_str, file_size, SKIP_CLOUD_PLACEHOLDER) // stat-only
+        } else {
+            compute_cache_entry(p, file_size, last_mod_str, extract_documents)
         };
-        let lines = content.lines().count();
-        let tokens = approximate_token_count(&content);
-        let new_entry = CacheEntry { last_modified: last_mod_str, size: file_size, lines, tokens };
+        chunks.file_done(p, Some(new_entry.clone()));
         { let mut guard = changed_entries.lock().unwrap(); guard.push((path_str.clone(), new_entry)); }
         Ok(())
 
     });
+    // Archive entries: one pass per archive instead of reopening it for each entry
+    let process_archives = || expanded_archives.par_iter().try_for_each(|expanded| {
+        wait_while_paused();
+        if is_scan_cancelled() { return Err("Scan cancelled during parallel processing.".to_string()); }
+        watch.note_path(&expanded.archive);
+        let counted = archives::count_entries(expanded, |key| cache_map.get(key), |entry, fresh| {
+            counted_files.fetch_add(1, Ordering::Relaxed);
+            progress.add_bytes(entry.size);
+            match fresh {
+                Some(fresh) => {
+                    chunks.file_done(&entry.path, Some(fresh.clone()));
+                    changed_entries.lock().unwrap().push((entry.path.to_string_lossy().to_string(), fresh));
+                }
+                None => chunks.file_done(&entry.path, cache_map.get(entry.path.to_string_lossy().as_ref()).cloned()),
+            }
+        });
+        if let Err(e) = counted {
+            unreadable_files.lock().unwrap().push(ScanError { path: expanded.archive.to_string_lossy().to_string(), message: format!("Couldn't read archive: {}", e) });
+        }
+        Ok(())
+    });
+    let process = || process_all().and_then(|_| process_archives());
+    watch.set_phase(PHASE_PROCESSING);
+    let parallel_result: Result<(), String> = match &network_pool {
+        Some(pool) => pool.install(process),
+        None => process(),
+    };
 
+    progress.finish();
+    drop(chunks);
     if let Err(e) = parallel_result { return Err(e); }
+    let processing_ms = processing_started.elapsed().as_millis() as u64;
     if is_scan_cancelled() { return Err("Scan cancelled after file processing.".to_string()); }
 
+    let skipped_placeholders = skipped_placeholders.into_inner().unwrap_or_default();
+    let skipped_placeholder_count = skipped_placeholders.len();
+    if !skipped_placeholders.is_empty() {
+        let skipped: std::collections::HashSet<PathBuf> = skipped_placeholders.into_iter().collect();
+        final_valid_paths.retain(|p| !skipped.contains(p));
+    }
+
+    watch.set_phase(PHASE_COMMITTING);
+    let commit_started = Instant::now();
     { // Scope for DB lock for saving cache
         // println!("[SCANNER] Starting transaction for cache updates...");
         let mut conn_lock = conn_arc.lock().map_err(|e| format!("Update lock failed: {}", e))?;
         let tx = conn_lock.transaction().map_err(|e| format!("Begin update transaction failed: {}", e))?;
         
         // Cleanup cache (must happen before saving new/changed entries if paths were removed)
-        scan_cache::cleanup_removed_files(&tx, &final_valid_paths, &mut cache_map)?;
+        cleanup_cache(&tx, &final_valid_paths, &mut cache_map, include_filter.as_ref(), &scan_errors)?;
         
         { // Inner scope for changed_entries lock
             let changed_list = changed_entries.lock().unwrap();
@@ -328,46 +770,254 @@ fn do_actual_scan(
         tx.commit().map_err(|e| format!("Commit update transaction failed: {}", e))?;
         // println!("[SCANNER] Update transaction committed successfully.");
     } // DB lock for saving cache released
+    let mut db_commit_ms = commit_started.elapsed().as_millis() as u64;
+
+    // Filtered files keep their cache rows (cleanup above saw them), so changing
+    // the filters doesn't force a recount; they're only left out of the tree
+    let mut filtered_files = filtered_files.into_inner().unwrap_or_default();
+    filtered_files.sort_by(|a, b| a.path.cmp(&b.path));
+    if !filtered_files.is_empty() {
+        println!("[SCANNER] {} file(s) left out by project {}'s size/age filters.", filtered_files.len(), project_id);
+        let filtered: HashSet<PathBuf> = filtered_files.iter().map(|f| PathBuf::from(&f.path)).collect();
+        final_valid_paths.retain(|p| !filtered.contains(p));
+    }
 
     // println!("[SCANNER] Building final file tree structure from {} final paths using in-memory cache map...", final_valid_paths.len());
-    let file_node = build_tree_from_paths(&root_path, &final_valid_paths, &cache_map);
+    watch.set_phase(PHASE_BUILDING_TREE);
+    let tree_build_started = Instant::now();
+    let mut file_node = build_tree_from_paths(&root_path, &final_valid_paths, &dir_paths, &cache_map, sort);
+    let mut unreadable_files = unreadable_files.into_inner().unwrap_or_default();
+    scan_errors.append(&mut unreadable_files);
+    scan_errors.sort_by(|a, b| a.path.cmp(&b.path));
+    if !scan_errors.is_empty() {
+        println!("[SCANNER] {} folder(s)/file(s) in project {} couldn't be read.", scan_errors.len(), project_id);
+    }
+    mark_errors(&mut file_node, &scan_errors);
+    let tree_build_ms = tree_build_started.elapsed().as_millis() as u64;
     
     // ... (logging of final tree node details can remain if desired) ...
 
+    let misses = changed_entries.lock().map(|c| c.len()).unwrap_or(0);
+    let hits = counted_files.load(Ordering::Relaxed).saturating_sub(misses);
+
+    // Persist the tree so the monitor and tree queries can work between scans;
+    // a targeted scan's partial tree would replace the full one, so it isn't kept
+    // (a subtree rescan is merged into it instead)
+    if let (Some((sub_path, _)), false) = (&subtree, is_scan_cancelled()) {
+        watch.set_phase(PHASE_SAVING);
+        let conn_lock = conn_arc.lock().map_err(|e| format!("Scan result lock failed: {}", e))?;
+        let mut saved = scan_results::load_scan_result(&conn_lock, project_id)?
+            .ok_or_else(|| app_error(errors::NO_SCAN_RESULT, &[("project_id", project_id.to_string())]))?;
+        scan_results::merge_subtree(&mut saved, file_node, &sub_path.to_string_lossy(), case_mode, sort);
+        scan_results::save_scan_result(&conn_lock, project_id, &saved)?;
+        file_node = saved;
+        println!("[SCANNER] Merged rescan of '{}' into project {}'s tree.", sub_path.display(), project_id);
+    } else if !is_scan_cancelled() && include_filter.is_none() {
+        watch.set_phase(PHASE_SAVING);
+        let save_started = Instant::now();
+        let conn_lock = conn_arc.lock().map_err(|e| format!("Scan result lock failed: {}", e))?;
+        if let Err(e) = scan_results::save_scan_result(&conn_lock, project_id, &file_node)
+            .and_then(|_| scan_results::record_cache_usage(&conn_lock, project_id, hits, misses))
+        {
+            eprintln!("[SCANNER] {}", e); // Non-fatal: the tree is still returned
+        }
+        db_commit_ms += save_started.elapsed().as_millis() as u64;
+
+        let metrics = ScanMetrics {
+            project_id,
+            total_ms: scan_started.elapsed().as_millis() as u64,
+            enumeration_ms,
+            processing_ms,
+            db_commit_ms,
+            tree_build_ms,
+            file_count: final_valid_paths.iter().filter(|p| !dir_paths.contains(*p)).count(),
+            dir_count: dir_paths.len(),
+            cache_hits: hits,
+            cache_misses: misses,
+            network_mode,
+            ..Default::default()
+        };
+        if let Err(e) = scan_metrics::record_scan_metrics(&conn_lock, &metrics.with_rates()) {
+            eprintln!("[SCANNER] {}", e);
+        }
+    }
+
+    let mut left_out: Vec<(&str, usize)> = vec![(SKIP_CLOUD_PLACEHOLDER, skipped_placeholder_count), (SKIP_SPECIAL_FILE, special_files.len())];
+    for file in &filtered_files {
+        match left_out.iter_mut().find(|(reason, _)| *reason == file.reason) {
+            Some((_, count)) => *count += 1,
+            None => left_out.push((&file.reason, 1)),
+        }
+    }
+    let mut summary = scan_results::summarize_tree(&file_node, &left_out, scan_started.elapsed().as_millis() as u64);
+    summary.truncated_subtrees = truncated_subtrees;
+    summary.filtered_files = filtered_files;
+    summary.errors = scan_errors;
+    summary.special_files = special_files;
+
+    // Pins are flagged on the returned copy only, so the stored tree never goes stale
+    match conn_arc.lock() {
+        Ok(conn_lock) => pinned_files::mark_pinned(&conn_lock, project_id, &mut file_node),
+        Err(e) => eprintln!("[SCANNER] Pin lookup lock failed: {}", e),
+    }
+    if !is_scan_cancelled() {
+        let statistics = scan_results::scan_statistics(project_id, &file_node, LARGEST_FILES_IN_SUMMARY, hits, misses);
+        emit_to_origin_and_project(window, project_id, "scan_summary", statistics);
+    }
+
+    // The persisted tree stays complete; only the returned copy is pruned/paginated
+    if prune_empty {
+        prune_empty_dirs(&mut file_node);
+    }
+    limit_children(&mut file_node, child_limit);
+
     // println!("[SCANNER] Scan finished successfully for project ID: {}", project_id);
-    Ok(file_node)
+    Ok(ScanOutput { tree: file_node, summary })
 }
 
 
-// --- Helper Function for Progress Emission Payload ---
-// This is separated to avoid repeating the payload creation logic.
-fn emit_progress_payload(
-    window: &Window,
-    path: &std::path::PathBuf,
-    count: usize,
-    total_items: usize,
-) {
-    let percentage = if total_items > 0 { (count as f64 / total_items as f64) * 100.0 } else { 100.0 };
-    
-    let short_path = path
-        .file_name()
-        .map(|os| os.to_string_lossy())
-        .unwrap_or_else(|| path.display().to_string().into());
+// Adds the run to scan_history; best-effort, the scan's result stands either way
+fn record_run(conn_arc: &Arc<Mutex<rusqlite::Connection>>, project_id: i32, scope: &str, started_at: DateTime<Utc>, result: &Result<ScanOutput, String>) {
+    let finished_at = Utc::now();
+    let run = ScanRun {
+        project_id,
+        started_at: started_at.to_rfc3339(),
+        finished_at: finished_at.to_rfc3339(),
+        scope: scope.to_string(),
+        duration_ms: (finished_at - started_at).num_milliseconds().max(0) as u64,
+        ..Default::default()
+    };
+    let run = match result {
+        Ok(output) => ScanRun { status: if is_scan_cancelled() { RUN_CANCELLED } else { RUN_DONE }.to_string(), ..run }.with_summary(&output.summary),
+        Err(_) if is_scan_cancelled() => ScanRun { status: RUN_CANCELLED.to_string(), ..run },
+        Err(e) => ScanRun { status: RUN_FAILED.to_string(), error: Some(e.clone()), ..run },
+    };
+    match conn_arc.lock() {
+        Ok(conn) => {
+            if let Err(e) = scan_history::record_scan_run(&conn, &run) {
+                eprintln!("[SCANNER] {}", e);
+            }
+        }
+        Err(e) => eprintln!("[SCANNER] Scan history lock failed: {}", e),
+    }
+}
 
-    let payload = serde_json::json!({
-        "progress": percentage,
-        "current_path": short_path,
-    });
+// (folder, anchored include glob) for a subtree rescan; None when `sub_path`
+// is the root itself, which is just a full scan
+fn subtree_target(root_path: &Path, sub_path: &str) -> Result<Option<(PathBuf, String)>, String> {
+    let relative = Path::new(sub_path.trim());
+    let relative = if relative.is_absolute() {
+        relative.strip_prefix(root_path).map_err(|_| format!("'{}' isn't inside the project folder.", sub_path))?
+    } else {
+        relative
+    };
+    let mut components = Vec::new();
+    for component in relative.components() {
+        match component {
+            std::path::Component::Normal(name) => components.push(name.to_string_lossy().to_string()),
+            std::path::Component::CurDir => {}
+            _ => return Err(format!("'{}' isn't a folder inside the project.", sub_path)),
+        }
+    }
+    if components.is_empty() {
+        return Ok(None);
+    }
+    let folder = components.iter().fold(root_path.to_path_buf(), |path, name| path.join(name));
+    // Names are literal; escape what gitignore syntax would read as a pattern
+    let escaped: Vec<String> = components
+        .iter()
+        .map(|name| {
+            name.chars().fold(String::new(), |mut out, c| {
+                if matches!(c, '*' | '?' | '[' | ']' | '\\' | '!' | '#') {
+                    out.push('\\');
+                }
+                out.push(c);
+                out
+            })
+        })
+        .collect();
+    Ok(Some((folder, format!("/{}/", escaped.join("/")))))
+}
+
+// Drops cache rows of files that are gone. A targeted scan only saw its slice,
+// so rows outside it stay, and so do rows below entries the walk couldn't read
+// (their files weren't seen, not deleted).
+fn cleanup_cache(
+    tx: &rusqlite::Transaction,
+    valid_paths: &[PathBuf],
+    cache_map: &mut HashMap<String, CacheEntry>,
+    include_filter: Option<&IncludeFilter>,
+    unreadable: &[ScanError],
+) -> Result<(), String> {
+    let in_slice = |p: &str| {
+        include_filter.is_none_or(|filter| filter.matches_file(Path::new(p)))
+            && !unreadable.iter().any(|e| Path::new(p).starts_with(&e.path))
+    };
+    let mut slice: HashMap<String, CacheEntry> =
+        cache_map.iter().filter(|(p, _)| in_slice(p)).map(|(p, e)| (p.clone(), e.clone())).collect();
+    scan_cache::cleanup_removed_files(tx, valid_paths, &mut slice)?;
+    cache_map.retain(|p, _| slice.contains_key(p) || !in_slice(p));
+    Ok(())
+}
 
-    if let Err(e) = window.emit("scan_progress", payload) {
-         eprintln!("Failed to emit scan_progress event: {}", e);
+// --- Per-file Stats ---
+// Reads one file and counts its lines/tokens (non-UTF-8 text is decoded first).
+// Binary and unreadable files get a 0/0 entry with the reason (so they aren't re-read every scan until
+// they change); zero-byte files skip the read.
+pub fn compute_cache_entry(p: &Path, file_size: u64, last_modified: String, extract_documents: bool) -> CacheEntry {
+    if file_size == 0 {
+        return CacheEntry { last_modified, size: 0, lines: 0, tokens: 0, is_binary: false, skipped_reason: None };
+    }
+    if extract_documents && document_extract::is_extractable(p) {
+        return match document_extract::extract_text(p) {
+            Ok(text) => counted_entry(&text, file_size, last_modified),
+            Err(_e) => CacheEntry::skipped(last_modified, file_size, SKIP_UNREADABLE),
+        };
+    }
+    match net_fs::retry_io(|| fs::read(p)) {
+        Ok(bytes) => cache_entry_from_bytes(bytes, file_size, last_modified),
+        Err(_e) => CacheEntry::skipped(last_modified, file_size, SKIP_UNREADABLE),
     }
 }
 
+// Same as `compute_cache_entry` for contents already in memory (archive entries)
+pub fn cache_entry_from_bytes(bytes: Vec<u8>, file_size: u64, last_modified: String) -> CacheEntry {
+    if is_binary_content(&bytes) {
+        let mut entry = CacheEntry::skipped(last_modified, file_size, SKIP_BINARY);
+        entry.is_binary = true;
+        return entry;
+    }
+    counted_entry(&decode_text(bytes), file_size, last_modified)
+}
 
-// Synchronous progress emitter (can be kept or removed if emit_progress_payload is sufficient)
+fn counted_entry(content: &str, file_size: u64, last_modified: String) -> CacheEntry {
+    CacheEntry {
+        last_modified,
+        size: file_size,
+        lines: content.lines().count(),
+        tokens: approximate_token_count(content),
+        is_binary: false,
+        skipped_reason: None,
+    }
+}
+
+// Text whose lines/tokens are counted: extracted document text or the raw file
+fn read_countable_text(p: &Path, extract_documents: bool) -> Result<String, String> {
+    if extract_documents && document_extract::is_extractable(p) {
+        document_extract::extract_text(p)
+    } else {
+        let bytes = net_fs::retry_io(|| fs::read(p)).map_err(|e| e.to_string())?;
+        if is_binary_content(&bytes) {
+            return Err("Binary file has no countable text.".to_string());
+        }
+        Ok(decode_text(bytes))
+    }
+}
+
+// Synchronous progress emitter for the phases outside the aggregator (enumeration)
 fn emit_progress_sync(
     window: &Window,
+    project_id: i32,
     path: &PathBuf,
     count: usize,
     total: usize,
@@ -376,10 +1026,9 @@ fn emit_progress_sync(
     let percentage = if total > 0 { (count as f64 / total as f64) * 100.0 } else { 0.0 };
     let current_path_str = path.file_name().unwrap_or_else(|| path.as_os_str()).to_string_lossy();
     let payload = serde_json::json!({
+        "seq": scan_progress::next_sequence(),
         "progress": percentage,
         "current_path": format!("{}{}", current_path_str, suffix),
     });
-     if let Err(e) = window.emit("scan_progress", payload) {
-         eprintln!("Failed to emit sync scan_progress event: {}", e);
-     }
+    emit_to_origin_and_project(window, project_id, scan_progress::PROGRESS_EVENT, payload);
 }
\ No newline at end of file
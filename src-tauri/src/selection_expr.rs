@@ -0,0 +1,265 @@
+// src-tauri/src/selection_expr.rs
+// Selection expressions: a context set written down instead of clicked, e.g.
+//
+//     src/** & !**/*_test.go | path:"README.md"
+//
+// A bare term is a glob in gitignore syntax relative to the project root;
+// `key:value` terms match on something else (see `Matcher`). Terms combine
+// with `!` (not), `&` (and) and `|` (or), in that order of precedence, and
+// parentheses group. Values with spaces or operator characters go in quotes.
+
+use crate::db::AppState;
+use crate::scan_results::collect_files;
+use crate::tree_queries::{build_glob_matcher, load_tree};
+use crate::types::FileNode;
+use ignore::gitignore::Gitignore;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{command, State};
+
+const KEYS: &[&str] = &["glob", "path", "ext", "lang", "is"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedSelection {
+    pub expression: String,
+    /// Matching files, sorted
+    pub paths: Vec<String>,
+    pub file_count: usize,
+    pub total_tokens: usize,
+    pub total_size: u64,
+}
+
+enum Matcher {
+    Glob(Gitignore),
+    /// `path:` exact path, project-relative (forward slashes) or absolute
+    Path(String),
+    /// `ext:` extension without the dot, case-insensitive
+    Ext(String),
+    /// `lang:` detected language id, e.g. "rust"
+    Lang(String),
+    /// `is:pinned`
+    Pinned,
+    /// `is:binary`
+    Binary,
+}
+
+enum Expr {
+    Term(Matcher),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Term { key: Option<String>, value: String },
+}
+
+fn read_quoted(chars: &[char], i: &mut usize, column: usize) -> Result<String, String> {
+    *i += 1;
+    let mut value = String::new();
+    while *i < chars.len() {
+        match chars[*i] {
+            '"' => {
+                *i += 1;
+                return Ok(value);
+            }
+            '\\' if *i + 1 < chars.len() && chars[*i + 1] == '"' => {
+                value.push('"');
+                *i += 2;
+            }
+            c => {
+                value.push(c);
+                *i += 1;
+            }
+        }
+    }
+    Err(format!("Unclosed quote starting at column {}.", column))
+}
+
+// (token, 1-based column)
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '!' => Token::Not,
+            '"' => {
+                let value = read_quoted(&chars, &mut i, column)?;
+                tokens.push((Token::Term { key: None, value }, column));
+                continue;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()&|\"".contains(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let key = word.split_once(':').map(|(k, _)| k.to_lowercase()).filter(|k| KEYS.contains(&k.as_str()));
+                let value = match &key {
+                    Some(key) if word.len() == key.len() + 1 && i < chars.len() && chars[i] == '"' => read_quoted(&chars, &mut i, column)?,
+                    Some(key) => word[key.len() + 1..].to_string(),
+                    None => word,
+                };
+                if value.is_empty() {
+                    return Err(format!("Empty value for '{}:' at column {}.", key.unwrap_or_default(), column));
+                }
+                tokens.push((Token::Term { key, value }, column));
+                continue;
+            }
+        };
+        tokens.push((token, column));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    // Column just past the last character
+    end: usize,
+    root: &'a Path,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, c)| *c)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        let column = self.column();
+        match self.tokens.get(self.pos).map(|(t, _)| t) {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Open) => {
+                self.pos += 1;
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(format!("Expected ')' at column {} to close the '(' at column {}.", self.column(), column));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Token::Term { key, value }) => {
+                let matcher = self.matcher(key.as_deref(), value).map_err(|e| format!("{} (column {})", e, column))?;
+                self.pos += 1;
+                Ok(Expr::Term(matcher))
+            }
+            Some(_) => Err(format!("Expected a term at column {}.", column)),
+            None => Err("The expression ends where a term was expected.".to_string()),
+        }
+    }
+
+    fn matcher(&self, key: Option<&str>, value: &str) -> Result<Matcher, String> {
+        Ok(match key.unwrap_or("glob") {
+            "glob" => Matcher::Glob(build_glob_matcher(self.root, &[value.to_string()])?),
+            "path" => Matcher::Path(value.replace('\\', "/").trim_start_matches("./").to_string()),
+            "ext" => Matcher::Ext(value.trim_start_matches('.').to_lowercase()),
+            "lang" => Matcher::Lang(value.to_lowercase()),
+            "is" => match value.to_lowercase().as_str() {
+                "pinned" => Matcher::Pinned,
+                "binary" => Matcher::Binary,
+                other => return Err(format!("Unknown 'is:{}'; use is:pinned or is:binary", other)),
+            },
+            other => return Err(format!("Unknown key '{}:'", other)),
+        })
+    }
+}
+
+fn parse(expression: &str, root: &Path) -> Result<Expr, String> {
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("The selection expression is empty.".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0, end: expression.chars().count() + 1, root };
+    let expr = parser.or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("Expected '&', '|' or the end at column {}.", parser.column()));
+    }
+    Ok(expr)
+}
+
+fn matches(expr: &Expr, file: &FileNode, root: &Path) -> bool {
+    match expr {
+        Expr::Not(inner) => !matches(inner, file, root),
+        Expr::And(a, b) => matches(a, file, root) && matches(b, file, root),
+        Expr::Or(a, b) => matches(a, file, root) || matches(b, file, root),
+        Expr::Term(matcher) => match matcher {
+            Matcher::Glob(glob) => glob.matched_path_or_any_parents(Path::new(&file.path), false).is_ignore(),
+            Matcher::Path(wanted) => {
+                let path = file.path.replace('\\', "/");
+                let relative = Path::new(&file.path).strip_prefix(root).map(|r| r.to_string_lossy().replace('\\', "/"));
+                path == *wanted || relative.is_ok_and(|r| r == *wanted)
+            }
+            Matcher::Ext(ext) => file.extension.as_deref() == Some(ext.as_str()),
+            Matcher::Lang(lang) => file.language.as_deref() == Some(lang.as_str()),
+            Matcher::Pinned => file.pinned,
+            Matcher::Binary => file.is_binary,
+        },
+    }
+}
+
+/// The files of `tree` selected by `expression`, sorted by path.
+pub fn resolve<'a>(tree: &'a FileNode, expression: &str) -> Result<Vec<&'a FileNode>, String> {
+    let root = Path::new(&tree.path);
+    let expr = parse(expression, root)?;
+    let mut files: Vec<&FileNode> = collect_files(tree).into_iter().filter(|f| matches(&expr, f, root)).collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Files of the last scan of `project_id` selected by a selection expression.
+#[command]
+pub fn resolve_selection(state: State<'_, AppState>, project_id: i32, expression: String) -> Result<ResolvedSelection, String> {
+    let tree = load_tree(&state, project_id)?;
+    let files = resolve(&tree, &expression)?;
+    Ok(ResolvedSelection {
+        file_count: files.len(),
+        total_tokens: files.iter().map(|f| f.tokens).sum(),
+        total_size: files.iter().map(|f| f.size).sum(),
+        paths: files.iter().map(|f| f.path.clone()).collect(),
+        expression,
+    })
+}
@@ -0,0 +1,188 @@
+// src-tauri/src/sensitive_files.rs
+// Pre-export check of a selection for files that likely hold secrets or
+// personal data: by file name (.env, id_rsa, *.pem, secrets.*) and by content
+// (the redaction rules plus simple PII patterns). The UI shows the warnings
+// before the context is assembled; nothing is blocked here.
+
+use crate::path_canon::canonical_paths;
+use crate::redaction::Redactor;
+use crate::scanner::MAX_FILE_SIZE_BYTES;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::command;
+
+pub const SEVERITY_HIGH: &str = "high";
+pub const SEVERITY_MEDIUM: &str = "medium";
+
+// Distinct email addresses before a file counts as a contact list
+const EMAIL_THRESHOLD: usize = 5;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SensitiveFileWarning {
+    pub path: String,
+    /// "secret", "credentials", "private_key" or "pii"
+    pub category: String,
+    pub severity: String,
+    /// "filename" or "content"
+    pub source: String,
+    pub detail: String,
+}
+
+// (lowercase file-name pattern, category, severity); `*` only as a leading or trailing wildcard
+const NAME_RULES: &[(&str, &str, &str)] = &[
+    (".env", "secret", SEVERITY_HIGH),
+    (".env.*", "secret", SEVERITY_HIGH),
+    ("*.env", "secret", SEVERITY_HIGH),
+    ("id_rsa", "private_key", SEVERITY_HIGH),
+    ("id_dsa", "private_key", SEVERITY_HIGH),
+    ("id_ecdsa", "private_key", SEVERITY_HIGH),
+    ("id_ed25519", "private_key", SEVERITY_HIGH),
+    ("*.pem", "private_key", SEVERITY_HIGH),
+    ("*.key", "private_key", SEVERITY_HIGH),
+    ("*.p12", "private_key", SEVERITY_HIGH),
+    ("*.pfx", "private_key", SEVERITY_HIGH),
+    ("*.jks", "private_key", SEVERITY_HIGH),
+    ("*.keystore", "private_key", SEVERITY_HIGH),
+    ("secrets.*", "secret", SEVERITY_HIGH),
+    ("secret.*", "secret", SEVERITY_HIGH),
+    ("credentials", "credentials", SEVERITY_HIGH),
+    ("credentials.*", "credentials", SEVERITY_HIGH),
+    ("service-account*", "credentials", SEVERITY_HIGH),
+    (".npmrc", "credentials", SEVERITY_MEDIUM),
+    (".pypirc", "credentials", SEVERITY_MEDIUM),
+    (".netrc", "credentials", SEVERITY_HIGH),
+    (".htpasswd", "credentials", SEVERITY_HIGH),
+    ("*.kdbx", "credentials", SEVERITY_HIGH),
+    ("*.tfstate", "secret", SEVERITY_HIGH),
+    ("*.sqlite", "pii", SEVERITY_MEDIUM),
+    ("*.db", "pii", SEVERITY_MEDIUM),
+];
+
+// Committed templates of sensitive files are meant to be shared
+const TEMPLATE_SUFFIXES: &[&str] = &[".example", ".sample", ".template", ".dist", ".defaults"];
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").expect("email regex"));
+static US_SSN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").expect("ssn regex"));
+static CARD_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:\d[ -]?){13,19}\b").expect("card regex"));
+
+fn name_matches(name: &str, pattern: &str) -> bool {
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        (Some(suffix), _) => name.ends_with(suffix),
+        (None, Some(prefix)) => name.starts_with(prefix),
+        (None, None) => name == pattern,
+    }
+}
+
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn has_card_number(text: &str) -> bool {
+    CARD_NUMBER.find_iter(text).any(|m| {
+        let digits: Vec<u32> = m.as_str().chars().filter_map(|c| c.to_digit(10)).collect();
+        (13..=19).contains(&digits.len()) && digits.iter().any(|&d| d != digits[0]) && luhn_valid(&digits)
+    })
+}
+
+fn warning(path: &str, category: &str, severity: &str, source: &str, detail: String) -> SensitiveFileWarning {
+    SensitiveFileWarning {
+        path: path.to_string(),
+        category: category.to_string(),
+        severity: severity.to_string(),
+        source: source.to_string(),
+        detail,
+    }
+}
+
+fn check_name(path: &str) -> Option<SensitiveFileWarning> {
+    let name = Path::new(path).file_name()?.to_string_lossy().to_lowercase();
+    if TEMPLATE_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+        return None;
+    }
+    NAME_RULES
+        .iter()
+        .find(|(pattern, _, _)| name_matches(&name, pattern))
+        .map(|(pattern, category, severity)| {
+            warning(path, category, severity, "filename", format!("File name matches '{}'", pattern))
+        })
+}
+
+fn check_content(path: &str, redactor: &Redactor) -> Vec<SensitiveFileWarning> {
+    let readable = fs::metadata(path).map(|m| m.is_file() && m.len() <= MAX_FILE_SIZE_BYTES).unwrap_or(false);
+    let Some(text) = readable.then(|| fs::read_to_string(path).ok()).flatten() else { return Vec::new() };
+    let mut warnings = Vec::new();
+
+    // Secrets: whatever the redaction pass would replace, grouped by type
+    let mut secret_lines: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for hit in redactor.redact(&text).redactions {
+        secret_lines.entry(hit.kind).or_default().push(hit.line);
+    }
+    for (kind, lines) in secret_lines {
+        let category = if kind == "private_key" { "private_key" } else { "secret" };
+        let shown: Vec<String> = lines.iter().take(5).map(|l| l.to_string()).collect();
+        warnings.push(warning(
+            path,
+            category,
+            SEVERITY_HIGH,
+            "content",
+            format!("{} ({}x, line {})", kind, lines.len(), shown.join(", ")),
+        ));
+    }
+
+    let emails: HashSet<&str> = EMAIL.find_iter(&text).map(|m| m.as_str()).collect();
+    if emails.len() >= EMAIL_THRESHOLD {
+        warnings.push(warning(path, "pii", SEVERITY_MEDIUM, "content", format!("{} distinct email addresses", emails.len())));
+    }
+    if US_SSN.is_match(&text) {
+        warnings.push(warning(path, "pii", SEVERITY_HIGH, "content", "Looks like a US social security number".to_string()));
+    }
+    if has_card_number(&text) {
+        warnings.push(warning(path, "pii", SEVERITY_HIGH, "content", "Looks like a payment card number".to_string()));
+    }
+    warnings
+}
+
+/// Warnings for one selection: name-based first, then content-based, in path order.
+pub fn check_paths(paths: &[String], check_contents: bool) -> Vec<SensitiveFileWarning> {
+    let redactor = Redactor::builtin();
+    let mut warnings: Vec<SensitiveFileWarning> = paths
+        .par_iter()
+        .flat_map_iter(|path| {
+            let mut found: Vec<SensitiveFileWarning> = check_name(path).into_iter().collect();
+            if check_contents {
+                found.extend(check_content(path, &redactor));
+            }
+            found
+        })
+        .collect();
+    warnings.sort_by(|a, b| a.path.cmp(&b.path));
+    warnings
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Flags selected files likely to contain secrets or PII. `check_contents`
+/// (default true) adds the content heuristics on top of the file-name rules.
+#[command(async)]
+pub async fn check_sensitive_files(
+    paths: Vec<String>,
+    check_contents: Option<bool>,
+) -> Result<Vec<SensitiveFileWarning>, String> {
+    let check_contents = check_contents.unwrap_or(true);
+    let paths = canonical_paths(&paths);
+    tauri::async_runtime::spawn_blocking(move || check_paths(&paths, check_contents))
+        .await
+        .map_err(|e| format!("Sensitive file check failed: {}", e))
+}
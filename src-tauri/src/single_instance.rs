@@ -0,0 +1,300 @@
+// src-tauri/src/single_instance.rs
+// One running instance per database directory. The first launch listens on a
+// loopback port recorded (with a secret token) next to the database; later
+// launches hand their "open project X" request to it and exit instead of
+// opening a second window on the same database. A lock file taken with
+// create-new keeps two launches from claiming the slot at once, and the
+// primary removes the instance file on exit.
+//
+// Launch arguments: `--open-project <id or root folder>` (or just the id/folder).
+
+use crate::db::{self, AppState};
+use crate::projects;
+use crate::scan_tree::fnv1a;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Emitter, Manager};
+
+const INSTANCE_FILE: &str = "code_context_builder.instance";
+const LOCK_FILE: &str = "code_context_builder.instance.lock";
+// How long a launch waits for another one to finish claiming the slot
+const LOCK_WAIT: Duration = Duration::from_secs(5);
+const LOCK_POLL: Duration = Duration::from_millis(50);
+// A lock this old was left by a launch that died while holding it
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(3);
+// Requests are one short JSON line; anything longer is not ours
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+// Open request of the launch that started this instance, until the frontend takes it
+static PENDING_REQUEST: Mutex<Option<OpenProjectRequest>> = Mutex::new(None);
+// Token this instance wrote to the instance file, so `release` only removes its own
+static OWN_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Debug)]
+struct HandoffMessage {
+    token: String,
+    /// Project id or root folder given on the command line, if any
+    project: Option<String>,
+}
+
+/// Payload of the `open-project-request` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenProjectRequest {
+    /// What the launch asked for, as given
+    pub requested: String,
+    /// The matching project; None when no project has that id or root folder
+    pub project_id: Option<i32>,
+    /// The requested folder when it didn't match a project, so the UI can offer to create one
+    pub root_folder: Option<String>,
+}
+
+pub enum Launch {
+    /// This is the only instance; serve handoffs from later launches with `serve`.
+    Primary(Option<TcpListener>),
+    /// The request went to the running instance; this process should exit.
+    HandedOff(String),
+}
+
+fn requested_project() -> Option<String> {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--open-project" {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix("--open-project=") {
+            return Some(value.to_string());
+        }
+        if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+// "<port> <token>" of the running instance, if the file exists
+fn read_instance_file(path: &Path) -> Option<(u16, String)> {
+    let text = fs::read_to_string(path).ok()?;
+    let mut parts = text.split_whitespace();
+    let port = parts.next()?.parse().ok()?;
+    let token = parts.next()?.to_string();
+    Some((port, token))
+}
+
+fn new_token() -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let seed = format!("{}:{}:{:p}", std::process::id(), nanos, &nanos);
+    format!("{:016x}{:016x}", fnv1a(seed.as_bytes()), fnv1a(nanos.to_string().as_bytes()))
+}
+
+// Held while a launch checks for and claims the instance slot; removed on drop
+struct LaunchLock(PathBuf);
+
+impl Drop for LaunchLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+// None when the lock can't be had in time (the launch then goes ahead unguarded)
+fn lock_launch(dir: &Path) -> Option<LaunchLock> {
+    let path = dir.join(LOCK_FILE);
+    let started = Instant::now();
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Some(LaunchLock(path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let age = fs::metadata(&path).and_then(|m| m.modified()).ok().and_then(|t| t.elapsed().ok());
+                if age.is_some_and(|age| age > STALE_LOCK_AGE) {
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                if started.elapsed() > LOCK_WAIT {
+                    eprintln!("[INSTANCE] '{}' is still locked after {:?}; continuing without it.", path.display(), LOCK_WAIT);
+                    return None;
+                }
+                thread::sleep(LOCK_POLL);
+            }
+            Err(e) => {
+                eprintln!("[INSTANCE] Failed to create '{}', continuing without it: {}", path.display(), e);
+                return None;
+            }
+        }
+    }
+}
+
+fn hand_off(stream: TcpStream, message: &HandoffMessage) -> Result<String, String> {
+    stream.set_read_timeout(Some(REPLY_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+    line.push('\n');
+    (&stream).write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply).map_err(|e| e.to_string())?;
+    match reply.trim() {
+        "ok" => Ok(match &message.project {
+            Some(project) => format!("Code Context Builder is already running; asked it to open '{}'.", project),
+            None => "Code Context Builder is already running; switched to its window.".to_string(),
+        }),
+        "" => Err("no reply".to_string()),
+        other => Err(other.to_string()),
+    }
+}
+
+/// Hands this launch's request to a running instance, or claims the instance
+/// slot. An instance file whose port doesn't answer as ours (left over from a
+/// crash, or the port reused by another program) is taken over. If the slot
+/// can't be claimed, the app still runs, just without handoff.
+pub fn acquire() -> Result<Launch, String> {
+    let dir = db::db_dir()?;
+    let instance_path = dir.join(INSTANCE_FILE);
+    let project = requested_project();
+    let _lock = lock_launch(&dir);
+
+    if let Some((port, token)) = read_instance_file(&instance_path) {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        if let Ok(stream) = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            let message = HandoffMessage { token, project: project.clone() };
+            match hand_off(stream, &message) {
+                Ok(handed_off) => return Ok(Launch::HandedOff(handed_off)),
+                Err(e) => eprintln!("[INSTANCE] Port {} from '{}' didn't answer as an instance ({}); taking over.", port, instance_path.display(), e),
+            }
+        }
+    }
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[INSTANCE] Failed to listen for other launches, continuing without handoff: {}", e);
+            return Ok(Launch::Primary(None));
+        }
+    };
+    let port = listener.local_addr().map_err(|e| format!("Failed to read listener address: {}", e))?.port();
+    let token = new_token();
+    if let Err(e) = fs::write(&instance_path, format!("{} {}", port, token)) {
+        eprintln!("[INSTANCE] Failed to write '{}', continuing without handoff: {}", instance_path.display(), e);
+        return Ok(Launch::Primary(None));
+    }
+    if let Ok(mut own) = OWN_TOKEN.lock() {
+        *own = Some(token);
+    }
+    if let Some(project) = project {
+        // Resolved once the database is open (see `serve`)
+        if let Ok(mut pending) = PENDING_REQUEST.lock() {
+            *pending = Some(OpenProjectRequest { requested: project, project_id: None, root_folder: None });
+        }
+    }
+    Ok(Launch::Primary(Some(listener)))
+}
+
+fn same_folder(a: &str, b: &str) -> bool {
+    let trim = |p: &str| p.trim_end_matches(['/', '\\']).to_string();
+    if cfg!(windows) {
+        trim(a).eq_ignore_ascii_case(&trim(b))
+    } else {
+        trim(a) == trim(b)
+    }
+}
+
+fn resolve_request(app_handle: &AppHandle, requested: String) -> OpenProjectRequest {
+    let projects = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "App state is not ready".to_string())
+        .and_then(|state| {
+            let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+            projects::load_all_projects(&conn)
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("[INSTANCE] Failed to load projects: {}", e);
+            Vec::new()
+        });
+    let by_id = requested.parse::<i32>().ok().filter(|id| projects.iter().any(|p| p.id == *id));
+    let project_id = by_id.or_else(|| {
+        projects
+            .iter()
+            .find(|p| p.root_folder.as_deref().is_some_and(|root| same_folder(root, &requested)))
+            .map(|p| p.id)
+    });
+    let root_folder = (project_id.is_none() && Path::new(&requested).is_dir()).then(|| requested.clone());
+    OpenProjectRequest { requested, project_id, root_folder }
+}
+
+fn handle_connection(app_handle: &AppHandle, stream: TcpStream, token: &str) -> Result<(), String> {
+    stream.set_read_timeout(Some(REPLY_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_REQUEST_BYTES)).read_line(&mut line).map_err(|e| e.to_string())?;
+    let message: HandoffMessage = serde_json::from_str(line.trim()).map_err(|e| format!("Bad request: {}", e))?;
+    if message.token != token {
+        let _ = (&stream).write_all(b"denied\n");
+        return Err("Rejected request with a wrong token".to_string());
+    }
+    (&stream).write_all(b"ok\n").map_err(|e| e.to_string())?;
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    if let Some(requested) = message.project {
+        let request = resolve_request(app_handle, requested);
+        println!("[INSTANCE] Open request from another launch: {:?}", request);
+        app_handle.emit("open-project-request", &request).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Serves handoffs from later launches on a background thread. Call once the
+/// database state is managed.
+pub fn serve(app_handle: AppHandle, listener: TcpListener) {
+    if let Ok(mut pending) = PENDING_REQUEST.lock() {
+        if let Some(request) = pending.take() {
+            *pending = Some(resolve_request(&app_handle, request.requested));
+        }
+    }
+    let Some((_, token)) = db::db_dir().ok().and_then(|dir| read_instance_file(&dir.join(INSTANCE_FILE))) else {
+        eprintln!("[INSTANCE] Instance file disappeared; not serving handoffs.");
+        return;
+    };
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_connection(&app_handle, stream, &token) {
+                        eprintln!("[INSTANCE] Handoff failed: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("[INSTANCE] Failed to accept connection: {}", e),
+            }
+        }
+    });
+}
+
+/// Removes the instance file on shutdown, unless another instance has taken
+/// the slot over since.
+pub fn release() {
+    let Some(token) = OWN_TOKEN.lock().ok().and_then(|mut own| own.take()) else { return };
+    let Ok(dir) = db::db_dir() else { return };
+    let instance_path = dir.join(INSTANCE_FILE);
+    let _lock = lock_launch(&dir);
+    if read_instance_file(&instance_path).is_some_and(|(_, current)| current == token) {
+        if let Err(e) = fs::remove_file(&instance_path) {
+            eprintln!("[INSTANCE] Failed to remove '{}': {}", instance_path.display(), e);
+        }
+    }
+}
+
+// --- Exposed Tauri Commands ---
+
+/// The project this instance was launched to open, once; the frontend calls it
+/// at startup. Later launches arrive as `open-project-request` events.
+#[command]
+pub fn take_launch_request() -> Result<Option<OpenProjectRequest>, String> {
+    let mut pending = PENDING_REQUEST.lock().map_err(|e| format!("Launch request lock failed: {}", e))?;
+    Ok(pending.take())
+}
@@ -0,0 +1,176 @@
+// src-tauri/src/snapshots.rs
+// Named snapshots of a project's scanned tree (per-file stats only) and diffs
+// between them, for tracking how a project's context footprint grows.
+
+use crate::db::AppState;
+use crate::errors::{self, app_error};
+use crate::scan_results;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SnapshotFileStats {
+    pub lines: usize,
+    pub tokens: usize,
+    pub size: u64,
+    pub last_modified: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TreeSnapshotInfo {
+    pub id: i64,
+    pub project_id: i32,
+    pub name: String,
+    pub created_at: String,
+    pub file_count: usize,
+    pub total_tokens: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SnapshotFileDelta {
+    pub path: String,
+    pub old_tokens: Option<usize>,
+    pub new_tokens: Option<usize>,
+    pub token_delta: i64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SnapshotDiff {
+    pub added: Vec<SnapshotFileDelta>,
+    pub removed: Vec<SnapshotFileDelta>,
+    pub changed: Vec<SnapshotFileDelta>,
+    pub total_token_delta: i64,
+}
+
+fn load_snapshot_files(conn: &Connection, snapshot_id: i64) -> Result<BTreeMap<String, SnapshotFileStats>, String> {
+    let files_json: String = conn
+        .query_row(
+            "SELECT files_json FROM tree_snapshots WHERE id = ?1",
+            params![snapshot_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query snapshot {}: {}", snapshot_id, e))?
+        .ok_or_else(|| format!("Snapshot with ID {} not found.", snapshot_id))?;
+    serde_json::from_str(&files_json).map_err(|e| format!("Snapshot {} data is corrupt: {}", snapshot_id, e))
+}
+
+pub fn diff_snapshot_files(
+    old: &BTreeMap<String, SnapshotFileStats>,
+    new: &BTreeMap<String, SnapshotFileStats>,
+) -> SnapshotDiff {
+    let mut diff = SnapshotDiff::default();
+    for (path, new_stats) in new {
+        match old.get(path) {
+            None => diff.added.push(SnapshotFileDelta {
+                path: path.clone(),
+                old_tokens: None,
+                new_tokens: Some(new_stats.tokens),
+                token_delta: new_stats.tokens as i64,
+            }),
+            Some(old_stats) if old_stats != new_stats => diff.changed.push(SnapshotFileDelta {
+                path: path.clone(),
+                old_tokens: Some(old_stats.tokens),
+                new_tokens: Some(new_stats.tokens),
+                token_delta: new_stats.tokens as i64 - old_stats.tokens as i64,
+            }),
+            Some(_) => (),
+        }
+    }
+    for (path, old_stats) in old {
+        if !new.contains_key(path) {
+            diff.removed.push(SnapshotFileDelta {
+                path: path.clone(),
+                old_tokens: Some(old_stats.tokens),
+                new_tokens: None,
+                token_delta: -(old_stats.tokens as i64),
+            });
+        }
+    }
+    diff.total_token_delta = diff.added.iter().chain(&diff.removed).chain(&diff.changed).map(|d| d.token_delta).sum();
+    diff
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Saves the project's last scanned tree as a named snapshot. Returns the snapshot ID.
+#[command]
+pub fn save_tree_snapshot(state: State<AppState>, project_id: i32, name: String) -> Result<i64, String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let tree = scan_results::load_scan_result(&conn, project_id)?
+        .ok_or_else(|| app_error(errors::NO_SCAN_RESULT, &[("project_id", project_id.to_string())]))?;
+
+    let files: BTreeMap<String, SnapshotFileStats> = scan_results::collect_files(&tree)
+        .into_iter()
+        .map(|f| {
+            let stats = SnapshotFileStats {
+                lines: f.lines,
+                tokens: f.tokens,
+                size: f.size,
+                last_modified: f.last_modified.clone(),
+            };
+            (f.path.clone(), stats)
+        })
+        .collect();
+    let files_json = serde_json::to_string(&files).map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+
+    conn.execute(
+        r#"
+        INSERT INTO tree_snapshots (project_id, name, created_at, file_count, total_tokens, files_json)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        params![project_id, name, Utc::now().to_rfc3339(), files.len() as i64, tree.tokens as i64, files_json],
+    )
+    .map_err(|e| format!("Failed to save snapshot '{}': {}", name, e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[command]
+pub fn list_tree_snapshots(state: State<AppState>, project_id: i32) -> Result<Vec<TreeSnapshotInfo>, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, project_id, name, created_at, file_count, total_tokens
+            FROM tree_snapshots
+            WHERE project_id = ?1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .map_err(|e| format!("Prepare statement failed: {}", e))?;
+    let rows = stmt
+        .query_map(params![project_id], |row| {
+            Ok(TreeSnapshotInfo {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                name: row.get(2)?,
+                created_at: row.get(3)?,
+                file_count: row.get::<_, i64>(4)? as usize,
+                total_tokens: row.get::<_, i64>(5)? as usize,
+            })
+        })
+        .map_err(|e| format!("Query snapshots failed: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to map snapshot row: {}", e))
+}
+
+#[command]
+pub fn delete_tree_snapshot(state: State<AppState>, snapshot_id: i64) -> Result<(), String> {
+    crate::db::ensure_writable(&state)?;
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    conn.execute("DELETE FROM tree_snapshots WHERE id = ?1", params![snapshot_id])
+        .map_err(|e| format!("Failed to delete snapshot {}: {}", snapshot_id, e))?;
+    Ok(())
+}
+
+/// Files added/removed/changed going from snapshot `from_id` to snapshot `to_id`.
+#[command]
+pub fn diff_tree_snapshots(state: State<AppState>, from_id: i64, to_id: i64) -> Result<SnapshotDiff, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let old = load_snapshot_files(&conn, from_id)?;
+    let new = load_snapshot_files(&conn, to_id)?;
+    Ok(diff_snapshot_files(&old, &new))
+}
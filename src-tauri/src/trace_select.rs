@@ -0,0 +1,328 @@
+// src-tauri/src/trace_select.rs
+// Builds a file selection from a pasted stack trace or compiler error: file
+// references and function names are extracted, mapped onto the project's last
+// scan tree, and optionally expanded with the matched files' direct imports.
+
+use crate::db::AppState;
+use crate::inclusion_rules::load_rules;
+use crate::relevance::SYMBOL_DEF_REGEX;
+use crate::scan_results;
+use crate::tree_queries::load_tree;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+// Python: File "app/models.py", line 42, in save
+static PYTHON_FRAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"File "([^"]+)", line (\d+)(?:, in ([A-Za-z_][\w.<>]*))?"#).expect("python frame regex"));
+// Java/Kotlin/C#: at com.acme.Foo.bar(Foo.java:42)
+static JVM_FRAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"at ((?:[\w$]+\.)+)([\w$<>]+)\(([\w$]+\.(?:java|kt|scala|groovy|cs)):(\d+)\)").expect("jvm frame regex")
+});
+// JS/Node: at handler (src/api/user.ts:10:5)
+static JS_FRAME_FN: Lazy<Regex> = Lazy::new(|| Regex::new(r"at (?:async )?([\w$.<>]+) \(").expect("js frame regex"));
+// Generic path:line[:col] or path(line,col), covering rustc (--> src/x.rs:1:2), tsc, go, gcc, node
+static PATH_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"((?:[A-Za-z]:)?[\w./\\@~+-]*[\w-]\.[A-Za-z][A-Za-z0-9]{0,7})(?::(\d+)(?::\d+)?|\((\d+)(?:,\d+)?\))")
+        .expect("path:line regex")
+});
+
+// Imports that can be resolved to a file relative to the importing file
+static JS_IMPORT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:import\s[^'"]*?from\s*|import\s*\(\s*|require\s*\(\s*|export\s[^'"]*?from\s*)['"](\.{1,2}/[^'"]+)['"]"#)
+        .expect("js import regex")
+});
+static PY_IMPORT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*(?:from\s+([\w.]+)\s+import|import\s+([\w.]+))").expect("python import regex"));
+static RS_MOD: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;").expect("rust mod regex"));
+static C_INCLUDE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?m)^\s*#\s*include\s+"([^"]+)""#).expect("include regex"));
+
+pub const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "vue", "svelte"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TraceMatch {
+    pub path: String,
+    /// Line numbers referenced by the trace for this file
+    pub lines: Vec<u32>,
+    /// "path" (file referenced directly), "symbol" (defines a function named in the trace), "import"
+    /// or "always" (project's always-include list)
+    pub matched_by: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TraceSelection {
+    pub matches: Vec<TraceMatch>,
+    /// File references from the trace that aren't in the project (stdlib, dependencies...)
+    pub unmatched_paths: Vec<String>,
+    pub symbols: Vec<String>,
+}
+
+#[derive(Default)]
+struct ParsedTrace {
+    // referenced path -> line numbers
+    paths: BTreeMap<String, Vec<u32>>,
+    symbols: Vec<String>,
+}
+
+fn parse_trace(trace: &str) -> ParsedTrace {
+    let mut parsed = ParsedTrace::default();
+    let mut symbols = HashSet::new();
+    let add_path = |path: &str, line: Option<u32>, parsed: &mut ParsedTrace| {
+        let lines = parsed.paths.entry(path.trim().to_string()).or_default();
+        if let Some(l) = line {
+            if !lines.contains(&l) {
+                lines.push(l);
+            }
+        }
+    };
+
+    for cap in PYTHON_FRAME.captures_iter(trace) {
+        add_path(&cap[1], cap[2].parse().ok(), &mut parsed);
+        if let Some(func) = cap.get(3) {
+            symbols.insert(func.as_str().to_string());
+        }
+    }
+    for cap in JVM_FRAME.captures_iter(trace) {
+        // Package path + file name: com.acme.Foo.bar(Foo.java) -> com/acme/Foo.java
+        let package = cap[1].trim_end_matches('.');
+        let package_dir = package.rsplit_once('.').map_or("", |(dir, _class)| dir).replace('.', "/");
+        let path = if package_dir.is_empty() { cap[3].to_string() } else { format!("{}/{}", package_dir, &cap[3]) };
+        add_path(&path, cap[4].parse().ok(), &mut parsed);
+        symbols.insert(cap[2].to_string());
+    }
+    for cap in JS_FRAME_FN.captures_iter(trace) {
+        let func = cap[1].rsplit('.').next().unwrap_or(&cap[1]);
+        symbols.insert(func.to_string());
+    }
+    for cap in PATH_LINE.captures_iter(trace) {
+        let line = cap.get(2).or_else(|| cap.get(3)).and_then(|m| m.as_str().parse().ok());
+        let path = cap[1].trim_start_matches("file://");
+        // JVM frames already added the package-qualified path for `Foo.java:42`
+        let known = parsed.paths.keys().any(|k| k.ends_with(&format!("/{}", path)));
+        if !known && !path.contains("://") {
+            add_path(path, line, &mut parsed);
+        }
+    }
+
+    symbols.retain(|s| s.len() > 2 && !matches!(s.as_str(), "<module>" | "<anonymous>" | "new" | "main"));
+    parsed.symbols = symbols.into_iter().collect();
+    parsed.symbols.sort();
+    parsed
+}
+
+fn path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\']).filter(|c| !c.is_empty() && *c != ".").collect()
+}
+
+// Files whose path shares the longest trailing run of components with `trace_path`
+fn resolve_trace_path<'a>(trace_path: &str, by_name: &HashMap<String, Vec<&'a str>>) -> Vec<&'a str> {
+    let trace_parts = path_components(trace_path);
+    let Some(file_name) = trace_parts.last() else { return Vec::new() };
+    let Some(candidates) = by_name.get(&file_name.to_lowercase()) else { return Vec::new() };
+
+    let mut best = 0;
+    let mut matched = Vec::new();
+    for &candidate in candidates {
+        let parts = path_components(candidate);
+        let common = parts
+            .iter()
+            .rev()
+            .zip(trace_parts.iter().rev())
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+        match common.cmp(&best) {
+            std::cmp::Ordering::Greater => {
+                best = common;
+                matched = vec![candidate];
+            }
+            std::cmp::Ordering::Equal if common > 0 => matched.push(candidate),
+            _ => (),
+        }
+    }
+    // A bare file name shared by many files is too ambiguous to select them all
+    if best == 1 && matched.len() > 3 {
+        return Vec::new();
+    }
+    matched
+}
+
+/// Paths of the project files directly imported by `path` (relative imports only).
+pub fn direct_imports(path: &str, project_files: &HashSet<&str>) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(path) else { return Vec::new() };
+    let file = Path::new(path);
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    match extension {
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "vue" | "svelte" => {
+            for cap in JS_IMPORT.captures_iter(&content) {
+                let base = dir.join(&cap[1]);
+                candidates.push(base.clone());
+                for ext in JS_EXTENSIONS {
+                    candidates.push(base.with_extension(ext));
+                    candidates.push(base.join(format!("index.{}", ext)));
+                }
+            }
+        }
+        "py" => {
+            for cap in PY_IMPORT.captures_iter(&content) {
+                let module = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+                let relative_depth = module.chars().take_while(|c| *c == '.').count();
+                let module_path = module.trim_start_matches('.').replace('.', "/");
+                // Relative imports climb from the file's package; absolute ones are tried from every ancestor
+                let bases: Vec<&Path> = if relative_depth > 0 {
+                    dir.ancestors().nth(relative_depth - 1).into_iter().collect()
+                } else {
+                    dir.ancestors().collect()
+                };
+                for base in bases {
+                    candidates.push(base.join(format!("{}.py", module_path)));
+                    candidates.push(base.join(&module_path).join("__init__.py"));
+                }
+            }
+        }
+        "rs" => {
+            let module_dir = match file.file_stem().and_then(|s| s.to_str()) {
+                Some("main" | "lib" | "mod") => dir.to_path_buf(),
+                Some(stem) => dir.join(stem),
+                None => dir.to_path_buf(),
+            };
+            for cap in RS_MOD.captures_iter(&content) {
+                candidates.push(module_dir.join(format!("{}.rs", &cap[1])));
+                candidates.push(module_dir.join(&cap[1]).join("mod.rs"));
+            }
+        }
+        "c" | "cc" | "cpp" | "cxx" | "h" | "hpp" | "hh" => {
+            for cap in C_INCLUDE.captures_iter(&content) {
+                candidates.push(dir.join(&cap[1]));
+            }
+        }
+        _ => (),
+    }
+
+    let mut imports: Vec<String> = candidates
+        .iter()
+        .map(|c| normalize_lexically(c))
+        .filter(|c| c != path && project_files.contains(c.as_str()))
+        .collect();
+    imports.sort();
+    imports.dedup();
+    imports
+}
+
+// Resolves `.`/`..` without touching the filesystem, keeping the tree's separators
+pub fn normalize_lexically(path: &Path) -> String {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => (),
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized.to_string_lossy().to_string()
+}
+
+pub fn select_from_trace(tree: &crate::types::FileNode, trace: &str, include_imports: bool) -> TraceSelection {
+    let parsed = parse_trace(trace);
+    let files: Vec<&str> = scan_results::collect_files(tree).into_iter().map(|f| f.path.as_str()).collect();
+    let mut by_name: HashMap<String, Vec<&str>> = HashMap::new();
+    for &file in &files {
+        if let Some(name) = path_components(file).last() {
+            by_name.entry(name.to_lowercase()).or_default().push(file);
+        }
+    }
+
+    let mut selection = TraceSelection { symbols: parsed.symbols.clone(), ..Default::default() };
+    let mut selected: BTreeMap<String, TraceMatch> = BTreeMap::new();
+    for (trace_path, lines) in &parsed.paths {
+        let resolved = resolve_trace_path(trace_path, &by_name);
+        if resolved.is_empty() {
+            selection.unmatched_paths.push(trace_path.clone());
+        }
+        for file in resolved {
+            let entry = selected.entry(file.to_string()).or_insert_with(|| TraceMatch {
+                path: file.to_string(),
+                lines: Vec::new(),
+                matched_by: "path".to_string(),
+            });
+            entry.lines.extend(lines.iter().copied().filter(|l| !entry.lines.contains(l)).collect::<Vec<_>>());
+        }
+    }
+
+    // Function names from the frames: files defining them that weren't matched by path
+    if !parsed.symbols.is_empty() {
+        let symbols: HashSet<&str> = parsed.symbols.iter().map(String::as_str).collect();
+        let defining: Vec<&str> = files
+            .par_iter()
+            .filter(|f| !selected.contains_key(**f))
+            .filter(|f| {
+                fs::read_to_string(f)
+                    .map(|content| SYMBOL_DEF_REGEX.captures_iter(&content).any(|c| symbols.contains(&c[1])))
+                    .unwrap_or(false)
+            })
+            .copied()
+            .collect();
+        // Common names defined all over the project are noise
+        if defining.len() <= 10 {
+            for file in defining {
+                selected.insert(
+                    file.to_string(),
+                    TraceMatch { path: file.to_string(), lines: Vec::new(), matched_by: "symbol".to_string() },
+                );
+            }
+        }
+    }
+
+    if include_imports {
+        let project_files: HashSet<&str> = files.iter().copied().collect();
+        let roots: Vec<String> = selected.keys().cloned().collect();
+        for root in roots {
+            for import in direct_imports(&root, &project_files) {
+                selected.entry(import.clone()).or_insert(TraceMatch {
+                    path: import,
+                    lines: Vec::new(),
+                    matched_by: "import".to_string(),
+                });
+            }
+        }
+    }
+
+    selection.matches = selected.into_values().collect();
+    selection
+}
+
+// --- Exposed Tauri Commands ---
+
+/// Maps a pasted stack trace / compiler error onto files of the project's last scan.
+#[command(async)]
+pub async fn select_files_from_trace(
+    state: State<'_, AppState>,
+    project_id: i32,
+    trace: String,
+    include_imports: Option<bool>,
+) -> Result<TraceSelection, String> {
+    let tree = load_tree(&state, project_id)?;
+    let rules = load_rules(&state, project_id, Path::new(&tree.path))?;
+    let include_imports = include_imports.unwrap_or(true);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut selection = select_from_trace(&tree, &trace, include_imports);
+        selection.matches.retain(|m| !rules.is_never(&m.path));
+        for path in rules.always_files(&tree) {
+            if !selection.matches.iter().any(|m| m.path == path) {
+                selection.matches.push(TraceMatch { path, lines: Vec::new(), matched_by: "always".to_string() });
+            }
+        }
+        selection
+    })
+    .await
+        .map_err(|e| format!("Trace selection task failed: {}", e))
+}
@@ -0,0 +1,139 @@
+// src-tauri/src/tree_queries.rs
+// Read-only views over a project's last scan tree (persisted by scan_results),
+// for frontend views that don't need to walk the nested FileNode structure.
+
+use crate::db::AppState;
+use crate::errors::{self, app_error};
+use crate::pinned_files;
+use crate::scan_results;
+use crate::scan_tree::reaggregate_node;
+use crate::path_case::CaseMode;
+use crate::types::{FileEntry, FileNode, TreeChildrenPage};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+use tauri::{command, State};
+
+// Page size of `get_tree_children` when the caller doesn't give one; keeps
+// directories like node_modules/.pnpm from producing multi-megabyte payloads.
+pub const DEFAULT_CHILD_LIMIT: usize = 2000;
+
+// Loads the persisted tree (pins flagged) or explains that the project needs a scan first
+pub fn load_tree(state: &State<'_, AppState>, project_id: i32) -> Result<FileNode, String> {
+    let conn = state.conn.lock().map_err(|e| format!("DB lock failed: {}", e))?;
+    let mut tree = scan_results::load_scan_result(&conn, project_id)?
+        .ok_or_else(|| app_error(errors::NO_SCAN_RESULT, &[("project_id", project_id.to_string())]))?;
+    pinned_files::mark_pinned(&conn, project_id, &mut tree);
+    Ok(tree)
+}
+
+/// All files of the last scan as a flat list sorted by path.
+#[command]
+pub fn get_flat_file_list(state: State<'_, AppState>, project_id: i32) -> Result<Vec<FileEntry>, String> {
+    let tree = load_tree(&state, project_id)?;
+    let mut entries: Vec<FileEntry> = scan_results::collect_files(&tree).into_iter().map(FileEntry::from).collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// A pruned copy of the last scan tree keeping only files that match every
+/// given criterion; directory totals are re-aggregated over what remains.
+/// `globs` use gitignore syntax relative to the project root ("*.rs", "src/**/*.ts").
+#[command]
+pub fn filter_tree(
+    state: State<'_, AppState>,
+    project_id: i32,
+    globs: Option<Vec<String>>,
+    min_tokens: Option<usize>,
+    languages: Option<Vec<String>>,
+) -> Result<FileNode, String> {
+    let tree = load_tree(&state, project_id)?;
+    let matcher = match globs.as_deref() {
+        Some(patterns) if !patterns.is_empty() => Some(build_glob_matcher(Path::new(&tree.path), patterns)?),
+        _ => None,
+    };
+    let languages: Option<Vec<String>> = languages
+        .filter(|l| !l.is_empty())
+        .map(|l| l.iter().map(|s| s.trim().to_lowercase()).collect());
+
+    let keep = |node: &FileNode| {
+        min_tokens.is_none_or(|min| node.tokens >= min)
+            && languages.as_ref().is_none_or(|langs| {
+                node.language.as_ref().is_some_and(|lang| langs.contains(lang))
+            })
+            && matcher.as_ref().is_none_or(|m| {
+                m.matched_path_or_any_parents(Path::new(&node.path), false).is_ignore()
+            })
+    };
+
+    let mut filtered = prune(&tree, &keep).unwrap_or_else(|| {
+        let mut empty = FileNode::new_dir(tree.path.clone(), tree.name.clone());
+        empty.id = tree.id.clone();
+        empty
+    });
+    reaggregate_node(&mut filtered);
+    Ok(filtered)
+}
+
+pub fn build_glob_matcher(root: &Path, globs: &[String]) -> Result<Gitignore, String> {
+    let mut builder = GitignoreBuilder::new(root);
+    for glob in globs.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+        builder.add_line(None, glob).map_err(|e| format!("Invalid glob '{}': {}", glob, e))?;
+    }
+    builder.build().map_err(|e| format!("Failed to compile globs: {}", e))
+}
+
+// Copies `node` keeping matching files and the directories that still contain any
+fn prune(node: &FileNode, keep: &dyn Fn(&FileNode) -> bool) -> Option<FileNode> {
+    if !node.is_dir {
+        return keep(node).then(|| node.clone());
+    }
+    let children: Vec<FileNode> = node.children.iter().filter_map(|c| prune(c, keep)).collect();
+    if children.is_empty() {
+        return None;
+    }
+    let mut copy = FileNode::new_dir(node.path.clone(), node.name.clone());
+    copy.id = node.id.clone();
+    copy.children = children;
+    Some(copy)
+}
+
+/// Truncates every directory's children to `limit` (0 = unlimited), marking
+/// cut directories with `has_more`. Totals stay those of the full directory.
+pub fn limit_children(node: &mut FileNode, limit: usize) {
+    if !node.is_dir || limit == 0 {
+        return;
+    }
+    if node.children.len() > limit {
+        node.children.truncate(limit);
+        node.has_more = true;
+    }
+    for child in &mut node.children {
+        limit_children(child, limit);
+    }
+}
+
+/// Continuation for directories returned with `has_more`: a page of the
+/// directory's children from the last scan, each subtree itself limited.
+#[command]
+pub fn get_tree_children(
+    state: State<'_, AppState>,
+    project_id: i32,
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TreeChildrenPage, String> {
+    let mut tree = load_tree(&state, project_id)?;
+    let dir = scan_results::find_node_mut(&mut tree, &path, CaseMode::Sensitive)
+        .filter(|n| n.is_dir)
+        .ok_or_else(|| format!("Directory '{}' is not in the last scan of project {}.", path, project_id))?;
+
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_CHILD_LIMIT);
+    let total = dir.children.len();
+    let end = if limit == 0 { total } else { offset.saturating_add(limit).min(total) };
+    let mut children: Vec<FileNode> = dir.children.drain(offset.min(total)..end).collect();
+    for child in &mut children {
+        limit_children(child, limit);
+    }
+    Ok(TreeChildrenPage { path, offset, total, has_more: end < total, children })
+}
@@ -1,5 +1,6 @@
 // src-tauri/src/types.rs
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Project {
@@ -13,11 +14,49 @@ pub struct Project {
     pub updated_at: Option<String>,
     #[serde(default)] // Default to empty string if missing in JSON
     pub prefix: String,
+    // Per-project scan settings below are Option so a save that omits them
+    // (older frontends send partial projects) leaves the stored value untouched.
+    /// Filesystem mode: "auto" (detect network roots), "local" or "network".
+    #[serde(default)]
+    pub fs_mode: Option<String>,
+    /// Path case handling: "auto" (detect from the volume), "sensitive" or "insensitive".
+    #[serde(default)]
+    pub case_sensitivity: Option<String>,
+    /// Apply the repository's .gitignore files (root, subfolders, .git/info/exclude) on top of the ignore patterns.
+    #[serde(default)]
+    pub respect_gitignore: Option<bool>,
+    /// Descend into symlinked folders (each folder is walked once, so link cycles end).
+    #[serde(default)]
+    pub follow_symlinks: Option<bool>,
+    /// Folder levels below the root a scan descends into (default 30).
+    #[serde(default)]
+    pub max_scan_depth: Option<usize>,
+    /// Scan dot-named files and folders (default true). When false they are
+    /// skipped unless a `!` ignore pattern keeps them.
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    /// Size range in bytes of the files a scan reads (0 = no limit); files
+    /// outside it are left out of the tree and listed in the scan summary.
+    #[serde(default)]
+    pub min_file_size: Option<u64>,
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    /// Only scan files modified in the last this many days (0 = any age).
+    #[serde(default)]
+    pub max_file_age_days: Option<u64>,
+    /// Include-only patterns (gitignore syntax, e.g. `src/**`, `docs/`): when
+    /// set, a scan keeps only matching files, then applies the ignore patterns.
+    #[serde(default)]
+    pub include_patterns: Option<Vec<String>>,
 }
 
-// --- FileNode Definition (No Change Needed) ---
+// --- FileNode Definition ---
+// The metadata fields default when deserializing trees persisted by older versions.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FileNode {
+    /// Stable identifier: hash of the project-relative path (see scan_tree::node_id).
+    #[serde(default)]
+    pub id: String,
     pub path: String,
     pub name: String,
     pub is_dir: bool,
@@ -26,4 +65,189 @@ pub struct FileNode {
     pub size: u64,
     pub last_modified: String,
     pub children: Vec<FileNode>,
-}
\ No newline at end of file
+    /// Lowercased extension without the dot (files only).
+    #[serde(default)]
+    pub extension: Option<String>,
+    /// Detected language, e.g. "typescript" (files only).
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub is_binary: bool,
+    /// Why the file has no line/token counts: one of the SKIP_* reasons below.
+    #[serde(default)]
+    pub skipped_reason: Option<String>,
+    /// Directories: files directly inside / anywhere below (0 for files).
+    #[serde(default)]
+    pub direct_file_count: usize,
+    #[serde(default)]
+    pub file_count: usize,
+    /// Directories: `children` was cut at the child limit; fetch the rest with get_tree_children.
+    #[serde(default)]
+    pub has_more: bool,
+    /// Files: share of the project's files with at most this many tokens (0-100), for heatmaps.
+    #[serde(default)]
+    pub token_percentile: f64,
+    /// Files: pinned by the user (see pinned_files). Set on returned trees only.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Why the entry couldn't be read during the scan (permission denied, I/O
+    /// error); a folder with one has no scanned contents.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// Values of FileNode::skipped_reason
+pub const SKIP_TOO_LARGE: &str = "too_large";
+pub const SKIP_BINARY: &str = "binary";
+// No longer produced (non-UTF-8 text is decoded); old cache rows are recounted
+pub const SKIP_NON_UTF8: &str = "non_utf8";
+pub const SKIP_UNREADABLE: &str = "unreadable";
+pub const SKIP_CLOUD_PLACEHOLDER: &str = "cloud_placeholder";
+// Named pipes, sockets and devices; left out of the tree (ScanSummary::special_files)
+pub const SKIP_SPECIAL_FILE: &str = "special_file";
+
+impl FileNode {
+    /// An empty directory node with no stats yet.
+    pub fn new_dir(path: String, name: String) -> Self {
+        FileNode {
+            id: String::new(),
+            path, name, is_dir: true,
+            lines: 0, tokens: 0, size: 0,
+            last_modified: "".to_string(),
+            children: Vec::new(),
+            extension: None, language: None, is_binary: false, skipped_reason: None,
+            direct_file_count: 0, file_count: 0, has_more: false, token_percentile: 0.0, pinned: false,
+            error: None,
+        }
+    }
+}
+// --- Scan summary (root-level totals of a scan) ---
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScanSummary {
+    pub total_files: usize,
+    pub total_dirs: usize,
+    pub total_lines: usize,
+    pub total_tokens: usize,
+    pub total_bytes: u64,
+    /// Files without counts (or left out) per SKIP_* reason.
+    pub skipped_by_reason: BTreeMap<String, usize>,
+    pub duration_ms: u64,
+    /// Folders at the depth limit whose contents weren't scanned.
+    #[serde(default)]
+    pub truncated_subtrees: Vec<String>,
+    /// Files the project's size/age filters left out of the tree, by path.
+    #[serde(default)]
+    pub filtered_files: Vec<FilteredFile>,
+    /// Folders and files the scan couldn't read, by path.
+    #[serde(default)]
+    pub errors: Vec<ScanError>,
+    /// Named pipes, sockets and device files the scan left out, by path.
+    #[serde(default)]
+    pub special_files: Vec<SpecialFile>,
+}
+
+// --- An entry a scan couldn't read (ScanSummary::errors, FileNode::error) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanError {
+    pub path: String,
+    pub message: String,
+}
+
+// --- A filesystem entry that isn't a file, folder or symlink (see scan_tree::special_kind) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpecialFile {
+    pub path: String,
+    /// "fifo", "socket", "block_device", "char_device" or "other"
+    pub kind: String,
+}
+
+// --- A file left out by the project's size/age filters (see scan_filters) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FilteredFile {
+    pub path: String,
+    pub size: u64,
+    pub last_modified: String,
+    /// One of the scan_filters::FILTER_* reasons.
+    pub reason: String,
+}
+
+// --- Scan statistics (the `scan_summary` event at the end of a scan) ---
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ScanStatistics {
+    pub project_id: i32,
+    /// Files per lowercased extension; "" for files without one.
+    pub files_by_extension: BTreeMap<String, usize>,
+    /// Tokens per detected language; "other" for files without one.
+    pub tokens_by_language: BTreeMap<String, usize>,
+    /// The heaviest files by tokens, heaviest first.
+    pub largest_files: Vec<FileEntry>,
+    /// Files whose counts came from the cache vs. were read again.
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
+
+// Tree plus summary, returned by scan_project_with_summary
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanOutput {
+    pub tree: FileNode,
+    pub summary: ScanSummary,
+}
+
+// --- Flat file list entry (get_flat_file_list) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileEntry {
+    pub id: String,
+    pub path: String,
+    pub name: String,
+    pub lines: usize,
+    pub tokens: usize,
+    pub size: u64,
+    pub last_modified: String,
+    pub extension: Option<String>,
+    pub language: Option<String>,
+    pub is_binary: bool,
+    pub skipped_reason: Option<String>,
+    pub pinned: bool,
+}
+
+impl From<&FileNode> for FileEntry {
+    fn from(node: &FileNode) -> Self {
+        FileEntry {
+            id: node.id.clone(),
+            path: node.path.clone(),
+            name: node.name.clone(),
+            lines: node.lines,
+            tokens: node.tokens,
+            size: node.size,
+            last_modified: node.last_modified.clone(),
+            extension: node.extension.clone(),
+            language: node.language.clone(),
+            is_binary: node.is_binary,
+            skipped_reason: node.skipped_reason.clone(),
+            pinned: node.pinned,
+        }
+    }
+}
+
+// --- One page of a directory's children (get_tree_children) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TreeChildrenPage {
+    pub path: String,
+    pub offset: usize,
+    pub total: usize,
+    pub has_more: bool,
+    pub children: Vec<FileNode>,
+}
+
+// --- Single-file stats (get_file_stats) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileStats {
+    pub path: String,
+    pub size: u64,
+    pub last_modified: String,
+    pub lines: usize,
+    pub tokens: usize,
+    pub tokenizer: String,
+    // False when the file exceeds the scan size limit and wasn't counted
+    pub counted: bool,
+}
@@ -1,42 +1,88 @@
 
 // src-tauri/src/utils.rs
-use tauri::command;
-use tiktoken_rs::{cl100k_base, CoreBPE};
-use once_cell::sync::Lazy;
-
-// Static Lazy-initialized tokenizer.
-// It will attempt to load the tokenizer once. If successful, it stores CoreBPE.
-// If it fails, it stores the error string.
-static TOKENIZER: Lazy<Result<CoreBPE, String>> = Lazy::new(|| {
-    cl100k_base().map_err(|e| {
+use tauri::{command, AppHandle, Emitter};
+use tiktoken_rs::{cl100k_base, o200k_base, CoreBPE};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+// The default tokenizer. tiktoken-rs compiles the BPE ranks into the binary
+// (include_str!), so loading never touches the network; it can still fail (e.g.
+// out of memory), in which case counts fall back to whitespace splitting.
+// Kept behind a lock so `reload_tokenizer` can swap in a fresh instance.
+static TOKENIZER: Lazy<RwLock<Result<Arc<CoreBPE>, String>>> = Lazy::new(|| RwLock::new(load_default_tokenizer()));
+
+// Secondary tokenizer (GPT-4o family), loaded only when first requested.
+static O200K_TOKENIZER: Lazy<Result<CoreBPE, String>> = Lazy::new(|| {
+    o200k_base().map_err(|e| format!("Failed to load o200k_base tokenizer: {:?}", e))
+});
+
+// Set at startup so the fallback warning can reach the UI
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+// The fallback is reported once per failure, not once per counted file
+static FALLBACK_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Event emitted when counts fall back to whitespace splitting; payload is the load error.
+pub const TOKENIZER_FALLBACK_EVENT: &str = "tokenizer-fallback";
+
+fn load_default_tokenizer() -> Result<Arc<CoreBPE>, String> {
+    cl100k_base().map(Arc::new).map_err(|e| {
         let err_msg = format!("Failed to load cl100k_base tokenizer: {:?}", e);
         eprintln!("{}", err_msg); // Log error during initialization
         err_msg
     })
-});
+}
 
-// Updated to use the globally initialized tokenizer.
-pub fn approximate_token_count(text: &str) -> usize {
-    match &*TOKENIZER {
-        Ok(bpe) => {
-            // Using encode_ordinary instead of encode_with_special_tokens
-            // as special tokens are typically not what users want to count
-            // when estimating context window size for LLMs with cl100k_base.
-            // If special tokens *are* desired, switch back to:
-            // bpe.encode_with_special_tokens(text).len()
-            bpe.encode_ordinary(text).len()
+fn report_fallback(error: &str) {
+    if FALLBACK_REPORTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    eprintln!("Tokenizer not available ({}). Falling back to whitespace counts.", error);
+    if let Some(app_handle) = APP_HANDLE.get() {
+        if let Err(e) = app_handle.emit(TOKENIZER_FALLBACK_EVENT, error) {
+            eprintln!("Failed to emit {}: {}", TOKENIZER_FALLBACK_EVENT, e);
         }
+    }
+}
+
+/// Loads the default tokenizer at startup and remembers the app handle for
+/// fallback warnings.
+pub fn init_tokenizer(app_handle: &AppHandle) {
+    let _ = APP_HANDLE.set(app_handle.clone());
+    if let Err(e) = &*TOKENIZER.read().unwrap_or_else(|p| p.into_inner()) {
+        report_fallback(e);
+    }
+}
+
+pub const DEFAULT_TOKENIZER: &str = "cl100k_base";
+
+// Counts with the default tokenizer, or whitespace-separated words when it failed to load.
+pub fn approximate_token_count(text: &str) -> usize {
+    let bpe = match &*TOKENIZER.read().unwrap_or_else(|p| p.into_inner()) {
+        Ok(bpe) => bpe.clone(),
         Err(e) => {
-            // Error already logged during Lazy initialization,
-            // but we can log a specific message for this call if needed.
-            eprintln!(
-                "Tokenizer not available (initialization failed: {}). Falling back to whitespace count for text ({}... chars).",
-                e,
-                text.chars().take(30).collect::<String>()
-            );
+            report_fallback(e);
             // Fallback to a rough approximation if tokenizer failed to load
-            text.split_whitespace().count()
+            return text.split_whitespace().count();
         }
+    };
+    // encode_ordinary: special tokens are typically not what users want to count
+    // when estimating context window size.
+    bpe.encode_ordinary(text).len()
+}
+
+/// Counts tokens with a named tokenizer ("cl100k_base" when None).
+/// Cached scan counts always use the default tokenizer.
+pub fn count_tokens_with(text: &str, tokenizer: Option<&str>) -> Result<usize, String> {
+    match tokenizer.unwrap_or(DEFAULT_TOKENIZER) {
+        DEFAULT_TOKENIZER => Ok(approximate_token_count(text)),
+        "o200k_base" => match &*O200K_TOKENIZER {
+            Ok(bpe) => Ok(bpe.encode_ordinary(text).len()),
+            Err(e) => Err(e.clone()),
+        },
+        other => Err(format!("Unknown tokenizer '{}'. Supported: cl100k_base, o200k_base.", other)),
     }
 }
 
@@ -44,4 +90,47 @@ pub fn approximate_token_count(text: &str) -> usize {
 pub fn get_text_token_count(text: String) -> Result<usize, String> {
     // Uses the updated approximate_token_count which now employs the Lazy-loaded tokenizer.
     Ok(approximate_token_count(&text))
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TokenizerHealth {
+    /// Tokenizer behind scan counts: "cl100k_base", or "whitespace" in fallback mode
+    pub active: String,
+    pub fallback: bool,
+    /// Where the BPE data comes from; always compiled into the binary
+    pub source: String,
+    pub error: Option<String>,
+    pub o200k_available: bool,
+    /// Time a sample encode took, as a basic smoke test
+    pub sample_ms: f64,
+}
+
+/// Reports which tokenizer counts are using and whether it works.
+#[command]
+pub fn get_tokenizer_health() -> Result<TokenizerHealth, String> {
+    let error = TOKENIZER.read().map_err(|e| format!("Tokenizer lock failed: {}", e))?.as_ref().err().cloned();
+    let started = Instant::now();
+    approximate_token_count("fn main() { println!(\"tokenizer health check\"); }");
+    Ok(TokenizerHealth {
+        active: if error.is_some() { "whitespace".to_string() } else { DEFAULT_TOKENIZER.to_string() },
+        fallback: error.is_some(),
+        source: "embedded".to_string(),
+        error,
+        o200k_available: O200K_TOKENIZER.is_ok(),
+        sample_ms: started.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+/// Loads the default tokenizer again and swaps it in if that works, e.g. after
+/// a failed start. Counts cached in fallback mode stay until their files change.
+#[command]
+pub fn reload_tokenizer() -> Result<TokenizerHealth, String> {
+    let reloaded = load_default_tokenizer();
+    let ok = reloaded.is_ok();
+    if ok || TOKENIZER.read().map_err(|e| format!("Tokenizer lock failed: {}", e))?.is_err() {
+        *TOKENIZER.write().map_err(|e| format!("Tokenizer lock failed: {}", e))? = reloaded;
+        FALLBACK_REPORTED.store(false, Ordering::Relaxed);
+    }
+    println!("[TOKENIZER] Reload {}.", if ok { "succeeded" } else { "failed" });
+    get_tokenizer_health()
+}
@@ -0,0 +1,118 @@
+// src-tauri/src/window_registry.rs
+// Tracks which windows are interested in which projects, so scan progress and
+// monitor events go to the windows showing that project instead of every
+// window (Tauri's `emit` broadcasts to all of them).
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, State, Window};
+
+#[derive(Default)]
+pub struct WindowRegistry {
+    // window label -> subscribed project ids
+    subscriptions: Mutex<HashMap<String, HashSet<i32>>>,
+}
+
+impl WindowRegistry {
+    pub fn subscribe(&self, label: &str, project_id: i32) {
+        if let Ok(mut subs) = self.subscriptions.lock() {
+            subs.entry(label.to_string()).or_default().insert(project_id);
+        }
+    }
+
+    /// Drops one subscription of a window, or all of them when `project_id` is None.
+    pub fn unsubscribe(&self, label: &str, project_id: Option<i32>) {
+        if let Ok(mut subs) = self.subscriptions.lock() {
+            match project_id {
+                Some(id) => {
+                    if let Some(set) = subs.get_mut(label) {
+                        set.remove(&id);
+                        if set.is_empty() {
+                            subs.remove(label);
+                        }
+                    }
+                }
+                None => {
+                    subs.remove(label);
+                }
+            }
+        }
+    }
+
+    pub fn windows_for_project(&self, project_id: i32) -> Vec<String> {
+        self.subscriptions
+            .lock()
+            .map(|subs| {
+                subs.iter()
+                    .filter(|(_, ids)| ids.contains(&project_id))
+                    .map(|(label, _)| label.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Emits a project event to every window subscribed to the project. With no
+/// subscribers (single-window setups that never subscribed) it broadcasts, as before.
+pub fn emit_to_project<S: Serialize + Clone>(app_handle: &AppHandle, project_id: i32, event: &str, payload: S) {
+    let labels = app_handle
+        .try_state::<WindowRegistry>()
+        .map(|r| r.windows_for_project(project_id))
+        .unwrap_or_default();
+    if labels.is_empty() {
+        if let Err(e) = app_handle.emit(event, payload) {
+            eprintln!("[Windows] Failed to emit {}: {}", event, e);
+        }
+        return;
+    }
+    for label in labels {
+        if let Err(e) = app_handle.emit_to(label.as_str(), event, payload.clone()) {
+            eprintln!("[Windows] Failed to emit {} to '{}': {}", event, label, e);
+        }
+    }
+}
+
+/// Emits a project event to the window that started the work plus any other
+/// windows subscribed to the project.
+pub fn emit_to_origin_and_project<S: Serialize + Clone>(window: &Window, project_id: i32, event: &str, payload: S) {
+    let origin = window.label().to_string();
+    if let Err(e) = window.emit_to(origin.as_str(), event, payload.clone()) {
+        eprintln!("[Windows] Failed to emit {} to '{}': {}", event, origin, e);
+    }
+    let others: Vec<String> = window
+        .try_state::<WindowRegistry>()
+        .map(|r| r.windows_for_project(project_id))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|label| *label != origin)
+        .collect();
+    for label in others {
+        if let Err(e) = window.emit_to(label.as_str(), event, payload.clone()) {
+            eprintln!("[Windows] Failed to emit {} to '{}': {}", event, label, e);
+        }
+    }
+}
+
+// --- Commands ---
+
+#[command]
+pub fn subscribe_window_to_project(
+    window: Window,
+    registry: State<'_, WindowRegistry>,
+    project_id: i32,
+) -> Result<(), String> {
+    registry.subscribe(window.label(), project_id);
+    Ok(())
+}
+
+/// Unsubscribes the calling window from one project, or from all when `project_id` is omitted.
+#[command]
+pub fn unsubscribe_window_from_project(
+    window: Window,
+    registry: State<'_, WindowRegistry>,
+    project_id: Option<i32>,
+) -> Result<(), String> {
+    registry.unsubscribe(window.label(), project_id);
+    Ok(())
+}
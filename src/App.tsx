@@ -15,6 +15,7 @@ import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import { Window, PhysicalPosition, PhysicalSize } from "@tauri-apps/api/window";
 import { findNodeByPath as findNodeByPathUtil } from "./components/CodeContextBuilder/FileTree/fileTreeUtils";
 import { OutputFormat } from "./hooks/useAggregator";
+import { errorMessage } from "./utils/errorMessage";
 
 interface ScanProgressPayload {
     progress: number;
@@ -471,7 +472,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
             setSelectedProjectId(projectToSelect);
         } catch (err) {
             if (isMountedRef.current) {
-                setError(`Failed to load projects: ${err instanceof Error ? err.message : String(err)}`);
+                setError(`Failed to load projects: ${errorMessage(err)}`);
                 setProjects([]); setSelectedProjectId(0);
             }
             localStorage.removeItem('ccb_lastSelectedProjectId');
@@ -532,7 +533,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
                         if (status === 'done') setOutOfDateFilePaths(new Set());
                     }
                 });
-            } catch (err) { if(localIsMountedRef.current && isMountedRef.current) { setError(`Listener setup failed: ${err instanceof Error ? err.message : String(err)}`); } }
+            } catch (err) { if(localIsMountedRef.current && isMountedRef.current) { setError(`Listener setup failed: ${errorMessage(err)}`); } }
         };
         setupListeners();
         return () => { localIsMountedRef.current = false; unlistenProgress?.(); unlistenComplete?.(); };
@@ -569,7 +570,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
                 return prevProjects.map(p => (p.id === selectedProjectId) ? { ...p, title: currentTitle, root_folder: currentRootFolder, ignore_patterns: currentIgnoreArr, updated_at: newUpdatedAt } : p);
             });
             return "saved";
-        } catch (err) { if (isMountedRef.current) setError(`Save failed: ${err instanceof Error ? err.message : String(err)}`); return "error"; }
+        } catch (err) { if (isMountedRef.current) setError(`Save failed: ${errorMessage(err)}`); return "error"; }
     }, [selectedProjectId, editableTitle, editableRootFolder, editableIgnorePatterns]);
 
     const handleCreateNewProject = useCallback(async () => {
@@ -586,7 +587,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
             try {
                 const newId = await invoke<number>("save_code_context_builder_project", { project: newProjectData });
                 if (isMountedRef.current) await loadProjects(newId);
-            } catch (err) { if (isMountedRef.current) setError(`Create failed: ${err instanceof Error ? err.message : String(err)}`); }
+            } catch (err) { if (isMountedRef.current) setError(`Create failed: ${errorMessage(err)}`); }
         }
     }, [loadProjects]);
 
@@ -606,7 +607,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
             }
         } catch (err) {
             if (isMountedRef.current) {
-                setError(`Delete process failed: ${err instanceof Error ? err.message : String(err)}`);
+                setError(`Delete process failed: ${errorMessage(err)}`);
             }
         }
     }, [selectedProjectId, loadProjects]);
@@ -625,7 +626,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
            setProjects(prev => prev.map(p => p.id === selectedProjectId ? {...p, updated_at: new Date().toISOString()} : p));
         } catch (err) {
             if (!isMountedRef.current) return;
-            setError(`Scan failed: ${err instanceof Error ? err.message : String(err)}`);
+            setError(`Scan failed: ${errorMessage(err)}`);
             setTreeData(null); localStorage.removeItem(`ccb_treeData_${selectedProjectId}`);
         }
     }, [selectedProjectId, isScanning, stopFileMonitoring, isMonitoringProject ]);
@@ -633,7 +634,7 @@ const effectiveAggTokens = aggTokenCount || aggregatedStats.tokens;
     const handleCancelScan = useCallback(async () => {
         if (!isScanning || typeof invoke !== 'function') return;
         try { await invoke("cancel_code_context_builder_scan"); } 
-        catch (err) { if (isMountedRef.current) setError(`Failed to cancel scan: ${err instanceof Error ? err.message : String(err)}`); }
+        catch (err) { if (isMountedRef.current) setError(`Failed to cancel scan: ${errorMessage(err)}`); }
     }, [isScanning]);
 
     const handleToggleSelection = useCallback((path: string, isDir: boolean) => {
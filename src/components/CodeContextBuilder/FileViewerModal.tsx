@@ -5,6 +5,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { Prism as SyntaxHighlighter } from 'react-syntax-highlighter';
 import { vscDarkPlus } from 'react-syntax-highlighter/dist/esm/styles/prism';
 import { getLanguageFromPath } from './Aggregator/aggregatorUtils'; // CORRECTED PATH
+import { errorMessage } from '../../utils/errorMessage';
 
 
 interface FileViewerModalProps {
@@ -38,7 +39,7 @@ const FileViewerModal: React.FC<FileViewerModalProps> = ({ filePath, onClose })
                     setContent(fileContent);
                 }
             } catch (err) {
-                const errorMsg = err instanceof Error ? err.message : String(err);
+                const errorMsg = errorMessage(err);
                 if (isMountedRef.current) {
                     setError(`Failed to load file: ${errorMsg}`);
                 }
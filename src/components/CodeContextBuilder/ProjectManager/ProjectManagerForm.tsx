@@ -2,6 +2,7 @@
 import React, { useCallback, useState } from "react"; // Removed useEffect, useRef, ReactDOM
 import { open } from '@tauri-apps/plugin-dialog';
 import IgnoreHelpModal from './IgnoreHelpModal'; // Import the new modal
+import { errorMessage } from '../../../utils/errorMessage';
 
 interface ProjectManagerFormProps {
   projectTitle: string;
@@ -34,7 +35,7 @@ const ProjectManagerForm: React.FC<ProjectManagerFormProps> = ({
            setRootFolder(selected);
        }
      } catch (error) {
-        alert(`Could not open folder picker: ${error instanceof Error ? error.message : String(error)}`);
+        alert(`Could not open folder picker: ${errorMessage(error)}`);
      }
   }, [rootFolder, setRootFolder]);
 
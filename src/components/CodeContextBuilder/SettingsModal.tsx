@@ -7,6 +7,7 @@ import type { Project as AppProject } from '../../types/projects';
 import { downloadDir } from '@tauri-apps/api/path';
 import { OutputFormat } from '../../hooks/useAggregator';
 import { DEFAULT_FORMAT_INSTRUCTIONS, FORMAT_INSTRUCTIONS_STORAGE_KEY_PREFIX } from './Aggregator/aggregatorUtils';
+import { errorMessage } from '../../utils/errorMessage';
 
 export type ThemeSetting = 'system' | 'light' | 'dark';
 
@@ -82,7 +83,7 @@ const SettingsModal: React.FC<SettingsModalProps> = ({
             setInstructionTexts(loadedInstructions);
 
         } catch (err) {
-            setError(err instanceof Error ? err.message : String(err));
+            setError(errorMessage(err));
         } finally {
             setDefaultIgnorePatterns(loadedPatterns);
             setIsLoading(false);
@@ -110,7 +111,7 @@ const SettingsModal: React.FC<SettingsModalProps> = ({
             setSaveStatus('saved');
             setTimeout(() => setSaveStatus('idle'), 2000);
         } catch (err) {
-            setError(err instanceof Error ? err.message : String(err));
+            setError(errorMessage(err));
             setSaveStatus('error_saving');
         }
     };
@@ -178,7 +179,7 @@ const SettingsModal: React.FC<SettingsModalProps> = ({
                 setExportMessage(`Successfully exported ${projectsToExport.length} projects.`);
             } else { setExportMessage('Export cancelled.'); }
         } catch (err) {
-            const errorMsg = err instanceof Error ? err.message : String(err);
+            const errorMsg = errorMessage(err);
             setExportMessage(`Export failed: ${errorMsg}`);
         } finally { setIsExporting(false); }
     };
@@ -201,7 +202,7 @@ const SettingsModal: React.FC<SettingsModalProps> = ({
                 onImportComplete();
             } else { setImportMessage('Import cancelled.'); }
         } catch (err) {
-            const errorMsg = err instanceof Error ? err.message : String(err);
+            const errorMsg = errorMessage(err);
             setImportMessage(`Import failed: ${errorMsg}`);
         } finally { setIsImporting(false); }
     };
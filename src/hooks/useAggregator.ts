@@ -2,6 +2,7 @@
 import { useState, useEffect, useCallback, useMemo } from 'react';
 import { FileNode } from '../types/scanner';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errorMessage';
 import {
     escapeXml,
     formatFileContent,
@@ -169,7 +170,7 @@ export const useAggregator = ({
             setAggregatedText(contentBody);
 
         } catch (err) {
-            const errorMsg = err instanceof Error ? err.message : String(err);
+            const errorMsg = errorMessage(err);
             setError(`Failed to aggregate content: ${errorMsg}`);
             setAggregatedText('');
         } finally {
@@ -0,0 +1,29 @@
+// src/utils/errorMessage.ts
+
+/** Structured command error (see src-tauri/src/errors.rs). */
+interface AppError {
+    code: string;
+    params: Record<string, string>;
+    fallback_message: string;
+}
+
+function parseAppError(text: string): AppError | null {
+    if (!text.startsWith('{')) {
+        return null;
+    }
+    try {
+        const parsed = JSON.parse(text);
+        return parsed && typeof parsed.code === 'string' && typeof parsed.fallback_message === 'string' ? parsed : null;
+    } catch {
+        return null;
+    }
+}
+
+/**
+ * Readable text of a caught error. Command errors are either plain strings or
+ * JSON `{code, params, fallback_message}`; the latter show their message.
+ */
+export function errorMessage(err: unknown): string {
+    const text = err instanceof Error ? err.message : String(err);
+    return parseAppError(text)?.fallback_message ?? text;
+}
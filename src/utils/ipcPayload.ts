@@ -0,0 +1,38 @@
+// src/utils/ipcPayload.ts
+import { invoke, InvokeArgs } from '@tauri-apps/api/core';
+
+/** First byte of a packed response (see src-tauri/src/ipc_payload.rs). */
+const FLAG_PLAIN = 0;
+const FLAG_GZIP = 1;
+
+/**
+ * Decodes a packed command response: one flag byte followed by JSON, gzipped
+ * when the backend compressed it.
+ */
+export async function decodePackedResponse<T>(payload: ArrayBuffer | Uint8Array | number[]): Promise<T> {
+    const bytes = payload instanceof Uint8Array ? payload : new Uint8Array(payload);
+    if (bytes.length === 0) {
+        throw new Error('Empty packed response');
+    }
+    const body = bytes.subarray(1);
+    let json: string;
+    switch (bytes[0]) {
+        case FLAG_PLAIN:
+            json = new TextDecoder().decode(body);
+            break;
+        case FLAG_GZIP: {
+            const stream = new Blob([body]).stream().pipeThrough(new DecompressionStream('gzip'));
+            json = await new Response(stream).text();
+            break;
+        }
+        default:
+            throw new Error(`Unknown packed response flag ${bytes[0]}`);
+    }
+    return JSON.parse(json) as T;
+}
+
+/** Invokes a packed command and decodes its response. */
+export async function invokePacked<T>(command: string, args?: InvokeArgs): Promise<T> {
+    const payload = await invoke<ArrayBuffer | number[]>(command, args);
+    return decodePackedResponse<T>(payload);
+}